@@ -0,0 +1,428 @@
+use rt_core::{Scalar, ToneMap, ToneOperator};
+use rt_render::{Aov, DebugChannel};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+usage: raytracer render <scene> [options]
+
+Renders a YAML or JSON scene file to an image.
+
+options:
+  -o, --output <file>    image to write; the format follows the extension
+                         (default: the scene name with a .png extension)
+      --width <pixels>   override the camera width
+      --height <pixels>  override the camera height
+      --threads <n>      worker threads, 0 for one per core (default: 0)
+      --samples <n>      samples per pixel; whitted rounds up to a square grid
+      --integrator <name>
+                         whitted (default) or path for Monte Carlo path tracing
+      --bounces <n>      path tracing bounce limit (default: 8)
+      --seed <n>         seed for jitter, soft shadows, emissive light
+                         samples, depth of field, motion blur and path
+                         tracing; equal seeds give identical images
+                         (default: 0)
+      --exposure <stops> scale the image by 2^stops before tone mapping
+      --tonemap <name>   none (default), reinhard or aces
+      --gamma <value>    gamma applied after tone mapping (default: 1)
+      --aov <names>      also write depth, normal, albedo or object-id
+                         buffers (comma separated, or all) beside the output
+      --debug <channel>  render false-color normal, depth, traversal or
+                         shadow-rays images instead of shading
+      --checkpoint <file>
+                         periodically save progress to <file> so an
+                         interrupted render can be resumed
+      --resume           continue from the checkpoint (default file: the
+                         output name with a .checkpoint extension)
+      --packets          trace primary rays in packets of four (whitted
+                         integrator without adaptive sampling only)
+      --stats            print ray, traversal and tile timing counters
+                         when the render finishes
+      --preview          show the render in a window as it progresses
+                         (needs the `preview` feature)
+  -q, --quiet            do not print progress
+  -h, --help             print this help
+  -V, --version          print the version";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Render(RenderOptions),
+    Help,
+    Version,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntegratorChoice {
+    Whitted,
+    Path,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    pub scene: PathBuf,
+    pub output: PathBuf,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub threads: usize,
+    pub samples: Option<usize>,
+    pub integrator: IntegratorChoice,
+    pub bounces: usize,
+    pub seed: u64,
+    pub tone_map: ToneMap,
+    pub aovs: Vec<Aov>,
+    pub debug: Option<DebugChannel>,
+    pub checkpoint: Option<PathBuf>,
+    pub resume: bool,
+    pub packets: bool,
+    pub stats: bool,
+    pub preview: bool,
+    pub quiet: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgsError(pub String);
+
+impl std::fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ArgsError {}
+
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, ArgsError> {
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        Some("render") => parse_render(args),
+        Some("help") | Some("-h") | Some("--help") => Ok(Command::Help),
+        Some("-V") | Some("--version") => Ok(Command::Version),
+        Some(other) => Err(ArgsError(format!("unknown command '{}'", other))),
+        None => Err(ArgsError("missing command".into())),
+    }
+}
+
+fn parse_render<I: Iterator<Item = String>>(mut args: I) -> Result<Command, ArgsError> {
+    let mut scene = None;
+    let mut output = None;
+    let mut width = None;
+    let mut height = None;
+    let mut threads = 0;
+    let mut samples = None;
+    let mut integrator = IntegratorChoice::Whitted;
+    let mut bounces = 8;
+    let mut seed = 0;
+    let mut tone_map = ToneMap::default();
+    let mut aovs = Vec::new();
+    let mut debug = None;
+    let mut checkpoint = None;
+    let mut resume = false;
+    let mut packets = false;
+    let mut stats = false;
+    let mut preview = false;
+    let mut quiet = false;
+
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => (flag.to_string(), Some(value.into())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| ArgsError(format!("'{}' needs a value", flag)))
+        };
+        match flag.as_str() {
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            "--width" => width = Some(positive(&flag, &value()?)?),
+            "--height" => height = Some(positive(&flag, &value()?)?),
+            "--threads" => threads = number(&flag, &value()?)?,
+            "--samples" => samples = Some(positive(&flag, &value()?)?),
+            "--integrator" => {
+                integrator = match value()?.as_str() {
+                    "whitted" => IntegratorChoice::Whitted,
+                    "path" => IntegratorChoice::Path,
+                    other => return Err(ArgsError(format!("unknown integrator '{}'", other))),
+                }
+            }
+            "--bounces" => bounces = number(&flag, &value()?)?,
+            "--seed" => seed = number(&flag, &value()?)? as u64,
+            "--exposure" => tone_map.exposure = decimal(&flag, &value()?)?,
+            "--tonemap" => {
+                tone_map.operator = match value()?.as_str() {
+                    "none" => ToneOperator::Linear,
+                    "reinhard" => ToneOperator::Reinhard,
+                    "aces" => ToneOperator::Aces,
+                    other => return Err(ArgsError(format!("unknown tone map '{}'", other))),
+                }
+            }
+            "--gamma" => match decimal(&flag, &value()?)? {
+                gamma if gamma > 0.0 => tone_map.gamma = gamma,
+                _ => return Err(ArgsError(format!("'{}' must be positive", flag))),
+            },
+            "--aov" => {
+                for name in value()?.split(',') {
+                    let requested = match name {
+                        "all" => Aov::ALL.to_vec(),
+                        _ => vec![Aov::from_name(name)
+                            .ok_or_else(|| ArgsError(format!("unknown AOV '{}'", name)))?],
+                    };
+                    for aov in requested {
+                        if !aovs.contains(&aov) {
+                            aovs.push(aov);
+                        }
+                    }
+                }
+            }
+            "--debug" => {
+                let name = value()?;
+                debug = Some(
+                    DebugChannel::from_name(&name)
+                        .ok_or_else(|| ArgsError(format!("unknown debug channel '{}'", name)))?,
+                );
+            }
+            "--checkpoint" => checkpoint = Some(PathBuf::from(value()?)),
+            "--resume" => resume = true,
+            "--packets" => packets = true,
+            "--stats" => stats = true,
+            "--preview" => preview = true,
+            "-q" | "--quiet" => quiet = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            _ if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(ArgsError(format!("unknown option '{}'", flag)))
+            }
+            _ if scene.is_none() => scene = Some(PathBuf::from(arg)),
+            _ => return Err(ArgsError(format!("unexpected argument '{}'", arg))),
+        }
+    }
+
+    let scene = scene.ok_or_else(|| ArgsError("missing scene file".into()))?;
+    let output = output.unwrap_or_else(|| scene.with_extension("png"));
+    let checkpoint = checkpoint.or_else(|| resume.then(|| output.with_extension("checkpoint")));
+    if preview && checkpoint.is_some() {
+        return Err(ArgsError(
+            "'--preview' cannot be combined with checkpoints".into(),
+        ));
+    }
+    if stats && (preview || checkpoint.is_some()) {
+        return Err(ArgsError(
+            "'--stats' cannot be combined with '--preview' or checkpoints".into(),
+        ));
+    }
+    Ok(Command::Render(RenderOptions {
+        scene,
+        output,
+        width,
+        height,
+        threads,
+        samples,
+        integrator,
+        bounces,
+        seed,
+        tone_map,
+        aovs,
+        debug,
+        checkpoint,
+        resume,
+        packets,
+        stats,
+        preview,
+        quiet,
+    }))
+}
+
+fn number(flag: &str, value: &str) -> Result<usize, ArgsError> {
+    value.parse().map_err(|_| {
+        ArgsError(format!(
+            "'{}' expects a whole number, found '{}'",
+            flag, value
+        ))
+    })
+}
+
+fn decimal(flag: &str, value: &str) -> Result<Scalar, ArgsError> {
+    match value.parse::<Scalar>() {
+        Ok(number) if number.is_finite() => Ok(number),
+        _ => Err(ArgsError(format!(
+            "'{}' expects a number, found '{}'",
+            flag, value
+        ))),
+    }
+}
+
+fn positive(flag: &str, value: &str) -> Result<usize, ArgsError> {
+    match number(flag, value)? {
+        0 => Err(ArgsError(format!("'{}' must be at least 1", flag))),
+        n => Ok(n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, ArgsError> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn render(args: &[&str]) -> RenderOptions {
+        match parse_args(args) {
+            Ok(Command::Render(options)) => options,
+            other => panic!("expected a render command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn render_with_every_option() {
+        let options = render(&[
+            "render",
+            "scene.yaml",
+            "-o",
+            "out.png",
+            "--width",
+            "1920",
+            "--height=1080",
+            "--threads",
+            "8",
+            "--samples",
+            "16",
+            "--integrator",
+            "path",
+            "--bounces=4",
+            "--seed",
+            "7",
+            "--exposure",
+            "-1.5",
+            "--tonemap",
+            "aces",
+            "--gamma=2.2",
+            "--aov",
+            "depth,albedo",
+            "--debug",
+            "traversal",
+            "--checkpoint",
+            "out.ckpt",
+            "--resume",
+            "--packets",
+            "-q",
+        ]);
+        assert_eq!(
+            options,
+            RenderOptions {
+                scene: PathBuf::from("scene.yaml"),
+                output: PathBuf::from("out.png"),
+                width: Some(1920),
+                height: Some(1080),
+                threads: 8,
+                samples: Some(16),
+                integrator: IntegratorChoice::Path,
+                bounces: 4,
+                seed: 7,
+                tone_map: ToneMap {
+                    exposure: -1.5,
+                    gamma: 2.2,
+                    operator: ToneOperator::Aces,
+                },
+                aovs: vec![Aov::Depth, Aov::Albedo],
+                debug: Some(DebugChannel::Traversal),
+                checkpoint: Some(PathBuf::from("out.ckpt")),
+                resume: true,
+                packets: true,
+                stats: false,
+                preview: false,
+                quiet: true,
+            }
+        );
+    }
+
+    #[test]
+    fn output_defaults_to_the_scene_name() {
+        let options = render(&["render", "scenes/cover.yml"]);
+        assert_eq!(options.output, PathBuf::from("scenes/cover.png"));
+        assert_eq!((options.width, options.threads), (None, 0));
+        assert!(options.aovs.is_empty());
+        assert_eq!(options.debug, None);
+    }
+
+    #[test]
+    fn aovs_can_be_repeated_or_all_requested() {
+        let options = render(&["render", "a.yml", "--aov", "normal", "--aov=all"]);
+        assert_eq!(
+            options.aovs,
+            vec![Aov::Normal, Aov::Depth, Aov::Albedo, Aov::ObjectId]
+        );
+    }
+
+    #[test]
+    fn resuming_defaults_to_a_checkpoint_beside_the_output() {
+        let options = render(&["render", "a.yml", "-o", "renders/a.png", "--resume"]);
+        assert_eq!(
+            options.checkpoint,
+            Some(PathBuf::from("renders/a.checkpoint"))
+        );
+        assert_eq!(render(&["render", "a.yml"]).checkpoint, None);
+    }
+
+    #[test]
+    fn help_and_version() {
+        assert_eq!(parse_args(&["--help"]), Ok(Command::Help));
+        assert_eq!(parse_args(&["render", "-h"]), Ok(Command::Help));
+        assert_eq!(parse_args(&["-V"]), Ok(Command::Version));
+    }
+
+    #[test]
+    fn usage_errors() {
+        let error = |args: &[&str]| parse_args(args).unwrap_err().0;
+        assert_eq!(error(&[]), "missing command");
+        assert_eq!(error(&["draw"]), "unknown command 'draw'");
+        assert_eq!(error(&["render"]), "missing scene file");
+        assert_eq!(
+            error(&["render", "a.yml", "b.yml"]),
+            "unexpected argument 'b.yml'"
+        );
+        assert_eq!(
+            error(&["render", "a.yml", "--fast"]),
+            "unknown option '--fast'"
+        );
+        assert_eq!(error(&["render", "a.yml", "-o"]), "'-o' needs a value");
+        assert_eq!(
+            error(&["render", "a.yml", "--width", "wide"]),
+            "'--width' expects a whole number, found 'wide'"
+        );
+        assert_eq!(
+            error(&["render", "a.yml", "--samples", "0"]),
+            "'--samples' must be at least 1"
+        );
+        assert_eq!(
+            error(&["render", "a.yml", "--integrator", "photon"]),
+            "unknown integrator 'photon'"
+        );
+        assert_eq!(
+            error(&["render", "a.yml", "--debug", "albedo"]),
+            "unknown debug channel 'albedo'"
+        );
+        assert_eq!(
+            error(&["render", "a.yml", "--tonemap", "filmic"]),
+            "unknown tone map 'filmic'"
+        );
+        assert_eq!(
+            error(&["render", "a.yml", "--exposure", "bright"]),
+            "'--exposure' expects a number, found 'bright'"
+        );
+        assert_eq!(
+            error(&["render", "a.yml", "--aov", "depth,motion"]),
+            "unknown AOV 'motion'"
+        );
+        assert_eq!(
+            error(&["render", "a.yml", "--gamma", "0"]),
+            "'--gamma' must be positive"
+        );
+        assert_eq!(
+            error(&["render", "a.yml", "--preview", "--resume"]),
+            "'--preview' cannot be combined with checkpoints"
+        );
+        assert_eq!(
+            error(&["render", "a.yml", "--stats", "--checkpoint", "a.ckpt"]),
+            "'--stats' cannot be combined with '--preview' or checkpoints"
+        );
+    }
+}
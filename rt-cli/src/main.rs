@@ -0,0 +1,362 @@
+mod args;
+#[cfg(feature = "preview")]
+mod preview;
+
+use args::{Command, IntegratorChoice, RenderOptions, USAGE};
+use rt_core::{Canvas, ToneMap};
+use rt_io::{save_render_output, Scene};
+use rt_render::{
+    Camera, Checkpoint, Integrator, RenderMode, RenderOutput, RenderProgress, RenderSettings,
+    Sampling, World,
+};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+const EXIT_FAILURE: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_INTERRUPTED: u8 = 130;
+const DEFAULT_PATH_SAMPLES: usize = 16;
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+enum Failure {
+    Error(String),
+    Interrupted(PathBuf),
+}
+
+impl From<String> for Failure {
+    fn from(message: String) -> Self {
+        Failure::Error(message)
+    }
+}
+
+fn main() -> ExitCode {
+    match args::parse(std::env::args().skip(1)) {
+        Ok(Command::Render(options)) => match render(&options) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(Failure::Error(message)) => {
+                eprintln!("error: {}", message);
+                ExitCode::from(EXIT_FAILURE)
+            }
+            Err(Failure::Interrupted(checkpoint)) => {
+                eprintln!(
+                    "interrupted; progress saved to {}, continue with --resume",
+                    checkpoint.display()
+                );
+                ExitCode::from(EXIT_INTERRUPTED)
+            }
+        },
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        Ok(Command::Version) => {
+            println!("raytracer {}", env!("CARGO_PKG_VERSION"));
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            ExitCode::from(EXIT_USAGE)
+        }
+    }
+}
+
+fn render(options: &RenderOptions) -> Result<(), Failure> {
+    let progress = |message: String| {
+        if !options.quiet {
+            eprintln!("{}", message);
+        }
+    };
+
+    progress(format!("loading {}", options.scene.display()));
+    let source = std::fs::read_to_string(&options.scene)
+        .map_err(|error| format!("{}: {}", options.scene.display(), error))?;
+    let Scene {
+        mut camera,
+        mut world,
+    } = Scene::parse_at(&source, &options.scene)
+        .map_err(|error| format!("{}: {}", options.scene.display(), error))?;
+
+    let width = options.width.unwrap_or_else(|| camera.hsize());
+    let height = options.height.unwrap_or_else(|| camera.vsize());
+    camera.set_size(width, height);
+    match options.integrator {
+        IntegratorChoice::Whitted => {
+            if let Some(samples) = options.samples {
+                camera.set_sampling(sampling_for(samples));
+            }
+        }
+        IntegratorChoice::Path => camera.set_settings(RenderSettings {
+            integrator: Integrator::PathTracer {
+                samples_per_pixel: options.samples.unwrap_or(DEFAULT_PATH_SAMPLES),
+                max_bounces: options.bounces,
+            },
+            ..camera.settings()
+        }),
+    }
+    camera.set_settings(RenderSettings {
+        mode: options.debug.map_or(RenderMode::Shaded, RenderMode::Debug),
+        packets: options.packets,
+        seed: options.seed,
+        ..camera.settings()
+    });
+    world.set_seed(options.seed);
+
+    progress(format!(
+        "rendering {}x{}, {}",
+        width,
+        height,
+        describe(&camera)
+    ));
+    let started = Instant::now();
+    let live = !options.quiet && std::io::stderr().is_terminal();
+    let show_progress = |update: &RenderProgress| {
+        if live {
+            let mut stderr = std::io::stderr().lock();
+            let _ = write!(stderr, "\r{}", progress_line(update));
+            if update.is_complete() {
+                let _ = writeln!(stderr);
+            }
+        }
+    };
+    let mut render_stats = None;
+    let canvas = if options.preview {
+        render_in_window(options, &camera, &world)?
+    } else if let Some(path) = &options.checkpoint {
+        let fingerprint = fingerprint(source.as_bytes(), &camera);
+        let mut checkpoint = if options.resume {
+            let checkpoint = resume_from(path, &camera, fingerprint)?;
+            progress(format!(
+                "resuming from {} with {} tile(s) done",
+                path.display(),
+                checkpoint.completed_tiles()
+            ));
+            checkpoint
+        } else {
+            Checkpoint::new(&camera, fingerprint)
+        };
+        render_with_checkpoint(
+            options,
+            &camera,
+            &world,
+            &mut checkpoint,
+            path,
+            show_progress,
+        )?;
+        checkpoint.into_canvas()
+    } else if options.stats {
+        let (canvas, stats) =
+            camera.render_with_stats(&world, options.threads, |update| show_progress(&update));
+        render_stats = Some(stats);
+        canvas
+    } else {
+        camera.render_with_progress(&world, options.threads, |update| show_progress(&update))
+    };
+    progress(format!(
+        "rendered in {:.2}s",
+        started.elapsed().as_secs_f64()
+    ));
+    if let Some(stats) = render_stats {
+        eprint!("{}", stats);
+    }
+
+    let canvas = if options.tone_map == ToneMap::default() {
+        canvas
+    } else {
+        canvas.tone_mapped(&options.tone_map)
+    };
+    let output = RenderOutput {
+        beauty: canvas,
+        aovs: camera.render_aovs(&world, &options.aovs),
+    };
+    let written = save_render_output(&output, &options.output)
+        .map_err(|error| format!("{}: {}", options.output.display(), error))?;
+    for path in written {
+        progress(format!("wrote {}", path.display()));
+    }
+    Ok(())
+}
+
+fn render_with_checkpoint(
+    options: &RenderOptions,
+    camera: &Camera,
+    world: &World,
+    checkpoint: &mut Checkpoint,
+    path: &Path,
+    show_progress: impl Fn(&RenderProgress) + Sync,
+) -> Result<(), Failure> {
+    ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::Relaxed))
+        .map_err(|error| format!("cannot install the interrupt handler: {}", error))?;
+    let save = |checkpoint: &Checkpoint| {
+        checkpoint
+            .save(path)
+            .map_err(|error| format!("{}: {}", path.display(), error))
+    };
+
+    let mut last_save = Instant::now();
+    let mut save_error = None;
+    let complete = camera.render_into(
+        world,
+        options.threads,
+        checkpoint,
+        &INTERRUPTED,
+        |update, state| {
+            show_progress(&update);
+            if last_save.elapsed() >= CHECKPOINT_INTERVAL && !update.is_complete() {
+                if let Err(error) = save(state) {
+                    save_error.get_or_insert(error);
+                }
+                last_save = Instant::now();
+            }
+        },
+    );
+
+    if complete {
+        match std::fs::remove_file(path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+                eprintln!("warning: cannot remove {}: {}", path.display(), error)
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+    if let Some(error) = save_error {
+        eprintln!("warning: {}", error);
+    }
+    save(checkpoint)?;
+    Err(Failure::Interrupted(path.to_path_buf()))
+}
+
+fn resume_from(path: &Path, camera: &Camera, fingerprint: u64) -> Result<Checkpoint, String> {
+    let checkpoint =
+        Checkpoint::load(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+    if checkpoint.fingerprint() != fingerprint || !checkpoint.matches(camera) {
+        return Err(format!(
+            "{} was saved for a different scene or settings",
+            path.display()
+        ));
+    }
+    Ok(checkpoint)
+}
+
+fn fingerprint(source: &[u8], camera: &Camera) -> u64 {
+    let settings = format!("{:?}", camera);
+    source
+        .iter()
+        .chain(settings.as_bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+#[cfg(feature = "preview")]
+fn render_in_window(
+    options: &RenderOptions,
+    camera: &Camera,
+    world: &World,
+) -> Result<Canvas, String> {
+    preview::show(camera, world, options.threads, &options.output)?
+        .ok_or_else(|| "render cancelled".to_string())
+}
+
+#[cfg(not(feature = "preview"))]
+fn render_in_window(
+    _options: &RenderOptions,
+    _camera: &Camera,
+    _world: &World,
+) -> Result<Canvas, String> {
+    Err("this build has no preview window; rebuild with `--features preview`".into())
+}
+
+fn sampling_for(samples: usize) -> Sampling {
+    let per_axis = (samples as f64).sqrt().ceil() as usize;
+    if per_axis <= 1 {
+        Sampling::Single
+    } else {
+        Sampling::Jittered(per_axis)
+    }
+}
+
+fn progress_line(progress: &RenderProgress) -> String {
+    let eta = progress.eta().map_or_else(|| "--".into(), seconds);
+    format!(
+        "{:5.1}% {}/{} tiles, {} elapsed, ETA {}   ",
+        progress.fraction() * 100.0,
+        progress.completed_tiles,
+        progress.total_tiles,
+        seconds(progress.elapsed),
+        eta
+    )
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.1}s", duration.as_secs_f64())
+}
+
+fn describe(camera: &Camera) -> String {
+    if let RenderMode::Debug(channel) = camera.settings().mode {
+        return format!("{} debug channel", channel.name());
+    }
+    if let Integrator::PathTracer {
+        samples_per_pixel,
+        max_bounces,
+    } = camera.settings().integrator
+    {
+        return format!(
+            "path tracing {} samples per pixel, up to {} bounces",
+            samples_per_pixel, max_bounces
+        );
+    }
+    match camera.sampling() {
+        Sampling::Single => "1 sample per pixel".into(),
+        Sampling::Grid(n) | Sampling::Jittered(n) => format!("{} samples per pixel", n * n),
+        Sampling::Adaptive { max_depth, .. } => format!("adaptive sampling to depth {}", max_depth),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_round_up_to_a_square_grid() {
+        assert_eq!(sampling_for(1), Sampling::Single);
+        assert_eq!(sampling_for(16), Sampling::Jittered(4));
+        assert_eq!(sampling_for(10), Sampling::Jittered(4));
+    }
+
+    #[test]
+    fn fingerprints_cover_the_scene_and_camera() {
+        let camera = Camera::new(10, 10, 1.0);
+        let base = fingerprint(b"- add: sphere", &camera);
+        assert_eq!(base, fingerprint(b"- add: sphere", &camera));
+        assert_ne!(base, fingerprint(b"- add: cube", &camera));
+        assert_ne!(
+            base,
+            fingerprint(b"- add: sphere", &Camera::new(10, 11, 1.0))
+        );
+    }
+
+    #[test]
+    fn progress_lines_show_percentage_and_eta() {
+        let progress = RenderProgress {
+            tile: rt_render::Tile {
+                x: 0,
+                y: 0,
+                width: 16,
+                height: 16,
+            },
+            completed_tiles: 25,
+            total_tiles: 100,
+            elapsed: Duration::from_secs(3),
+        };
+        assert_eq!(
+            progress_line(&progress),
+            " 25.0% 25/100 tiles, 3.0s elapsed, ETA 9.0s   "
+        );
+    }
+}
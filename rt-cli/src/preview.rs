@@ -0,0 +1,153 @@
+use minifb::{Key, KeyRepeat, Window, WindowOptions};
+use rt_core::{Canvas, Color, Scalar};
+use rt_io::save_canvas;
+use rt_render::{Camera, RenderProgress, Sampling, World};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+
+const FRAMES_PER_SECOND: usize = 30;
+
+enum Update {
+    Tile(RenderProgress, Vec<Color>),
+    PassDone,
+}
+
+pub fn show(
+    camera: &Camera,
+    world: &World,
+    threads: usize,
+    output: &Path,
+) -> Result<Option<Canvas>, String> {
+    let (width, height) = (camera.hsize(), camera.vsize());
+    let mut window = Window::new(
+        "raytracer preview",
+        width,
+        height,
+        WindowOptions {
+            resize: true,
+            ..WindowOptions::default()
+        },
+    )
+    .map_err(|error| format!("cannot open preview window: {}", error))?;
+    window.set_target_fps(FRAMES_PER_SECOND);
+
+    let passes = refinement_passes(camera.sampling());
+    let cancel = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel();
+    let mut canvas = Canvas::new(width, height);
+    let mut buffer = vec![0u32; width * height];
+
+    let finished = std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for &sampling in &passes {
+                let mut pass = camera.clone();
+                pass.set_sampling(sampling);
+                let tiles = pass.tiles();
+                let completed = pass.render_tiles(world, threads, &tiles, &cancel, |p, colors| {
+                    let _ = sender.send(Update::Tile(p, colors));
+                });
+                if completed < tiles.len() || sender.send(Update::PassDone).is_err() {
+                    return;
+                }
+            }
+        });
+
+        let mut passes_done = 0;
+        let mut fraction = 0.0;
+        let mut failure = None;
+        while window.is_open() && !window.is_key_down(Key::Escape) {
+            for update in receiver.try_iter() {
+                match update {
+                    Update::Tile(progress, colors) => {
+                        for ((x, y), color) in progress.tile.pixels().zip(colors) {
+                            canvas.write_pixel(x, y, color);
+                            buffer[y * width + x] = pack(color);
+                        }
+                        fraction = progress.fraction();
+                    }
+                    Update::PassDone => passes_done += 1,
+                }
+            }
+            if window.is_key_pressed(Key::S, KeyRepeat::No) {
+                match save_canvas(&canvas, output) {
+                    Ok(()) => eprintln!("saved {}", output.display()),
+                    Err(error) => eprintln!("cannot save {}: {}", output.display(), error),
+                }
+            }
+            window.set_title(&title(passes_done, passes.len(), fraction));
+            if let Err(error) = window.update_with_buffer(&buffer, width, height) {
+                failure = Some(format!("preview window failed: {}", error));
+                break;
+            }
+        }
+        cancel.store(true, Ordering::Relaxed);
+        match failure {
+            Some(message) => Err(message),
+            None => Ok(passes_done == passes.len()),
+        }
+    })?;
+
+    Ok(if finished { Some(canvas) } else { None })
+}
+
+fn refinement_passes(target: Sampling) -> Vec<Sampling> {
+    let mut passes = vec![Sampling::Single];
+    if let Sampling::Grid(n) | Sampling::Jittered(n) = target {
+        let mut per_axis = 2;
+        while per_axis < n {
+            passes.push(Sampling::Jittered(per_axis));
+            per_axis *= 2;
+        }
+    }
+    if target != Sampling::Single {
+        passes.push(target);
+    }
+    passes
+}
+
+fn title(passes_done: usize, passes: usize, fraction: f64) -> String {
+    if passes_done == passes {
+        "raytracer preview - done (S to save, Esc to close)".into()
+    } else {
+        format!(
+            "raytracer preview - pass {}/{} {:.0}% (S to save, Esc to cancel)",
+            passes_done + 1,
+            passes,
+            fraction * 100.0
+        )
+    }
+}
+
+fn pack(color: Color) -> u32 {
+    let channel = |value: Scalar| (value.clamp(0.0, 1.0) * 255.0).round() as u32;
+    channel(color.red()) << 16 | channel(color.green()) << 8 | channel(color.blue())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes_refine_up_to_the_target_sampling() {
+        assert_eq!(refinement_passes(Sampling::Single), vec![Sampling::Single]);
+        assert_eq!(
+            refinement_passes(Sampling::Jittered(4)),
+            vec![
+                Sampling::Single,
+                Sampling::Jittered(2),
+                Sampling::Jittered(4)
+            ]
+        );
+        assert_eq!(
+            refinement_passes(Sampling::Grid(3)),
+            vec![Sampling::Single, Sampling::Jittered(2), Sampling::Grid(3)]
+        );
+    }
+
+    #[test]
+    fn colors_pack_into_0rgb() {
+        assert_eq!(pack(Color::new(1.0, 0.5, 0.0)), 0x00ff_8000);
+        assert_eq!(pack(Color::new(2.0, -1.0, 1.0)), 0x00ff_00ff);
+    }
+}
@@ -0,0 +1,114 @@
+use rt_core::{Matrix, Scalar, Tuple, EPSILON};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const BATCH: usize = 4096;
+const ROUNDS: usize = 2_000;
+
+type Array = [Scalar; 4];
+
+fn time(label: &str, mut f: impl FnMut()) -> Duration {
+    f();
+    let started = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    let elapsed = started.elapsed();
+    println!(
+        "{:<24} {:>8.2} ns/op",
+        label,
+        elapsed.as_nanos() as f64 / (ROUNDS * BATCH) as f64
+    );
+    elapsed
+}
+
+fn compare(name: &str, reference: impl FnMut(), library: impl FnMut()) {
+    let before = time(&format!("{} (loop)", name), reference);
+    let after = time(&format!("{} (Tuple)", name), library);
+    println!(
+        "{:<24} {:>8.2}x",
+        "",
+        before.as_secs_f64() / after.as_secs_f64()
+    );
+}
+
+fn assert_close(a: &Array, b: &Tuple<4>) {
+    for (i, value) in a.iter().enumerate() {
+        assert!((value - b[i]).abs() < EPSILON, "{:?} vs {:?}", a, b);
+    }
+}
+
+fn main() {
+    let rows: [Array; 4] = [
+        [0.8, -0.6, 0.0, 1.0],
+        [0.6, 0.8, 0.0, 2.0],
+        [0.0, 0.0, 1.0, -3.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ];
+    let matrix = Matrix::from(rows);
+    let other: Array = [0.25, -1.5, 2.0, 0.5];
+    let tuple = Tuple::from(other);
+
+    let arrays: Vec<Array> = (0..BATCH)
+        .map(|i| {
+            let x = (i % 97) as Scalar;
+            [x, x * 0.5, -x, 1.0]
+        })
+        .collect();
+    let tuples: Vec<Tuple<4>> = arrays.iter().map(|&a| Tuple::from(a)).collect();
+
+    let add = |a: &Array| -> Array { std::array::from_fn(|i| a[i] + other[i]) };
+    let sub = |a: &Array| -> Array { std::array::from_fn(|i| a[i] - other[i]) };
+    let mul = |a: &Array| -> Array { std::array::from_fn(|i| a[i] * other[i]) };
+    let dot = |a: &Array| (0..4).map(|i| a[i] * other[i]).sum::<Scalar>();
+    let transform = |a: &Array| rows.map(|row| (0..4).map(|i| row[i] * a[i]).sum::<Scalar>());
+
+    for (a, t) in arrays.iter().zip(&tuples) {
+        assert_close(&add(a), &(t + &tuple));
+        assert_close(&sub(a), &(t - &tuple));
+        assert_close(&mul(a), &(t * &tuple));
+        assert!((dot(a) - t.dot(&tuple)).abs() < EPSILON);
+        assert_close(&transform(a), &(matrix * *t));
+    }
+
+    let mut arrays_out = vec![[0.0; 4]; BATCH];
+    let mut tuples_out = vec![Tuple::from([0.0; 4]); BATCH];
+    macro_rules! compare_map {
+        ($name:expr, $reference:expr, $library:expr) => {
+            compare(
+                $name,
+                || {
+                    for (out, a) in arrays_out.iter_mut().zip(black_box(&arrays)) {
+                        *out = $reference(a);
+                    }
+                    black_box(&arrays_out);
+                },
+                || {
+                    for (out, t) in tuples_out.iter_mut().zip(black_box(&tuples)) {
+                        *out = $library(t);
+                    }
+                    black_box(&tuples_out);
+                },
+            )
+        };
+    }
+
+    compare_map!("add", add, |t: &Tuple<4>| t + &tuple);
+    compare_map!("sub", sub, |t: &Tuple<4>| t - &tuple);
+    compare_map!("mul", mul, |t: &Tuple<4>| t * &tuple);
+    compare_map!("matrix x tuple", transform, |t: &Tuple<4>| matrix * *t);
+    compare(
+        "dot",
+        || {
+            black_box(black_box(&arrays).iter().map(dot).sum::<Scalar>());
+        },
+        || {
+            black_box(
+                black_box(&tuples)
+                    .iter()
+                    .map(|t| t.dot(&tuple))
+                    .sum::<Scalar>(),
+            );
+        },
+    );
+}
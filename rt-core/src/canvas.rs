@@ -0,0 +1,174 @@
+use crate::Color;
+
+const PPM_LINE_LENGTH: usize = 70;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: vec![Color::black(); width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixel_at(&self, x: usize, y: usize) -> Color {
+        self.pixels[self.index(x, y)]
+    }
+
+    pub fn write_pixel(&mut self, x: usize, y: usize, color: Color) {
+        let index = self.index(x, y);
+        self.pixels[index] = color;
+    }
+
+    pub fn to_rgb8(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|c| [c.red(), c.green(), c.blue()])
+            .map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8)
+            .collect()
+    }
+
+    pub fn to_ppm(&self) -> String {
+        let mut ppm = format!("P3\n{} {}\n255\n", self.width, self.height);
+        let bytes = self.to_rgb8();
+        for row in bytes.chunks((self.width * 3).max(1)) {
+            let mut line = String::new();
+            for value in row.iter().map(|value| value.to_string()) {
+                if line.len() + 1 + value.len() > PPM_LINE_LENGTH {
+                    ppm.push_str(&line);
+                    ppm.push('\n');
+                    line.clear();
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(&value);
+            }
+            ppm.push_str(&line);
+            ppm.push('\n');
+        }
+        ppm
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        assert!(
+            x < self.width && y < self.height,
+            "Pixel out of bounds: the canvas is {}x{} but the pixel is ({}, {})",
+            self.width,
+            self.height,
+            x,
+            y
+        );
+        y * self.width + x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creating_a_canvas() {
+        let c = Canvas::new(10, 20);
+        assert_eq!(c.width(), 10);
+        assert_eq!(c.height(), 20);
+        for y in 0..20 {
+            for x in 0..10 {
+                assert_eq!(c.pixel_at(x, y), Color::black());
+            }
+        }
+    }
+
+    #[test]
+    fn writing_pixels() {
+        let mut c = Canvas::new(10, 20);
+        let red = Color::new(1.0, 0.0, 0.0);
+        c.write_pixel(2, 3, red);
+        assert_eq!(c.pixel_at(2, 3), red);
+        assert_eq!(c.pixel_at(3, 2), Color::black());
+    }
+
+    #[test]
+    fn rgb8_bytes_are_row_major() {
+        let mut c = Canvas::new(2, 1);
+        c.write_pixel(1, 0, Color::new(1.0, 0.5, 2.0));
+        assert_eq!(c.to_rgb8(), vec![0, 0, 0, 255, 128, 255]);
+    }
+
+    mod ppm {
+        use super::*;
+
+        #[test]
+        fn header() {
+            let ppm = Canvas::new(5, 3).to_ppm();
+            let header: Vec<&str> = ppm.lines().take(3).collect();
+            assert_eq!(header, vec!["P3", "5 3", "255"]);
+        }
+
+        #[test]
+        fn pixel_data_is_clamped_and_scaled() {
+            let mut c = Canvas::new(5, 3);
+            c.write_pixel(0, 0, Color::new(1.5, 0.0, 0.0));
+            c.write_pixel(2, 1, Color::new(0.0, 0.5, 0.0));
+            c.write_pixel(4, 2, Color::new(-0.5, 0.0, 1.0));
+            let ppm = c.to_ppm();
+            let data: Vec<&str> = ppm.lines().skip(3).collect();
+            assert_eq!(
+                data,
+                vec![
+                    "255 0 0 0 0 0 0 0 0 0 0 0 0 0 0",
+                    "0 0 0 0 0 0 0 128 0 0 0 0 0 0 0",
+                    "0 0 0 0 0 0 0 0 0 0 0 0 0 0 255",
+                ]
+            );
+        }
+
+        #[test]
+        fn long_lines_are_split() {
+            let mut c = Canvas::new(10, 2);
+            for y in 0..2 {
+                for x in 0..10 {
+                    c.write_pixel(x, y, Color::new(1.0, 0.8, 0.6));
+                }
+            }
+            let ppm = c.to_ppm();
+            let data: Vec<&str> = ppm.lines().skip(3).collect();
+            assert_eq!(
+                data,
+                vec![
+                    "255 204 153 255 204 153 255 204 153 255 204 153 255 204 153 255 204",
+                    "153 255 204 153 255 204 153 255 204 153 255 204 153",
+                    "255 204 153 255 204 153 255 204 153 255 204 153 255 204 153 255 204",
+                    "153 255 204 153 255 204 153 255 204 153 255 204 153",
+                ]
+            );
+            assert!(ppm.lines().all(|line| line.len() <= PPM_LINE_LENGTH));
+        }
+
+        #[test]
+        fn ends_with_a_newline() {
+            assert!(Canvas::new(5, 3).to_ppm().ends_with('\n'));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Pixel out of bounds")]
+    fn writing_outside_the_canvas() {
+        Canvas::new(10, 20).write_pixel(10, 0, Color::white());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{Scalar, Tuple};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    tuple: Tuple<3>,
+}
+
+impl Color {
+    pub fn new(red: Scalar, green: Scalar, blue: Scalar) -> Self {
+        Color {
+            tuple: Tuple::from([red, green, blue]),
+        }
+    }
+
+    pub fn black() -> Self {
+        Color::new(0.0, 0.0, 0.0)
+    }
+
+    pub fn white() -> Self {
+        Color::new(1.0, 1.0, 1.0)
+    }
+
+    pub fn red(&self) -> Scalar {
+        self.tuple[0]
+    }
+
+    pub fn green(&self) -> Scalar {
+        self.tuple[1]
+    }
+
+    pub fn blue(&self) -> Scalar {
+        self.tuple[2]
+    }
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Color::black()
+    }
+}
+
+macro_rules! implement_color_operations {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl std::ops::$trait<Color> for Color {
+            type Output = Color;
+
+            fn $method(self, other: Color) -> Self::Output {
+                Color {
+                    tuple: &self.tuple $op &other.tuple,
+                }
+            }
+        }
+    };
+
+    ($trait:ident, $method:ident, $op:tt, $scalar:ty) => {
+        impl std::ops::$trait<$scalar> for Color {
+            type Output = Color;
+
+            fn $method(self, scalar: $scalar) -> Self::Output {
+                Color {
+                    tuple: &self.tuple $op scalar,
+                }
+            }
+        }
+    };
+}
+
+implement_color_operations!(Add, add, +);
+implement_color_operations!(Sub, sub, -);
+implement_color_operations!(Mul, mul, *);
+implement_color_operations!(Mul, mul, *, Scalar);
+implement_color_operations!(Div, div, /, Scalar);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_are_red_green_blue_tuples() {
+        let c = Color::new(-0.5, 0.4, 1.7);
+        assert_eq!(c.red(), -0.5);
+        assert_eq!(c.green(), 0.4);
+        assert_eq!(c.blue(), 1.7);
+    }
+
+    mod arithmetic {
+        use super::*;
+
+        #[test]
+        fn adding_colors() {
+            assert_eq!(
+                Color::new(0.9, 0.6, 0.75) + Color::new(0.7, 0.1, 0.25),
+                Color::new(1.6, 0.7, 1.0)
+            );
+        }
+
+        #[test]
+        fn subtracting_colors() {
+            assert_eq!(
+                Color::new(0.9, 0.6, 0.75) - Color::new(0.7, 0.1, 0.25),
+                Color::new(0.2, 0.5, 0.5)
+            );
+        }
+
+        #[test]
+        fn multiplying_by_a_scalar() {
+            assert_eq!(Color::new(0.2, 0.3, 0.4) * 2.0, Color::new(0.4, 0.6, 0.8));
+            assert_eq!(Color::new(0.2, 0.3, 0.4) / 2.0, Color::new(0.1, 0.15, 0.2));
+        }
+
+        #[test]
+        fn multiplying_colors() {
+            assert_eq!(
+                Color::new(1.0, 0.2, 0.4) * Color::new(0.9, 1.0, 0.1),
+                Color::new(0.9, 0.2, 0.04)
+            );
+        }
+    }
+}
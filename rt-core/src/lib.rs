@@ -1 +1,26 @@
+mod canvas;
+mod color;
+mod matrix;
+mod noise;
+mod point;
+mod ray;
+mod roots;
+mod scalar;
+#[cfg(feature = "simd")]
+mod simd;
+mod tone_map;
+mod transform;
 mod tuple;
+mod vector;
+
+pub use canvas::Canvas;
+pub use color::Color;
+pub use matrix::Matrix;
+pub use noise::Perlin;
+pub use point::Point;
+pub use ray::Ray;
+pub use roots::{solve_cubic, solve_quadratic, solve_quartic};
+pub use scalar::{consts, from_f64, to_bits, to_f64, Scalar, EPSILON};
+pub use tone_map::{ToneMap, ToneOperator};
+pub use tuple::Tuple;
+pub use vector::Vector;
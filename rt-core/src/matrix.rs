@@ -0,0 +1,503 @@
+use crate::{Point, Scalar, Tuple, Vector};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Matrix<const R: usize, const C: usize> {
+    data: [[Scalar; C]; R],
+}
+
+impl<const R: usize, const C: usize> Matrix<R, C> {
+    pub fn transpose(&self) -> Matrix<C, R> {
+        Matrix::from(std::array::from_fn(|row| {
+            std::array::from_fn(|col| self.data[col][row])
+        }))
+    }
+}
+
+impl<const N: usize> Matrix<N, N> {
+    pub fn identity() -> Self {
+        Matrix::from(std::array::from_fn(|row| {
+            std::array::from_fn(|col| if row == col { 1.0 } else { 0.0 })
+        }))
+    }
+}
+
+impl Matrix<2, 2> {
+    pub fn determinant(&self) -> Scalar {
+        self.data[0][0] * self.data[1][1] - self.data[0][1] * self.data[1][0]
+    }
+}
+
+macro_rules! implement_cofactors {
+    ($n:literal, $sub:literal) => {
+        impl Matrix<$n, $n> {
+            pub fn submatrix(&self, row: usize, col: usize) -> Matrix<$sub, $sub> {
+                Matrix::from(std::array::from_fn(|r| {
+                    let r = if r < row { r } else { r + 1 };
+                    std::array::from_fn(|c| {
+                        let c = if c < col { c } else { c + 1 };
+                        self.data[r][c]
+                    })
+                }))
+            }
+
+            pub fn minor(&self, row: usize, col: usize) -> Scalar {
+                self.submatrix(row, col).determinant()
+            }
+
+            pub fn cofactor(&self, row: usize, col: usize) -> Scalar {
+                let minor = self.minor(row, col);
+                if (row + col) % 2 == 0 {
+                    minor
+                } else {
+                    -minor
+                }
+            }
+
+            pub fn determinant(&self) -> Scalar {
+                (0..$n)
+                    .map(|col| self.data[0][col] * self.cofactor(0, col))
+                    .sum()
+            }
+
+            pub fn is_invertible(&self) -> bool {
+                self.determinant() != 0.0
+            }
+
+            pub fn inverse(&self) -> Option<Self> {
+                let determinant = self.determinant();
+                if determinant == 0.0 {
+                    return None;
+                }
+                Some(Matrix::from(std::array::from_fn(|row| {
+                    std::array::from_fn(|col| self.cofactor(col, row) / determinant)
+                })))
+            }
+        }
+    };
+}
+
+implement_cofactors!(3, 2);
+implement_cofactors!(4, 3);
+
+impl<const R: usize, const C: usize> From<[[Scalar; C]; R]> for Matrix<R, C> {
+    fn from(data: [[Scalar; C]; R]) -> Self {
+        Matrix { data }
+    }
+}
+
+impl<const R: usize, const C: usize> std::ops::Index<usize> for Matrix<R, C> {
+    type Output = [Scalar; C];
+
+    fn index(&self, row: usize) -> &Self::Output {
+        &self.data[row]
+    }
+}
+
+impl<const R: usize, const C: usize> std::ops::IndexMut<usize> for Matrix<R, C> {
+    fn index_mut(&mut self, row: usize) -> &mut Self::Output {
+        &mut self.data[row]
+    }
+}
+
+impl<const R: usize, const C: usize> std::cmp::PartialEq for Matrix<R, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.data
+            .iter()
+            .flatten()
+            .zip(other.data.iter().flatten())
+            .all(|(a, b)| (a - b).abs() < crate::EPSILON)
+    }
+}
+
+impl<const R: usize, const K: usize, const C: usize> std::ops::Mul<Matrix<K, C>> for Matrix<R, K> {
+    type Output = Matrix<R, C>;
+
+    fn mul(self, other: Matrix<K, C>) -> Self::Output {
+        Matrix::from(std::array::from_fn(|row| {
+            std::array::from_fn(|col| (0..K).map(|k| self.data[row][k] * other.data[k][col]).sum())
+        }))
+    }
+}
+
+impl<const R: usize, const C: usize> std::ops::Mul<Tuple<C>> for Matrix<R, C> {
+    type Output = Tuple<R>;
+
+    fn mul(self, tuple: Tuple<C>) -> Self::Output {
+        #[cfg(feature = "simd")]
+        if R == 4 && C == 4 {
+            let rows = std::array::from_fn(|row| std::array::from_fn(|col| self.data[row][col]));
+            let column = std::array::from_fn(|col| tuple[col]);
+            let result = crate::simd::transform(&rows, &column);
+            return Tuple::from(std::array::from_fn(|row| result[row]));
+        }
+        Tuple::from(std::array::from_fn(|row| {
+            (0..C).map(|col| self.data[row][col] * tuple[col]).sum()
+        }))
+    }
+}
+
+impl std::ops::Mul<Point> for Matrix<4, 4> {
+    type Output = Point;
+
+    fn mul(self, point: Point) -> Self::Output {
+        let t = self * point.tuple;
+        Point::new(t[0], t[1], t[2])
+    }
+}
+
+impl std::ops::Mul<Vector> for Matrix<4, 4> {
+    type Output = Vector;
+
+    fn mul(self, vector: Vector) -> Self::Output {
+        let t = self * vector.tuple;
+        Vector::new(t[0], t[1], t[2])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod creation {
+        use super::*;
+
+        #[test]
+        fn four_by_four() {
+            let m = Matrix::from([
+                [1.0, 2.0, 3.0, 4.0],
+                [5.5, 6.5, 7.5, 8.5],
+                [9.0, 10.0, 11.0, 12.0],
+                [13.5, 14.5, 15.5, 16.5],
+            ]);
+            assert_eq!(m[0][0], 1.0);
+            assert_eq!(m[0][3], 4.0);
+            assert_eq!(m[1][0], 5.5);
+            assert_eq!(m[1][2], 7.5);
+            assert_eq!(m[2][2], 11.0);
+            assert_eq!(m[3][0], 13.5);
+            assert_eq!(m[3][2], 15.5);
+        }
+
+        #[test]
+        fn two_by_two_and_three_by_three() {
+            let m = Matrix::from([[-3.0, 5.0], [1.0, -2.0]]);
+            assert_eq!(m[0][0], -3.0);
+            assert_eq!(m[1][1], -2.0);
+
+            let m = Matrix::from([[-3.0, 5.0, 0.0], [1.0, -2.0, -7.0], [0.0, 1.0, 1.0]]);
+            assert_eq!(m[1][1], -2.0);
+            assert_eq!(m[2][2], 1.0);
+        }
+
+        #[test]
+        fn identity() {
+            assert_eq!(
+                Matrix::<3, 3>::identity(),
+                Matrix::from([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+            );
+        }
+    }
+
+    mod comparison {
+        use super::*;
+
+        #[test]
+        fn identical_matrices() {
+            let a = Matrix::from([[1.0, 2.0], [3.0, 4.0]]);
+            let b = Matrix::from([[1.0, 2.0], [3.0, 4.000001]]);
+            assert_eq!(a, b);
+        }
+
+        #[test]
+        fn different_matrices() {
+            let a = Matrix::from([[1.0, 2.0], [3.0, 4.0]]);
+            let b = Matrix::from([[2.0, 3.0], [4.0, 5.0]]);
+            assert_ne!(a, b);
+        }
+    }
+
+    mod multiplication {
+        use super::*;
+
+        #[test]
+        fn multiplying_two_matrices() {
+            let a = Matrix::from([
+                [1.0, 2.0, 3.0, 4.0],
+                [5.0, 6.0, 7.0, 8.0],
+                [9.0, 8.0, 7.0, 6.0],
+                [5.0, 4.0, 3.0, 2.0],
+            ]);
+            let b = Matrix::from([
+                [-2.0, 1.0, 2.0, 3.0],
+                [3.0, 2.0, 1.0, -1.0],
+                [4.0, 3.0, 6.0, 5.0],
+                [1.0, 2.0, 7.0, 8.0],
+            ]);
+            assert_eq!(
+                a * b,
+                Matrix::from([
+                    [20.0, 22.0, 50.0, 48.0],
+                    [44.0, 54.0, 114.0, 108.0],
+                    [40.0, 58.0, 110.0, 102.0],
+                    [16.0, 26.0, 46.0, 42.0],
+                ])
+            );
+        }
+
+        #[test]
+        fn multiplying_non_square_matrices() {
+            let a = Matrix::from([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+            let b = Matrix::from([[7.0, 8.0], [9.0, 10.0], [11.0, 12.0]]);
+            assert_eq!(a * b, Matrix::from([[58.0, 64.0], [139.0, 154.0]]));
+        }
+
+        #[test]
+        fn multiplying_by_a_tuple() {
+            let a = Matrix::from([
+                [1.0, 2.0, 3.0, 4.0],
+                [2.0, 4.0, 4.0, 2.0],
+                [8.0, 6.0, 4.0, 1.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]);
+            assert_eq!(
+                a * Tuple::from([1.0, 2.0, 3.0, 1.0]),
+                Tuple::from([18.0, 24.0, 33.0, 1.0])
+            );
+        }
+
+        #[test]
+        fn multiplying_by_a_point_and_a_vector() {
+            let a = Matrix::from([
+                [1.0, 2.0, 3.0, 4.0],
+                [2.0, 4.0, 4.0, 2.0],
+                [8.0, 6.0, 4.0, 1.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]);
+            assert_eq!(a * Point::new(1.0, 2.0, 3.0), Point::new(18.0, 24.0, 33.0));
+            assert_eq!(
+                a * Vector::new(1.0, 2.0, 3.0),
+                Vector::new(14.0, 22.0, 32.0)
+            );
+        }
+
+        #[test]
+        fn multiplying_a_vector_keeps_w_at_zero() {
+            let a = Matrix::from([
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [1.0, 2.0, 3.0, 1.0],
+            ]);
+            assert_eq!((a * Vector::new(1.0, 2.0, 3.0)).w(), 0.0);
+        }
+
+        #[test]
+        fn multiplying_by_the_identity() {
+            let a = Matrix::from([
+                [0.0, 1.0, 2.0, 4.0],
+                [1.0, 2.0, 4.0, 8.0],
+                [2.0, 4.0, 8.0, 16.0],
+                [4.0, 8.0, 16.0, 32.0],
+            ]);
+            assert_eq!(a * Matrix::identity(), a);
+            let t = Tuple::from([1.0, 2.0, 3.0, 4.0]);
+            assert_eq!(Matrix::<4, 4>::identity() * t, t);
+        }
+    }
+
+    mod transposition {
+        use super::*;
+
+        #[test]
+        fn transposing_a_matrix() {
+            let a = Matrix::from([
+                [0.0, 9.0, 3.0, 0.0],
+                [9.0, 8.0, 0.0, 8.0],
+                [1.0, 8.0, 5.0, 3.0],
+                [0.0, 0.0, 5.0, 8.0],
+            ]);
+            assert_eq!(
+                a.transpose(),
+                Matrix::from([
+                    [0.0, 9.0, 1.0, 0.0],
+                    [9.0, 8.0, 8.0, 0.0],
+                    [3.0, 0.0, 5.0, 5.0],
+                    [0.0, 8.0, 3.0, 8.0],
+                ])
+            );
+        }
+
+        #[test]
+        fn transposing_a_non_square_matrix() {
+            let a = Matrix::from([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+            assert_eq!(
+                a.transpose(),
+                Matrix::from([[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]])
+            );
+        }
+
+        #[test]
+        fn transposing_the_identity() {
+            assert_eq!(Matrix::<4, 4>::identity().transpose(), Matrix::identity());
+        }
+    }
+
+    mod determinants {
+        use super::*;
+
+        #[test]
+        fn two_by_two() {
+            assert_eq!(Matrix::from([[1.0, 5.0], [-3.0, 2.0]]).determinant(), 17.0);
+        }
+
+        #[test]
+        fn submatrices() {
+            let a = Matrix::from([[1.0, 5.0, 0.0], [-3.0, 2.0, 7.0], [0.0, 6.0, -3.0]]);
+            assert_eq!(a.submatrix(0, 2), Matrix::from([[-3.0, 2.0], [0.0, 6.0]]));
+
+            let a = Matrix::from([
+                [-6.0, 1.0, 1.0, 6.0],
+                [-8.0, 5.0, 8.0, 6.0],
+                [-1.0, 0.0, 8.0, 2.0],
+                [-7.0, 1.0, -1.0, 1.0],
+            ]);
+            assert_eq!(
+                a.submatrix(2, 1),
+                Matrix::from([[-6.0, 1.0, 6.0], [-8.0, 8.0, 6.0], [-7.0, -1.0, 1.0]])
+            );
+        }
+
+        #[test]
+        fn minors_and_cofactors() {
+            let a = Matrix::from([[3.0, 5.0, 0.0], [2.0, -1.0, -7.0], [6.0, -1.0, 5.0]]);
+            assert_eq!(a.minor(1, 0), 25.0);
+            assert_eq!(a.minor(0, 0), -12.0);
+            assert_eq!(a.cofactor(0, 0), -12.0);
+            assert_eq!(a.cofactor(1, 0), -25.0);
+        }
+
+        #[test]
+        fn three_by_three() {
+            let a = Matrix::from([[1.0, 2.0, 6.0], [-5.0, 8.0, -4.0], [2.0, 6.0, 4.0]]);
+            assert_eq!(a.cofactor(0, 0), 56.0);
+            assert_eq!(a.cofactor(0, 1), 12.0);
+            assert_eq!(a.cofactor(0, 2), -46.0);
+            assert_eq!(a.determinant(), -196.0);
+        }
+
+        #[test]
+        fn four_by_four() {
+            let a = Matrix::from([
+                [-2.0, -8.0, 3.0, 5.0],
+                [-3.0, 1.0, 7.0, 3.0],
+                [1.0, 2.0, -9.0, 6.0],
+                [-6.0, 7.0, 7.0, -9.0],
+            ]);
+            assert_eq!(a.cofactor(0, 0), 690.0);
+            assert_eq!(a.cofactor(0, 1), 447.0);
+            assert_eq!(a.cofactor(0, 2), 210.0);
+            assert_eq!(a.cofactor(0, 3), 51.0);
+            assert_eq!(a.determinant(), -4071.0);
+        }
+    }
+
+    mod inversion {
+        use super::*;
+
+        #[test]
+        fn invertible_matrix() {
+            let a = Matrix::from([
+                [6.0, 4.0, 4.0, 4.0],
+                [5.0, 5.0, 7.0, 6.0],
+                [4.0, -9.0, 3.0, -7.0],
+                [9.0, 1.0, 7.0, -6.0],
+            ]);
+            assert_eq!(a.determinant(), -2120.0);
+            assert!(a.is_invertible());
+        }
+
+        #[test]
+        fn non_invertible_matrix() {
+            let a = Matrix::from([
+                [-4.0, 2.0, -2.0, -3.0],
+                [9.0, 6.0, 2.0, 6.0],
+                [0.0, -5.0, 1.0, -5.0],
+                [0.0, 0.0, 0.0, 0.0],
+            ]);
+            assert_eq!(a.determinant(), 0.0);
+            assert!(!a.is_invertible());
+            assert_eq!(a.inverse(), None);
+        }
+
+        #[test]
+        fn calculating_the_inverse() {
+            let a = Matrix::from([
+                [-5.0, 2.0, 6.0, -8.0],
+                [1.0, -5.0, 1.0, 8.0],
+                [7.0, 7.0, -6.0, -7.0],
+                [1.0, -3.0, 7.0, 4.0],
+            ]);
+            let b = a.inverse().unwrap();
+            assert_eq!(a.determinant(), 532.0);
+            assert_eq!(a.cofactor(2, 3), -160.0);
+            assert_eq!(b[3][2], -160.0 / 532.0);
+            assert_eq!(a.cofactor(3, 2), 105.0);
+            assert_eq!(b[2][3], 105.0 / 532.0);
+            assert_eq!(
+                b,
+                Matrix::from([
+                    [0.21805, 0.45113, 0.24060, -0.04511],
+                    [-0.80827, -1.45677, -0.44361, 0.52068],
+                    [-0.07895, -0.22368, -0.05263, 0.19737],
+                    [-0.52256, -0.81391, -0.30075, 0.30639],
+                ])
+            );
+        }
+
+        #[test]
+        fn inverse_of_another_matrix() {
+            let a = Matrix::from([
+                [9.0, 3.0, 0.0, 9.0],
+                [-5.0, -2.0, -6.0, -3.0],
+                [-4.0, 9.0, 6.0, 4.0],
+                [-7.0, 6.0, 6.0, 2.0],
+            ]);
+            assert_eq!(
+                a.inverse().unwrap(),
+                Matrix::from([
+                    [-0.04074, -0.07778, 0.14444, -0.22222],
+                    [-0.07778, 0.03333, 0.36667, -0.33333],
+                    [-0.02901, -0.14630, -0.10926, 0.12963],
+                    [0.17778, 0.06667, -0.26667, 0.33333],
+                ])
+            );
+        }
+
+        #[test]
+        fn multiplying_a_product_by_its_inverse() {
+            let a = Matrix::from([
+                [3.0, -9.0, 7.0, 3.0],
+                [3.0, -8.0, 2.0, -9.0],
+                [-4.0, 4.0, 4.0, 1.0],
+                [-6.0, 5.0, -1.0, 1.0],
+            ]);
+            let b = Matrix::from([
+                [8.0, 2.0, 2.0, 2.0],
+                [3.0, -1.0, 7.0, 0.0],
+                [7.0, 0.0, 5.0, 4.0],
+                [6.0, -2.0, 0.0, 5.0],
+            ]);
+            let c = a * b;
+            assert_eq!(c * b.inverse().unwrap(), a);
+        }
+
+        #[test]
+        fn three_by_three_inverse() {
+            let a = Matrix::from([[2.0, 0.0, 0.0], [0.0, 4.0, 0.0], [0.0, 0.0, 8.0]]);
+            assert_eq!(
+                a.inverse().unwrap(),
+                Matrix::from([[0.5, 0.0, 0.0], [0.0, 0.25, 0.0], [0.0, 0.0, 0.125]])
+            );
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{Point, Scalar};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Perlin {
+    seed: u64,
+    permutation: Vec<u8>,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        let mut table: Vec<u8> = (0..=255).collect();
+        let mut state = seed;
+        for i in (1..table.len()).rev() {
+            let j = (splitmix64(&mut state) % (i as u64 + 1)) as usize;
+            table.swap(i, j);
+        }
+        let permutation = table.iter().chain(table.iter()).copied().collect();
+        Perlin { seed, permutation }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn noise(&self, point: &Point) -> Scalar {
+        let (x, y, z) = (point.x(), point.y(), point.z());
+        let (xi, yi, zi) = (lattice(x), lattice(y), lattice(z));
+        let (x, y, z) = (x - x.floor(), y - y.floor(), z - z.floor());
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        let p = |i: usize| self.permutation[i] as usize;
+        let a = p(xi) + yi;
+        let aa = p(a) + zi;
+        let ab = p(a + 1) + zi;
+        let b = p(xi + 1) + yi;
+        let ba = p(b) + zi;
+        let bb = p(b + 1) + zi;
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, grad(p(aa), x, y, z), grad(p(ba), x - 1.0, y, z)),
+                lerp(
+                    u,
+                    grad(p(ab), x, y - 1.0, z),
+                    grad(p(bb), x - 1.0, y - 1.0, z),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad(p(aa + 1), x, y, z - 1.0),
+                    grad(p(ba + 1), x - 1.0, y, z - 1.0),
+                ),
+                lerp(
+                    u,
+                    grad(p(ab + 1), x, y - 1.0, z - 1.0),
+                    grad(p(bb + 1), x - 1.0, y - 1.0, z - 1.0),
+                ),
+            ),
+        )
+    }
+
+    pub fn turbulence(&self, point: &Point, octaves: usize) -> Scalar {
+        let mut sum = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        for _ in 0..octaves {
+            let p = Point::new(
+                point.x() * frequency,
+                point.y() * frequency,
+                point.z() * frequency,
+            );
+            sum += self.noise(&p).abs() * amplitude;
+            frequency *= 2.0;
+            amplitude *= 0.5;
+        }
+        sum
+    }
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Perlin::new(0)
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn lattice(value: Scalar) -> usize {
+    (value.floor() as i64).rem_euclid(256) as usize
+}
+
+fn fade(t: Scalar) -> Scalar {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: Scalar, a: Scalar, b: Scalar) -> Scalar {
+    a + t * (b - a)
+}
+
+fn grad(hash: usize, x: Scalar, y: Scalar, z: Scalar) -> Scalar {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = match h {
+        0..=3 => y,
+        12 | 14 => x,
+        _ => z,
+    };
+    let u = if h & 1 == 0 { u } else { -u };
+    let v = if h & 2 == 0 { v } else { -v };
+    u + v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_points() -> Vec<Point> {
+        (0..100)
+            .map(|i| {
+                let i = i as Scalar;
+                Point::new(i * 0.37 - 12.0, i * 0.11 + 3.5, -i * 0.23)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn noise_is_deterministic_for_a_seed() {
+        let a = Perlin::new(42);
+        let b = Perlin::new(42);
+        for p in sample_points() {
+            assert_eq!(a.noise(&p), b.noise(&p));
+        }
+    }
+
+    #[test]
+    fn different_seeds_produce_different_noise() {
+        let a = Perlin::new(1);
+        let b = Perlin::new(2);
+        assert!(sample_points().iter().any(|p| a.noise(p) != b.noise(p)));
+    }
+
+    #[test]
+    fn noise_is_zero_on_lattice_points() {
+        let n = Perlin::new(7);
+        for (x, y, z) in [(0.0, 0.0, 0.0), (1.0, 2.0, 3.0), (-4.0, 5.0, -6.0)] {
+            assert_eq!(n.noise(&Point::new(x, y, z)), 0.0);
+        }
+    }
+
+    #[test]
+    fn noise_stays_within_bounds() {
+        let n = Perlin::default();
+        for p in sample_points() {
+            let value = n.noise(&p);
+            assert!((-1.0..=1.0).contains(&value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn turbulence_is_non_negative() {
+        let n = Perlin::default();
+        for p in sample_points() {
+            assert!(n.turbulence(&p, 4) >= 0.0);
+        }
+    }
+}
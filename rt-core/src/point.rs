@@ -0,0 +1,124 @@
+use crate::{Scalar, Tuple, Vector};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub(crate) tuple: Tuple<4>,
+}
+
+impl Point {
+    pub fn new(x: Scalar, y: Scalar, z: Scalar) -> Self {
+        Point {
+            tuple: Tuple::from([x, y, z, 1.0]),
+        }
+    }
+
+    pub fn origin() -> Self {
+        Point::new(0.0, 0.0, 0.0)
+    }
+
+    pub fn x(&self) -> Scalar {
+        self.tuple[0]
+    }
+
+    pub fn y(&self) -> Scalar {
+        self.tuple[1]
+    }
+
+    pub fn z(&self) -> Scalar {
+        self.tuple[2]
+    }
+
+    pub fn w(&self) -> Scalar {
+        self.tuple[3]
+    }
+}
+
+impl From<Point> for Tuple<4> {
+    fn from(point: Point) -> Self {
+        point.tuple
+    }
+}
+
+impl std::ops::Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, vector: Vector) -> Self::Output {
+        Point {
+            tuple: &self.tuple + &vector.tuple,
+        }
+    }
+}
+
+impl std::ops::Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, vector: Vector) -> Self::Output {
+        Point {
+            tuple: &self.tuple - &vector.tuple,
+        }
+    }
+}
+
+impl std::ops::Sub<Point> for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Self::Output {
+        Vector {
+            tuple: &self.tuple - &other.tuple,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod creation {
+        use super::*;
+
+        #[test]
+        fn new_point_has_w_of_one() {
+            let p = Point::new(4.3, -4.2, 3.1);
+            assert_eq!(p.x(), 4.3);
+            assert_eq!(p.y(), -4.2);
+            assert_eq!(p.z(), 3.1);
+            assert_eq!(p.w(), 1.0);
+        }
+
+        #[test]
+        fn origin() {
+            assert_eq!(Point::origin(), Point::new(0.0, 0.0, 0.0));
+        }
+
+        #[test]
+        fn into_tuple() {
+            let t: Tuple<4> = Point::new(1.0, 2.0, 3.0).into();
+            assert_eq!(t, Tuple::from([1.0, 2.0, 3.0, 1.0]));
+        }
+    }
+
+    mod arithmetic {
+        use super::*;
+
+        #[test]
+        fn adding_a_vector_to_a_point() {
+            let p = Point::new(3.0, -2.0, 5.0) + Vector::new(-2.0, 3.0, 1.0);
+            assert_eq!(p, Point::new(1.0, 1.0, 6.0));
+            assert_eq!(p.w(), 1.0);
+        }
+
+        #[test]
+        fn subtracting_two_points() {
+            let v = Point::new(3.0, 2.0, 1.0) - Point::new(5.0, 6.0, 7.0);
+            assert_eq!(v, Vector::new(-2.0, -4.0, -6.0));
+            assert_eq!(v.w(), 0.0);
+        }
+
+        #[test]
+        fn subtracting_a_vector_from_a_point() {
+            let p = Point::new(3.0, 2.0, 1.0) - Vector::new(5.0, 6.0, 7.0);
+            assert_eq!(p, Point::new(-2.0, -4.0, -6.0));
+            assert_eq!(p.w(), 1.0);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{Matrix, Point, Scalar, Vector};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Point,
+    pub direction: Vector,
+    pub time: Scalar,
+}
+
+impl Ray {
+    pub fn new(origin: Point, direction: Vector) -> Self {
+        Ray {
+            origin,
+            direction,
+            time: 0.0,
+        }
+    }
+
+    pub fn with_time(self, time: Scalar) -> Self {
+        Ray { time, ..self }
+    }
+
+    pub fn position(&self, t: Scalar) -> Point {
+        self.origin + self.direction * t
+    }
+
+    pub fn transform(&self, matrix: &Matrix<4, 4>) -> Ray {
+        Ray::new(*matrix * self.origin, *matrix * self.direction).with_time(self.time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creating_a_ray() {
+        let origin = Point::new(1.0, 2.0, 3.0);
+        let direction = Vector::new(4.0, 5.0, 6.0);
+        let r = Ray::new(origin, direction);
+        assert_eq!(r.origin, origin);
+        assert_eq!(r.direction, direction);
+        assert_eq!(r.time, 0.0);
+    }
+
+    #[test]
+    fn computing_a_point_from_a_distance() {
+        let r = Ray::new(Point::new(2.0, 3.0, 4.0), Vector::new(1.0, 0.0, 0.0));
+        assert_eq!(r.position(0.0), Point::new(2.0, 3.0, 4.0));
+        assert_eq!(r.position(1.0), Point::new(3.0, 3.0, 4.0));
+        assert_eq!(r.position(-1.0), Point::new(1.0, 3.0, 4.0));
+        assert_eq!(r.position(2.5), Point::new(4.5, 3.0, 4.0));
+    }
+
+    mod transformation {
+        use super::*;
+
+        #[test]
+        fn translating_a_ray() {
+            let r = Ray::new(Point::new(1.0, 2.0, 3.0), Vector::new(0.0, 1.0, 0.0));
+            let r2 = r.transform(&Matrix::translation(3.0, 4.0, 5.0));
+            assert_eq!(r2.origin, Point::new(4.0, 6.0, 8.0));
+            assert_eq!(r2.direction, Vector::new(0.0, 1.0, 0.0));
+        }
+
+        #[test]
+        fn scaling_a_ray() {
+            let r = Ray::new(Point::new(1.0, 2.0, 3.0), Vector::new(0.0, 1.0, 0.0));
+            let r2 = r.transform(&Matrix::scaling(2.0, 3.0, 4.0));
+            assert_eq!(r2.origin, Point::new(2.0, 6.0, 12.0));
+            assert_eq!(r2.direction, Vector::new(0.0, 3.0, 0.0));
+        }
+
+        #[test]
+        fn transforming_keeps_the_time() {
+            let r = Ray::new(Point::origin(), Vector::new(0.0, 1.0, 0.0)).with_time(0.25);
+            assert_eq!(r.transform(&Matrix::scaling(2.0, 2.0, 2.0)).time, 0.25);
+        }
+
+        #[test]
+        fn original_ray_is_unchanged() {
+            let r = Ray::new(Point::new(1.0, 2.0, 3.0), Vector::new(0.0, 1.0, 0.0));
+            let _ = r.transform(&Matrix::translation(3.0, 4.0, 5.0));
+            assert_eq!(r.origin, Point::new(1.0, 2.0, 3.0));
+        }
+    }
+}
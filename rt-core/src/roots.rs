@@ -0,0 +1,241 @@
+use crate::consts::PI;
+use crate::Scalar;
+
+const ZERO: Scalar = 1e-9;
+const POLISH_STEPS: usize = 2;
+
+fn is_zero(x: Scalar) -> bool {
+    x.abs() < ZERO
+}
+
+fn sorted(mut roots: Vec<Scalar>) -> Vec<Scalar> {
+    roots.retain(|root| root.is_finite());
+    roots.sort_by(|a, b| a.total_cmp(b));
+    roots
+}
+
+pub fn solve_quadratic(a: Scalar, b: Scalar, c: Scalar) -> Vec<Scalar> {
+    if a == 0.0 {
+        return if b == 0.0 { vec![] } else { vec![-c / b] };
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return vec![];
+    }
+    if discriminant == 0.0 {
+        return vec![-b / (2.0 * a)];
+    }
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    if q == 0.0 {
+        return vec![0.0];
+    }
+    sorted(vec![q / a, c / q])
+}
+
+pub fn solve_cubic(a: Scalar, b: Scalar, c: Scalar, d: Scalar) -> Vec<Scalar> {
+    if a == 0.0 {
+        return solve_quadratic(b, c, d);
+    }
+    let (b, c, d) = (b / a, c / a, d / a);
+
+    // Substitute x = y - b/3 to reach y³ + 3p·y + 2q = 0.
+    let p = (c - b * b / 3.0) / 3.0;
+    let q = (2.0 / 27.0 * b * b * b - b * c / 3.0 + d) / 2.0;
+    let cube_p = p * p * p;
+    let discriminant = q * q + cube_p;
+
+    let roots = if is_zero(discriminant) {
+        if is_zero(q) {
+            vec![0.0]
+        } else {
+            let u = (-q).cbrt();
+            vec![2.0 * u, -u]
+        }
+    } else if discriminant < 0.0 {
+        let phi = (-q / (-cube_p).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        vec![
+            t * phi.cos(),
+            -t * (phi + PI / 3.0).cos(),
+            -t * (phi - PI / 3.0).cos(),
+        ]
+    } else {
+        let root = discriminant.sqrt();
+        vec![(root - q).cbrt() - (root + q).cbrt()]
+    };
+
+    let shift = b / 3.0;
+    let coefficients = [1.0, b, c, d];
+    sorted(
+        roots
+            .into_iter()
+            .map(|y| polish(&coefficients, y - shift))
+            .collect(),
+    )
+}
+
+pub fn solve_quartic(a: Scalar, b: Scalar, c: Scalar, d: Scalar, e: Scalar) -> Vec<Scalar> {
+    if a == 0.0 {
+        return solve_cubic(b, c, d, e);
+    }
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+
+    // Substitute x = y - b/4 to reach y⁴ + p·y² + q·y + r = 0.
+    let bb = b * b;
+    let p = c - 3.0 / 8.0 * bb;
+    let q = bb * b / 8.0 - b * c / 2.0 + d;
+    let r = -3.0 / 256.0 * bb * bb + bb * c / 16.0 - b * d / 4.0 + e;
+
+    let roots = if is_zero(r) {
+        let mut roots = solve_cubic(1.0, 0.0, p, q);
+        roots.push(0.0);
+        roots
+    } else {
+        // Any real root z of the resolvent cubic factors the quartic into two quadratics.
+        let z = solve_cubic(1.0, -p / 2.0, -r, r * p / 2.0 - q * q / 8.0)
+            .into_iter()
+            .fold(Scalar::NEG_INFINITY, Scalar::max);
+        let u = z * z - r;
+        let v = 2.0 * z - p;
+        let u = if is_zero(u) {
+            0.0
+        } else if u > 0.0 {
+            u.sqrt()
+        } else {
+            return vec![];
+        };
+        let v = if is_zero(v) {
+            0.0
+        } else if v > 0.0 {
+            v.sqrt()
+        } else {
+            return vec![];
+        };
+        let v = if q < 0.0 { -v } else { v };
+
+        let mut roots = solve_quadratic(1.0, v, z - u);
+        roots.extend(solve_quadratic(1.0, -v, z + u));
+        roots
+    };
+
+    let shift = b / 4.0;
+    let coefficients = [1.0, b, c, d, e];
+    sorted(
+        roots
+            .into_iter()
+            .map(|y| polish(&coefficients, y - shift))
+            .collect(),
+    )
+}
+
+fn polish(coefficients: &[Scalar], mut x: Scalar) -> Scalar {
+    for _ in 0..POLISH_STEPS {
+        let (value, slope) = coefficients.iter().fold((0.0, 0.0), |(value, slope), &k| {
+            (value * x + k, slope * x + value)
+        });
+        if slope == 0.0 {
+            break;
+        }
+        x -= value / slope;
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EPSILON;
+
+    fn assert_roots(actual: Vec<Scalar>, expected: &[Scalar]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < EPSILON, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn non_finite_roots_are_dropped() {
+        let nan = Scalar::NAN;
+        assert_eq!(sorted(vec![nan, 1.0, -2.0]), vec![-2.0, 1.0]);
+        assert!(solve_quadratic(1.0, nan, 2.0).is_empty());
+    }
+
+    mod quadratic {
+        use super::*;
+
+        #[test]
+        fn two_real_roots() {
+            assert_roots(solve_quadratic(1.0, -3.0, 2.0), &[1.0, 2.0]);
+        }
+
+        #[test]
+        fn a_repeated_root() {
+            assert_roots(solve_quadratic(1.0, -4.0, 4.0), &[2.0]);
+        }
+
+        #[test]
+        fn no_real_roots() {
+            assert!(solve_quadratic(1.0, 0.0, 1.0).is_empty());
+        }
+
+        #[test]
+        fn degenerates_to_a_line() {
+            assert_roots(solve_quadratic(0.0, 2.0, -1.0), &[0.5]);
+        }
+    }
+
+    mod cubic {
+        use super::*;
+
+        #[test]
+        fn three_real_roots() {
+            // (x + 1)(x - 2)(x - 3)
+            assert_roots(solve_cubic(1.0, -4.0, 1.0, 6.0), &[-1.0, 2.0, 3.0]);
+        }
+
+        #[test]
+        fn one_real_root() {
+            // (x - 2)(x² + 1)
+            assert_roots(solve_cubic(2.0, -4.0, 2.0, -4.0), &[2.0]);
+        }
+
+        #[test]
+        fn a_double_root() {
+            // (x - 1)²(x + 2)
+            assert_roots(solve_cubic(1.0, 0.0, -3.0, 2.0), &[-2.0, 1.0]);
+        }
+    }
+
+    mod quartic {
+        use super::*;
+
+        #[test]
+        fn four_real_roots() {
+            // (x - 1)(x - 2)(x - 3)(x - 4)
+            assert_roots(
+                solve_quartic(1.0, -10.0, 35.0, -50.0, 24.0),
+                &[1.0, 2.0, 3.0, 4.0],
+            );
+        }
+
+        #[test]
+        fn two_real_roots() {
+            // (x² - 4)(x² + 1)
+            assert_roots(solve_quartic(3.0, 0.0, -9.0, 0.0, -12.0), &[-2.0, 2.0]);
+        }
+
+        #[test]
+        fn no_real_roots() {
+            assert!(solve_quartic(1.0, 0.0, 2.0, 0.0, 1.0).is_empty());
+        }
+
+        #[test]
+        fn a_root_at_zero() {
+            // x(x - 1)(x + 1)(x - 2)
+            assert_roots(
+                solve_quartic(1.0, -2.0, -1.0, 2.0, 0.0),
+                &[-1.0, 0.0, 1.0, 2.0],
+            );
+        }
+    }
+}
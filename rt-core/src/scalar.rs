@@ -0,0 +1,58 @@
+#[cfg(not(feature = "f32"))]
+mod precision {
+    pub type Scalar = f64;
+    pub use std::f64::consts;
+
+    pub const EPSILON: Scalar = 0.00001;
+
+    pub fn to_f64(value: Scalar) -> f64 {
+        value
+    }
+
+    pub fn from_f64(value: f64) -> Scalar {
+        value
+    }
+
+    pub fn to_bits(value: Scalar) -> u64 {
+        value.to_bits()
+    }
+}
+
+#[cfg(feature = "f32")]
+mod precision {
+    pub type Scalar = f32;
+    pub use std::f32::consts;
+
+    pub const EPSILON: Scalar = 0.001;
+
+    pub fn to_f64(value: Scalar) -> f64 {
+        f64::from(value)
+    }
+
+    pub fn from_f64(value: f64) -> Scalar {
+        value as Scalar
+    }
+
+    pub fn to_bits(value: Scalar) -> u64 {
+        u64::from(value.to_bits())
+    }
+}
+
+pub use precision::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions_round_trip() {
+        assert_eq!(from_f64(to_f64(0.5)), 0.5);
+        assert_eq!(to_f64(from_f64(-2.25)), -2.25);
+    }
+
+    #[test]
+    fn bits_distinguish_values() {
+        assert_eq!(to_bits(1.5), to_bits(1.5));
+        assert_ne!(to_bits(1.5), to_bits(-1.5));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::Scalar;
+
+#[cfg(not(feature = "f32"))]
+type Lanes = wide::f64x4;
+#[cfg(feature = "f32")]
+type Lanes = wide::f32x4;
+
+pub(crate) type Array = [Scalar; 4];
+
+#[inline]
+fn lanes(array: &Array) -> Lanes {
+    Lanes::new(*array)
+}
+
+#[inline]
+pub(crate) fn add(a: &Array, b: &Array) -> Array {
+    (lanes(a) + lanes(b)).to_array()
+}
+
+#[inline]
+pub(crate) fn sub(a: &Array, b: &Array) -> Array {
+    (lanes(a) - lanes(b)).to_array()
+}
+
+#[inline]
+pub(crate) fn mul(a: &Array, b: &Array) -> Array {
+    (lanes(a) * lanes(b)).to_array()
+}
+
+#[inline]
+pub(crate) fn div(a: &Array, b: &Array) -> Array {
+    (lanes(a) / lanes(b)).to_array()
+}
+
+#[inline]
+pub(crate) fn scale(a: &Array, factor: Scalar) -> Array {
+    (lanes(a) * Lanes::splat(factor)).to_array()
+}
+
+#[inline]
+pub(crate) fn shrink(a: &Array, divisor: Scalar) -> Array {
+    (lanes(a) / Lanes::splat(divisor)).to_array()
+}
+
+#[inline]
+pub(crate) fn dot(a: &Array, b: &Array) -> Scalar {
+    (lanes(a) * lanes(b)).reduce_add()
+}
+
+#[inline]
+pub(crate) fn transform(rows: &[Array; 4], tuple: &Array) -> Array {
+    [
+        dot(&rows[0], tuple),
+        dot(&rows[1], tuple),
+        dot(&rows[2], tuple),
+        dot(&rows[3], tuple),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EPSILON;
+
+    const A: Array = [1.5, -2.0, 3.25, 1.0];
+    const B: Array = [0.5, 4.0, -1.0, 0.0];
+
+    fn assert_close(actual: Array, expected: Array) {
+        for (a, e) in actual.iter().zip(&expected) {
+            assert!((a - e).abs() < EPSILON, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn lane_arithmetic_matches_component_loops() {
+        assert_close(add(&A, &B), std::array::from_fn(|i| A[i] + B[i]));
+        assert_close(sub(&A, &B), std::array::from_fn(|i| A[i] - B[i]));
+        assert_close(mul(&A, &B), std::array::from_fn(|i| A[i] * B[i]));
+        assert_close(div(&B, &A), std::array::from_fn(|i| B[i] / A[i]));
+        assert_close(scale(&A, 2.5), std::array::from_fn(|i| A[i] * 2.5));
+        assert_close(shrink(&A, 4.0), std::array::from_fn(|i| A[i] / 4.0));
+        assert!((dot(&A, &B) - (0.75 - 8.0 - 3.25)).abs() < EPSILON);
+    }
+
+    #[test]
+    fn transforming_matches_row_dot_products() {
+        let rows = [A, B, [0.0, 1.0, 0.0, 2.0], [4.0, 3.0, 2.0, 1.0]];
+        let tuple = [1.0, 2.0, 3.0, 1.0];
+        let expected = rows.map(|row| (0..4).map(|i| row[i] * tuple[i]).sum());
+        assert_close(transform(&rows, &tuple), expected);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{Canvas, Color, Scalar};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneOperator {
+    #[default]
+    Linear,
+    Reinhard,
+    Aces,
+}
+
+impl ToneOperator {
+    fn map(&self, value: Scalar) -> Scalar {
+        let value = value.max(0.0);
+        match self {
+            ToneOperator::Linear => value,
+            ToneOperator::Reinhard => value / (1.0 + value),
+            ToneOperator::Aces => {
+                let mapped = value * (2.51 * value + 0.03) / (value * (2.43 * value + 0.59) + 0.14);
+                mapped.clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToneMap {
+    pub exposure: Scalar,
+    pub gamma: Scalar,
+    pub operator: ToneOperator,
+}
+
+impl ToneMap {
+    pub fn apply(&self, color: Color) -> Color {
+        let scale = self.exposure.exp2();
+        let channel = |value: Scalar| {
+            let mapped = self.operator.map(value * scale);
+            if self.gamma == 1.0 {
+                mapped
+            } else {
+                mapped.powf(1.0 / self.gamma)
+            }
+        };
+        Color::new(
+            channel(color.red()),
+            channel(color.green()),
+            channel(color.blue()),
+        )
+    }
+}
+
+impl Default for ToneMap {
+    fn default() -> Self {
+        ToneMap {
+            exposure: 0.0,
+            gamma: 1.0,
+            operator: ToneOperator::default(),
+        }
+    }
+}
+
+impl Canvas {
+    pub fn tone_mapped(&self, tone_map: &ToneMap) -> Canvas {
+        let mut canvas = Canvas::new(self.width(), self.height());
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                canvas.write_pixel(x, y, tone_map.apply(self.pixel_at(x, y)));
+            }
+        }
+        canvas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with(operator: ToneOperator) -> ToneMap {
+        ToneMap {
+            operator,
+            ..ToneMap::default()
+        }
+    }
+
+    #[test]
+    fn the_default_tone_map_changes_nothing_in_range() {
+        let color = Color::new(0.25, 0.5, 1.0);
+        assert_eq!(ToneMap::default().apply(color), color);
+    }
+
+    #[test]
+    fn exposure_is_measured_in_stops() {
+        let tone_map = ToneMap {
+            exposure: 1.0,
+            ..ToneMap::default()
+        };
+        assert_eq!(
+            tone_map.apply(Color::new(0.25, 0.5, 0.1)),
+            Color::new(0.5, 1.0, 0.2)
+        );
+        let darker = ToneMap {
+            exposure: -2.0,
+            ..tone_map
+        };
+        assert_eq!(darker.apply(Color::white()), Color::new(0.25, 0.25, 0.25));
+    }
+
+    #[test]
+    fn gamma_brightens_mid_tones() {
+        let tone_map = ToneMap {
+            gamma: 2.0,
+            ..ToneMap::default()
+        };
+        assert_eq!(
+            tone_map.apply(Color::new(0.25, 0.0, 1.0)),
+            Color::new(0.5, 0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn reinhard_compresses_highlights_below_one() {
+        let tone_map = with(ToneOperator::Reinhard);
+        assert_eq!(
+            tone_map.apply(Color::new(1.0, 3.0, 0.0)),
+            Color::new(0.5, 0.75, 0.0)
+        );
+        assert!(tone_map.apply(Color::new(1e6, 1e6, 1e6)).red() < 1.0);
+    }
+
+    #[test]
+    fn aces_keeps_order_and_saturates_at_white() {
+        let tone_map = with(ToneOperator::Aces);
+        let values: Vec<Scalar> = [0.0, 0.1, 0.5, 1.0, 4.0, 100.0]
+            .iter()
+            .map(|&v| tone_map.apply(Color::new(v, v, v)).red())
+            .collect();
+        assert_eq!(values[0], 0.0);
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(values[5], 1.0);
+    }
+
+    #[test]
+    fn negative_radiance_maps_to_black() {
+        for operator in [
+            ToneOperator::Linear,
+            ToneOperator::Reinhard,
+            ToneOperator::Aces,
+        ] {
+            let tone_map = ToneMap {
+                gamma: 2.2,
+                ..with(operator)
+            };
+            assert_eq!(tone_map.apply(Color::new(-1.0, 0.0, 0.0)), Color::black());
+        }
+    }
+
+    #[test]
+    fn canvases_are_mapped_pixel_by_pixel() {
+        let mut canvas = Canvas::new(2, 1);
+        canvas.write_pixel(1, 0, Color::new(3.0, 1.0, 0.0));
+        let mapped = canvas.tone_mapped(&with(ToneOperator::Reinhard));
+        assert_eq!(mapped.pixel_at(0, 0), Color::black());
+        assert_eq!(mapped.pixel_at(1, 0), Color::new(0.75, 0.5, 0.0));
+    }
+}
@@ -0,0 +1,438 @@
+use crate::{Matrix, Scalar, EPSILON};
+
+impl Matrix<4, 4> {
+    pub fn translation(x: Scalar, y: Scalar, z: Scalar) -> Self {
+        Matrix::from([
+            [1.0, 0.0, 0.0, x],
+            [0.0, 1.0, 0.0, y],
+            [0.0, 0.0, 1.0, z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn scaling(x: Scalar, y: Scalar, z: Scalar) -> Self {
+        Matrix::from([
+            [x, 0.0, 0.0, 0.0],
+            [0.0, y, 0.0, 0.0],
+            [0.0, 0.0, z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn rotation_x(radians: Scalar) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Matrix::from([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, cos, -sin, 0.0],
+            [0.0, sin, cos, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn rotation_y(radians: Scalar) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Matrix::from([
+            [cos, 0.0, sin, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [-sin, 0.0, cos, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn rotation_z(radians: Scalar) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Matrix::from([
+            [cos, -sin, 0.0, 0.0],
+            [sin, cos, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn shearing(
+        xy: Scalar,
+        xz: Scalar,
+        yx: Scalar,
+        yz: Scalar,
+        zx: Scalar,
+        zy: Scalar,
+    ) -> Self {
+        Matrix::from([
+            [1.0, xy, xz, 0.0],
+            [yx, 1.0, yz, 0.0],
+            [zx, zy, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn translate(self, x: Scalar, y: Scalar, z: Scalar) -> Self {
+        Matrix::translation(x, y, z) * self
+    }
+
+    pub fn scale(self, x: Scalar, y: Scalar, z: Scalar) -> Self {
+        Matrix::scaling(x, y, z) * self
+    }
+
+    pub fn rotate_x(self, radians: Scalar) -> Self {
+        Matrix::rotation_x(radians) * self
+    }
+
+    pub fn rotate_y(self, radians: Scalar) -> Self {
+        Matrix::rotation_y(radians) * self
+    }
+
+    pub fn rotate_z(self, radians: Scalar) -> Self {
+        Matrix::rotation_z(radians) * self
+    }
+
+    pub fn shear(
+        self,
+        xy: Scalar,
+        xz: Scalar,
+        yx: Scalar,
+        yz: Scalar,
+        zx: Scalar,
+        zy: Scalar,
+    ) -> Self {
+        Matrix::shearing(xy, xz, yx, yz, zx, zy) * self
+    }
+
+    pub fn lerp(&self, other: &Self, t: Scalar) -> Self {
+        Matrix::from(std::array::from_fn(|row| {
+            std::array::from_fn(|col| self[row][col] + (other[row][col] - self[row][col]) * t)
+        }))
+    }
+
+    pub fn interpolate(&self, other: &Self, t: Scalar) -> Self {
+        match (decompose(self), decompose(other)) {
+            (Some(a), Some(b)) => compose(&Decomposed {
+                translation: lerp3(a.translation, b.translation, t),
+                rotation: slerp(a.rotation, b.rotation, t),
+                scale: lerp3(a.scale, b.scale, t),
+            }),
+            _ => self.lerp(other, t),
+        }
+    }
+}
+
+type Quaternion = [Scalar; 4];
+
+struct Decomposed {
+    translation: [Scalar; 3],
+    rotation: Quaternion,
+    scale: [Scalar; 3],
+}
+
+fn decompose(m: &Matrix<4, 4>) -> Option<Decomposed> {
+    if m[3] != [0.0, 0.0, 0.0, 1.0] {
+        return None;
+    }
+    let mut scale: [Scalar; 3] =
+        std::array::from_fn(|col| (0..3).map(|row| m[row][col].powi(2)).sum::<Scalar>().sqrt());
+    if scale.iter().any(|&s| s < EPSILON) {
+        return None;
+    }
+    let linear = Matrix::from([
+        [m[0][0], m[0][1], m[0][2]],
+        [m[1][0], m[1][1], m[1][2]],
+        [m[2][0], m[2][1], m[2][2]],
+    ]);
+    if linear.determinant() < 0.0 {
+        scale[0] = -scale[0];
+    }
+    let r: [[Scalar; 3]; 3] =
+        std::array::from_fn(|row| std::array::from_fn(|col| m[row][col] / scale[col]));
+    Some(Decomposed {
+        translation: [m[0][3], m[1][3], m[2][3]],
+        rotation: quaternion_from(&r),
+        scale,
+    })
+}
+
+fn compose(d: &Decomposed) -> Matrix<4, 4> {
+    let [w, x, y, z] = d.rotation;
+    let r = [
+        [
+            1.0 - 2.0 * (y * y + z * z),
+            2.0 * (x * y - w * z),
+            2.0 * (x * z + w * y),
+        ],
+        [
+            2.0 * (x * y + w * z),
+            1.0 - 2.0 * (x * x + z * z),
+            2.0 * (y * z - w * x),
+        ],
+        [
+            2.0 * (x * z - w * y),
+            2.0 * (y * z + w * x),
+            1.0 - 2.0 * (x * x + y * y),
+        ],
+    ];
+    Matrix::from(std::array::from_fn(|row| {
+        std::array::from_fn(|col| match (row, col) {
+            (3, 3) => 1.0,
+            (3, _) => 0.0,
+            (_, 3) => d.translation[row],
+            _ => r[row][col] * d.scale[col],
+        })
+    }))
+}
+
+fn quaternion_from(r: &[[Scalar; 3]; 3]) -> Quaternion {
+    let trace = r[0][0] + r[1][1] + r[2][2];
+    if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [
+            s / 4.0,
+            (r[2][1] - r[1][2]) / s,
+            (r[0][2] - r[2][0]) / s,
+            (r[1][0] - r[0][1]) / s,
+        ]
+    } else if r[0][0] > r[1][1] && r[0][0] > r[2][2] {
+        let s = (1.0 + r[0][0] - r[1][1] - r[2][2]).sqrt() * 2.0;
+        [
+            (r[2][1] - r[1][2]) / s,
+            s / 4.0,
+            (r[0][1] + r[1][0]) / s,
+            (r[0][2] + r[2][0]) / s,
+        ]
+    } else if r[1][1] > r[2][2] {
+        let s = (1.0 + r[1][1] - r[0][0] - r[2][2]).sqrt() * 2.0;
+        [
+            (r[0][2] - r[2][0]) / s,
+            (r[0][1] + r[1][0]) / s,
+            s / 4.0,
+            (r[1][2] + r[2][1]) / s,
+        ]
+    } else {
+        let s = (1.0 + r[2][2] - r[0][0] - r[1][1]).sqrt() * 2.0;
+        [
+            (r[1][0] - r[0][1]) / s,
+            (r[0][2] + r[2][0]) / s,
+            (r[1][2] + r[2][1]) / s,
+            s / 4.0,
+        ]
+    }
+}
+
+fn slerp(a: Quaternion, mut b: Quaternion, t: Scalar) -> Quaternion {
+    let mut cos = (0..4).map(|i| a[i] * b[i]).sum::<Scalar>();
+    if cos < 0.0 {
+        b = b.map(|component| -component);
+        cos = -cos;
+    }
+    let (wa, wb) = if cos > 1.0 - EPSILON {
+        (1.0 - t, t)
+    } else {
+        let theta = cos.acos();
+        (
+            ((1.0 - t) * theta).sin() / theta.sin(),
+            (t * theta).sin() / theta.sin(),
+        )
+    };
+    let q: Quaternion = std::array::from_fn(|i| a[i] * wa + b[i] * wb);
+    let norm = q.iter().map(|c| c * c).sum::<Scalar>().sqrt();
+    q.map(|component| component / norm)
+}
+
+fn lerp3(a: [Scalar; 3], b: [Scalar; 3], t: Scalar) -> [Scalar; 3] {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::PI;
+    use crate::{Point, Vector};
+
+    mod translation {
+        use super::*;
+
+        #[test]
+        fn moves_a_point() {
+            let transform = Matrix::translation(5.0, -3.0, 2.0);
+            assert_eq!(
+                transform * Point::new(-3.0, 4.0, 5.0),
+                Point::new(2.0, 1.0, 7.0)
+            );
+        }
+
+        #[test]
+        fn inverse_moves_a_point_back() {
+            let inv = Matrix::translation(5.0, -3.0, 2.0).inverse().unwrap();
+            assert_eq!(inv * Point::new(-3.0, 4.0, 5.0), Point::new(-8.0, 7.0, 3.0));
+        }
+
+        #[test]
+        fn does_not_affect_vectors() {
+            let v = Vector::new(-3.0, 4.0, 5.0);
+            assert_eq!(Matrix::translation(5.0, -3.0, 2.0) * v, v);
+        }
+    }
+
+    mod scaling {
+        use super::*;
+
+        #[test]
+        fn scales_a_point_and_a_vector() {
+            let transform = Matrix::scaling(2.0, 3.0, 4.0);
+            assert_eq!(
+                transform * Point::new(-4.0, 6.0, 8.0),
+                Point::new(-8.0, 18.0, 32.0)
+            );
+            assert_eq!(
+                transform * Vector::new(-4.0, 6.0, 8.0),
+                Vector::new(-8.0, 18.0, 32.0)
+            );
+        }
+
+        #[test]
+        fn inverse_shrinks() {
+            let inv = Matrix::scaling(2.0, 3.0, 4.0).inverse().unwrap();
+            assert_eq!(
+                inv * Vector::new(-4.0, 6.0, 8.0),
+                Vector::new(-2.0, 2.0, 2.0)
+            );
+        }
+
+        #[test]
+        fn reflection_is_negative_scaling() {
+            assert_eq!(
+                Matrix::scaling(-1.0, 1.0, 1.0) * Point::new(2.0, 3.0, 4.0),
+                Point::new(-2.0, 3.0, 4.0)
+            );
+        }
+    }
+
+    mod rotation {
+        use super::*;
+
+        #[test]
+        fn around_the_x_axis() {
+            let p = Point::new(0.0, 1.0, 0.0);
+            let half_quarter = Matrix::rotation_x(PI / 4.0);
+            let full_quarter = Matrix::rotation_x(PI / 2.0);
+            let h = Scalar::sqrt(2.0) / 2.0;
+            assert_eq!(half_quarter * p, Point::new(0.0, h, h));
+            assert_eq!(full_quarter * p, Point::new(0.0, 0.0, 1.0));
+            assert_eq!(half_quarter.inverse().unwrap() * p, Point::new(0.0, h, -h));
+        }
+
+        #[test]
+        fn around_the_y_axis() {
+            let p = Point::new(0.0, 0.0, 1.0);
+            let h = Scalar::sqrt(2.0) / 2.0;
+            assert_eq!(Matrix::rotation_y(PI / 4.0) * p, Point::new(h, 0.0, h));
+            assert_eq!(Matrix::rotation_y(PI / 2.0) * p, Point::new(1.0, 0.0, 0.0));
+        }
+
+        #[test]
+        fn around_the_z_axis() {
+            let p = Point::new(0.0, 1.0, 0.0);
+            let h = Scalar::sqrt(2.0) / 2.0;
+            assert_eq!(Matrix::rotation_z(PI / 4.0) * p, Point::new(-h, h, 0.0));
+            assert_eq!(Matrix::rotation_z(PI / 2.0) * p, Point::new(-1.0, 0.0, 0.0));
+        }
+    }
+
+    mod shearing {
+        use super::*;
+
+        #[test]
+        fn moves_each_component_in_proportion_to_the_others() {
+            let p = Point::new(2.0, 3.0, 4.0);
+            let cases = [
+                ([1.0, 0.0, 0.0, 0.0, 0.0, 0.0], Point::new(5.0, 3.0, 4.0)),
+                ([0.0, 1.0, 0.0, 0.0, 0.0, 0.0], Point::new(6.0, 3.0, 4.0)),
+                ([0.0, 0.0, 1.0, 0.0, 0.0, 0.0], Point::new(2.0, 5.0, 4.0)),
+                ([0.0, 0.0, 0.0, 1.0, 0.0, 0.0], Point::new(2.0, 7.0, 4.0)),
+                ([0.0, 0.0, 0.0, 0.0, 1.0, 0.0], Point::new(2.0, 3.0, 6.0)),
+                ([0.0, 0.0, 0.0, 0.0, 0.0, 1.0], Point::new(2.0, 3.0, 7.0)),
+            ];
+            for ([xy, xz, yx, yz, zx, zy], expected) in cases {
+                assert_eq!(Matrix::shearing(xy, xz, yx, yz, zx, zy) * p, expected);
+            }
+        }
+    }
+
+    mod chaining {
+        use super::*;
+
+        #[test]
+        fn individual_transformations_are_applied_in_sequence() {
+            let p = Point::new(1.0, 0.0, 1.0);
+            let a = Matrix::rotation_x(PI / 2.0);
+            let b = Matrix::scaling(5.0, 5.0, 5.0);
+            let c = Matrix::translation(10.0, 5.0, 7.0);
+            let p2 = a * p;
+            assert_eq!(p2, Point::new(1.0, -1.0, 0.0));
+            let p3 = b * p2;
+            assert_eq!(p3, Point::new(5.0, -5.0, 0.0));
+            let p4 = c * p3;
+            assert_eq!(p4, Point::new(15.0, 0.0, 7.0));
+        }
+
+        #[test]
+        fn chained_transformations_are_applied_in_reverse_order() {
+            let p = Point::new(1.0, 0.0, 1.0);
+            let t = Matrix::translation(10.0, 5.0, 7.0)
+                * Matrix::scaling(5.0, 5.0, 5.0)
+                * Matrix::rotation_x(PI / 2.0);
+            assert_eq!(t * p, Point::new(15.0, 0.0, 7.0));
+        }
+
+        #[test]
+        fn fluent_api_reads_in_application_order() {
+            let t = Matrix::identity()
+                .rotate_x(PI / 2.0)
+                .scale(5.0, 5.0, 5.0)
+                .translate(10.0, 5.0, 7.0);
+            assert_eq!(t * Point::new(1.0, 0.0, 1.0), Point::new(15.0, 0.0, 7.0));
+        }
+    }
+
+    mod interpolation {
+        use super::*;
+
+        #[test]
+        fn lerp_blends_every_component() {
+            let a = Matrix::translation(0.0, 0.0, 0.0);
+            let b = Matrix::translation(4.0, -2.0, 8.0);
+            assert_eq!(a.lerp(&b, 0.25), Matrix::translation(1.0, -0.5, 2.0));
+        }
+
+        #[test]
+        fn interpolation_hits_both_endpoints() {
+            let a = Matrix::scaling(2.0, 1.0, 1.0)
+                .rotate_z(0.3)
+                .translate(1.0, 2.0, 3.0);
+            let b = Matrix::rotation_x(-1.2).translate(-4.0, 0.0, 1.0);
+            assert_eq!(a.interpolate(&b, 0.0), a);
+            assert_eq!(a.interpolate(&b, 1.0), b);
+        }
+
+        #[test]
+        fn rotations_are_interpolated_by_angle() {
+            let a = Matrix::rotation_y(0.0);
+            let b = Matrix::rotation_y(PI / 2.0);
+            assert_eq!(a.interpolate(&b, 0.5), Matrix::rotation_y(PI / 4.0));
+            assert_ne!(a.lerp(&b, 0.5), Matrix::rotation_y(PI / 4.0));
+        }
+
+        #[test]
+        fn translation_and_scale_are_interpolated_linearly() {
+            let a = Matrix::scaling(1.0, 1.0, 1.0);
+            let b = Matrix::scaling(3.0, 1.0, 1.0).translate(0.0, 4.0, 0.0);
+            assert_eq!(
+                a.interpolate(&b, 0.5),
+                Matrix::scaling(2.0, 1.0, 1.0).translate(0.0, 2.0, 0.0)
+            );
+        }
+
+        #[test]
+        fn mirrored_transforms_keep_their_handedness() {
+            let a = Matrix::scaling(-1.0, 1.0, 1.0);
+            assert_eq!(a.interpolate(&a, 0.5), a);
+        }
+    }
+}
@@ -1,48 +1,105 @@
-#[derive(Debug)]
-struct Tuple<const N: usize> {
-    data: [f64; N],
+#[cfg(feature = "simd")]
+use crate::simd;
+use crate::Scalar;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Tuple<const N: usize> {
+    data: [Scalar; N],
 }
 
 impl<const N: usize> Tuple<N> {
-    fn iter(&self) -> std::slice::Iter<f64> {
+    fn iter(&self) -> std::slice::Iter<'_, Scalar> {
         self.data.iter()
     }
+
+    pub fn dot(&self, other: &Tuple<N>) -> Scalar {
+        #[cfg(feature = "simd")]
+        if let (Some(a), Some(b)) = (lanes(&self.data), lanes(&other.data)) {
+            return simd::dot(a, b);
+        }
+        self.iter().zip(other.iter()).map(|(a, b)| a * b).sum()
+    }
+
+    pub fn abs(&self) -> Tuple<N> {
+        Tuple::from(std::array::from_fn(|i| self.data[i].abs()))
+    }
+
+    pub fn min_component(&self) -> (usize, Scalar) {
+        self.select_component(|candidate, best| candidate < best)
+    }
+
+    pub fn max_component(&self) -> (usize, Scalar) {
+        self.select_component(|candidate, best| candidate > best)
+    }
+
+    /// Calling this on a zero-length tuple is a compile-time error.
+    fn select_component(&self, better: impl Fn(Scalar, Scalar) -> bool) -> (usize, Scalar) {
+        const { assert!(N > 0, "tuples need a component to select") };
+        self.iter()
+            .copied()
+            .enumerate()
+            .fold((0, self[0]), |best, (i, value)| {
+                if better(value, best.1) {
+                    (i, value)
+                } else {
+                    best
+                }
+            })
+    }
 }
 
-impl<const N: usize> From<[f64; N]> for Tuple<N> {
-    fn from(array: [f64; N]) -> Self {
+#[cfg(feature = "simd")]
+fn lanes<const N: usize>(data: &[Scalar; N]) -> Option<&simd::Array> {
+    std::convert::TryFrom::try_from(&data[..]).ok()
+}
+
+#[cfg(feature = "simd")]
+fn from_lanes<const N: usize>(array: simd::Array) -> Tuple<N> {
+    Tuple::from(std::array::from_fn(|i| array[i]))
+}
+
+impl<const N: usize> From<[Scalar; N]> for Tuple<N> {
+    fn from(array: [Scalar; N]) -> Self {
         Tuple { data: array }
     }
 }
 
 macro_rules! implement_operations {
-    ($trait:ident, $method:ident, $op:tt) => {
+    ($trait:ident, $method:ident, $op:tt, $lanes:ident) => {
         impl<const N: usize> std::ops::$trait<&Tuple<N>> for &Tuple<N> {
             type Output = Tuple<N>;
 
             fn $method(self, other: &Tuple<N>) -> Self::Output {
+                #[cfg(feature = "simd")]
+                if let (Some(a), Some(b)) = (lanes(&self.data), lanes(&other.data)) {
+                    return from_lanes(simd::$lanes(a, b));
+                }
                 Tuple::from(std::array::from_fn(|i| self.data[i] $op other.data[i]))
             }
         }
     };
 
-    ($trait:ident, $method:ident, $op:tt, $scalar:ty) => {
+    ($trait:ident, $method:ident, $op:tt, $lanes:ident, $scalar:ty) => {
         impl<const N: usize> std::ops::$trait<$scalar> for &Tuple<N> {
             type Output = Tuple<N>;
 
             fn $method(self, scalar: $scalar) -> Tuple<N> {
+                #[cfg(feature = "simd")]
+                if let Some(a) = lanes(&self.data) {
+                    return from_lanes(simd::$lanes(a, scalar));
+                }
                 Tuple::from(std::array::from_fn(|i| self.data[i] $op scalar))
             }
         }
     };
 }
 
-implement_operations!(Add, add, +);
-implement_operations!(Sub, sub, -);
-implement_operations!(Mul, mul, *);
-implement_operations!(Div, div, /);
-implement_operations!(Mul, mul, *, f64);
-implement_operations!(Div, div, /, f64);
+implement_operations!(Add, add, +, add);
+implement_operations!(Sub, sub, -, sub);
+implement_operations!(Mul, mul, *, mul);
+implement_operations!(Div, div, /, div);
+implement_operations!(Mul, mul, *, scale, Scalar);
+implement_operations!(Div, div, /, shrink, Scalar);
 
 impl<const N: usize> std::ops::Neg for &Tuple<N> {
     type Output = Tuple<N>;
@@ -52,16 +109,36 @@ impl<const N: usize> std::ops::Neg for &Tuple<N> {
     }
 }
 
+macro_rules! implement_exp_formatting {
+    ($trait:ident) => {
+        impl<const N: usize> std::fmt::$trait for Tuple<N> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "(")?;
+                for (i, value) in self.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    std::fmt::$trait::fmt(value, f)?;
+                }
+                write!(f, ")")
+            }
+        }
+    };
+}
+
+implement_exp_formatting!(LowerExp);
+implement_exp_formatting!(UpperExp);
+
 impl<const N: usize> std::cmp::PartialEq for Tuple<N> {
     fn eq(&self, other: &Self) -> bool {
         self.iter()
             .zip(other.iter())
-            .all(|(a, b)| (a - b).abs() < 0.00001)
+            .all(|(a, b)| (a - b).abs() < crate::EPSILON)
     }
 }
 
 impl<const N: usize> std::ops::Index<usize> for Tuple<N> {
-    type Output = f64;
+    type Output = Scalar;
 
     fn index(&self, index: usize) -> &Self::Output {
         assert!(
@@ -97,6 +174,49 @@ mod tests {
         }
     }
 
+    mod components {
+        use super::*;
+
+        #[test]
+        fn max_component() {
+            assert_eq!(Tuple::from([3.0, 1.0, 4.0]).max_component(), (2, 4.0));
+        }
+
+        #[test]
+        fn min_component() {
+            assert_eq!(Tuple::from([3.0, 1.0, 4.0]).min_component(), (1, 1.0));
+        }
+
+        #[test]
+        fn ties_return_first_index() {
+            let t = Tuple::from([2.0, 5.0, 5.0, 2.0]);
+            assert_eq!(t.max_component(), (1, 5.0));
+            assert_eq!(t.min_component(), (0, 2.0));
+        }
+    }
+
+    mod formatting {
+        use super::*;
+
+        #[test]
+        fn lower_exp() {
+            let t = Tuple::from([1.0, 0.002, 314_100.0]);
+            assert_eq!(format!("{:.3e}", t), "(1.000e0, 2.000e-3, 3.141e5)");
+        }
+
+        #[test]
+        fn upper_exp() {
+            let t = Tuple::from([1.0, 0.002, 314_100.0]);
+            assert_eq!(format!("{:.3E}", t), "(1.000E0, 2.000E-3, 3.141E5)");
+        }
+
+        #[test]
+        fn lower_exp_without_precision() {
+            let t = Tuple::from([0.5, 1.0e-9]);
+            assert_eq!(format!("{:e}", t), "(5e-1, 1e-9)");
+        }
+    }
+
     mod comparison {
         use super::*;
 
@@ -199,5 +319,13 @@ mod tests {
                 Tuple::from([-1.0, -2.0, -3.0, -4.0])
             );
         }
+
+        #[test]
+        fn absolute_value_of_tuple() {
+            assert_eq!(
+                Tuple::from([-1.0, 2.0, -3.0]).abs(),
+                Tuple::from([1.0, 2.0, 3.0])
+            );
+        }
     }
 }
@@ -0,0 +1,259 @@
+use crate::{Point, Scalar, Tuple};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector {
+    pub(crate) tuple: Tuple<4>,
+}
+
+impl Vector {
+    pub fn new(x: Scalar, y: Scalar, z: Scalar) -> Self {
+        Vector {
+            tuple: Tuple::from([x, y, z, 0.0]),
+        }
+    }
+
+    pub fn zero() -> Self {
+        Vector::new(0.0, 0.0, 0.0)
+    }
+
+    pub fn x(&self) -> Scalar {
+        self.tuple[0]
+    }
+
+    pub fn y(&self) -> Scalar {
+        self.tuple[1]
+    }
+
+    pub fn z(&self) -> Scalar {
+        self.tuple[2]
+    }
+
+    pub fn w(&self) -> Scalar {
+        self.tuple[3]
+    }
+
+    pub fn magnitude(&self) -> Scalar {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(&self) -> Vector {
+        *self / self.magnitude()
+    }
+
+    pub fn dot(&self, other: &Vector) -> Scalar {
+        self.tuple.dot(&other.tuple)
+    }
+
+    pub fn cross(&self, other: &Vector) -> Vector {
+        Vector::new(
+            self.y() * other.z() - self.z() * other.y(),
+            self.z() * other.x() - self.x() * other.z(),
+            self.x() * other.y() - self.y() * other.x(),
+        )
+    }
+
+    pub fn reflect(&self, normal: &Vector) -> Vector {
+        *self - *normal * 2.0 * self.dot(normal)
+    }
+}
+
+impl From<Vector> for Tuple<4> {
+    fn from(vector: Vector) -> Self {
+        vector.tuple
+    }
+}
+
+macro_rules! implement_vector_operations {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl std::ops::$trait<Vector> for Vector {
+            type Output = Vector;
+
+            fn $method(self, other: Vector) -> Self::Output {
+                Vector {
+                    tuple: &self.tuple $op &other.tuple,
+                }
+            }
+        }
+    };
+
+    ($trait:ident, $method:ident, $op:tt, $scalar:ty) => {
+        impl std::ops::$trait<$scalar> for Vector {
+            type Output = Vector;
+
+            fn $method(self, scalar: $scalar) -> Self::Output {
+                Vector {
+                    tuple: &self.tuple $op scalar,
+                }
+            }
+        }
+    };
+}
+
+implement_vector_operations!(Add, add, +);
+implement_vector_operations!(Sub, sub, -);
+implement_vector_operations!(Mul, mul, *, Scalar);
+implement_vector_operations!(Div, div, /, Scalar);
+
+impl std::ops::Add<Point> for Vector {
+    type Output = Point;
+
+    fn add(self, point: Point) -> Self::Output {
+        point + self
+    }
+}
+
+impl std::ops::Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Self::Output {
+        Vector {
+            tuple: -&self.tuple,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod creation {
+        use super::*;
+
+        #[test]
+        fn new_vector_has_w_of_zero() {
+            let v = Vector::new(4.3, -4.2, 3.1);
+            assert_eq!(v.x(), 4.3);
+            assert_eq!(v.y(), -4.2);
+            assert_eq!(v.z(), 3.1);
+            assert_eq!(v.w(), 0.0);
+        }
+
+        #[test]
+        fn into_tuple() {
+            let t: Tuple<4> = Vector::new(1.0, 2.0, 3.0).into();
+            assert_eq!(t, Tuple::from([1.0, 2.0, 3.0, 0.0]));
+        }
+    }
+
+    mod arithmetic {
+        use super::*;
+
+        #[test]
+        fn adding_two_vectors() {
+            assert_eq!(
+                Vector::new(3.0, -2.0, 5.0) + Vector::new(-2.0, 3.0, 1.0),
+                Vector::new(1.0, 1.0, 6.0)
+            );
+        }
+
+        #[test]
+        fn adding_a_point_to_a_vector() {
+            let p = Vector::new(1.0, 2.0, 3.0) + Point::new(1.0, 1.0, 1.0);
+            assert_eq!(p, Point::new(2.0, 3.0, 4.0));
+            assert_eq!(p.w(), 1.0);
+        }
+
+        #[test]
+        fn subtracting_two_vectors() {
+            let v = Vector::new(3.0, 2.0, 1.0) - Vector::new(5.0, 6.0, 7.0);
+            assert_eq!(v, Vector::new(-2.0, -4.0, -6.0));
+            assert_eq!(v.w(), 0.0);
+        }
+
+        #[test]
+        fn subtracting_a_vector_from_the_zero_vector() {
+            assert_eq!(
+                Vector::zero() - Vector::new(1.0, -2.0, 3.0),
+                Vector::new(-1.0, 2.0, -3.0)
+            );
+        }
+
+        #[test]
+        fn negating_a_vector() {
+            assert_eq!(-Vector::new(1.0, -2.0, 3.0), Vector::new(-1.0, 2.0, -3.0));
+        }
+
+        #[test]
+        fn scaling_a_vector() {
+            assert_eq!(
+                Vector::new(1.0, -2.0, 3.0) * 3.5,
+                Vector::new(3.5, -7.0, 10.5)
+            );
+            assert_eq!(
+                Vector::new(1.0, -2.0, 3.0) / 2.0,
+                Vector::new(0.5, -1.0, 1.5)
+            );
+        }
+    }
+
+    mod magnitude {
+        use super::*;
+
+        #[test]
+        fn unit_vectors() {
+            assert_eq!(Vector::new(1.0, 0.0, 0.0).magnitude(), 1.0);
+            assert_eq!(Vector::new(0.0, 1.0, 0.0).magnitude(), 1.0);
+            assert_eq!(Vector::new(0.0, 0.0, 1.0).magnitude(), 1.0);
+        }
+
+        #[test]
+        fn arbitrary_vectors() {
+            assert_eq!(Vector::new(1.0, 2.0, 3.0).magnitude(), Scalar::sqrt(14.0));
+            assert_eq!(
+                Vector::new(-1.0, -2.0, -3.0).magnitude(),
+                Scalar::sqrt(14.0)
+            );
+        }
+
+        #[test]
+        fn normalizing() {
+            assert_eq!(
+                Vector::new(4.0, 0.0, 0.0).normalize(),
+                Vector::new(1.0, 0.0, 0.0)
+            );
+            let v = Vector::new(1.0, 2.0, 3.0).normalize();
+            let len = Scalar::sqrt(14.0);
+            assert_eq!(v, Vector::new(1.0 / len, 2.0 / len, 3.0 / len));
+            assert!((v.magnitude() - 1.0).abs() < 0.00001);
+        }
+    }
+
+    mod products {
+        use super::*;
+
+        #[test]
+        fn dot_product() {
+            assert_eq!(
+                Vector::new(1.0, 2.0, 3.0).dot(&Vector::new(2.0, 3.0, 4.0)),
+                20.0
+            );
+        }
+
+        #[test]
+        fn cross_product() {
+            let a = Vector::new(1.0, 2.0, 3.0);
+            let b = Vector::new(2.0, 3.0, 4.0);
+            assert_eq!(a.cross(&b), Vector::new(-1.0, 2.0, -1.0));
+            assert_eq!(b.cross(&a), Vector::new(1.0, -2.0, 1.0));
+        }
+    }
+
+    mod reflection {
+        use super::*;
+
+        #[test]
+        fn reflecting_a_vector_approaching_at_45_degrees() {
+            let v = Vector::new(1.0, -1.0, 0.0);
+            let n = Vector::new(0.0, 1.0, 0.0);
+            assert_eq!(v.reflect(&n), Vector::new(1.0, 1.0, 0.0));
+        }
+
+        #[test]
+        fn reflecting_a_vector_off_a_slanted_surface() {
+            let v = Vector::new(0.0, -1.0, 0.0);
+            let h = Scalar::sqrt(2.0) / 2.0;
+            let n = Vector::new(h, h, 0.0);
+            assert_eq!(v.reflect(&n), Vector::new(1.0, 0.0, 0.0));
+        }
+    }
+}
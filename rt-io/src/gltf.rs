@@ -0,0 +1,984 @@
+use crate::image_reader::decode_canvas;
+use crate::yaml_node::{Node, Value};
+use rt_core::{from_f64, Canvas, Color, Matrix, Point, Scalar, Vector};
+use rt_shapes::{
+    Group, Material, Shape, SmoothTriangle, Triangle, UvImage, UvPattern, VertexUvMap,
+};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const GLB_MAGIC: &[u8; 4] = b"glTF";
+const GLB_JSON_CHUNK: u32 = 0x4e4f_534a;
+const GLB_BIN_CHUNK: u32 = 0x004e_4942;
+const TRIANGLES: usize = 4;
+const MAX_NODE_DEPTH: usize = 64;
+
+#[derive(Debug)]
+pub enum GltfError {
+    Io(std::io::Error),
+    Invalid(String),
+    Unsupported(String),
+}
+
+impl std::fmt::Display for GltfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GltfError::Io(error) => write!(f, "I/O error: {}", error),
+            GltfError::Invalid(message) => write!(f, "invalid glTF: {}", message),
+            GltfError::Unsupported(what) => write!(f, "unsupported glTF feature: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for GltfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GltfError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for GltfError {
+    fn from(error: std::io::Error) -> Self {
+        GltfError::Io(error)
+    }
+}
+
+fn invalid<T>(message: String) -> Result<T, GltfError> {
+    Err(GltfError::Invalid(message))
+}
+
+#[derive(Debug)]
+pub struct GltfParser {
+    group: Group,
+    materials: Vec<Material>,
+}
+
+impl GltfParser {
+    pub fn parse(data: &[u8], base_dir: &Path) -> Result<Self, GltfError> {
+        let (json, binary) = if data.starts_with(GLB_MAGIC) {
+            split_glb(data)?
+        } else {
+            (data, None)
+        };
+        let json = std::str::from_utf8(json)
+            .map_err(|_| GltfError::Invalid("JSON is not valid UTF-8".into()))?;
+        let root = match Node::parse(json) {
+            Ok(Some(root)) => root,
+            Ok(None) => return invalid("empty document".into()),
+            Err(error) => return invalid(format!("line {}: {}", error.line, error.message)),
+        };
+        let version = root.get("asset").and_then(|asset| asset.get("version"));
+        match version.map(|version| &version.value) {
+            Some(Value::String(version)) if version.starts_with("2.") => {}
+            Some(Value::String(version)) => {
+                return Err(GltfError::Unsupported(format!("version {}", version)))
+            }
+            _ => return invalid("missing asset version".into()),
+        }
+
+        let mut document = Document {
+            root: &root,
+            base_dir: base_dir.to_path_buf(),
+            buffers: Vec::new(),
+            materials: Vec::new(),
+        };
+        document.load_buffers(binary)?;
+        document.load_materials()?;
+        let group = document.scene()?;
+        let materials = document
+            .materials
+            .into_iter()
+            .map(|(material, _)| material)
+            .collect();
+        Ok(GltfParser { group, materials })
+    }
+
+    pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Self, GltfError> {
+        let path = path.as_ref();
+        let base_dir = path
+            .parent()
+            .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+        GltfParser::parse(&std::fs::read(path)?, &base_dir)
+    }
+
+    pub fn materials(&self) -> &[Material] {
+        &self.materials
+    }
+
+    pub fn into_group(self) -> Group {
+        self.group
+    }
+}
+
+fn split_glb(data: &[u8]) -> Result<(&[u8], Option<&[u8]>), GltfError> {
+    let word = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .ok_or_else(|| GltfError::Invalid("truncated binary container".into()))
+    };
+    if word(4)? != 2 {
+        return Err(GltfError::Unsupported(format!(
+            "binary container version {}",
+            word(4)?
+        )));
+    }
+    let end = (word(8)? as usize).min(data.len());
+    let (mut json, mut binary) = (None, None);
+    let mut offset = 12;
+    while offset + 8 <= end {
+        let length = word(offset)? as usize;
+        let chunk = data
+            .get(offset + 8..offset + 8 + length)
+            .ok_or_else(|| GltfError::Invalid("truncated binary chunk".into()))?;
+        match word(offset + 4)? {
+            GLB_JSON_CHUNK if json.is_none() => json = Some(chunk),
+            GLB_BIN_CHUNK if binary.is_none() => binary = Some(chunk),
+            _ => {}
+        }
+        offset += 8 + length;
+    }
+    match json {
+        Some(json) => Ok((json, binary)),
+        None => invalid("binary container has no JSON chunk".into()),
+    }
+}
+
+fn items<'a>(node: &'a Node, key: &str) -> Result<&'a [Node], GltfError> {
+    match node.get(key).map(|value| &value.value) {
+        None => Ok(&[]),
+        Some(Value::Sequence(items)) => Ok(items),
+        Some(_) => invalid(format!("'{}' must be an array", key)),
+    }
+}
+
+fn item<'a>(node: &'a Node, key: &str, index: usize) -> Result<&'a Node, GltfError> {
+    items(node, key)?
+        .get(index)
+        .ok_or_else(|| GltfError::Invalid(format!("{} {} does not exist", key, index)))
+}
+
+fn number(node: &Node, key: &str) -> Result<Option<f64>, GltfError> {
+    match node.get(key).map(|value| &value.value) {
+        None => Ok(None),
+        Some(Value::Number(n)) => Ok(Some(*n)),
+        Some(_) => invalid(format!("'{}' must be a number", key)),
+    }
+}
+
+fn index(node: &Node, key: &str) -> Result<Option<usize>, GltfError> {
+    match number(node, key)? {
+        Some(n) if n >= 0.0 && n.fract() == 0.0 => Ok(Some(n as usize)),
+        Some(_) => invalid(format!("'{}' must be an index", key)),
+        None => Ok(None),
+    }
+}
+
+fn indices(node: &Node, key: &str) -> Result<Vec<usize>, GltfError> {
+    items(node, key)?
+        .iter()
+        .map(|item| match item.value {
+            Value::Number(n) if n >= 0.0 && n.fract() == 0.0 => Ok(n as usize),
+            _ => invalid(format!("'{}' must list indices", key)),
+        })
+        .collect()
+}
+
+fn numbers<const N: usize>(node: &Node, key: &str) -> Result<Option<[f64; N]>, GltfError> {
+    let values = items(node, key)?
+        .iter()
+        .map(|item| match item.value {
+            Value::Number(n) => Ok(n),
+            _ => invalid(format!("'{}' must list numbers", key)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    match values.len() {
+        0 if node.get(key).is_none() => Ok(None),
+        n if n == N => Ok(Some(std::array::from_fn(|i| values[i]))),
+        n => invalid(format!("'{}' must have {} numbers, found {}", key, N, n)),
+    }
+}
+
+fn text<'a>(node: &'a Node, key: &str) -> Option<&'a str> {
+    match node.get(key).map(|value| &value.value) {
+        Some(Value::String(text)) => Some(text),
+        _ => None,
+    }
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut bits, mut count) = (0u32, 0);
+    for c in text.bytes().filter(|&c| c != b'=') {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        bits = (bits << 6) | value as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            bytes.push((bits >> count) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn component_size(kind: usize) -> Option<usize> {
+    match kind {
+        5120 | 5121 => Some(1),
+        5122 | 5123 => Some(2),
+        5125 | 5126 => Some(4),
+        _ => None,
+    }
+}
+
+fn component(bytes: &[u8], kind: usize, normalized: bool) -> f64 {
+    match (kind, normalized) {
+        (5120, false) => bytes[0] as i8 as f64,
+        (5120, true) => (bytes[0] as i8 as f64 / 127.0).max(-1.0),
+        (5121, false) => bytes[0] as f64,
+        (5121, true) => bytes[0] as f64 / 255.0,
+        (5122, normalized) => {
+            let value = i16::from_le_bytes([bytes[0], bytes[1]]) as f64;
+            if normalized {
+                (value / 32767.0).max(-1.0)
+            } else {
+                value
+            }
+        }
+        (5123, normalized) => {
+            let value = u16::from_le_bytes([bytes[0], bytes[1]]) as f64;
+            if normalized {
+                value / 65535.0
+            } else {
+                value
+            }
+        }
+        (5125, _) => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+        _ => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+    }
+}
+
+fn components(kind: &str) -> Option<usize> {
+    match kind {
+        "SCALAR" => Some(1),
+        "VEC2" => Some(2),
+        "VEC3" => Some(3),
+        "VEC4" => Some(4),
+        "MAT4" => Some(16),
+        _ => None,
+    }
+}
+
+fn local_transform(node: &Node) -> Result<Matrix<4, 4>, GltfError> {
+    if let Some(values) = numbers::<16>(node, "matrix")? {
+        return Ok(Matrix::from(std::array::from_fn(|row| {
+            std::array::from_fn(|col| from_f64(values[col * 4 + row]))
+        })));
+    }
+    let [tx, ty, tz] = numbers(node, "translation")?.unwrap_or([0.0; 3]);
+    let [sx, sy, sz] = numbers(node, "scale")?.unwrap_or([1.0; 3]);
+    let [x, y, z, w] = numbers(node, "rotation")?.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+    let length = (x * x + y * y + z * z + w * w).sqrt();
+    let (x, y, z, w) = if length > 0.0 {
+        (x / length, y / length, z / length, w / length)
+    } else {
+        (0.0, 0.0, 0.0, 1.0)
+    };
+    let rotation = Matrix::from(
+        [
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - z * w),
+                2.0 * (x * z + y * w),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + z * w),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - x * w),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - y * w),
+                2.0 * (y * z + x * w),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ]
+        .map(|row| row.map(from_f64)),
+    );
+    let translation = Matrix::translation(from_f64(tx), from_f64(ty), from_f64(tz));
+    let scaling = Matrix::scaling(from_f64(sx), from_f64(sy), from_f64(sz));
+    Ok(translation * rotation * scaling)
+}
+
+type BaseColorTexture = (Arc<dyn UvPattern>, usize);
+
+struct Primitive {
+    positions: Vec<Point>,
+    normals: Option<Vec<Vector>>,
+    uvs: Option<Vec<(Scalar, Scalar)>>,
+    indices: Vec<usize>,
+    material: Option<usize>,
+}
+
+struct Document<'a> {
+    root: &'a Node,
+    base_dir: PathBuf,
+    buffers: Vec<Vec<u8>>,
+    materials: Vec<(Material, Option<BaseColorTexture>)>,
+}
+
+impl Document<'_> {
+    fn resolve(&self, uri: &str) -> Result<Vec<u8>, GltfError> {
+        match uri.strip_prefix("data:") {
+            Some(data) => match data.split_once(";base64,") {
+                Some((_, encoded)) => decode_base64(encoded)
+                    .ok_or_else(|| GltfError::Invalid("malformed base64 data URI".into())),
+                None => Err(GltfError::Unsupported("data URIs without base64".into())),
+            },
+            None => Ok(std::fs::read(self.base_dir.join(percent_decode(uri)))?),
+        }
+    }
+
+    fn load_buffers(&mut self, mut binary: Option<&[u8]>) -> Result<(), GltfError> {
+        for (i, buffer) in items(self.root, "buffers")?.iter().enumerate() {
+            let data = match text(buffer, "uri") {
+                Some(uri) => self.resolve(uri)?,
+                None => binary
+                    .take()
+                    .ok_or_else(|| GltfError::Invalid(format!("buffer {} has no data", i)))?
+                    .to_vec(),
+            };
+            let length = index(buffer, "byteLength")?.unwrap_or(data.len());
+            if data.len() < length {
+                return invalid(format!(
+                    "buffer {} holds {} bytes, expected {}",
+                    i,
+                    data.len(),
+                    length
+                ));
+            }
+            self.buffers.push(data);
+        }
+        Ok(())
+    }
+
+    fn view(&self, i: usize) -> Result<(&[u8], Option<usize>), GltfError> {
+        let view = item(self.root, "bufferViews", i)?;
+        let buffer = index(view, "buffer")?
+            .and_then(|buffer| self.buffers.get(buffer))
+            .ok_or_else(|| GltfError::Invalid(format!("buffer view {} has no buffer", i)))?;
+        let offset = index(view, "byteOffset")?.unwrap_or(0);
+        let length = index(view, "byteLength")?.unwrap_or(0);
+        let bytes = buffer
+            .get(offset..offset + length)
+            .ok_or_else(|| GltfError::Invalid(format!("buffer view {} is out of range", i)))?;
+        Ok((bytes, index(view, "byteStride")?))
+    }
+
+    fn accessor(&self, i: usize, expected: usize, what: &str) -> Result<Vec<f64>, GltfError> {
+        let accessor = item(self.root, "accessors", i)?;
+        if accessor.get("sparse").is_some() {
+            return Err(GltfError::Unsupported("sparse accessors".into()));
+        }
+        let count = index(accessor, "count")?.unwrap_or(0);
+        let kind = index(accessor, "componentType")?.unwrap_or(0);
+        let size = component_size(kind).ok_or_else(|| {
+            GltfError::Invalid(format!("accessor {} has an unknown component type", i))
+        })?;
+        let width = text(accessor, "type").and_then(components);
+        if width != Some(expected) {
+            return invalid(format!("accessor {} has the wrong type for {}", i, what));
+        }
+        let normalized = matches!(
+            accessor.get("normalized").map(|value| &value.value),
+            Some(Value::Bool(true))
+        );
+        let view = match index(accessor, "bufferView")? {
+            Some(view) => view,
+            None => return Ok(vec![0.0; count * expected]),
+        };
+        let (bytes, stride) = self.view(view)?;
+        let offset = index(accessor, "byteOffset")?.unwrap_or(0);
+        let element = size * expected;
+        let stride = stride.unwrap_or(element);
+        let needed = match count {
+            0 => 0,
+            count => offset + stride * (count - 1) + element,
+        };
+        if needed > bytes.len() {
+            return invalid(format!("accessor {} reads past its buffer view", i));
+        }
+        let mut values = Vec::with_capacity(count * expected);
+        for n in 0..count {
+            let start = offset + n * stride;
+            for c in 0..expected {
+                let at = start + c * size;
+                values.push(component(&bytes[at..at + size], kind, normalized));
+            }
+        }
+        Ok(values)
+    }
+
+    fn load_materials(&mut self) -> Result<(), GltfError> {
+        let mut canvases: HashMap<usize, Canvas> = HashMap::new();
+        let mut materials = Vec::new();
+        for node in items(self.root, "materials")? {
+            let mut material = Material::default();
+            let pbr = node.get("pbrMetallicRoughness");
+            let [r, g, b, alpha] = match pbr {
+                Some(pbr) => numbers(pbr, "baseColorFactor")?,
+                None => None,
+            }
+            .unwrap_or([1.0; 4]);
+            let factor = Color::new(from_f64(r), from_f64(g), from_f64(b));
+            material.color = factor;
+            if text(node, "alphaMode") == Some("BLEND") {
+                material.transparency = from_f64(1.0 - alpha);
+            }
+            if let Some([r, g, b]) = numbers(node, "emissiveFactor")? {
+                material.emissive = Color::new(from_f64(r), from_f64(g), from_f64(b));
+            }
+            if let Some(pbr) = pbr {
+                let metallic = number(pbr, "metallicFactor")?.unwrap_or(1.0);
+                let roughness = number(pbr, "roughnessFactor")?.unwrap_or(1.0);
+                material.reflective = from_f64(metallic * (1.0 - roughness));
+            }
+
+            let texture = match pbr.and_then(|pbr| pbr.get("baseColorTexture")) {
+                Some(info) => {
+                    let texture = index(info, "index")?
+                        .ok_or_else(|| GltfError::Invalid("texture info has no index".into()))?;
+                    let image = index(item(self.root, "textures", texture)?, "source")?
+                        .ok_or_else(|| {
+                            GltfError::Unsupported(format!(
+                                "texture {} without a PNG or JPEG source",
+                                texture
+                            ))
+                        })?;
+                    let mut canvas = match canvases.entry(image) {
+                        Entry::Occupied(entry) => entry.get().clone(),
+                        Entry::Vacant(entry) => entry.insert(self.image(image)?).clone(),
+                    };
+                    if factor != Color::white() {
+                        for y in 0..canvas.height() {
+                            for x in 0..canvas.width() {
+                                canvas.write_pixel(x, y, canvas.pixel_at(x, y) * factor);
+                            }
+                        }
+                    }
+                    let uv_pattern: Arc<dyn UvPattern> = Arc::new(UvImage::new(canvas));
+                    Some((uv_pattern, index(info, "texCoord")?.unwrap_or(0)))
+                }
+                None => None,
+            };
+            materials.push((material, texture));
+        }
+        self.materials = materials;
+        Ok(())
+    }
+
+    fn image(&self, i: usize) -> Result<Canvas, GltfError> {
+        let image = item(self.root, "images", i)?;
+        let bytes = match (text(image, "uri"), index(image, "bufferView")?) {
+            (Some(uri), _) => self.resolve(uri)?,
+            (None, Some(view)) => self.view(view)?.0.to_vec(),
+            (None, None) => return invalid(format!("image {} has no data", i)),
+        };
+        decode_canvas(&bytes).map_err(|error| GltfError::Invalid(format!("image {}: {}", i, error)))
+    }
+
+    fn primitive(&self, node: &Node) -> Result<Primitive, GltfError> {
+        let mode = index(node, "mode")?.unwrap_or(TRIANGLES);
+        if mode != TRIANGLES {
+            return Err(GltfError::Unsupported(format!("primitive mode {}", mode)));
+        }
+        let attributes = node
+            .get("attributes")
+            .ok_or_else(|| GltfError::Invalid("primitive has no attributes".into()))?;
+        let position = index(attributes, "POSITION")?
+            .ok_or_else(|| GltfError::Invalid("primitive has no POSITION".into()))?;
+        let triples = |values: Vec<f64>| -> Vec<[Scalar; 3]> {
+            values
+                .chunks_exact(3)
+                .map(|xyz| [from_f64(xyz[0]), from_f64(xyz[1]), from_f64(xyz[2])])
+                .collect()
+        };
+        let positions: Vec<Point> = triples(self.accessor(position, 3, "POSITION")?)
+            .into_iter()
+            .map(|[x, y, z]| Point::new(x, y, z))
+            .collect();
+        let normals = match index(attributes, "NORMAL")? {
+            Some(normal) => Some(
+                triples(self.accessor(normal, 3, "NORMAL")?)
+                    .into_iter()
+                    .map(|[x, y, z]| Vector::new(x, y, z))
+                    .collect::<Vec<_>>(),
+            ),
+            None => None,
+        };
+        let material = index(node, "material")?;
+        let set = material
+            .and_then(|material| self.materials.get(material))
+            .and_then(|(_, texture)| texture.as_ref())
+            .map_or(0, |(_, set)| *set);
+        let uvs = match index(attributes, &format!("TEXCOORD_{}", set))? {
+            Some(uv) => Some(
+                self.accessor(uv, 2, "TEXCOORD")?
+                    .chunks_exact(2)
+                    .map(|uv| (from_f64(uv[0]), from_f64(1.0 - uv[1])))
+                    .collect::<Vec<_>>(),
+            ),
+            None => None,
+        };
+        let indices = match index(node, "indices")? {
+            Some(accessor) => self
+                .accessor(accessor, 1, "indices")?
+                .into_iter()
+                .map(|i| i as usize)
+                .collect(),
+            None => (0..positions.len()).collect::<Vec<_>>(),
+        };
+        let vertices = positions.len();
+        let short = |values: Option<usize>| values.is_some_and(|n| n < vertices);
+        if indices.iter().any(|&i| i >= vertices)
+            || short(normals.as_ref().map(Vec::len))
+            || short(uvs.as_ref().map(Vec::len))
+        {
+            return invalid("primitive indexes past its vertices".into());
+        }
+        Ok(Primitive {
+            positions,
+            normals,
+            uvs,
+            indices,
+            material,
+        })
+    }
+
+    fn triangles(&self, primitive: &Primitive) -> Result<Vec<Box<dyn Shape>>, GltfError> {
+        let (base, texture) = match primitive.material {
+            Some(i) => self
+                .materials
+                .get(i)
+                .map(|(material, texture)| (material.clone(), texture.as_ref()))
+                .ok_or_else(|| GltfError::Invalid(format!("material {} does not exist", i)))?,
+            None => (Material::default(), None),
+        };
+        let mut triangles: Vec<Box<dyn Shape>> = Vec::new();
+        for corners in primitive.indices.chunks_exact(3) {
+            let [a, b, c] = [corners[0], corners[1], corners[2]];
+            let points = [a, b, c].map(|i| primitive.positions[i]);
+            if (points[1] - points[0])
+                .cross(&(points[2] - points[0]))
+                .magnitude()
+                == 0.0
+            {
+                continue;
+            }
+            let mut triangle: Box<dyn Shape> = match &primitive.normals {
+                Some(normals) => Box::new(SmoothTriangle::new(
+                    points[0], points[1], points[2], normals[a], normals[b], normals[c],
+                )),
+                None => Box::new(Triangle::new(points[0], points[1], points[2])),
+            };
+            let mut material = base.clone();
+            if let (Some((uv_pattern, _)), Some(uvs)) = (texture, &primitive.uvs) {
+                let uvs = [uvs[a], uvs[b], uvs[c]];
+                let map = VertexUvMap::new(Arc::clone(uv_pattern), points, uvs);
+                material.pattern = Some(Arc::new(map));
+            }
+            triangle.set_material(material);
+            triangles.push(triangle);
+        }
+        Ok(triangles)
+    }
+
+    fn mesh(&self, i: usize) -> Result<Vec<Box<dyn Shape>>, GltfError> {
+        let mesh = item(self.root, "meshes", i)?;
+        let mut triangles = Vec::new();
+        for primitive in items(mesh, "primitives")? {
+            triangles.extend(self.triangles(&self.primitive(primitive)?)?);
+        }
+        Ok(triangles)
+    }
+
+    fn node(&self, i: usize, depth: usize) -> Result<Option<Group>, GltfError> {
+        if depth >= MAX_NODE_DEPTH {
+            return invalid("node hierarchy is cyclic or too deep".into());
+        }
+        let node = item(self.root, "nodes", i)?;
+        let mut group = Group::new();
+        if let Some(mesh) = index(node, "mesh")? {
+            for triangle in self.mesh(mesh)? {
+                group.add_child(triangle);
+            }
+        }
+        for child in indices(node, "children")? {
+            if let Some(child) = self.node(child, depth + 1)? {
+                group.add_child(Box::new(child));
+            }
+        }
+        if group.is_empty() {
+            return Ok(None);
+        }
+        group.set_transform(local_transform(node)?);
+        Ok(Some(group))
+    }
+
+    fn scene(&self) -> Result<Group, GltfError> {
+        let roots = match items(self.root, "scenes")? {
+            [] => {
+                let nodes = items(self.root, "nodes")?;
+                let mut children = Vec::new();
+                for node in nodes {
+                    children.extend(indices(node, "children")?);
+                }
+                (0..nodes.len()).filter(|i| !children.contains(i)).collect()
+            }
+            scenes => {
+                let scene = index(self.root, "scene")?.unwrap_or(0);
+                let scene = scenes
+                    .get(scene)
+                    .ok_or_else(|| GltfError::Invalid(format!("scene {} does not exist", scene)))?;
+                indices(scene, "nodes")?
+            }
+        };
+        let mut group = Group::new();
+        for root in roots {
+            if let Some(node) = self.node(root, 0)? {
+                group.add_child(Box::new(node));
+            }
+        }
+        // glTF is right-handed; mirroring z keeps assets from rendering flipped.
+        group.set_transform(Matrix::scaling(1.0, 1.0, -1.0));
+        Ok(group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::consts::FRAC_1_SQRT_2;
+    use std::any::Any;
+
+    const TRIANGLE: [f32; 9] = [0.0, 1.0, 0.0, -1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+    fn base64(bytes: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut encoded = String::new();
+        for chunk in bytes.chunks(3) {
+            let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, &byte)| {
+                bits | (byte as u32) << (16 - 8 * i)
+            });
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    encoded.push(ALPHABET[(bits >> (18 - 6 * i) & 63) as usize] as char);
+                } else {
+                    encoded.push('=');
+                }
+            }
+        }
+        encoded
+    }
+
+    fn floats(values: &[f32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    fn gltf(buffer: &[u8], body: &str) -> Vec<u8> {
+        format!(
+            r#"{{
+  "asset": {{ "version": "2.0" }},
+  "buffers": [{{
+    "uri": "data:application/octet-stream;base64,{}",
+    "byteLength": {}
+  }}],
+  {}
+}}"#,
+            base64(buffer),
+            buffer.len(),
+            body
+        )
+        .into_bytes()
+    }
+
+    fn parse(data: &[u8]) -> Result<GltfParser, GltfError> {
+        GltfParser::parse(data, Path::new("."))
+    }
+
+    fn downcast<T: 'static>(shape: &dyn Shape) -> &T {
+        (shape as &dyn Any).downcast_ref::<T>().unwrap()
+    }
+
+    fn only_child(group: &Group) -> &dyn Shape {
+        assert_eq!(group.len(), 1);
+        group.children()[0].as_ref()
+    }
+
+    fn triangle_mesh(buffer: &[u8], node: &str) -> Vec<u8> {
+        gltf(
+            buffer,
+            &format!(
+                r#""bufferViews": [{{ "buffer": 0, "byteLength": 36 }}],
+  "accessors": [{{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }}],
+  "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }} }}] }}],
+  "nodes": [{}]"#,
+                node
+            ),
+        )
+    }
+
+    #[test]
+    fn a_mesh_node_becomes_a_group_of_triangles() {
+        let data = triangle_mesh(&floats(&TRIANGLE), r#"{ "mesh": 0 }"#);
+        let root = parse(&data).unwrap().into_group();
+        assert_eq!(*root.transform(), Matrix::scaling(1.0, 1.0, -1.0));
+        let node = downcast::<Group>(only_child(&root));
+        let triangle = downcast::<Triangle>(only_child(node));
+        assert_eq!(triangle.p1(), Point::new(0.0, 1.0, 0.0));
+        assert_eq!(triangle.p3(), Point::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn indexed_primitives_with_normals_become_smooth_triangles() {
+        let mut buffer = floats(&[
+            -1.0, -1.0, 0.0, 1.0, -1.0, 0.0, 1.0, 1.0, 0.0, -1.0, 1.0, 0.0,
+        ]);
+        buffer.extend(floats(&[0.0, 0.0, 1.0].repeat(4)));
+        buffer.extend([0u16, 1, 2, 0, 2, 3].iter().flat_map(|i| i.to_le_bytes()));
+        let data = gltf(
+            &buffer,
+            r#""bufferViews": [
+    { "buffer": 0, "byteLength": 48 },
+    { "buffer": 0, "byteOffset": 48, "byteLength": 48 },
+    { "buffer": 0, "byteOffset": 96, "byteLength": 12 }
+  ],
+  "accessors": [
+    { "bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3" },
+    { "bufferView": 1, "componentType": 5126, "count": 4, "type": "VEC3" },
+    { "bufferView": 2, "componentType": 5123, "count": 6, "type": "SCALAR" }
+  ],
+  "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0, "NORMAL": 1 }, "indices": 2 }] }],
+  "scenes": [{ "nodes": [0] }],
+  "nodes": [{ "mesh": 0 }]"#,
+        );
+        let root = parse(&data).unwrap().into_group();
+        let node = downcast::<Group>(only_child(&root));
+        assert_eq!(node.len(), 2);
+        let second = downcast::<SmoothTriangle>(node.children()[1].as_ref());
+        assert_eq!(second.p3(), Point::new(-1.0, 1.0, 0.0));
+        assert_eq!(second.n1(), Vector::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn node_transforms_compose_down_the_hierarchy() {
+        let data = triangle_mesh(
+            &floats(&TRIANGLE),
+            r#"{ "translation": [1, 0, 0], "children": [1] },
+    { "mesh": 0, "scale": [2, 2, 2], "rotation": [0, 0.7071068, 0, 0.7071068] }"#,
+        );
+        let root = parse(&data).unwrap().into_group();
+        let parent = downcast::<Group>(only_child(&root));
+        let child = downcast::<Group>(only_child(parent));
+        let expected = Matrix::scaling(1.0, 1.0, -1.0)
+            * Matrix::translation(1.0, 0.0, 0.0)
+            * Matrix::rotation_y(std::f64::consts::FRAC_PI_2 as Scalar)
+            * Matrix::scaling(2.0, 2.0, 2.0);
+        assert_eq!(*child.transform(), expected);
+        assert_eq!(*child.children()[0].transform(), expected);
+    }
+
+    #[test]
+    fn node_matrices_are_column_major() {
+        let data = triangle_mesh(
+            &floats(&TRIANGLE),
+            r#"{ "mesh": 0, "matrix": [1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 4, 5, 6, 1] }"#,
+        );
+        let root = parse(&data).unwrap().into_group();
+        assert_eq!(
+            *only_child(&root).transform(),
+            Matrix::scaling(1.0, 1.0, -1.0) * Matrix::translation(4.0, 5.0, 6.0)
+        );
+    }
+
+    #[test]
+    fn binary_containers_carry_their_own_buffer() {
+        let json = br#"{
+  "asset": { "version": "2.0" },
+  "buffers": [{ "byteLength": 36 }],
+  "bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+  "accessors": [{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }],
+  "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+  "nodes": [{ "mesh": 0 }]
+}   "#;
+        let binary = floats(&TRIANGLE);
+        let mut data = b"glTF".to_vec();
+        let length = 12 + 8 + json.len() + 8 + binary.len();
+        for word in [2, length as u32, json.len() as u32, GLB_JSON_CHUNK] {
+            data.extend(word.to_le_bytes());
+        }
+        data.extend(json);
+        data.extend((binary.len() as u32).to_le_bytes());
+        data.extend(GLB_BIN_CHUNK.to_le_bytes());
+        data.extend(&binary);
+        let root = parse(&data).unwrap().into_group();
+        let node = downcast::<Group>(only_child(&root));
+        assert_eq!(
+            downcast::<Triangle>(only_child(node)).p2(),
+            Point::new(-1.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn materials_carry_base_color_emission_and_transparency() {
+        let data = gltf(
+            &floats(&TRIANGLE),
+            r#""bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+  "accessors": [{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }],
+  "materials": [{
+    "pbrMetallicRoughness": { "baseColorFactor": [1, 0.5, 0.25, 0.75], "metallicFactor": 1, "roughnessFactor": 0.5 },
+    "emissiveFactor": [0, 0, 2],
+    "alphaMode": "BLEND"
+  }],
+  "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 }, "material": 0 }] }],
+  "nodes": [{ "mesh": 0 }]"#,
+        );
+        let parser = parse(&data).unwrap();
+        let material = &parser.materials()[0];
+        assert_eq!(material.color, Color::new(1.0, 0.5, 0.25));
+        assert_eq!(material.emissive, Color::new(0.0, 0.0, 2.0));
+        assert_eq!(material.transparency, 0.25);
+        assert_eq!(material.reflective, 0.5);
+        let root = parser.into_group();
+        let node = downcast::<Group>(only_child(&root));
+        assert_eq!(
+            only_child(node).material().color,
+            Color::new(1.0, 0.5, 0.25)
+        );
+    }
+
+    #[test]
+    fn base_color_textures_follow_the_vertex_uvs() {
+        let pixels = image::RgbImage::from_raw(2, 1, vec![255, 0, 0, 0, 0, 255]).unwrap();
+        let mut png = std::io::Cursor::new(Vec::new());
+        pixels.write_to(&mut png, image::ImageFormat::Png).unwrap();
+        let png = png.into_inner();
+        let mut buffer = floats(&TRIANGLE);
+        buffer.extend(floats(&[0.5, 0.0, 0.0, 1.0, 1.0, 1.0]));
+        let data = gltf(
+            &buffer,
+            &format!(
+                r#""bufferViews": [
+    {{ "buffer": 0, "byteLength": 36 }},
+    {{ "buffer": 0, "byteOffset": 36, "byteLength": 24 }}
+  ],
+  "accessors": [
+    {{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }},
+    {{ "bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC2" }}
+  ],
+  "images": [{{ "uri": "data:image/png;base64,{}" }}],
+  "textures": [{{ "source": 0 }}],
+  "materials": [{{ "pbrMetallicRoughness": {{ "baseColorTexture": {{ "index": 0 }} }} }}],
+  "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0, "TEXCOORD_0": 1 }}, "material": 0 }}] }}],
+  "nodes": [{{ "mesh": 0 }}]"#,
+                base64(&png)
+            ),
+        );
+        let root = parse(&data).unwrap().into_group();
+        let node = downcast::<Group>(only_child(&root));
+        let triangle = only_child(node);
+        let color_at = |x: Scalar| {
+            let local = Point::new(x, 0.1, 0.0);
+            let world = *triangle.transform() * local;
+            triangle.material().color_at(triangle, &world)
+        };
+        assert_eq!(color_at(-0.8), Color::new(1.0, 0.0, 0.0));
+        assert_eq!(color_at(0.8), Color::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn unsupported_and_malformed_files_are_reported() {
+        let old = br#"{ "asset": { "version": "1.0" } }"#;
+        assert!(matches!(parse(old), Err(GltfError::Unsupported(_))));
+
+        let lines = gltf(
+            &floats(&TRIANGLE),
+            r#""bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+  "accessors": [{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }],
+  "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 }, "mode": 1 }] }],
+  "nodes": [{ "mesh": 0 }]"#,
+        );
+        assert_eq!(
+            parse(&lines).unwrap_err().to_string(),
+            "unsupported glTF feature: primitive mode 1"
+        );
+
+        let short = triangle_mesh(&floats(&TRIANGLE[..6]), r#"{ "mesh": 0 }"#);
+        assert!(matches!(parse(&short), Err(GltfError::Invalid(_))));
+
+        let cyclic = triangle_mesh(
+            &floats(&TRIANGLE),
+            r#"{ "children": [1] }, { "mesh": 0, "children": [1] }"#,
+        );
+        assert_eq!(
+            parse(&cyclic).unwrap_err().to_string(),
+            "invalid glTF: node hierarchy is cyclic or too deep"
+        );
+    }
+
+    #[test]
+    fn rotations_are_normalized_quaternions() {
+        let data = triangle_mesh(
+            &floats(&TRIANGLE),
+            &format!(
+                r#"{{ "mesh": 0, "rotation": [{}, 0, 0, {}] }}"#,
+                2.0 * FRAC_1_SQRT_2,
+                2.0 * FRAC_1_SQRT_2
+            ),
+        );
+        let root = parse(&data).unwrap().into_group();
+        assert_eq!(
+            *only_child(&root).transform(),
+            Matrix::scaling(1.0, 1.0, -1.0)
+                * Matrix::rotation_x(std::f64::consts::FRAC_PI_2 as Scalar)
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use crate::ImageError;
+use rt_core::{Canvas, Color, Scalar};
+use std::path::Path;
+
+pub fn canvas_from_ppm(source: &str) -> Result<Canvas, ImageError> {
+    let mut tokens = source
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .flat_map(str::split_whitespace);
+
+    if tokens.next() != Some("P3") {
+        return Err(ImageError::Decoding("expected PPM magic number P3".into()));
+    }
+    let mut next_number = |what: &str| -> Result<usize, ImageError> {
+        let token = tokens
+            .next()
+            .ok_or_else(|| ImageError::Decoding(format!("missing {}", what)))?;
+        number(token, what)
+    };
+    let width = next_number("width")?;
+    let height = next_number("height")?;
+    let maximum = next_number("maximum color value")?;
+    if maximum == 0 {
+        return Err(ImageError::Decoding(
+            "maximum color value must be positive".into(),
+        ));
+    }
+
+    let samples: Vec<&str> = tokens.collect();
+    let expected = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(3))
+        .ok_or_else(|| ImageError::Decoding(format!("image too large: {}x{}", width, height)))?;
+    if samples.len() < expected {
+        return Err(ImageError::Decoding("missing pixel value".into()));
+    }
+
+    let sample = |token: &str| -> Result<Scalar, ImageError> {
+        let value = number(token, "pixel value")?;
+        if value > maximum {
+            return Err(ImageError::Decoding(format!(
+                "pixel value {} exceeds the maximum {}",
+                value, maximum
+            )));
+        }
+        Ok(value as Scalar / maximum as Scalar)
+    };
+    let mut canvas = Canvas::new(width, height);
+    for (i, rgb) in samples[..expected].chunks_exact(3).enumerate() {
+        let color = Color::new(sample(rgb[0])?, sample(rgb[1])?, sample(rgb[2])?);
+        canvas.write_pixel(i % width, i / width, color);
+    }
+    Ok(canvas)
+}
+
+fn number(token: &str, what: &str) -> Result<usize, ImageError> {
+    token
+        .parse()
+        .map_err(|_| ImageError::Decoding(format!("invalid {}: {:?}", what, token)))
+}
+
+pub fn load_canvas<P: AsRef<Path>>(path: P) -> Result<Canvas, ImageError> {
+    let path = path.as_ref();
+    let is_ppm = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ppm"));
+    if is_ppm {
+        return canvas_from_ppm(&std::fs::read_to_string(path)?);
+    }
+
+    let image = image::open(path).map_err(|error| ImageError::Decoding(error.to_string()))?;
+    Ok(canvas_from_image(image))
+}
+
+pub(crate) fn decode_canvas(bytes: &[u8]) -> Result<Canvas, ImageError> {
+    let image =
+        image::load_from_memory(bytes).map_err(|error| ImageError::Decoding(error.to_string()))?;
+    Ok(canvas_from_image(image))
+}
+
+fn canvas_from_image(image: image::DynamicImage) -> Canvas {
+    let image = image.to_rgb32f();
+    let mut canvas = Canvas::new(image.width() as usize, image.height() as usize);
+    for (x, y, pixel) in image.enumerate_pixels() {
+        let [red, green, blue] = pixel.0;
+        canvas.write_pixel(
+            x as usize,
+            y as usize,
+            Color::new(red as Scalar, green as Scalar, blue as Scalar),
+        );
+    }
+    canvas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save_canvas;
+
+    mod ppm {
+        use super::*;
+
+        #[test]
+        fn reading_a_file_with_the_wrong_magic_number() {
+            let ppm = "P32\n1 1\n255\n0 0 0\n";
+            assert!(matches!(canvas_from_ppm(ppm), Err(ImageError::Decoding(_))));
+        }
+
+        #[test]
+        fn headers_are_checked_against_the_pixel_data() {
+            for ppm in [
+                "P3\n4294967296 4294967297\n255\n",
+                "P3\n100000 100000\n255\n0 0 0\n",
+                "P3\n1 1\n0\n0 0 0\n",
+                "P3\n1 1\n255\n0 256 0\n",
+            ] {
+                assert!(
+                    matches!(canvas_from_ppm(ppm), Err(ImageError::Decoding(_))),
+                    "{:?}",
+                    ppm
+                );
+            }
+        }
+
+        #[test]
+        fn reading_a_ppm_returns_a_canvas() {
+            let ppm = "P3\n10 2\n255\n\
+                0 0 0  1 1 1  2 2 2  3 3 3  4 4 4  5 5 5  6 6 6  7 7 7  8 8 8  9 9 9\n\
+                0 0 0  1 1 1  2 2 2  3 3 3  4 4 4  5 5 5  6 6 6  7 7 7  8 8 8  9 9 9\n";
+            let canvas = canvas_from_ppm(ppm).unwrap();
+            assert_eq!(canvas.width(), 10);
+            assert_eq!(canvas.height(), 2);
+        }
+
+        #[test]
+        fn reading_pixel_data_from_a_ppm_file() {
+            let ppm = "P3\n4 3\n255\n\
+                255 127 0  0 127 255  127 255 0  255 255 255\n\
+                0 0 0  255 0 0  0 255 0  0 0 255\n\
+                255 255 0  0 255 255  255 0 255  127 127 127\n";
+            let canvas = canvas_from_ppm(ppm).unwrap();
+            let cases = [
+                (0, 0, Color::new(1.0, 0.49804, 0.0)),
+                (1, 0, Color::new(0.0, 0.49804, 1.0)),
+                (2, 0, Color::new(0.49804, 1.0, 0.0)),
+                (3, 0, Color::new(1.0, 1.0, 1.0)),
+                (0, 1, Color::new(0.0, 0.0, 0.0)),
+                (1, 1, Color::new(1.0, 0.0, 0.0)),
+                (2, 1, Color::new(0.0, 1.0, 0.0)),
+                (3, 1, Color::new(0.0, 0.0, 1.0)),
+                (0, 2, Color::new(1.0, 1.0, 0.0)),
+                (1, 2, Color::new(0.0, 1.0, 1.0)),
+                (2, 2, Color::new(1.0, 0.0, 1.0)),
+                (3, 2, Color::new(0.49804, 0.49804, 0.49804)),
+            ];
+            for (x, y, color) in cases {
+                assert_eq!(canvas.pixel_at(x, y), color, "({}, {})", x, y);
+            }
+        }
+
+        #[test]
+        fn ppm_parsing_ignores_comment_lines() {
+            let ppm = "P3\n# this is a comment\n2 1\n# this, too\n255\n\
+                # another comment\n255 255 255\n# oh, no, comments in the pixel data!\n255 0 255\n";
+            let canvas = canvas_from_ppm(ppm).unwrap();
+            assert_eq!(canvas.pixel_at(0, 0), Color::new(1.0, 1.0, 1.0));
+            assert_eq!(canvas.pixel_at(1, 0), Color::new(1.0, 0.0, 1.0));
+        }
+
+        #[test]
+        fn ppm_parsing_allows_an_rgb_triple_to_span_lines() {
+            let ppm = "P3\n1 1\n255\n51\n153\n\n204\n";
+            let canvas = canvas_from_ppm(ppm).unwrap();
+            assert_eq!(canvas.pixel_at(0, 0), Color::new(0.2, 0.6, 0.8));
+        }
+
+        #[test]
+        fn ppm_parsing_respects_the_scale_setting() {
+            let ppm = "P3\n2 2\n100\n100 100 100  50 50 50\n75 50 25  0 0 0\n";
+            let canvas = canvas_from_ppm(ppm).unwrap();
+            assert_eq!(canvas.pixel_at(0, 1), Color::new(0.75, 0.5, 0.25));
+        }
+
+        #[test]
+        fn truncated_pixel_data_is_an_error() {
+            let ppm = "P3\n2 1\n255\n255 255 255\n";
+            assert!(matches!(
+                canvas_from_ppm(ppm),
+                Err(ImageError::Decoding(message)) if message == "missing pixel value"
+            ));
+        }
+    }
+
+    #[test]
+    fn loading_round_trips_through_the_writers() {
+        let mut canvas = Canvas::new(3, 2);
+        canvas.write_pixel(0, 0, Color::new(1.0, 0.0, 0.0));
+        canvas.write_pixel(2, 1, Color::new(0.0, 0.0, 1.0));
+        for extension in ["png", "ppm"] {
+            let path = std::env::temp_dir().join(format!(
+                "rt-io-load-{}.{}",
+                std::process::id(),
+                extension
+            ));
+            save_canvas(&canvas, &path).unwrap();
+            let loaded = load_canvas(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(loaded.to_rgb8(), canvas.to_rgb8(), "{}", extension);
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use image::codecs::hdr::HdrEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::openexr::OpenExrEncoder;
+use image::codecs::png::PngEncoder;
+use image::{ExtendedColorType, ImageEncoder, Rgb};
+use rt_core::{to_f64, Canvas};
+use rt_render::{Aov, RenderOutput};
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum ImageError {
+    UnsupportedFormat(String),
+    Io(std::io::Error),
+    Encoding(String),
+    Decoding(String),
+}
+
+impl std::fmt::Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageError::UnsupportedFormat(extension) => {
+                write!(f, "unsupported image format: {:?}", extension)
+            }
+            ImageError::Io(error) => write!(f, "I/O error: {}", error),
+            ImageError::Encoding(message) => write!(f, "encoding error: {}", message),
+            ImageError::Decoding(message) => write!(f, "decoding error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImageError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ImageError {
+    fn from(error: std::io::Error) -> Self {
+        ImageError::Io(error)
+    }
+}
+
+impl From<image::ImageError> for ImageError {
+    fn from(error: image::ImageError) -> Self {
+        ImageError::Encoding(error.to_string())
+    }
+}
+
+pub trait ImageWriter {
+    fn write(&self, canvas: &Canvas, out: &mut dyn Write) -> Result<(), ImageError>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PpmWriter;
+
+impl ImageWriter for PpmWriter {
+    fn write(&self, canvas: &Canvas, out: &mut dyn Write) -> Result<(), ImageError> {
+        out.write_all(canvas.to_ppm().as_bytes())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PngWriter;
+
+impl ImageWriter for PngWriter {
+    fn write(&self, canvas: &Canvas, out: &mut dyn Write) -> Result<(), ImageError> {
+        PngEncoder::new(out).write_image(
+            &canvas.to_rgb8(),
+            canvas.width() as u32,
+            canvas.height() as u32,
+            ExtendedColorType::Rgb8,
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct JpegWriter {
+    pub quality: u8,
+}
+
+impl Default for JpegWriter {
+    fn default() -> Self {
+        JpegWriter { quality: 90 }
+    }
+}
+
+impl ImageWriter for JpegWriter {
+    fn write(&self, canvas: &Canvas, out: &mut dyn Write) -> Result<(), ImageError> {
+        JpegEncoder::new_with_quality(out, self.quality).write_image(
+            &canvas.to_rgb8(),
+            canvas.width() as u32,
+            canvas.height() as u32,
+            ExtendedColorType::Rgb8,
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HdrWriter;
+
+impl ImageWriter for HdrWriter {
+    fn write(&self, canvas: &Canvas, out: &mut dyn Write) -> Result<(), ImageError> {
+        let pixels: Vec<Rgb<f32>> = radiance(canvas)
+            .chunks(3)
+            .map(|c| Rgb([c[0], c[1], c[2]]))
+            .collect();
+        HdrEncoder::new(out).encode(&pixels, canvas.width(), canvas.height())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExrWriter;
+
+impl ImageWriter for ExrWriter {
+    fn write(&self, canvas: &Canvas, out: &mut dyn Write) -> Result<(), ImageError> {
+        let bytes: Vec<u8> = radiance(canvas)
+            .iter()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect();
+        let mut buffer = Cursor::new(Vec::new());
+        OpenExrEncoder::new(&mut buffer).write_image(
+            &bytes,
+            canvas.width() as u32,
+            canvas.height() as u32,
+            ExtendedColorType::Rgb32F,
+        )?;
+        out.write_all(buffer.get_ref())?;
+        Ok(())
+    }
+}
+
+fn radiance(canvas: &Canvas) -> Vec<f32> {
+    (0..canvas.height())
+        .flat_map(|y| (0..canvas.width()).map(move |x| (x, y)))
+        .map(|(x, y)| canvas.pixel_at(x, y))
+        .flat_map(|c| [c.red(), c.green(), c.blue()].map(|value| to_f64(value) as f32))
+        .map(|value| value.max(0.0))
+        .collect()
+}
+
+pub fn writer_for_path(path: &Path) -> Result<Box<dyn ImageWriter>, ImageError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "ppm" => Ok(Box::new(PpmWriter)),
+        "png" => Ok(Box::new(PngWriter)),
+        "jpg" | "jpeg" => Ok(Box::new(JpegWriter::default())),
+        "hdr" => Ok(Box::new(HdrWriter)),
+        "exr" => Ok(Box::new(ExrWriter)),
+        _ => Err(ImageError::UnsupportedFormat(extension)),
+    }
+}
+
+pub fn save_canvas<P: AsRef<Path>>(canvas: &Canvas, path: P) -> Result<(), ImageError> {
+    let writer = writer_for_path(path.as_ref())?;
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    writer.write(canvas, &mut file)?;
+    file.flush()?;
+    Ok(())
+}
+
+pub fn aov_path(path: &Path, aov: Aov) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut name = format!("{}.{}", stem, aov.name());
+    if let Some(extension) = path.extension() {
+        name.push('.');
+        name.push_str(&extension.to_string_lossy());
+    }
+    path.with_file_name(name)
+}
+
+pub fn save_render_output<P: AsRef<Path>>(
+    output: &RenderOutput,
+    path: P,
+) -> Result<Vec<PathBuf>, ImageError> {
+    let path = path.as_ref();
+    save_canvas(&output.beauty, path)?;
+    let mut written = vec![path.to_path_buf()];
+    for (aov, canvas) in &output.aovs {
+        let aov_path = aov_path(path, *aov);
+        save_canvas(canvas, &aov_path)?;
+        written.push(aov_path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::Color;
+
+    fn test_canvas() -> Canvas {
+        let mut c = Canvas::new(4, 2);
+        c.write_pixel(0, 0, Color::new(1.0, 0.0, 0.0));
+        c.write_pixel(3, 1, Color::new(0.0, 0.0, 1.0));
+        c
+    }
+
+    mod formats {
+        use super::*;
+
+        #[test]
+        fn selected_by_extension() {
+            for name in [
+                "out.ppm", "out.png", "out.jpg", "out.JPEG", "out.hdr", "out.exr",
+            ] {
+                assert!(writer_for_path(Path::new(name)).is_ok(), "{}", name);
+            }
+        }
+
+        #[test]
+        fn unknown_extension_is_rejected() {
+            assert!(matches!(
+                writer_for_path(Path::new("out.bmp")),
+                Err(ImageError::UnsupportedFormat(ext)) if ext == "bmp"
+            ));
+            assert!(matches!(
+                writer_for_path(Path::new("out")),
+                Err(ImageError::UnsupportedFormat(_))
+            ));
+        }
+    }
+
+    mod encoding {
+        use super::*;
+
+        #[test]
+        fn ppm_matches_canvas_output() {
+            let c = test_canvas();
+            let mut out = Vec::new();
+            PpmWriter.write(&c, &mut out).unwrap();
+            assert_eq!(out, c.to_ppm().into_bytes());
+        }
+
+        #[test]
+        fn png_round_trips_pixels() {
+            let c = test_canvas();
+            let mut out = Vec::new();
+            PngWriter.write(&c, &mut out).unwrap();
+            let decoded = image::load_from_memory(&out).unwrap().to_rgb8();
+            assert_eq!(decoded.dimensions(), (4, 2));
+            assert_eq!(decoded.into_raw(), c.to_rgb8());
+        }
+
+        #[test]
+        fn jpeg_preserves_dimensions() {
+            let c = test_canvas();
+            let mut out = Vec::new();
+            JpegWriter::default().write(&c, &mut out).unwrap();
+            let decoded = image::load_from_memory(&out).unwrap().to_rgb8();
+            assert_eq!(decoded.dimensions(), (4, 2));
+        }
+    }
+
+    mod high_dynamic_range {
+        use super::*;
+
+        fn bright_canvas() -> Canvas {
+            let mut c = Canvas::new(3, 2);
+            c.write_pixel(0, 0, Color::new(4.0, 0.5, 0.25));
+            c.write_pixel(2, 1, Color::new(0.0, 16.0, 1.0));
+            c
+        }
+
+        fn decode(bytes: &[u8]) -> Vec<f32> {
+            image::load_from_memory(bytes)
+                .unwrap()
+                .to_rgb32f()
+                .into_raw()
+        }
+
+        #[test]
+        fn hdr_keeps_values_above_one() {
+            let c = bright_canvas();
+            let mut out = Vec::new();
+            HdrWriter.write(&c, &mut out).unwrap();
+            assert!(out.starts_with(b"#?RADIANCE"));
+            let decoded = decode(&out);
+            for (actual, expected) in decoded.iter().zip(radiance(&c)) {
+                assert!(
+                    (actual - expected).abs() <= expected * 0.01,
+                    "{} vs {}",
+                    actual,
+                    expected
+                );
+            }
+            assert!(decoded[0] > 3.9);
+        }
+
+        #[test]
+        fn exr_stores_exact_float_radiance() {
+            let c = bright_canvas();
+            let mut out = Vec::new();
+            ExrWriter.write(&c, &mut out).unwrap();
+            assert_eq!(decode(&out), radiance(&c));
+        }
+
+        #[test]
+        fn negative_radiance_is_clamped_to_zero() {
+            let mut c = Canvas::new(1, 1);
+            c.write_pixel(0, 0, Color::new(-1.0, 2.0, 0.5));
+            assert_eq!(radiance(&c), vec![0.0, 2.0, 0.5]);
+        }
+    }
+
+    #[test]
+    fn aov_files_sit_beside_the_beauty_pass() {
+        assert_eq!(
+            aov_path(Path::new("renders/cover.exr"), Aov::Depth),
+            PathBuf::from("renders/cover.depth.exr")
+        );
+        assert_eq!(
+            aov_path(Path::new("cover"), Aov::ObjectId),
+            PathBuf::from("cover.object-id")
+        );
+    }
+
+    #[test]
+    fn saving_a_render_output_writes_every_buffer() {
+        let dir = std::env::temp_dir().join(format!("rt-io-aovs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = RenderOutput {
+            beauty: test_canvas(),
+            aovs: vec![(Aov::Albedo, Canvas::new(2, 2))],
+        };
+        let written = save_render_output(&output, dir.join("out.ppm"));
+        let albedo = std::fs::read_to_string(dir.join("out.albedo.ppm"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            written.unwrap(),
+            vec![dir.join("out.ppm"), dir.join("out.albedo.ppm")]
+        );
+        assert_eq!(albedo.unwrap(), Canvas::new(2, 2).to_ppm());
+    }
+
+    #[test]
+    fn saving_to_a_file() {
+        let path = std::env::temp_dir().join(format!("rt-io-save-{}.png", std::process::id()));
+        save_canvas(&test_canvas(), &path).unwrap();
+        let decoded = image::open(&path).unwrap().to_rgb8();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(decoded.into_raw(), test_canvas().to_rgb8());
+    }
+}
@@ -0,0 +1,19 @@
+mod gltf;
+mod image_reader;
+mod image_writer;
+mod obj_parser;
+mod scene;
+mod scene_export;
+mod sequence;
+mod yaml_node;
+
+pub use gltf::{GltfError, GltfParser};
+pub use image_reader::{canvas_from_ppm, load_canvas};
+pub use image_writer::{
+    aov_path, save_canvas, save_render_output, writer_for_path, ExrWriter, HdrWriter, ImageError,
+    ImageWriter, JpegWriter, PngWriter, PpmWriter,
+};
+pub use obj_parser::{ObjError, ObjParser};
+pub use scene::{Scene, SceneError};
+pub use scene_export::ExportError;
+pub use sequence::{frame_path, render_sequence};
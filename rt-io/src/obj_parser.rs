@@ -0,0 +1,377 @@
+use rt_core::{Point, Scalar, Vector};
+use rt_shapes::{Group, Shape, SmoothTriangle, Triangle};
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io(std::io::Error),
+    InvalidIndex { line: usize, index: String },
+}
+
+impl std::fmt::Display for ObjError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjError::Io(error) => write!(f, "I/O error: {}", error),
+            ObjError::InvalidIndex { line, index } => {
+                write!(f, "line {}: invalid index {:?}", line, index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ObjError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ObjError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ObjError {
+    fn from(error: std::io::Error) -> Self {
+        ObjError::Io(error)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ObjParser {
+    vertices: Vec<Point>,
+    normals: Vec<Vector>,
+    default_group: Vec<Box<dyn Shape>>,
+    groups: Vec<(String, Vec<Box<dyn Shape>>)>,
+    ignored_lines: Vec<usize>,
+}
+
+impl ObjParser {
+    pub fn parse(input: &str) -> Result<Self, ObjError> {
+        let mut parser = ObjParser::default();
+        let mut current_group: Option<usize> = None;
+
+        for (number, line) in input.lines().enumerate().map(|(i, line)| (i + 1, line)) {
+            let mut words = line.split_whitespace();
+            let parsed = match words.next() {
+                Some("v") => parse_floats(words).map(|[x, y, z]| {
+                    parser.vertices.push(Point::new(x, y, z));
+                }),
+                Some("vn") => parse_floats(words).map(|[x, y, z]| {
+                    parser.normals.push(Vector::new(x, y, z));
+                }),
+                Some("f") => {
+                    let triangles = parser.parse_face(number, words)?;
+                    if triangles.is_empty() {
+                        None
+                    } else {
+                        match current_group {
+                            Some(index) => parser.groups[index].1.extend(triangles),
+                            None => parser.default_group.extend(triangles),
+                        }
+                        Some(())
+                    }
+                }
+                Some("g") => words.next().map(|name| {
+                    current_group = Some(parser.group_index(name));
+                }),
+                _ => None,
+            };
+            if parsed.is_none() {
+                parser.ignored_lines.push(number);
+            }
+        }
+        Ok(parser)
+    }
+
+    pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Self, ObjError> {
+        ObjParser::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn vertex(&self, index: usize) -> Option<Point> {
+        index
+            .checked_sub(1)
+            .and_then(|i| self.vertices.get(i))
+            .copied()
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    pub fn normal(&self, index: usize) -> Option<Vector> {
+        index
+            .checked_sub(1)
+            .and_then(|i| self.normals.get(i))
+            .copied()
+    }
+
+    pub fn normals(&self) -> &[Vector] {
+        &self.normals
+    }
+
+    pub fn default_group(&self) -> &[Box<dyn Shape>] {
+        &self.default_group
+    }
+
+    pub fn group(&self, name: &str) -> Option<&[Box<dyn Shape>]> {
+        self.groups
+            .iter()
+            .find(|(group, _)| group == name)
+            .map(|(_, shapes)| shapes.as_slice())
+    }
+
+    pub fn group_names(&self) -> impl Iterator<Item = &str> {
+        self.groups.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn ignored_lines(&self) -> &[usize] {
+        &self.ignored_lines
+    }
+
+    pub fn into_group(self) -> Group {
+        let mut group = Group::new();
+        for shape in self.default_group {
+            group.add_child(shape);
+        }
+        for (_, shapes) in self.groups {
+            let mut child = Group::new();
+            for shape in shapes {
+                child.add_child(shape);
+            }
+            group.add_child(Box::new(child));
+        }
+        group
+    }
+
+    fn group_index(&mut self, name: &str) -> usize {
+        match self.groups.iter().position(|(group, _)| group == name) {
+            Some(index) => index,
+            None => {
+                self.groups.push((name.to_string(), Vec::new()));
+                self.groups.len() - 1
+            }
+        }
+    }
+
+    fn parse_face<'a>(
+        &self,
+        line: usize,
+        words: impl Iterator<Item = &'a str>,
+    ) -> Result<Vec<Box<dyn Shape>>, ObjError> {
+        let mut corners = Vec::new();
+        for word in words {
+            let mut indices = word.split('/');
+            let vertex = indices
+                .next()
+                .and_then(|index| index.parse().ok())
+                .and_then(|index| self.vertex(index));
+            let normal = match indices.nth(1) {
+                Some(index) if !index.is_empty() => {
+                    match index.parse().ok().and_then(|index| self.normal(index)) {
+                        Some(normal) => Some(normal),
+                        None => return Err(invalid_index(line, word)),
+                    }
+                }
+                _ => None,
+            };
+            match vertex {
+                Some(vertex) => corners.push((vertex, normal)),
+                None => return Err(invalid_index(line, word)),
+            }
+        }
+        if corners.len() < 3 {
+            return Ok(Vec::new());
+        }
+
+        let (p1, n1) = corners[0];
+        Ok(corners[1..]
+            .windows(2)
+            .map(|pair| {
+                let ((p2, n2), (p3, n3)) = (pair[0], pair[1]);
+                match (n1, n2, n3) {
+                    (Some(n1), Some(n2), Some(n3)) => {
+                        Box::new(SmoothTriangle::new(p1, p2, p3, n1, n2, n3)) as Box<dyn Shape>
+                    }
+                    _ => Box::new(Triangle::new(p1, p2, p3)),
+                }
+            })
+            .collect())
+    }
+}
+
+fn invalid_index(line: usize, index: &str) -> ObjError {
+    ObjError::InvalidIndex {
+        line,
+        index: index.to_string(),
+    }
+}
+
+fn parse_floats<'a>(mut words: impl Iterator<Item = &'a str>) -> Option<[Scalar; 3]> {
+    let mut values = [0.0; 3];
+    for value in values.iter_mut() {
+        *value = words.next()?.parse().ok()?;
+    }
+    Some(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::any::Any;
+
+    fn triangle(shape: &dyn Shape) -> &Triangle {
+        (shape as &dyn Any).downcast_ref().unwrap()
+    }
+
+    fn smooth_triangle(shape: &dyn Shape) -> &SmoothTriangle {
+        (shape as &dyn Any).downcast_ref().unwrap()
+    }
+
+    #[test]
+    fn ignoring_unrecognized_lines() {
+        let gibberish = "There was a young lady named Bright\n\
+                         who traveled much faster than light.\n\
+                         She set out one day\n\
+                         in a relative way,\n\
+                         and came back the previous night.\n";
+        let parser = ObjParser::parse(gibberish).unwrap();
+        assert_eq!(parser.ignored_lines(), &[1, 2, 3, 4, 5]);
+        assert!(parser.vertices().is_empty());
+    }
+
+    #[test]
+    fn vertex_records() {
+        let parser =
+            ObjParser::parse("v -1 1 0\nv -1.0000 0.5000 0.0000\nv 1 0 0\nv 1 1 0\n").unwrap();
+        assert_eq!(parser.vertex(1), Some(Point::new(-1.0, 1.0, 0.0)));
+        assert_eq!(parser.vertex(2), Some(Point::new(-1.0, 0.5, 0.0)));
+        assert_eq!(parser.vertex(3), Some(Point::new(1.0, 0.0, 0.0)));
+        assert_eq!(parser.vertex(4), Some(Point::new(1.0, 1.0, 0.0)));
+        assert_eq!(parser.vertex(0), None);
+        assert_eq!(parser.vertex(5), None);
+    }
+
+    #[test]
+    fn vertex_normal_records() {
+        let parser = ObjParser::parse("vn 0 0 1\nvn 0.707 0 -0.707\nvn 1 2 3\n").unwrap();
+        assert_eq!(parser.normal(1), Some(Vector::new(0.0, 0.0, 1.0)));
+        assert_eq!(parser.normal(2), Some(Vector::new(0.707, 0.0, -0.707)));
+        assert_eq!(parser.normal(3), Some(Vector::new(1.0, 2.0, 3.0)));
+    }
+
+    #[test]
+    fn malformed_vertex_is_ignored() {
+        let parser = ObjParser::parse("v 1 2\nv 1 2 x\nv 1 2 3\n").unwrap();
+        assert_eq!(parser.ignored_lines(), &[1, 2]);
+        assert_eq!(parser.vertices(), &[Point::new(1.0, 2.0, 3.0)]);
+    }
+
+    mod faces {
+        use super::*;
+
+        #[test]
+        fn parsing_triangle_faces() {
+            let parser =
+                ObjParser::parse("v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\nf 1 2 3\nf 1 3 4\n")
+                    .unwrap();
+            let g = parser.default_group();
+            let t1 = triangle(g[0].as_ref());
+            let t2 = triangle(g[1].as_ref());
+            assert_eq!(t1.p1(), parser.vertices()[0]);
+            assert_eq!(t1.p2(), parser.vertices()[1]);
+            assert_eq!(t1.p3(), parser.vertices()[2]);
+            assert_eq!(t2.p1(), parser.vertices()[0]);
+            assert_eq!(t2.p2(), parser.vertices()[2]);
+            assert_eq!(t2.p3(), parser.vertices()[3]);
+            assert_eq!(parser.ignored_lines(), &[5]);
+        }
+
+        #[test]
+        fn triangulating_polygons() {
+            let parser =
+                ObjParser::parse("v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nv 0 2 0\n\nf 1 2 3 4 5\n")
+                    .unwrap();
+            let g = parser.default_group();
+            assert_eq!(g.len(), 3);
+            for (i, shape) in g.iter().enumerate() {
+                let t = triangle(shape.as_ref());
+                assert_eq!(t.p1(), parser.vertices()[0]);
+                assert_eq!(t.p2(), parser.vertices()[i + 1]);
+                assert_eq!(t.p3(), parser.vertices()[i + 2]);
+            }
+        }
+
+        #[test]
+        fn faces_with_normals() {
+            let parser = ObjParser::parse(
+                "v 0 1 0\nv -1 0 0\nv 1 0 0\n\nvn -1 0 0\nvn 1 0 0\nvn 0 1 0\n\n\
+                 f 1//3 2//1 3//2\nf 1/0/3 2/102/1 3/14/2\n",
+            )
+            .unwrap();
+            let g = parser.default_group();
+            for shape in g {
+                let t = smooth_triangle(shape.as_ref());
+                assert_eq!(t.p1(), parser.vertices()[0]);
+                assert_eq!(t.p2(), parser.vertices()[1]);
+                assert_eq!(t.p3(), parser.vertices()[2]);
+                assert_eq!(t.n1(), parser.normals()[2]);
+                assert_eq!(t.n2(), parser.normals()[0]);
+                assert_eq!(t.n3(), parser.normals()[1]);
+            }
+        }
+
+        #[test]
+        fn face_with_an_unknown_vertex_is_an_error() {
+            let result = ObjParser::parse("v 0 1 0\nv -1 0 0\nf 1 2 3\n");
+            assert!(matches!(
+                result,
+                Err(ObjError::InvalidIndex { line: 3, index }) if index == "3"
+            ));
+        }
+
+        #[test]
+        fn face_with_an_unknown_normal_is_an_error() {
+            let result = ObjParser::parse("v 0 1 0\nv -1 0 0\nv 1 0 0\nf 1//1 2//1 3//1\n");
+            assert!(matches!(
+                result,
+                Err(ObjError::InvalidIndex { line: 4, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn triangles_in_named_groups() {
+        let parser = ObjParser::parse(
+            "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\ng FirstGroup\nf 1 2 3\ng SecondGroup\nf 1 3 4\n",
+        )
+        .unwrap();
+        assert!(parser.default_group().is_empty());
+        assert_eq!(
+            parser.group_names().collect::<Vec<_>>(),
+            vec!["FirstGroup", "SecondGroup"]
+        );
+        let t1 = triangle(parser.group("FirstGroup").unwrap()[0].as_ref());
+        let t2 = triangle(parser.group("SecondGroup").unwrap()[0].as_ref());
+        assert_eq!(t1.p3(), parser.vertices()[2]);
+        assert_eq!(t2.p3(), parser.vertices()[3]);
+        assert!(parser.group("ThirdGroup").is_none());
+    }
+
+    #[test]
+    fn converting_to_a_group() {
+        let parser = ObjParser::parse(
+            "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 4\ng FirstGroup\nf 1 2 3\ng SecondGroup\nf 1 3 4\n",
+        )
+        .unwrap();
+        let g = parser.into_group();
+        assert_eq!(g.len(), 3);
+        assert_eq!(
+            triangle(g.children()[0].as_ref()).p3(),
+            Point::new(1.0, 1.0, 0.0)
+        );
+        for child in &g.children()[1..] {
+            let child = (child.as_ref() as &dyn Any)
+                .downcast_ref::<Group>()
+                .unwrap();
+            assert_eq!(child.len(), 1);
+        }
+    }
+}
@@ -0,0 +1,1829 @@
+use crate::yaml_node::{Node, Value};
+use crate::{load_canvas, GltfParser, ObjParser};
+use rt_core::{from_f64, Color, Matrix, Point, Scalar, Vector, EPSILON};
+use rt_render::{
+    view_transform, AreaLight, Background, Camera, Light, Medium, PointLight, SpotLight, Volume,
+    World,
+};
+use rt_shapes::{
+    Blend, Checker, Clip, ClipPlane, Cone, Csg, CsgOperation, Cube, CubeMap, Cylinder, Gradient,
+    Group, Instance, Material, Noise, Pattern, PatternNormalMap, Perturb, Plane, Quadric, Ring,
+    Sdf, SdfShape, Shape, SmoothTriangle, Solid, Sphere, Stripe, TextureMap, Torus, Triangle,
+    UvAlignCheck, UvChecker, UvImage, UvMapping, UvPattern,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const MAX_DEFINE_DEPTH: usize = 32;
+const DEFAULT_FOG_DISTANCE: Scalar = 100.0;
+
+type PatternPair = (Box<dyn Pattern>, Box<dyn Pattern>);
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Syntax { line: usize, message: String },
+    Invalid { line: usize, message: String },
+    MissingCamera,
+}
+
+impl std::fmt::Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SceneError::Io(error) => write!(f, "I/O error: {}", error),
+            SceneError::Syntax { line, message } => {
+                write!(f, "line {}: syntax error: {}", line, message)
+            }
+            SceneError::Invalid { line, message } => write!(f, "line {}: {}", line, message),
+            SceneError::MissingCamera => write!(f, "scene does not add a camera"),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SceneError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SceneError {
+    fn from(error: std::io::Error) -> Self {
+        SceneError::Io(error)
+    }
+}
+
+#[derive(Debug)]
+pub struct Scene {
+    pub camera: Camera,
+    pub world: World,
+}
+
+impl Scene {
+    pub fn parse(source: &str) -> Result<Self, SceneError> {
+        SceneParser::new(PathBuf::from(".")).parse(source)
+    }
+
+    pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+        Scene::parse_at(&std::fs::read_to_string(path)?, path)
+    }
+
+    pub fn parse_at<P: AsRef<Path>>(source: &str, path: P) -> Result<Self, SceneError> {
+        let base_dir = path
+            .as_ref()
+            .parent()
+            .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+        SceneParser::new(base_dir).parse(source)
+    }
+}
+
+fn invalid(node: &Node, message: String) -> SceneError {
+    SceneError::Invalid {
+        line: node.line,
+        message,
+    }
+}
+
+fn required<'a>(node: &'a Node, key: &str) -> Result<&'a Node, SceneError> {
+    node.get(key)
+        .ok_or_else(|| invalid(node, format!("missing '{}'", key)))
+}
+
+fn expected(node: &Node, what: &str, kind: &str) -> SceneError {
+    invalid(
+        node,
+        format!("'{}' must be {}, found {}", what, kind, node.describe()),
+    )
+}
+
+fn number(node: &Node, what: &str) -> Result<Scalar, SceneError> {
+    match node.value {
+        Value::Number(n) => Ok(from_f64(n)),
+        _ => Err(expected(node, what, "a number")),
+    }
+}
+
+fn count(node: &Node, what: &str) -> Result<usize, SceneError> {
+    match node.value {
+        Value::Number(n) if n >= 0.0 && n.fract() == 0.0 => Ok(n as usize),
+        _ => Err(expected(node, what, "a non-negative integer")),
+    }
+}
+
+fn size(node: &Node, what: &str) -> Result<usize, SceneError> {
+    match node.value {
+        Value::Number(n) if n >= 1.0 && n.fract() == 0.0 => Ok(n as usize),
+        _ => Err(expected(node, what, "a positive integer")),
+    }
+}
+
+fn boolean(node: &Node, what: &str) -> Result<bool, SceneError> {
+    match node.value {
+        Value::Bool(b) => Ok(b),
+        _ => Err(expected(node, what, "a boolean")),
+    }
+}
+
+fn string<'a>(node: &'a Node, what: &str) -> Result<&'a str, SceneError> {
+    match &node.value {
+        Value::String(s) => Ok(s),
+        _ => Err(expected(node, what, "a string")),
+    }
+}
+
+fn sequence<'a>(node: &'a Node, what: &str) -> Result<&'a [Node], SceneError> {
+    match &node.value {
+        Value::Sequence(items) => Ok(items),
+        _ => Err(expected(node, what, "a list")),
+    }
+}
+
+fn entries<'a>(node: &'a Node, what: &str) -> Result<&'a [(String, Node)], SceneError> {
+    match &node.value {
+        Value::Mapping(entries) => Ok(entries),
+        _ => Err(expected(node, what, "a mapping")),
+    }
+}
+
+fn triple(node: &Node, what: &str) -> Result<[Scalar; 3], SceneError> {
+    match sequence(node, what)? {
+        [x, y, z] => Ok([number(x, what)?, number(y, what)?, number(z, what)?]),
+        _ => Err(invalid(
+            node,
+            format!("'{}' must have three components", what),
+        )),
+    }
+}
+
+fn color(node: &Node, what: &str) -> Result<Color, SceneError> {
+    triple(node, what).map(|[r, g, b]| Color::new(r, g, b))
+}
+
+fn point(node: &Node, what: &str) -> Result<Point, SceneError> {
+    triple(node, what).map(|[x, y, z]| Point::new(x, y, z))
+}
+
+fn vector(node: &Node, what: &str) -> Result<Vector, SceneError> {
+    triple(node, what).map(|[x, y, z]| Vector::new(x, y, z))
+}
+
+fn optional<T>(
+    node: &Node,
+    key: &str,
+    parse: impl Fn(&Node, &str) -> Result<T, SceneError>,
+) -> Result<Option<T>, SceneError> {
+    node.get(key).map(|value| parse(value, key)).transpose()
+}
+
+fn merge(base: &Node, overrides: &Node) -> Result<Node, SceneError> {
+    let value = match (&base.value, &overrides.value) {
+        (Value::Mapping(a), Value::Mapping(b)) => {
+            Value::Mapping(a.iter().chain(b.iter()).cloned().collect())
+        }
+        (Value::Sequence(a), Value::Sequence(b)) => {
+            Value::Sequence(a.iter().chain(b.iter()).cloned().collect())
+        }
+        _ => {
+            return Err(invalid(
+                overrides,
+                format!(
+                    "cannot extend {} with {}",
+                    base.describe(),
+                    overrides.describe()
+                ),
+            ))
+        }
+    };
+    Ok(Node {
+        value,
+        line: overrides.line,
+    })
+}
+
+struct SceneParser {
+    base_dir: PathBuf,
+    defines: HashMap<String, Node>,
+    prototypes: RefCell<HashMap<String, Arc<dyn Shape>>>,
+}
+
+impl SceneParser {
+    fn new(base_dir: PathBuf) -> Self {
+        SceneParser {
+            base_dir,
+            defines: HashMap::new(),
+            prototypes: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn parse(mut self, source: &str) -> Result<Scene, SceneError> {
+        let root = match Node::parse(source) {
+            Ok(Some(root)) => root,
+            Ok(None) => return Err(SceneError::MissingCamera),
+            Err(error) => {
+                return Err(SceneError::Syntax {
+                    line: error.line,
+                    message: error.message,
+                })
+            }
+        };
+
+        let mut camera = None;
+        let mut world = World::new();
+        for item in sequence(&root, "scene")? {
+            entries(item, "scene item")?;
+            if let Some(name) = item.get("define") {
+                self.define(item, string(name, "define")?)?;
+            } else if let Some(kind) = item.get("add") {
+                match string(kind, "add")? {
+                    "camera" => camera = Some(self.camera(item)?),
+                    "light" | "spot-light" => world.add_light(self.light(item)?),
+                    "background" => world.set_background(self.background(item)?),
+                    "fog" | "volume" => world.add_volume(self.volume(item)?),
+                    _ => world.add_shape(self.shape(item, 0)?),
+                }
+            } else {
+                return Err(invalid(item, "expected 'add' or 'define'".into()));
+            }
+        }
+
+        match camera {
+            Some(camera) => Ok(Scene { camera, world }),
+            None => Err(SceneError::MissingCamera),
+        }
+    }
+
+    fn define(&mut self, item: &Node, name: &str) -> Result<(), SceneError> {
+        let value = required(item, "value")?;
+        let value = match item.get("extend") {
+            Some(parent) => merge(self.lookup(parent)?, value)?,
+            None => value.clone(),
+        };
+        self.defines.insert(name.to_string(), value);
+        self.prototypes.get_mut().remove(name);
+        Ok(())
+    }
+
+    fn prototype(&self, reference: &Node, depth: usize) -> Result<Arc<dyn Shape>, SceneError> {
+        let name = string(reference, "of")?;
+        if let Some(prototype) = self.prototypes.borrow().get(name) {
+            return Ok(Arc::clone(prototype));
+        }
+        if depth >= MAX_DEFINE_DEPTH {
+            return Err(invalid(
+                reference,
+                format!("definition '{}' is nested too deeply", name),
+            ));
+        }
+        let prototype: Arc<dyn Shape> = Arc::from(self.shape(self.lookup(reference)?, depth + 1)?);
+        self.prototypes
+            .borrow_mut()
+            .insert(name.to_string(), Arc::clone(&prototype));
+        Ok(prototype)
+    }
+
+    fn lookup(&self, reference: &Node) -> Result<&Node, SceneError> {
+        let name = string(reference, "reference")?;
+        self.defines
+            .get(name)
+            .ok_or_else(|| invalid(reference, format!("unknown definition '{}'", name)))
+    }
+
+    fn path(&self, node: &Node, what: &str) -> Result<PathBuf, SceneError> {
+        Ok(self.base_dir.join(string(node, what)?))
+    }
+
+    fn camera(&self, item: &Node) -> Result<Camera, SceneError> {
+        let mut camera = Camera::new(
+            size(required(item, "width")?, "width")?,
+            size(required(item, "height")?, "height")?,
+            number(required(item, "field-of-view")?, "field-of-view")?,
+        );
+        let from = point(required(item, "from")?, "from")?;
+        let to_node = required(item, "to")?;
+        let to = point(to_node, "to")?;
+        let up_node = required(item, "up")?;
+        let up = vector(up_node, "up")?;
+        let forward = to - from;
+        if forward.magnitude() < EPSILON {
+            return Err(invalid(to_node, "'to' must differ from 'from'".into()));
+        }
+        if forward.normalize().cross(&up).magnitude() <= EPSILON * up.magnitude() {
+            return Err(invalid(
+                up_node,
+                "'up' must not be parallel to the view direction".into(),
+            ));
+        }
+        camera.set_transform(view_transform(from, to, up));
+        if let Some(node) = item.get("aperture") {
+            let aperture = number(node, "aperture")?;
+            if aperture < 0.0 {
+                return Err(invalid(node, "aperture must not be negative".into()));
+            }
+            camera.set_aperture(aperture);
+        }
+        if let Some(node) = item.get("focal-distance") {
+            let distance = number(node, "focal-distance")?;
+            if distance <= 0.0 {
+                return Err(invalid(node, "focal distance must be positive".into()));
+            }
+            camera.set_focal_distance(distance);
+        }
+        if let Some(shutter) = item.get("shutter") {
+            match sequence(shutter, "shutter")? {
+                [open, close] => {
+                    let (open, close) = (number(open, "shutter")?, number(close, "shutter")?);
+                    if close < open {
+                        return Err(invalid(shutter, "shutter must close after it opens".into()));
+                    }
+                    camera.set_shutter(open, close);
+                }
+                _ => {
+                    return Err(invalid(
+                        shutter,
+                        "'shutter' must have two components".into(),
+                    ))
+                }
+            }
+        }
+        Ok(camera)
+    }
+
+    fn background(&self, item: &Node) -> Result<Background, SceneError> {
+        if let Some(file) = item.get("image") {
+            let canvas = load_canvas(self.path(file, "image")?).map_err(|error| {
+                invalid(file, format!("cannot load {:?}: {}", file.value, error))
+            })?;
+            return Ok(Background::Environment(Arc::new(canvas)));
+        }
+        match item.get("color") {
+            Some(value) => Ok(Background::Solid(color(value, "color")?)),
+            None => Ok(Background::Gradient {
+                bottom: color(required(item, "bottom")?, "bottom")?,
+                top: color(required(item, "top")?, "top")?,
+            }),
+        }
+    }
+
+    fn volume(&self, item: &Node) -> Result<Volume, SceneError> {
+        let albedo = optional(item, "color", color)?.unwrap_or_else(Color::white);
+        let density = required(item, "density")?;
+        let mut medium = match number(density, "density")? {
+            value if value >= 0.0 => Medium::new(value, albedo),
+            _ => return Err(invalid(density, "density must not be negative".into())),
+        };
+        if let Some(steps) = optional(item, "steps", count)? {
+            medium.steps = steps.max(1);
+        }
+        if string(required(item, "add")?, "add")? == "fog" {
+            let distance = optional(item, "distance", number)?.unwrap_or(DEFAULT_FOG_DISTANCE);
+            return Ok(Volume::fog(medium, distance));
+        }
+        let boundary = self.shape(required(item, "shape")?, 0)?;
+        Ok(Volume::bounded(medium, boundary))
+    }
+
+    fn sdf(&self, node: &Node, depth: usize) -> Result<Sdf, SceneError> {
+        let node = match node.value {
+            Value::String(_) if depth < MAX_DEFINE_DEPTH => {
+                return self.sdf(self.lookup(node)?, depth + 1)
+            }
+            _ => node,
+        };
+        let kind = required(node, "type")?;
+        let name = string(kind, "type")?;
+        let blend = optional(node, "blend", number)?.unwrap_or(0.0);
+        let sdf = match name {
+            "sphere" => Sdf::sphere(number(required(node, "radius")?, "radius")?),
+            "box" => Sdf::cuboid(vector(required(node, "size")?, "size")? / 2.0),
+            "torus" => Sdf::torus(
+                number(required(node, "major")?, "major")?,
+                number(required(node, "minor")?, "minor")?,
+            ),
+            "capsule" => Sdf::capsule(
+                point(required(node, "from")?, "from")?,
+                point(required(node, "to")?, "to")?,
+                number(required(node, "radius")?, "radius")?,
+            ),
+            "union" | "subtract" | "intersect" => {
+                let operands = sequence(required(node, "of")?, "of")?;
+                if operands.len() < 2 {
+                    return Err(invalid(
+                        node,
+                        format!("'{}' needs at least two fields", name),
+                    ));
+                }
+                let mut combined = self.sdf(&operands[0], depth)?;
+                for operand in &operands[1..] {
+                    let operand = self.sdf(operand, depth)?;
+                    combined = match name {
+                        "union" => combined.smooth_union(operand, blend),
+                        "subtract" => combined.smooth_subtract(operand, blend),
+                        _ => combined.smooth_intersect(operand, blend),
+                    };
+                }
+                combined
+            }
+            other => return Err(invalid(kind, format!("unknown distance field '{}'", other))),
+        };
+        match optional(node, "at", vector)? {
+            Some(offset) => Ok(sdf.translate(offset.x(), offset.y(), offset.z())),
+            None => Ok(sdf),
+        }
+    }
+
+    fn light(&self, item: &Node) -> Result<Box<dyn Light>, SceneError> {
+        let intensity = color(required(item, "intensity")?, "intensity")?;
+        if string(required(item, "add")?, "add")? == "spot-light" {
+            let inner = required(item, "inner-angle")?;
+            let inner_angle = number(inner, "inner-angle")?;
+            let outer_angle = number(required(item, "outer-angle")?, "outer-angle")?;
+            if inner_angle > outer_angle {
+                return Err(invalid(
+                    inner,
+                    "inner angle must not exceed the outer angle".into(),
+                ));
+            }
+            return Ok(Box::new(SpotLight::new(
+                point(required(item, "at")?, "at")?,
+                vector(required(item, "direction")?, "direction")?,
+                inner_angle,
+                outer_angle,
+                intensity,
+            )));
+        }
+        match item.get("corner") {
+            Some(corner) => {
+                let mut light = AreaLight::new(
+                    point(corner, "corner")?,
+                    vector(required(item, "uvec")?, "uvec")?,
+                    count(required(item, "usteps")?, "usteps")?.max(1),
+                    vector(required(item, "vvec")?, "vvec")?,
+                    count(required(item, "vsteps")?, "vsteps")?.max(1),
+                    intensity,
+                );
+                light.jitter = optional(item, "jitter", boolean)?.unwrap_or(false);
+                Ok(Box::new(light))
+            }
+            None => Ok(Box::new(PointLight::new(
+                point(required(item, "at")?, "at")?,
+                intensity,
+            ))),
+        }
+    }
+
+    fn shape(&self, item: &Node, depth: usize) -> Result<Box<dyn Shape>, SceneError> {
+        let kind = required(item, "add")?;
+        let mut shape: Box<dyn Shape> = match string(kind, "add")? {
+            "sphere" => Box::new(Sphere::new()),
+            "plane" => Box::new(Plane::new()),
+            "cube" => Box::new(Cube::new()),
+            "cylinder" | "cone" => {
+                let minimum = optional(item, "min", number)?.unwrap_or(Scalar::NEG_INFINITY);
+                let maximum = optional(item, "max", number)?.unwrap_or(Scalar::INFINITY);
+                let closed = optional(item, "closed", boolean)?.unwrap_or(false);
+                if string(kind, "add")? == "cylinder" {
+                    Box::new(Cylinder::truncated(minimum, maximum, closed))
+                } else {
+                    Box::new(Cone::truncated(minimum, maximum, closed))
+                }
+            }
+            "triangle" => Box::new(Triangle::new(
+                point(required(item, "p1")?, "p1")?,
+                point(required(item, "p2")?, "p2")?,
+                point(required(item, "p3")?, "p3")?,
+            )),
+            "smooth-triangle" => Box::new(SmoothTriangle::new(
+                point(required(item, "p1")?, "p1")?,
+                point(required(item, "p2")?, "p2")?,
+                point(required(item, "p3")?, "p3")?,
+                vector(required(item, "n1")?, "n1")?,
+                vector(required(item, "n2")?, "n2")?,
+                vector(required(item, "n3")?, "n3")?,
+            )),
+            "group" => {
+                let mut group = Group::new();
+                if let Some(children) = item.get("children") {
+                    for child in sequence(children, "children")? {
+                        group.add_child(self.shape(child, depth)?);
+                    }
+                }
+                if let Some(material) = item.get("material") {
+                    group.inherit_material(&self.material(material)?);
+                }
+                Box::new(group)
+            }
+            "csg" => {
+                let operation = required(item, "operation")?;
+                let operation = match string(operation, "operation")? {
+                    "union" => CsgOperation::Union,
+                    "intersection" => CsgOperation::Intersection,
+                    "difference" => CsgOperation::Difference,
+                    other => {
+                        return Err(invalid(
+                            operation,
+                            format!("unknown CSG operation '{}'", other),
+                        ))
+                    }
+                };
+                Box::new(Csg::new(
+                    operation,
+                    self.shape(required(item, "left")?, depth)?,
+                    self.shape(required(item, "right")?, depth)?,
+                ))
+            }
+            "clip" => {
+                let shape = self.shape(required(item, "shape")?, depth)?;
+                let mut clip = match (item.get("min"), item.get("max")) {
+                    (Some(min), Some(max)) => {
+                        Clip::to_box(shape, point(min, "min")?, point(max, "max")?)
+                    }
+                    (None, None) => Clip::new(shape, Vec::new()),
+                    (Some(bound), None) | (None, Some(bound)) => {
+                        return Err(invalid(
+                            bound,
+                            "a clip box needs both 'min' and 'max'".into(),
+                        ))
+                    }
+                };
+                if let Some(planes) = item.get("planes") {
+                    for plane in sequence(planes, "planes")? {
+                        clip.add_plane(ClipPlane::new(
+                            point(required(plane, "point")?, "point")?,
+                            vector(required(plane, "normal")?, "normal")?,
+                        ));
+                    }
+                }
+                if clip.planes().is_empty() {
+                    return Err(invalid(
+                        item,
+                        "a clip needs 'min' and 'max' or 'planes'".into(),
+                    ));
+                }
+                clip.capped = optional(item, "capped", boolean)?.unwrap_or(false);
+                Box::new(clip)
+            }
+            "torus" => Box::new(Torus::new(
+                number(required(item, "major")?, "major")?,
+                number(required(item, "minor")?, "minor")?,
+            )),
+            "quadric" => Box::new(quadric(item)?),
+            "instance" => Box::new(Instance::new(self.prototype(required(item, "of")?, depth)?)),
+            "sdf" => Box::new(SdfShape::new(self.sdf(required(item, "field")?, 0)?)),
+            "obj" => {
+                let file = required(item, "file")?;
+                let parser = ObjParser::parse_file(self.path(file, "file")?).map_err(|error| {
+                    invalid(file, format!("cannot load {:?}: {}", file.value, error))
+                })?;
+                Box::new(parser.into_group())
+            }
+            "gltf" => {
+                let file = required(item, "file")?;
+                let parser = GltfParser::parse_file(self.path(file, "file")?).map_err(|error| {
+                    invalid(file, format!("cannot load {:?}: {}", file.value, error))
+                })?;
+                Box::new(parser.into_group())
+            }
+            name => {
+                if depth >= MAX_DEFINE_DEPTH {
+                    return Err(invalid(
+                        kind,
+                        format!("definition '{}' is nested too deeply", name),
+                    ));
+                }
+                let template = self.defines.get(name).ok_or_else(|| {
+                    invalid(kind, format!("unknown shape or definition '{}'", name))
+                })?;
+                let overrides = match &item.value {
+                    Value::Mapping(entries) => Node {
+                        value: Value::Mapping(
+                            entries
+                                .iter()
+                                .filter(|(k, _)| k != "add")
+                                .cloned()
+                                .collect(),
+                        ),
+                        line: item.line,
+                    },
+                    _ => item.clone(),
+                };
+                return self.shape(&merge(template, &overrides)?, depth + 1);
+            }
+        };
+
+        if let Some(material) = item.get("material") {
+            shape.set_material(self.material(material)?);
+        }
+        if let Some(transform) = item.get("transform") {
+            shape.set_transform(self.invertible(transform, "transform")?);
+        }
+        if let Some(motion) = item.get("motion") {
+            shape.set_motion(self.invertible(motion, "motion")?);
+        }
+        if let Some(casts) = optional(item, "casts-shadow", boolean)? {
+            shape.set_casts_shadow(casts);
+        }
+        if let Some(receives) = optional(item, "receives-shadow", boolean)? {
+            shape.set_receives_shadow(receives);
+        }
+        if let Some(threshold) = optional(item, "divide", count)? {
+            shape.divide(threshold);
+        }
+        Ok(shape)
+    }
+
+    fn transform(&self, node: &Node, depth: usize) -> Result<Matrix<4, 4>, SceneError> {
+        if depth >= MAX_DEFINE_DEPTH {
+            return Err(invalid(
+                node,
+                "transform definitions nest too deeply".into(),
+            ));
+        }
+        if let Value::String(_) = node.value {
+            return self.transform(self.lookup(node)?, depth + 1);
+        }
+        let mut matrix = Matrix::identity();
+        for step in sequence(node, "transform")? {
+            matrix = match &step.value {
+                Value::String(_) => self.transform(self.lookup(step)?, depth + 1)? * matrix,
+                _ => transform_step(step)? * matrix,
+            };
+        }
+        Ok(matrix)
+    }
+
+    fn invertible(&self, node: &Node, what: &str) -> Result<Matrix<4, 4>, SceneError> {
+        let matrix = self.transform(node, 0)?;
+        if matrix.inverse().is_none() {
+            return Err(invalid(node, format!("'{}' must be invertible", what)));
+        }
+        Ok(matrix)
+    }
+
+    fn material(&self, node: &Node) -> Result<Material, SceneError> {
+        let node = match node.value {
+            Value::String(_) => self.lookup(node)?,
+            _ => node,
+        };
+        let mut material = Material::default();
+        for (key, value) in entries(node, "material")? {
+            match key.as_str() {
+                "color" => material.color = color(value, key)?,
+                "emissive" => material.emissive = color(value, key)?,
+                "ambient" => material.ambient = number(value, key)?,
+                "diffuse" => material.diffuse = number(value, key)?,
+                "specular" => material.specular = number(value, key)?,
+                "shininess" => material.shininess = number(value, key)?,
+                "reflective" => material.reflective = number(value, key)?,
+                "transparency" => material.transparency = number(value, key)?,
+                "refractive-index" => material.refractive_index = number(value, key)?,
+                "pattern" => material.pattern = Some(Arc::from(self.pattern(value)?)),
+                "normal-map" => {
+                    let pattern = self.pattern(value)?;
+                    material.normal_map = Some(Arc::new(PatternNormalMap::new(pattern)));
+                }
+                _ => {
+                    return Err(invalid(
+                        value,
+                        format!("unknown material attribute '{}'", key),
+                    ))
+                }
+            }
+        }
+        Ok(material)
+    }
+
+    fn pattern(&self, node: &Node) -> Result<Box<dyn Pattern>, SceneError> {
+        let kind = required(node, "type")?;
+        let mut pattern: Box<dyn Pattern> = match string(kind, "type")? {
+            "solid" => Box::new(Solid::new(color(required(node, "color")?, "color")?)),
+            "stripes" => {
+                let (a, b) = self.pair(node)?;
+                Box::new(Stripe::nested(a, b))
+            }
+            "gradient" => {
+                let (a, b) = self.pair(node)?;
+                Box::new(Gradient::nested(a, b))
+            }
+            "rings" => {
+                let (a, b) = self.pair(node)?;
+                Box::new(Ring::nested(a, b))
+            }
+            "checkers" => {
+                let (a, b) = self.pair(node)?;
+                Box::new(Checker::nested(a, b))
+            }
+            "blend" => {
+                let (a, b) = self.pair(node)?;
+                Box::new(Blend::nested(a, b))
+            }
+            "noise" => {
+                let (a, b) = self.pair(node)?;
+                let seed = optional(node, "seed", count)?.unwrap_or(0);
+                Box::new(Noise::nested(a, b, seed as u64))
+            }
+            "perturb" => Box::new(Perturb::new(
+                self.pattern(required(node, "pattern")?)?,
+                optional(node, "scale", number)?.unwrap_or(0.2),
+                optional(node, "seed", count)?.unwrap_or(0) as u64,
+            )),
+            "map" => {
+                let mapping = required(node, "mapping")?;
+                let mapping = match string(mapping, "mapping")? {
+                    "spherical" => UvMapping::Spherical,
+                    "planar" => UvMapping::Planar,
+                    "cylindrical" => UvMapping::Cylindrical,
+                    "cube" => UvMapping::Cubic,
+                    other => {
+                        return Err(invalid(mapping, format!("unknown UV mapping '{}'", other)))
+                    }
+                };
+                if mapping == UvMapping::Cubic {
+                    let face = |name| self.uv_pattern(required(node, name)?);
+                    Box::new(CubeMap::new([
+                        face("left")?,
+                        face("right")?,
+                        face("front")?,
+                        face("back")?,
+                        face("up")?,
+                        face("down")?,
+                    ]))
+                } else {
+                    Box::new(TextureMap::new(
+                        self.uv_pattern(required(node, "uv_pattern")?)?,
+                        mapping,
+                    ))
+                }
+            }
+            other => return Err(invalid(kind, format!("unknown pattern type '{}'", other))),
+        };
+        if let Some(transform) = node.get("transform") {
+            pattern.set_transform(self.invertible(transform, "transform")?);
+        }
+        Ok(pattern)
+    }
+
+    fn pair(&self, node: &Node) -> Result<PatternPair, SceneError> {
+        let (items, solid) = match (node.get("colors"), node.get("patterns")) {
+            (Some(colors), _) => (colors, true),
+            (None, Some(patterns)) => (patterns, false),
+            (None, None) => return Err(invalid(node, "missing 'colors' or 'patterns'".into())),
+        };
+        let parts = sequence(items, if solid { "colors" } else { "patterns" })?
+            .iter()
+            .map(|item| -> Result<Box<dyn Pattern>, SceneError> {
+                if solid {
+                    Ok(Box::new(Solid::new(color(item, "colors")?)))
+                } else {
+                    self.pattern(item)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut parts = parts.into_iter();
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(a), Some(b), None) => Ok((a, b)),
+            _ => Err(invalid(items, "patterns combine exactly two parts".into())),
+        }
+    }
+
+    fn uv_pattern(&self, node: &Node) -> Result<Box<dyn UvPattern>, SceneError> {
+        let kind = required(node, "type")?;
+        match string(kind, "type")? {
+            "checkers" => {
+                let colors = required(node, "colors")?;
+                let [a, b] = match sequence(colors, "colors")? {
+                    [a, b] => [color(a, "colors")?, color(b, "colors")?],
+                    _ => return Err(invalid(colors, "'colors' must list two colors".into())),
+                };
+                Ok(Box::new(UvChecker::new(
+                    number(required(node, "width")?, "width")?,
+                    number(required(node, "height")?, "height")?,
+                    a,
+                    b,
+                )))
+            }
+            "align_check" => {
+                let colors = required(node, "colors")?;
+                let corner = |name| color(required(colors, name)?, name);
+                Ok(Box::new(UvAlignCheck::new(
+                    corner("main")?,
+                    corner("ul")?,
+                    corner("ur")?,
+                    corner("bl")?,
+                    corner("br")?,
+                )))
+            }
+            "image" => {
+                let file = required(node, "file")?;
+                let canvas = load_canvas(self.path(file, "file")?).map_err(|error| {
+                    invalid(file, format!("cannot load {:?}: {}", file.value, error))
+                })?;
+                Ok(Box::new(UvImage::new(canvas)))
+            }
+            other => Err(invalid(
+                kind,
+                format!("unknown UV pattern type '{}'", other),
+            )),
+        }
+    }
+}
+
+fn quadric(item: &Node) -> Result<Quadric, SceneError> {
+    let quadric = match item.get("surface") {
+        Some(surface) => match string(surface, "surface")? {
+            "ellipsoid" => {
+                let radii = required(item, "radii")?;
+                let [a, b, c] = triple(radii, "radii")?;
+                if a <= 0.0 || b <= 0.0 || c <= 0.0 {
+                    return Err(invalid(radii, "'radii' must be positive".into()));
+                }
+                Quadric::ellipsoid(a, b, c)
+            }
+            "paraboloid" => Quadric::paraboloid(),
+            "hyperboloid" => Quadric::hyperboloid(),
+            "hyperboloid-of-two-sheets" => Quadric::hyperboloid_of_two_sheets(),
+            other => {
+                return Err(invalid(
+                    surface,
+                    format!("unknown quadric surface '{}'", other),
+                ))
+            }
+        },
+        None => {
+            let node = required(item, "coefficients")?;
+            let rows = sequence(node, "coefficients")?;
+            if rows.len() != 4 {
+                return Err(invalid(node, "'coefficients' must have four rows".into()));
+            }
+            let mut coefficients = Matrix::identity();
+            for (r, row) in rows.iter().enumerate() {
+                match sequence(row, "coefficients")? {
+                    values if values.len() == 4 => {
+                        for (c, value) in values.iter().enumerate() {
+                            coefficients[r][c] = number(value, "coefficients")?;
+                        }
+                    }
+                    _ => {
+                        return Err(invalid(
+                            row,
+                            "each 'coefficients' row must have four numbers".into(),
+                        ))
+                    }
+                }
+            }
+            Quadric::new(coefficients)
+        }
+    };
+    let mut clip = *quadric.clip();
+    if let Some(min) = optional(item, "min", point)? {
+        clip.min = min;
+    }
+    if let Some(max) = optional(item, "max", point)? {
+        clip.max = max;
+    }
+    Ok(quadric.clipped(clip))
+}
+
+fn transform_step(step: &Node) -> Result<Matrix<4, 4>, SceneError> {
+    let items = sequence(step, "transform")?;
+    let (name, args) = match items.split_first() {
+        Some((name, args)) => (string(name, "transform")?, args),
+        None => return Err(invalid(step, "empty transform step".into())),
+    };
+    let args = args
+        .iter()
+        .map(|arg| number(arg, name))
+        .collect::<Result<Vec<_>, _>>()?;
+    let arity = |n: usize| {
+        if args.len() == n {
+            Ok(())
+        } else {
+            Err(invalid(
+                step,
+                format!("'{}' takes {} arguments, found {}", name, n, args.len()),
+            ))
+        }
+    };
+    match name {
+        "translate" => arity(3).map(|_| Matrix::translation(args[0], args[1], args[2])),
+        "scale" => arity(3).map(|_| Matrix::scaling(args[0], args[1], args[2])),
+        "rotate-x" => arity(1).map(|_| Matrix::rotation_x(args[0])),
+        "rotate-y" => arity(1).map(|_| Matrix::rotation_y(args[0])),
+        "rotate-z" => arity(1).map(|_| Matrix::rotation_z(args[0])),
+        "shear" => {
+            arity(6).map(|_| Matrix::shearing(args[0], args[1], args[2], args[3], args[4], args[5]))
+        }
+        "matrix" => arity(16).map(|_| {
+            Matrix::from(std::array::from_fn(|row| {
+                std::array::from_fn(|col| args[row * 4 + col])
+            }))
+        }),
+        _ => Err(invalid(step, format!("unknown transform '{}'", name))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::consts::PI;
+    use std::any::Any;
+
+    const CAMERA: &str = "
+- add: camera
+  width: 100
+  height: 50
+  field-of-view: 0.785
+  from: [0, 0, -5]
+  to: [0, 0, 0]
+  up: [0, 1, 0]
+";
+
+    fn scene(body: &str) -> Scene {
+        Scene::parse(&format!("{}{}", CAMERA, body)).unwrap()
+    }
+
+    fn error(body: &str) -> SceneError {
+        Scene::parse(&format!("{}{}", CAMERA, body)).unwrap_err()
+    }
+
+    fn invalid_line(error: SceneError) -> (usize, String) {
+        match error {
+            SceneError::Invalid { line, message } => (line, message),
+            other => panic!("expected an invalid scene, got {:?}", other),
+        }
+    }
+
+    fn downcast<T: 'static>(shape: &dyn Shape) -> &T {
+        (shape as &dyn Any).downcast_ref::<T>().unwrap()
+    }
+
+    #[test]
+    fn parsing_the_camera() {
+        let s = scene("");
+        assert_eq!(s.camera.hsize(), 100);
+        assert_eq!(s.camera.vsize(), 50);
+        assert_eq!(s.camera.field_of_view(), 0.785);
+        assert_eq!(
+            *s.camera.transform(),
+            view_transform(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::new(0.0, 1.0, 0.0)
+            )
+        );
+        assert!(s.world.shapes().is_empty());
+    }
+
+    #[test]
+    fn camera_lens_settings_are_optional() {
+        let s = Scene::parse(&format!("{}  aperture: 0.1\n  focal-distance: 4\n", CAMERA)).unwrap();
+        assert_eq!(s.camera.aperture(), 0.1);
+        assert_eq!(s.camera.focal_distance(), 4.0);
+    }
+
+    #[test]
+    fn camera_shutter_interval() {
+        let s = Scene::parse(&format!("{}  shutter: [0.25, 0.75]\n", CAMERA)).unwrap();
+        assert_eq!(s.camera.shutter(), (0.25, 0.75));
+        assert_eq!(
+            invalid_line(error("  shutter: [1, 0]\n")).1,
+            "shutter must close after it opens"
+        );
+        assert_eq!(
+            invalid_line(error("  shutter: [1]\n")).1,
+            "'shutter' must have two components"
+        );
+    }
+
+    #[test]
+    fn camera_lenses_must_be_physical() {
+        assert_eq!(
+            invalid_line(error("  aperture: -1\n")),
+            (9, "aperture must not be negative".to_string())
+        );
+        assert_eq!(
+            invalid_line(error("  focal-distance: 0\n")),
+            (9, "focal distance must be positive".to_string())
+        );
+    }
+
+    #[test]
+    fn the_camera_must_look_somewhere() {
+        let (line, message) = invalid_line(
+            Scene::parse(&CAMERA.replace("to: [0, 0, 0]", "to: [0, 0, -5]")).unwrap_err(),
+        );
+        assert_eq!(line, 7);
+        assert_eq!(message, "'to' must differ from 'from'");
+        let (line, message) = invalid_line(
+            Scene::parse(&CAMERA.replace("up: [0, 1, 0]", "up: [0, 0, 1]")).unwrap_err(),
+        );
+        assert_eq!(line, 8);
+        assert_eq!(message, "'up' must not be parallel to the view direction");
+    }
+
+    #[test]
+    fn images_must_have_pixels() {
+        let (line, message) = invalid_line(Scene::parse(&CAMERA.replace("100", "0")).unwrap_err());
+        assert_eq!(line, 3);
+        assert_eq!(
+            message,
+            "'width' must be a positive integer, found a number"
+        );
+        let (line, _) = invalid_line(Scene::parse(&CAMERA.replace("50", "0")).unwrap_err());
+        assert_eq!(line, 4);
+    }
+
+    #[test]
+    fn a_scene_needs_a_camera() {
+        assert!(matches!(
+            Scene::parse("- add: sphere\n"),
+            Err(SceneError::MissingCamera)
+        ));
+        assert!(matches!(Scene::parse(""), Err(SceneError::MissingCamera)));
+    }
+
+    mod backgrounds {
+        use super::*;
+
+        #[test]
+        fn solid_and_gradient_backgrounds() {
+            let s = scene("- add: background\n  color: [0.1, 0.2, 0.3]\n");
+            assert_eq!(
+                s.world.background(),
+                &Background::Solid(Color::new(0.1, 0.2, 0.3))
+            );
+            let s = scene("- add: background\n  bottom: [1, 1, 1]\n  top: [0, 0, 1]\n");
+            assert_eq!(
+                s.world.background(),
+                &Background::Gradient {
+                    bottom: Color::white(),
+                    top: Color::new(0.0, 0.0, 1.0),
+                }
+            );
+        }
+
+        #[test]
+        fn environment_images_load_relative_to_the_scene() {
+            let dir = std::env::temp_dir().join(format!("rt-io-sky-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("sky.ppm"), "P3\n2 1\n255\n255 0 0 0 0 255\n").unwrap();
+            let path = dir.join("scene.yml");
+            let body = "- add: background\n  image: sky.ppm\n";
+            std::fs::write(&path, format!("{}{}", CAMERA, body)).unwrap();
+            let s = Scene::parse_file(&path);
+            std::fs::remove_dir_all(&dir).unwrap();
+            match s.unwrap().world.background() {
+                Background::Environment(image) => {
+                    assert_eq!(image.pixel_at(1, 0), Color::new(0.0, 0.0, 1.0))
+                }
+                other => panic!("expected an environment, got {:?}", other),
+            }
+        }
+    }
+
+    mod volumes {
+        use super::*;
+
+        #[test]
+        fn fog_fills_the_world() {
+            let s = scene("- add: fog\n  density: 0.02\n  color: [0.8, 0.9, 1]\n");
+            match &s.world.volumes()[0] {
+                Volume::Fog { medium, distance } => {
+                    assert_eq!(medium.density, 0.02);
+                    assert_eq!(medium.albedo, Color::new(0.8, 0.9, 1.0));
+                    assert_eq!(*distance, DEFAULT_FOG_DISTANCE);
+                }
+                other => panic!("expected fog, got {:?}", other),
+            }
+        }
+
+        #[test]
+        fn negative_density_is_rejected() {
+            let error = error("- add: fog\n  density: -1\n");
+            assert!(matches!(error, SceneError::Invalid { .. }), "{:?}", error);
+        }
+
+        #[test]
+        fn volumes_are_bounded_by_a_shape() {
+            let s = scene(
+                "- add: volume
+  density: 0.5
+  steps: 8
+  shape:
+    add: cube
+    transform:
+      - [scale, 2, 2, 2]
+",
+            );
+            assert!(s.world.shapes().is_empty());
+            match &s.world.volumes()[0] {
+                Volume::Bounded { medium, boundary } => {
+                    assert_eq!((medium.density, medium.steps), (0.5, 8));
+                    assert_eq!(medium.albedo, Color::white());
+                    downcast::<Cube>(boundary.as_ref());
+                    assert_eq!(*boundary.transform(), Matrix::scaling(2.0, 2.0, 2.0));
+                }
+                other => panic!("expected a bounded volume, got {:?}", other),
+            }
+        }
+    }
+
+    mod lights {
+        use super::*;
+
+        #[test]
+        fn point_light() {
+            let s = scene("- add: light\n  at: [-10, 10, -10]\n  intensity: [1, 1, 1]\n");
+            let light = s.world.lights()[0].as_ref() as &dyn Any;
+            assert_eq!(
+                light.downcast_ref::<PointLight>(),
+                Some(&PointLight::new(
+                    Point::new(-10.0, 10.0, -10.0),
+                    Color::white()
+                ))
+            );
+        }
+
+        #[test]
+        fn area_light() {
+            let s = scene(
+                "- add: light
+  corner: [-1, 2, 4]
+  uvec: [2, 0, 0]
+  usteps: 10
+  vvec: [0, 2, 0]
+  vsteps: 10
+  jitter: true
+  intensity: [1.5, 1.5, 1.5]
+",
+            );
+            let light = s.world.lights()[0].as_ref() as &dyn Any;
+            let light = light.downcast_ref::<AreaLight>().unwrap();
+            assert_eq!(light.samples(), 100);
+            assert!(light.jitter);
+            assert_eq!(light.uvec, Vector::new(0.2, 0.0, 0.0));
+        }
+
+        #[test]
+        fn spot_light() {
+            let s = scene(
+                "- add: spot-light
+  at: [0, 10, 0]
+  direction: [0, -1, 0]
+  inner-angle: 0.3
+  outer-angle: 0.5
+  intensity: [1, 1, 1]
+",
+            );
+            let light = s.world.lights()[0].as_ref() as &dyn Any;
+            let light = light.downcast_ref::<SpotLight>().unwrap();
+            assert_eq!(light.outer_angle, 0.5);
+        }
+
+        #[test]
+        fn spot_light_cones_must_widen() {
+            let (line, message) = invalid_line(error(
+                "- add: spot-light
+  at: [0, 10, 0]
+  direction: [0, -1, 0]
+  inner-angle: 0.5
+  outer-angle: 0.3
+  intensity: [1, 1, 1]
+",
+            ));
+            assert_eq!(line, 12);
+            assert_eq!(message, "inner angle must not exceed the outer angle");
+        }
+    }
+
+    mod shapes {
+        use super::*;
+
+        #[test]
+        fn primitives_with_material_and_transform() {
+            let s = scene(
+                "- add: sphere
+  material:
+    color: [1, 0, 0]
+    diffuse: 0.7
+    refractive-index: 1.5
+  transform:
+    - [scale, 2, 2, 2]
+    - [translate, 0, 1, 0]
+- add: plane
+- add: cube
+",
+            );
+            let shapes = s.world.shapes();
+            assert_eq!(shapes.len(), 3);
+            let m = shapes[0].material();
+            assert_eq!(m.color, Color::new(1.0, 0.0, 0.0));
+            assert_eq!(m.diffuse, 0.7);
+            assert_eq!(m.refractive_index, 1.5);
+            assert_eq!(
+                *shapes[0].transform(),
+                Matrix::scaling(2.0, 2.0, 2.0).translate(0.0, 1.0, 0.0)
+            );
+            downcast::<Plane>(shapes[1].as_ref());
+            downcast::<Cube>(shapes[2].as_ref());
+        }
+
+        #[test]
+        fn moving_shapes_have_an_end_transform() {
+            let s = scene(
+                "- add: sphere
+  transform:
+    - [translate, -1, 0, 0]
+  motion:
+    - [translate, 1, 0, 0]
+- add: sphere
+",
+            );
+            let shapes = s.world.shapes();
+            assert_eq!(
+                shapes[0].end_transform(),
+                Some(&Matrix::translation(1.0, 0.0, 0.0))
+            );
+            assert_eq!(shapes[0].transform_at(0.5), Matrix::identity());
+            assert_eq!(shapes[1].end_transform(), None);
+        }
+
+        #[test]
+        fn shapes_can_opt_out_of_shadows() {
+            let s = scene(
+                "- add: plane
+  receives-shadow: false
+- add: sphere
+  casts-shadow: false
+- add: cube
+",
+            );
+            let shapes = s.world.shapes();
+            assert!(shapes[0].casts_shadow() && !shapes[0].receives_shadow());
+            assert!(!shapes[1].casts_shadow() && shapes[1].receives_shadow());
+            assert!(shapes[2].casts_shadow() && shapes[2].receives_shadow());
+        }
+
+        #[test]
+        fn transforms_can_be_given_as_matrices() {
+            let s = scene(
+                "- add: sphere
+  transform:
+    - [matrix, 1, 0, 0, 2, 0, 1, 0, 3, 0, 0, 1, 4, 0, 0, 0, 1]
+",
+            );
+            assert_eq!(
+                *s.world.shapes()[0].transform(),
+                Matrix::translation(2.0, 3.0, 4.0)
+            );
+            assert_eq!(
+                invalid_line(error("- add: sphere\n  transform:\n    - [matrix, 1, 0]\n")).1,
+                "'matrix' takes 16 arguments, found 2"
+            );
+        }
+
+        #[test]
+        fn smooth_triangles_interpolate_their_normals() {
+            let s = scene(
+                "- add: smooth-triangle
+  p1: [0, 1, 0]
+  p2: [-1, 0, 0]
+  p3: [1, 0, 0]
+  n1: [0, 1, 0]
+  n2: [-1, 0, 0]
+  n3: [1, 0, 0]
+",
+            );
+            let t = downcast::<SmoothTriangle>(s.world.shapes()[0].as_ref());
+            assert_eq!(t.p2(), Point::new(-1.0, 0.0, 0.0));
+            assert_eq!(t.n3(), Vector::new(1.0, 0.0, 0.0));
+        }
+
+        #[test]
+        fn emissive_shapes_light_the_world() {
+            let s = scene("- add: sphere\n  material:\n    emissive: [1, 0.5, 0]\n");
+            let m = s.world.shapes()[0].material();
+            assert_eq!(m.emissive, Color::new(1.0, 0.5, 0.0));
+            assert_eq!(s.world.emitters().len(), 1);
+        }
+
+        #[test]
+        fn distance_fields_combine_primitives() {
+            let s = scene(
+                "- define: blob
+  value:
+    type: sphere
+    radius: 1
+    at: [1, 0, 0]
+- add: sdf
+  field:
+    type: union
+    blend: 0.5
+    of:
+      - { type: box, size: [2, 2, 2] }
+      - blob
+      - { type: torus, major: 2, minor: 0.25 }
+",
+            );
+            let shape = downcast::<SdfShape>(s.world.shapes()[0].as_ref());
+            assert!(shape.sdf().distance(&Point::new(1.9, 0.0, 0.0)) < 0.0);
+            assert!(shape.sdf().distance(&Point::new(0.0, 0.0, 2.2)) < 0.0);
+            assert!(shape.sdf().distance(&Point::new(0.0, 3.0, 0.0)) > 0.0);
+        }
+
+        #[test]
+        fn unknown_distance_fields_are_rejected() {
+            let (line, message) = invalid_line(error("- add: sdf\n  field:\n    type: blob\n"));
+            assert_eq!(line, 11);
+            assert_eq!(message, "unknown distance field 'blob'");
+        }
+
+        #[test]
+        fn tori_and_quadrics() {
+            let s = scene(
+                "- add: torus
+  major: 2
+  minor: 0.5
+- add: quadric
+  surface: ellipsoid
+  radii: [1, 2, 3]
+- add: quadric
+  coefficients:
+    - [1, 0, 0, 0]
+    - [0, -1, 0, 0]
+    - [0, 0, 1, 0]
+    - [0, 0, 0, -1]
+  min: [-5, -1, -5]
+  max: [5, 1, 5]
+",
+            );
+            let torus = downcast::<Torus>(s.world.shapes()[0].as_ref());
+            assert_eq!((torus.major, torus.minor), (2.0, 0.5));
+            let ellipsoid = downcast::<Quadric>(s.world.shapes()[1].as_ref());
+            assert_eq!(ellipsoid.clip().max, Point::new(1.0, 2.0, 3.0));
+            let hyperboloid = downcast::<Quadric>(s.world.shapes()[2].as_ref());
+            assert_eq!(hyperboloid.coefficients()[1][1], -1.0);
+            assert_eq!(hyperboloid.clip().min, Point::new(-5.0, -1.0, -5.0));
+        }
+
+        #[test]
+        fn ellipsoid_radii_must_be_positive() {
+            let (line, message) = invalid_line(error(
+                "- add: quadric\n  surface: ellipsoid\n  radii: [0, 1, 1]\n",
+            ));
+            assert_eq!(line, 11);
+            assert_eq!(message, "'radii' must be positive");
+        }
+
+        #[test]
+        fn quadric_coefficients_must_be_four_by_four() {
+            let (line, message) = invalid_line(error(
+                "- add: quadric\n  coefficients:\n    - [1, 0, 0, 0]\n",
+            ));
+            assert_eq!(line, 11);
+            assert_eq!(message, "'coefficients' must have four rows");
+        }
+
+        #[test]
+        fn instances_share_one_prototype_per_definition() {
+            let s = scene(
+                "- define: tree
+  value:
+    add: group
+    children:
+      - add: sphere
+        material: { color: [0, 1, 0] }
+- add: instance
+  of: tree
+  transform: [[translate, -2, 0, 0]]
+- add: instance
+  of: tree
+  transform: [[translate, 2, 0, 0]]
+  material: { color: [1, 0, 0] }
+",
+            );
+            let a = downcast::<Instance>(s.world.shapes()[0].as_ref());
+            let b = downcast::<Instance>(s.world.shapes()[1].as_ref());
+            assert!(Arc::ptr_eq(a.prototype(), b.prototype()));
+            assert_eq!(a.material_override(), None);
+            assert_eq!(
+                b.material_override().unwrap().color,
+                Color::new(1.0, 0.0, 0.0)
+            );
+            assert_eq!(a.bounds().min, Point::new(-3.0, -1.0, -1.0));
+        }
+
+        #[test]
+        fn instances_must_name_a_definition() {
+            let (line, message) = invalid_line(error("- add: instance\n  of: forest\n"));
+            assert_eq!(line, 10);
+            assert_eq!(message, "unknown definition 'forest'");
+        }
+
+        #[test]
+        fn truncated_cylinders_and_cones() {
+            let s = scene(
+                "- add: cylinder\n  min: -1\n  max: 2\n  closed: true\n- add: cone\n  max: 0\n",
+            );
+            let cylinder = downcast::<Cylinder>(s.world.shapes()[0].as_ref());
+            assert_eq!(
+                (cylinder.minimum, cylinder.maximum, cylinder.closed),
+                (-1.0, 2.0, true)
+            );
+            let cone = downcast::<Cone>(s.world.shapes()[1].as_ref());
+            assert_eq!(
+                (cone.minimum, cone.maximum, cone.closed),
+                (Scalar::NEG_INFINITY, 0.0, false)
+            );
+        }
+
+        #[test]
+        fn groups_and_csg() {
+            let s = scene(
+                "- add: group
+  transform: [[translate, 0, 0, 10]]
+  children:
+    - add: sphere
+    - add: csg
+      operation: difference
+      left: { add: cube }
+      right: { add: sphere, transform: [[scale, 0.5, 0.5, 0.5]] }
+",
+            );
+            let group = downcast::<Group>(s.world.shapes()[0].as_ref());
+            assert_eq!(group.len(), 2);
+            assert_eq!(
+                *group.children()[0].transform(),
+                Matrix::translation(0.0, 0.0, 10.0)
+            );
+            let csg = downcast::<Csg>(group.children()[1].as_ref());
+            assert_eq!(csg.operation(), CsgOperation::Difference);
+            assert_eq!(
+                *csg.right().transform(),
+                Matrix::translation(0.0, 0.0, 10.0) * Matrix::scaling(0.5, 0.5, 0.5)
+            );
+        }
+
+        #[test]
+        fn group_materials_match_the_builder() {
+            fn materials(shape: &dyn Shape, out: &mut Vec<Material>) {
+                out.push(shape.material().clone());
+                if let Some(group) = (shape as &dyn Any).downcast_ref::<Group>() {
+                    for child in group.children() {
+                        materials(child.as_ref(), out);
+                    }
+                }
+            }
+            let s = scene(
+                "- add: group
+  material: { color: [1, 0, 0] }
+  children:
+    - add: sphere
+    - add: group
+      children:
+        - add: cube
+    - add: sphere
+      material: { color: [0, 0, 1] }
+",
+            );
+            let built = Group::builder()
+                .material(Material::builder().color(Color::new(1.0, 0.0, 0.0)))
+                .child(Sphere::new())
+                .child(Group::builder().child(Cube::new()))
+                .child(
+                    Sphere::builder()
+                        .material(Material::builder().color(Color::new(0.0, 0.0, 1.0))),
+                )
+                .build();
+            let (mut loaded, mut expected) = (Vec::new(), Vec::new());
+            materials(s.world.shapes()[0].as_ref(), &mut loaded);
+            materials(&built, &mut expected);
+            assert_eq!(loaded, expected);
+            assert_eq!(loaded[2].color, Color::new(1.0, 0.0, 0.0));
+            assert_eq!(loaded[4].color, Color::new(0.0, 0.0, 1.0));
+        }
+
+        #[test]
+        fn clipping_shapes_to_a_box_and_planes() {
+            let s = scene(
+                "- add: clip
+  shape: { add: sphere }
+  min: [-2, -2, -2]
+  max: [2, 0, 2]
+  planes:
+    - { point: [0, 0, 0], normal: [1, 0, 0] }
+  capped: true
+  transform: [[translate, 0, 1, 0]]
+",
+            );
+            let clip = downcast::<Clip>(s.world.shapes()[0].as_ref());
+            assert_eq!(clip.planes().len(), 7);
+            assert!(clip.capped);
+            assert_eq!(
+                *clip.shape().transform(),
+                Matrix::translation(0.0, 1.0, 0.0)
+            );
+            assert_eq!(
+                invalid_line(error("- add: clip\n  shape: { add: sphere }\n")).1,
+                "a clip needs 'min' and 'max' or 'planes'"
+            );
+            assert_eq!(
+                invalid_line(error(
+                    "- add: clip\n  shape: { add: sphere }\n  max: [1, 1, 1]\n"
+                ))
+                .1,
+                "a clip box needs both 'min' and 'max'"
+            );
+        }
+
+        #[test]
+        fn loading_obj_files_relative_to_the_scene() {
+            let dir = std::env::temp_dir().join(format!("rt-io-scene-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("tri.obj"), "v 0 1 0\nv -1 0 0\nv 1 0 0\nf 1 2 3\n").unwrap();
+            let path = dir.join("scene.yml");
+            let source = format!("{}- add: obj\n  file: tri.obj\n", CAMERA);
+            std::fs::write(&path, &source).unwrap();
+            let s = Scene::parse_file(&path);
+            let unsaved = Scene::parse_at(&source, &path);
+            std::fs::remove_dir_all(&dir).unwrap();
+            for s in [s.unwrap(), unsaved.unwrap()] {
+                let group = downcast::<Group>(s.world.shapes()[0].as_ref());
+                assert_eq!(group.len(), 1);
+            }
+        }
+    }
+
+    mod definitions {
+        use super::*;
+
+        #[test]
+        fn loading_gltf_files_relative_to_the_scene() {
+            let dir = std::env::temp_dir().join(format!("rt-io-gltf-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let gltf = r#"{
+  "asset": { "version": "2.0" },
+  "buffers": [{ "uri": "data:application/octet-stream;base64,AAAAAAAAgD8AAAAAAACAvwAAAAAAAAAAAACAPwAAAAAAAAAA", "byteLength": 36 }],
+  "bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+  "accessors": [{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }],
+  "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+  "nodes": [{ "mesh": 0 }]
+}"#;
+            std::fs::write(dir.join("tri.gltf"), gltf).unwrap();
+            let path = dir.join("scene.yml");
+            std::fs::write(&path, format!("{}- add: gltf\n  file: tri.gltf\n", CAMERA)).unwrap();
+            let s = Scene::parse_file(&path);
+            std::fs::remove_dir_all(&dir).unwrap();
+            let s = s.unwrap();
+            let group = downcast::<Group>(s.world.shapes()[0].as_ref());
+            assert_eq!(group.len(), 1);
+        }
+
+        #[test]
+        fn materials_can_be_defined_and_extended() {
+            let s = scene(
+                "- define: white-material
+  value:
+    color: [1, 1, 1]
+    diffuse: 0.7
+    ambient: 0.1
+- define: blue-material
+  extend: white-material
+  value:
+    color: [0.537, 0.831, 0.914]
+- add: sphere
+  material: blue-material
+",
+            );
+            let m = s.world.shapes()[0].material();
+            assert_eq!(m.color, Color::new(0.537, 0.831, 0.914));
+            assert_eq!(m.diffuse, 0.7);
+        }
+
+        #[test]
+        fn transforms_can_reference_definitions() {
+            let s = scene(
+                "- define: standard-transform
+  value:
+    - [translate, 1, -1, 1]
+    - [scale, 0.5, 0.5, 0.5]
+- define: large-object
+  value:
+    - standard-transform
+    - [scale, 3.5, 3.5, 3.5]
+- add: cube
+  transform:
+    - large-object
+    - [rotate-y, 1]
+",
+            );
+            let expected = Matrix::translation(1.0, -1.0, 1.0)
+                .scale(0.5, 0.5, 0.5)
+                .scale(3.5, 3.5, 3.5)
+                .rotate_y(1.0);
+            assert_eq!(*s.world.shapes()[0].transform(), expected);
+        }
+
+        #[test]
+        fn shapes_can_be_defined_and_reused() {
+            let s = scene(
+                "- define: leg
+  value:
+    add: group
+    children:
+      - add: sphere
+      - add: cylinder
+        max: 1
+- add: leg
+  transform: [[rotate-y, 0.5]]
+- add: leg
+",
+            );
+            assert_eq!(s.world.shapes().len(), 2);
+            let first = downcast::<Group>(s.world.shapes()[0].as_ref());
+            assert_eq!(*first.children()[0].transform(), Matrix::rotation_y(0.5));
+        }
+    }
+
+    mod patterns {
+        use super::*;
+
+        fn pattern_color(body: &str, at: Point) -> Color {
+            let s = scene(&format!(
+                "- add: sphere\n  material:\n    pattern:\n{}",
+                body
+            ));
+            let shape = s.world.shapes()[0].as_ref();
+            shape.material().color_at(shape, &at)
+        }
+
+        #[test]
+        fn two_tone_patterns_with_transforms() {
+            let body = "      type: stripes
+      colors: [[1, 1, 1], [0, 0, 0]]
+      transform: [[scale, 0.5, 1, 1]]
+";
+            assert_eq!(
+                pattern_color(body, Point::new(0.25, 0.0, 0.0)),
+                Color::white()
+            );
+            assert_eq!(
+                pattern_color(body, Point::new(0.75, 0.0, 0.0)),
+                Color::black()
+            );
+        }
+
+        #[test]
+        fn nested_patterns() {
+            let body = "      type: checkers
+      patterns:
+        - { type: solid, color: [1, 0, 0] }
+        - type: stripes
+          colors: [[0, 1, 0], [0, 0, 1]]
+";
+            assert_eq!(
+                pattern_color(body, Point::new(0.5, 0.5, 0.5)),
+                Color::new(1.0, 0.0, 0.0)
+            );
+            assert_eq!(
+                pattern_color(body, Point::new(1.5, 0.5, 0.5)),
+                Color::new(0.0, 0.0, 1.0)
+            );
+        }
+
+        #[test]
+        fn uv_mapped_patterns() {
+            let body = "      type: map
+      mapping: spherical
+      uv_pattern:
+        type: checkers
+        width: 16
+        height: 8
+        colors: [[0, 0, 0], [1, 1, 1]]
+";
+            assert_eq!(
+                pattern_color(body, Point::new(0.4315, 0.4670, 0.7719)),
+                Color::white()
+            );
+        }
+
+        #[test]
+        fn cube_maps_need_every_face() {
+            let (line, message) = invalid_line(error(
+                "- add: cube
+  material:
+    pattern:
+      type: map
+      mapping: cube
+      left: { type: checkers, width: 1, height: 1, colors: [[0, 0, 0], [1, 1, 1]] }
+",
+            ));
+            assert_eq!(line, 12);
+            assert_eq!(message, "missing 'right'");
+        }
+    }
+
+    mod errors {
+        use super::*;
+
+        #[test]
+        fn unknown_shapes_report_their_line() {
+            let (line, message) = invalid_line(error("- add: sphere\n- add: teapot\n"));
+            assert_eq!(line, 10);
+            assert_eq!(message, "unknown shape or definition 'teapot'");
+        }
+
+        #[test]
+        fn type_errors_name_the_attribute() {
+            let (line, message) =
+                invalid_line(error("- add: sphere\n  material:\n    diffuse: lots\n"));
+            assert_eq!(line, 11);
+            assert_eq!(message, "'diffuse' must be a number, found a string");
+        }
+
+        #[test]
+        fn unknown_material_attributes_are_rejected() {
+            let (_, message) =
+                invalid_line(error("- add: sphere\n  material:\n    colour: [1, 0, 0]\n"));
+            assert_eq!(message, "unknown material attribute 'colour'");
+        }
+
+        #[test]
+        fn transform_arity_is_checked() {
+            let (_, message) =
+                invalid_line(error("- add: sphere\n  transform: [[translate, 1, 2]]\n"));
+            assert_eq!(message, "'translate' takes 3 arguments, found 2");
+        }
+
+        #[test]
+        fn singular_transforms_are_rejected() {
+            let (line, message) =
+                invalid_line(error("- add: sphere\n  transform: [[scale, 1, 0, 1]]\n"));
+            assert_eq!(line, 10);
+            assert_eq!(message, "'transform' must be invertible");
+        }
+
+        #[test]
+        fn singular_motion_is_rejected() {
+            let (line, message) =
+                invalid_line(error("- add: sphere\n  motion: [[scale, 0, 0, 0]]\n"));
+            assert_eq!(line, 10);
+            assert_eq!(message, "'motion' must be invertible");
+        }
+
+        #[test]
+        fn undefined_references_are_reported() {
+            let (_, message) = invalid_line(error("- add: sphere\n  material: shiny\n"));
+            assert_eq!(message, "unknown definition 'shiny'");
+        }
+
+        #[test]
+        fn self_referencing_definitions_are_caught() {
+            let (_, message) = invalid_line(error(
+                "- define: loop\n  value:\n    add: loop\n- add: loop\n",
+            ));
+            assert_eq!(message, "definition 'loop' is nested too deeply");
+        }
+
+        #[test]
+        fn syntax_errors_carry_a_line() {
+            match Scene::parse("- add: camera\n  width: [1, 2\n") {
+                Err(SceneError::Syntax { line, .. }) => assert!(line >= 2),
+                other => panic!("expected a syntax error, got {:?}", other),
+            }
+        }
+
+        #[test]
+        fn errors_display_their_line() {
+            let e = error("- add: teapot\n");
+            assert_eq!(
+                e.to_string(),
+                "line 9: unknown shape or definition 'teapot'"
+            );
+        }
+    }
+
+    #[test]
+    fn json_scenes_are_supported() {
+        let s = Scene::parse(
+            r#"[
+  {"add": "camera", "width": 10, "height": 10, "field-of-view": 1.0471975511965976,
+   "from": [0, 1.5, -5], "to": [0, 1, 0], "up": [0, 1, 0]},
+  {"add": "light", "at": [-10, 10, -10], "intensity": [1, 1, 1]},
+  {"add": "sphere", "material": {"color": [0.1, 1, 0.5], "diffuse": 0.7},
+   "transform": [["rotate-x", 1.5707963267948966]]}
+]"#,
+        )
+        .unwrap();
+        assert_eq!(s.camera.field_of_view(), PI / 3.0);
+        assert_eq!(s.world.lights().len(), 1);
+        assert_eq!(
+            *s.world.shapes()[0].transform(),
+            Matrix::rotation_x(PI / 2.0)
+        );
+    }
+}
@@ -0,0 +1,973 @@
+use crate::yaml_node::{Node, Value};
+use crate::Scene;
+use rt_core::{to_f64, Color, Matrix, Point, Scalar, Vector};
+use rt_render::{
+    view_transform, AreaLight, Background, Camera, Light, Medium, PointLight, SpotLight, Volume,
+};
+use rt_shapes::{
+    Blend, Checker, Clip, Cone, Csg, CsgOperation, Cube, CubeFace, CubeMap, Cylinder, Gradient,
+    Group, Instance, Material, Noise, Pattern, PatternNormalMap, Perturb, Plane, Quadric, Ring,
+    Sdf, SdfShape, Shape, SmoothTriangle, Solid, Sphere, Stripe, TextureMap, Torus, Triangle,
+    UvAlignCheck, UvChecker, UvMapping, UvPattern,
+};
+use std::any::Any;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+const LARGEST_EXACT_SEED: u64 = 1 << 53;
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(std::io::Error),
+    Unsupported(String),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::Io(error) => write!(f, "I/O error: {}", error),
+            ExportError::Unsupported(what) => write!(f, "cannot export {}", what),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExportError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ExportError {
+    fn from(error: std::io::Error) -> Self {
+        ExportError::Io(error)
+    }
+}
+
+impl Scene {
+    pub fn to_yaml(&self) -> Result<String, ExportError> {
+        Ok(SceneWriter::default().document(self)?.to_yaml())
+    }
+
+    pub fn to_json(&self) -> Result<String, ExportError> {
+        Ok(SceneWriter::default().document(self)?.to_json())
+    }
+
+    pub fn save_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ExportError> {
+        let path = path.as_ref();
+        let json = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        let text = if json {
+            self.to_json()?
+        } else {
+            self.to_yaml()?
+        };
+        std::fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+fn unsupported<T>(what: impl Into<String>) -> Result<T, ExportError> {
+    Err(ExportError::Unsupported(what.into()))
+}
+
+fn type_name(value: &impl std::fmt::Debug) -> String {
+    let debug = format!("{:?}", value);
+    debug
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn number(value: Scalar) -> Node {
+    let value = to_f64(value);
+    let rounded = (value * 1e9).round() / 1e9;
+    if (rounded - value).abs() < 1e-12 * value.abs().max(1.0) {
+        Node::new(Value::Number(rounded))
+    } else {
+        Node::new(Value::Number(value))
+    }
+}
+
+fn count(value: usize) -> Node {
+    Node::new(Value::Number(value as f64))
+}
+
+fn seed(value: u64) -> Result<Node, ExportError> {
+    if value > LARGEST_EXACT_SEED {
+        return unsupported(format!("seed {} exactly", value));
+    }
+    Ok(Node::new(Value::Number(value as f64)))
+}
+
+fn text(value: &str) -> Node {
+    Node::new(Value::String(value.to_string()))
+}
+
+fn list(items: Vec<Node>) -> Node {
+    Node::new(Value::Sequence(items))
+}
+
+fn triple(values: [Scalar; 3]) -> Node {
+    list(values.iter().copied().map(number).collect())
+}
+
+fn color(color: Color) -> Node {
+    triple([color.red(), color.green(), color.blue()])
+}
+
+fn point(point: Point) -> Node {
+    triple([point.x(), point.y(), point.z()])
+}
+
+fn vector(vector: Vector) -> Node {
+    triple([vector.x(), vector.y(), vector.z()])
+}
+
+fn matrix_step(matrix: &Matrix<4, 4>) -> Node {
+    let mut step = vec![text("matrix")];
+    step.extend((0..16).map(|i| number(matrix[i / 4][i % 4])));
+    list(vec![list(step)])
+}
+
+struct Fields(Vec<(String, Node)>);
+
+impl Fields {
+    fn new(key: &str, kind: &str) -> Self {
+        Fields(vec![(key.to_string(), text(kind))])
+    }
+
+    fn with(mut self, key: &str, value: Node) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    fn set(&mut self, key: &str, value: Node) {
+        self.0.push((key.to_string(), value));
+    }
+
+    fn set_transform(&mut self, key: &str, matrix: &Matrix<4, 4>) {
+        if *matrix != Matrix::identity() {
+            self.set(key, matrix_step(matrix));
+        }
+    }
+
+    fn node(self) -> Node {
+        Node::new(Value::Mapping(self.0))
+    }
+}
+
+struct Parent {
+    inverse: Matrix<4, 4>,
+    end_inverse: Matrix<4, 4>,
+    casts_shadow: bool,
+    receives_shadow: bool,
+}
+
+impl Parent {
+    fn root() -> Self {
+        Parent {
+            inverse: Matrix::identity(),
+            end_inverse: Matrix::identity(),
+            casts_shadow: true,
+            receives_shadow: true,
+        }
+    }
+
+    fn of(shape: &dyn Shape) -> Self {
+        let inverse = *shape.inverse_transform();
+        let end_inverse = shape.end_transform().map_or(inverse, |end| {
+            end.inverse()
+                .expect("shape end transform must be invertible")
+        });
+        Parent {
+            inverse,
+            end_inverse,
+            casts_shadow: shape.casts_shadow(),
+            receives_shadow: shape.receives_shadow(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct SceneWriter {
+    defines: Vec<Node>,
+    prototypes: HashMap<*const (), String>,
+}
+
+impl SceneWriter {
+    fn document(mut self, scene: &Scene) -> Result<Node, ExportError> {
+        let world = &scene.world;
+        let mut items = vec![camera(&scene.camera)?];
+        for each in world.lights() {
+            items.push(light(each.as_ref())?);
+        }
+        if *world.background() != Background::default() {
+            items.push(background(world.background())?);
+        }
+        for volume in world.volumes() {
+            items.push(self.volume(volume)?);
+        }
+        for shape in world.shapes() {
+            items.push(self.shape(shape.as_ref(), &Parent::root())?);
+        }
+        self.defines.extend(items);
+        Ok(list(self.defines))
+    }
+
+    fn prototype(&mut self, prototype: &Arc<dyn Shape>) -> Result<String, ExportError> {
+        let key = Arc::as_ptr(prototype) as *const ();
+        if let Some(name) = self.prototypes.get(&key) {
+            return Ok(name.clone());
+        }
+        let value = self.shape(prototype.as_ref(), &Parent::root())?;
+        let name = format!("prototype-{}", self.prototypes.len() + 1);
+        self.defines
+            .push(Fields::new("define", &name).with("value", value).node());
+        self.prototypes.insert(key, name.clone());
+        Ok(name)
+    }
+
+    fn volume(&mut self, volume: &Volume) -> Result<Node, ExportError> {
+        let mut fields = match volume {
+            Volume::Fog { distance, .. } => {
+                Fields::new("add", "fog").with("distance", number(*distance))
+            }
+            Volume::Bounded { boundary, .. } => {
+                let boundary = self.shape(boundary.as_ref(), &Parent::root())?;
+                Fields::new("add", "volume").with("shape", boundary)
+            }
+        };
+        let medium = volume.medium();
+        fields.set("density", number(medium.density));
+        if medium.albedo != Color::white() {
+            fields.set("color", color(medium.albedo));
+        }
+        if medium.steps != Medium::new(medium.density, medium.albedo).steps {
+            fields.set("steps", count(medium.steps));
+        }
+        Ok(fields.node())
+    }
+
+    fn shape(&mut self, shape: &dyn Shape, parent: &Parent) -> Result<Node, ExportError> {
+        let any = shape as &dyn Any;
+        let mut fields = if any.is::<Sphere>() {
+            Fields::new("add", "sphere")
+        } else if any.is::<Plane>() {
+            Fields::new("add", "plane")
+        } else if any.is::<Cube>() {
+            Fields::new("add", "cube")
+        } else if let Some(cylinder) = any.downcast_ref::<Cylinder>() {
+            truncated(
+                "cylinder",
+                cylinder.minimum,
+                cylinder.maximum,
+                cylinder.closed,
+            )
+        } else if let Some(cone) = any.downcast_ref::<Cone>() {
+            truncated("cone", cone.minimum, cone.maximum, cone.closed)
+        } else if let Some(triangle) = any.downcast_ref::<Triangle>() {
+            Fields::new("add", "triangle")
+                .with("p1", point(triangle.p1()))
+                .with("p2", point(triangle.p2()))
+                .with("p3", point(triangle.p3()))
+        } else if let Some(triangle) = any.downcast_ref::<SmoothTriangle>() {
+            Fields::new("add", "smooth-triangle")
+                .with("p1", point(triangle.p1()))
+                .with("p2", point(triangle.p2()))
+                .with("p3", point(triangle.p3()))
+                .with("n1", vector(triangle.n1()))
+                .with("n2", vector(triangle.n2()))
+                .with("n3", vector(triangle.n3()))
+        } else if let Some(torus) = any.downcast_ref::<Torus>() {
+            Fields::new("add", "torus")
+                .with("major", number(torus.major))
+                .with("minor", number(torus.minor))
+        } else if let Some(surface) = any.downcast_ref::<Quadric>() {
+            quadric(surface)
+        } else if let Some(shape) = any.downcast_ref::<SdfShape>() {
+            Fields::new("add", "sdf").with("field", sdf(shape.sdf(), Vector::new(0.0, 0.0, 0.0))?)
+        } else if let Some(group) = any.downcast_ref::<Group>() {
+            let children = group
+                .children()
+                .iter()
+                .map(|child| self.shape(child.as_ref(), &Parent::of(group)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut fields = Fields::new("add", "group");
+            if !children.is_empty() {
+                fields.set("children", list(children));
+            }
+            fields
+        } else if let Some(csg) = any.downcast_ref::<Csg>() {
+            let operation = match csg.operation() {
+                CsgOperation::Union => "union",
+                CsgOperation::Intersection => "intersection",
+                CsgOperation::Difference => "difference",
+            };
+            Fields::new("add", "csg")
+                .with("operation", text(operation))
+                .with("left", self.shape(csg.left(), &Parent::of(csg))?)
+                .with("right", self.shape(csg.right(), &Parent::of(csg))?)
+        } else if let Some(clip) = any.downcast_ref::<Clip>() {
+            let planes = clip
+                .planes()
+                .iter()
+                .map(|plane| {
+                    Fields(Vec::new())
+                        .with("point", point(plane.point))
+                        .with("normal", vector(plane.normal))
+                        .node()
+                })
+                .collect();
+            let mut fields = Fields::new("add", "clip")
+                .with("shape", self.shape(clip.shape(), &Parent::of(clip))?)
+                .with("planes", list(planes));
+            if clip.capped {
+                fields.set("capped", Node::new(Value::Bool(true)));
+            }
+            fields
+        } else if let Some(instance) = any.downcast_ref::<Instance>() {
+            let name = self.prototype(instance.prototype())?;
+            let mut fields = Fields::new("add", "instance").with("of", text(&name));
+            if let Some(overridden) = instance.material_override() {
+                fields.set("material", material(overridden)?.node());
+            }
+            fields
+        } else {
+            return unsupported(format!("{} shapes", type_name(&shape)));
+        };
+
+        let own_material = material(shape.material())?;
+        if !any.is::<Instance>() && !own_material.0.is_empty() {
+            fields.set("material", own_material.node());
+        }
+        let start = parent.inverse * *shape.transform();
+        fields.set_transform("transform", &start);
+        if let Some(end) = shape.end_transform() {
+            let end = parent.end_inverse * *end;
+            if end != start {
+                fields.set("motion", matrix_step(&end));
+            }
+        }
+        if parent.casts_shadow && !shape.casts_shadow() {
+            fields.set("casts-shadow", Node::new(Value::Bool(false)));
+        }
+        if parent.receives_shadow && !shape.receives_shadow() {
+            fields.set("receives-shadow", Node::new(Value::Bool(false)));
+        }
+        Ok(fields.node())
+    }
+}
+
+fn camera(camera: &Camera) -> Result<Node, ExportError> {
+    let transform = camera.transform();
+    let row = |r: usize| Vector::new(transform[r][0], transform[r][1], transform[r][2]);
+    let from = transform
+        .inverse()
+        .expect("camera transform must be invertible")
+        * Point::origin();
+    let forward = -row(2);
+    let to = from + forward;
+    // view_transform leaves the up row scaled by the sine of the angle between
+    // forward and the given up vector, so any up vector at that angle rebuilds it.
+    let tilt = (1.0 - row(1).magnitude().powi(2)).max(0.0).sqrt();
+    let up = row(1) + forward * tilt;
+    if view_transform(from, to, up) != *camera.transform() {
+        return unsupported("a camera transform that is not a view transform");
+    }
+
+    let defaults = Camera::new(camera.hsize(), camera.vsize(), camera.field_of_view());
+    let mut fields = Fields::new("add", "camera")
+        .with("width", count(camera.hsize()))
+        .with("height", count(camera.vsize()))
+        .with("field-of-view", number(camera.field_of_view()))
+        .with("from", point(from))
+        .with("to", point(to))
+        .with("up", vector(up));
+    if camera.aperture() != defaults.aperture() {
+        fields.set("aperture", number(camera.aperture()));
+    }
+    if camera.focal_distance() != defaults.focal_distance() {
+        fields.set("focal-distance", number(camera.focal_distance()));
+    }
+    if camera.shutter() != defaults.shutter() {
+        let (open, close) = camera.shutter();
+        fields.set("shutter", list(vec![number(open), number(close)]));
+    }
+    Ok(fields.node())
+}
+
+fn light(light: &dyn Light) -> Result<Node, ExportError> {
+    let any = light as &dyn Any;
+    let fields = if let Some(light) = any.downcast_ref::<PointLight>() {
+        Fields::new("add", "light").with("at", point(light.position))
+    } else if let Some(light) = any.downcast_ref::<AreaLight>() {
+        let mut fields = Fields::new("add", "light")
+            .with("corner", point(light.corner))
+            .with("uvec", vector(light.uvec * light.usteps as Scalar))
+            .with("usteps", count(light.usteps))
+            .with("vvec", vector(light.vvec * light.vsteps as Scalar))
+            .with("vsteps", count(light.vsteps));
+        if light.jitter {
+            fields.set("jitter", Node::new(Value::Bool(true)));
+        }
+        fields
+    } else if let Some(light) = any.downcast_ref::<SpotLight>() {
+        Fields::new("add", "spot-light")
+            .with("at", point(light.position))
+            .with("direction", vector(light.direction))
+            .with("inner-angle", number(light.inner_angle))
+            .with("outer-angle", number(light.outer_angle))
+    } else {
+        return unsupported(format!("{} lights", type_name(&light)));
+    };
+    Ok(fields.with("intensity", color(light.intensity())).node())
+}
+
+fn background(background: &Background) -> Result<Node, ExportError> {
+    let fields = Fields::new("add", "background");
+    match background {
+        Background::Solid(solid) => Ok(fields.with("color", color(*solid)).node()),
+        Background::Gradient { bottom, top } => Ok(fields
+            .with("bottom", color(*bottom))
+            .with("top", color(*top))
+            .node()),
+        Background::Environment(_) => unsupported("environment image backgrounds"),
+    }
+}
+
+fn truncated(kind: &str, minimum: Scalar, maximum: Scalar, closed: bool) -> Fields {
+    let mut fields = Fields::new("add", kind);
+    if minimum.is_finite() {
+        fields.set("min", number(minimum));
+    }
+    if maximum.is_finite() {
+        fields.set("max", number(maximum));
+    }
+    if closed {
+        fields.set("closed", Node::new(Value::Bool(true)));
+    }
+    fields
+}
+
+fn quadric(quadric: &Quadric) -> Fields {
+    let coefficients = quadric.coefficients();
+    let rows = (0..4)
+        .map(|row| list((0..4).map(|col| number(coefficients[row][col])).collect()))
+        .collect();
+    let mut fields = Fields::new("add", "quadric").with("coefficients", list(rows));
+    let unclipped = *Quadric::new(*coefficients).clip();
+    if quadric.clip().min != unclipped.min {
+        fields.set("min", point(quadric.clip().min));
+    }
+    if quadric.clip().max != unclipped.max {
+        fields.set("max", point(quadric.clip().max));
+    }
+    fields
+}
+
+fn sdf(field: &Sdf, offset: Vector) -> Result<Node, ExportError> {
+    let combined = |kind, a: &Sdf, b: &Sdf, blend: Scalar| -> Result<Fields, ExportError> {
+        let origin = Vector::new(0.0, 0.0, 0.0);
+        let operands = list(vec![sdf(a, origin)?, sdf(b, origin)?]);
+        let mut fields = Fields::new("type", kind).with("of", operands);
+        if blend != 0.0 {
+            fields.set("blend", number(blend));
+        }
+        Ok(fields)
+    };
+    let mut fields = match field {
+        Sdf::Sphere { radius } => Fields::new("type", "sphere").with("radius", number(*radius)),
+        Sdf::Cuboid { half_extents } => {
+            Fields::new("type", "box").with("size", vector(*half_extents * 2.0))
+        }
+        Sdf::Torus { major, minor } => Fields::new("type", "torus")
+            .with("major", number(*major))
+            .with("minor", number(*minor)),
+        Sdf::Capsule { a, b, radius } => Fields::new("type", "capsule")
+            .with("from", point(*a))
+            .with("to", point(*b))
+            .with("radius", number(*radius)),
+        Sdf::Translate(inner, by) => return sdf(inner, offset + *by),
+        Sdf::Union(a, b, blend) => combined("union", a, b, *blend)?,
+        Sdf::Subtract(a, b, blend) => combined("subtract", a, b, *blend)?,
+        Sdf::Intersect(a, b, blend) => combined("intersect", a, b, *blend)?,
+        Sdf::Custom(_) => return unsupported("custom distance fields"),
+    };
+    if offset != Vector::new(0.0, 0.0, 0.0) {
+        fields.set("at", vector(offset));
+    }
+    Ok(fields.node())
+}
+
+fn material(material: &Material) -> Result<Fields, ExportError> {
+    let defaults = Material::default();
+    let mut fields = Fields(Vec::new());
+    let colors = [
+        ("color", material.color, defaults.color),
+        ("emissive", material.emissive, defaults.emissive),
+    ];
+    for (key, value, default) in colors {
+        if value != default {
+            fields.set(key, color(value));
+        }
+    }
+    let numbers = [
+        ("ambient", material.ambient, defaults.ambient),
+        ("diffuse", material.diffuse, defaults.diffuse),
+        ("specular", material.specular, defaults.specular),
+        ("shininess", material.shininess, defaults.shininess),
+        ("reflective", material.reflective, defaults.reflective),
+        ("transparency", material.transparency, defaults.transparency),
+        (
+            "refractive-index",
+            material.refractive_index,
+            defaults.refractive_index,
+        ),
+    ];
+    for (key, value, default) in numbers {
+        if value != default {
+            fields.set(key, number(value));
+        }
+    }
+    if let Some(colors) = &material.pattern {
+        fields.set("pattern", pattern(colors.as_ref())?);
+    }
+    if let Some(normal_map) = &material.normal_map {
+        match (normal_map.as_ref() as &dyn Any).downcast_ref::<PatternNormalMap>() {
+            Some(normal_map) => fields.set("normal-map", pattern(normal_map.pattern())?),
+            None => return unsupported(format!("{} normal maps", type_name(normal_map))),
+        }
+    }
+    Ok(fields)
+}
+
+fn pattern(colors: &dyn Pattern) -> Result<Node, ExportError> {
+    let any = colors as &dyn Any;
+    let mut fields = if let Some(solid) = any.downcast_ref::<Solid>() {
+        Fields::new("type", "solid").with("color", color(solid.color()))
+    } else if let Some(stripe) = any.downcast_ref::<Stripe>() {
+        two_tone("stripes", stripe.a(), stripe.b())?
+    } else if let Some(gradient) = any.downcast_ref::<Gradient>() {
+        two_tone("gradient", gradient.a(), gradient.b())?
+    } else if let Some(ring) = any.downcast_ref::<Ring>() {
+        two_tone("rings", ring.a(), ring.b())?
+    } else if let Some(checker) = any.downcast_ref::<Checker>() {
+        two_tone("checkers", checker.a(), checker.b())?
+    } else if let Some(blend) = any.downcast_ref::<Blend>() {
+        two_tone("blend", blend.a(), blend.b())?
+    } else if let Some(noise) = any.downcast_ref::<Noise>() {
+        two_tone("noise", noise.a(), noise.b())?.with("seed", seed(noise.seed())?)
+    } else if let Some(perturb) = any.downcast_ref::<Perturb>() {
+        Fields::new("type", "perturb")
+            .with("pattern", pattern(perturb.pattern())?)
+            .with("scale", number(perturb.scale()))
+            .with("seed", seed(perturb.seed())?)
+    } else if let Some(map) = any.downcast_ref::<TextureMap>() {
+        let mapping = match map.mapping() {
+            UvMapping::Spherical => "spherical",
+            UvMapping::Planar => "planar",
+            UvMapping::Cylindrical => "cylindrical",
+            UvMapping::Cubic => return unsupported("cubic texture maps with a single UV pattern"),
+        };
+        Fields::new("type", "map")
+            .with("mapping", text(mapping))
+            .with("uv_pattern", uv_pattern(map.uv_pattern())?)
+    } else if let Some(map) = any.downcast_ref::<CubeMap>() {
+        let mut fields = Fields::new("type", "map").with("mapping", text("cube"));
+        for face in CubeFace::ALL {
+            let name = format!("{:?}", face).to_lowercase();
+            fields.set(&name, uv_pattern(map.face(face))?);
+        }
+        fields
+    } else {
+        return unsupported(format!("{} patterns", type_name(&colors)));
+    };
+    fields.set_transform("transform", colors.transform());
+    Ok(fields.node())
+}
+
+fn two_tone(kind: &str, a: &dyn Pattern, b: &dyn Pattern) -> Result<Fields, ExportError> {
+    let plain = |part: &dyn Pattern| {
+        (part as &dyn Any)
+            .downcast_ref::<Solid>()
+            .filter(|_| *part.transform() == Matrix::identity())
+            .map(|solid| color(solid.color()))
+    };
+    let fields = Fields::new("type", kind);
+    match (plain(a), plain(b)) {
+        (Some(a), Some(b)) => Ok(fields.with("colors", list(vec![a, b]))),
+        _ => Ok(fields.with("patterns", list(vec![pattern(a)?, pattern(b)?]))),
+    }
+}
+
+fn uv_pattern(pattern: &dyn UvPattern) -> Result<Node, ExportError> {
+    let any = pattern as &dyn Any;
+    if let Some(checker) = any.downcast_ref::<UvChecker>() {
+        return Ok(Fields::new("type", "checkers")
+            .with("width", number(checker.width))
+            .with("height", number(checker.height))
+            .with("colors", list(vec![color(checker.a), color(checker.b)]))
+            .node());
+    }
+    if let Some(check) = any.downcast_ref::<UvAlignCheck>() {
+        let colors = Fields(Vec::new())
+            .with("main", color(check.main))
+            .with("ul", color(check.upper_left))
+            .with("ur", color(check.upper_right))
+            .with("bl", color(check.bottom_left))
+            .with("br", color(check.bottom_right));
+        return Ok(Fields::new("type", "align_check")
+            .with("colors", colors.node())
+            .node());
+    }
+    unsupported(format!("{} UV patterns", type_name(&pattern)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::{consts::PI, EPSILON};
+    use rt_shapes::{BoundingBox, NoiseNormalMap};
+
+    const SCENE: &str = "
+- add: camera
+  width: 40
+  height: 20
+  field-of-view: 1.2
+  from: [1, 2, -5]
+  to: [0, 1, 0]
+  up: [0, 1, 0]
+  aperture: 0.05
+  focal-distance: 5
+  shutter: [0, 0.5]
+- add: light
+  at: [-10, 10, -10]
+  intensity: [1, 1, 1]
+- add: light
+  corner: [-1, 2, 4]
+  uvec: [2, 0, 0]
+  usteps: 4
+  vvec: [0, 2, 0]
+  vsteps: 2
+  jitter: true
+  intensity: [1.5, 1.5, 1.5]
+- add: spot-light
+  at: [0, 5, 0]
+  direction: [0, -1, 0]
+  inner-angle: 0.3
+  outer-angle: 0.5
+  intensity: [1, 0.9, 0.8]
+- add: background
+  bottom: [0, 0, 0]
+  top: [0.2, 0.4, 1]
+- add: fog
+  density: 0.01
+  color: [0.9, 0.9, 1]
+- add: volume
+  density: 0.5
+  steps: 8
+  shape:
+    add: sphere
+    transform: [[scale, 2, 2, 2]]
+- add: cylinder
+  min: 0
+  max: 2
+  closed: true
+  material:
+    color: [1, 0, 0]
+    reflective: 0.5
+    pattern:
+      type: checkers
+      patterns:
+        - { type: stripes, colors: [[1, 1, 1], [0, 0, 0]], transform: [[scale, 0.25, 1, 1]] }
+        - { type: noise, colors: [[0, 0, 1], [0, 1, 0]], seed: 7 }
+  transform: [[translate, 1, 0, 0], [rotate-y, 0.5]]
+  motion: [[translate, 1, 1, 0]]
+  casts-shadow: false
+- add: quadric
+  surface: ellipsoid
+  radii: [1, 2, 3]
+- add: sdf
+  field:
+    type: union
+    blend: 0.25
+    of:
+      - { type: sphere, radius: 1, at: [0, 1, 0] }
+      - { type: box, size: [1, 1, 1] }
+- add: smooth-triangle
+  p1: [0, 1, 0]
+  p2: [-1, 0, 0]
+  p3: [1, 0, 0]
+  n1: [0, 1, 0]
+  n2: [-1, 0, 0]
+  n3: [1, 0, 0]
+- add: torus
+  major: 1
+  minor: 0.25
+  material:
+    normal-map:
+      type: perturb
+      pattern: { type: gradient, colors: [[0, 0, 0], [1, 1, 1]] }
+      scale: 0.1
+      seed: 3
+- add: cube
+  material:
+    pattern:
+      type: map
+      mapping: cube
+      left: { type: checkers, width: 2, height: 2, colors: [[1, 0, 0], [0, 0, 0]] }
+      right: { type: checkers, width: 2, height: 2, colors: [[0, 1, 0], [0, 0, 0]] }
+      front: { type: align_check, colors: { main: [1, 1, 1], ul: [1, 0, 0], ur: [1, 1, 0], bl: [0, 1, 0], br: [0, 1, 1] } }
+      back: { type: checkers, width: 2, height: 2, colors: [[0, 0, 1], [0, 0, 0]] }
+      up: { type: checkers, width: 2, height: 2, colors: [[1, 1, 0], [0, 0, 0]] }
+      down: { type: checkers, width: 2, height: 2, colors: [[0, 1, 1], [0, 0, 0]] }
+      transform: [[rotate-y, 0.5]]
+- add: clip
+  shape: { add: sphere, transform: [[scale, 2, 2, 2]] }
+  planes:
+    - { point: [0, 0, 0], normal: [0, 1, 0] }
+  capped: true
+  transform: [[translate, 0, 3, 0]]
+";
+
+    fn assert_same_document(a: &Node, b: &Node) {
+        match (&a.value, &b.value) {
+            (Value::Number(x), Value::Number(y)) => {
+                assert!((x - y).abs() < 1e-4, "{} != {}", x, y)
+            }
+            (Value::Sequence(xs), Value::Sequence(ys)) => {
+                assert_eq!(xs.len(), ys.len());
+                for (x, y) in xs.iter().zip(ys) {
+                    assert_same_document(x, y);
+                }
+            }
+            (Value::Mapping(xs), Value::Mapping(ys)) => {
+                assert_eq!(xs.len(), ys.len());
+                for ((kx, x), (ky, y)) in xs.iter().zip(ys) {
+                    assert_eq!(kx, ky);
+                    assert_same_document(x, y);
+                }
+            }
+            (x, y) => assert_eq!(x, y),
+        }
+    }
+
+    fn document(text: &str) -> Node {
+        Node::parse(text).unwrap().unwrap()
+    }
+
+    fn downcast<T: 'static>(shape: &dyn Shape) -> &T {
+        (shape as &dyn Any).downcast_ref::<T>().unwrap()
+    }
+
+    fn reloaded(scene: &Scene) -> Scene {
+        Scene::parse(&scene.to_yaml().unwrap()).unwrap()
+    }
+
+    fn camera_only() -> Scene {
+        let mut camera = Camera::new(10, 10, PI / 2.0);
+        camera.set_transform(view_transform(
+            Point::new(0.0, 0.0, -5.0),
+            Point::origin(),
+            Vector::new(0.0, 1.0, 0.0),
+        ));
+        Scene {
+            camera,
+            world: rt_render::World::new(),
+        }
+    }
+
+    #[test]
+    fn exported_scenes_load_back_into_the_same_scene() {
+        let original = Scene::parse(SCENE).unwrap();
+        let yaml = original.to_yaml().unwrap();
+        let copy = Scene::parse(&yaml).unwrap();
+        assert_same_document(&document(&copy.to_yaml().unwrap()), &document(&yaml));
+
+        assert_eq!(copy.camera.transform(), original.camera.transform());
+        assert_eq!(copy.camera.shutter(), (0.0, 0.5));
+        assert_eq!(copy.camera.focal_distance(), 5.0);
+        assert_eq!(copy.world.lights().len(), 3);
+        assert_eq!(copy.world.background(), original.world.background());
+        assert_eq!(copy.world.volumes().len(), 2);
+        assert_eq!(copy.world.shapes().len(), original.world.shapes().len());
+        for (a, b) in copy.world.shapes().iter().zip(original.world.shapes()) {
+            assert_eq!(a.transform(), b.transform());
+            assert_eq!(a.end_transform(), b.end_transform());
+            assert_eq!(a.material().color, b.material().color);
+            assert_eq!(a.casts_shadow(), b.casts_shadow());
+        }
+    }
+
+    #[test]
+    fn exported_patterns_color_the_same_points() {
+        let original = Scene::parse(SCENE).unwrap();
+        let copy = reloaded(&original);
+        let points = [
+            Point::new(0.1, 0.2, 0.3),
+            Point::new(-0.7, 0.45, 0.9),
+            Point::new(1.0, -0.3, 0.25),
+        ];
+        for (a, b) in copy.world.shapes().iter().zip(original.world.shapes()) {
+            if let (Some(pa), Some(pb)) = (&a.material().pattern, &b.material().pattern) {
+                for p in &points {
+                    assert_eq!(pa.pattern_at(p), pb.pattern_at(p));
+                }
+            }
+        }
+        let torus = copy.world.shapes()[4].as_ref();
+        assert!(torus.material().normal_map.is_some());
+        let cylinder = downcast::<Cylinder>(copy.world.shapes()[0].as_ref());
+        assert_eq!((cylinder.minimum, cylinder.maximum), (0.0, 2.0));
+        assert!(cylinder.closed);
+    }
+
+    #[test]
+    fn lights_keep_their_full_extent() {
+        let copy = reloaded(&Scene::parse(SCENE).unwrap());
+        let area = (copy.world.lights()[1].as_ref() as &dyn Any)
+            .downcast_ref::<AreaLight>()
+            .unwrap();
+        assert_eq!(area.uvec, Vector::new(0.5, 0.0, 0.0));
+        assert_eq!((area.usteps, area.vsteps), (4, 2));
+        assert!(area.jitter);
+        let spot = (copy.world.lights()[2].as_ref() as &dyn Any)
+            .downcast_ref::<SpotLight>()
+            .unwrap();
+        assert_eq!(spot.outer_angle, 0.5);
+    }
+
+    #[test]
+    fn group_children_are_written_relative_to_their_parent() {
+        let mut scene = camera_only();
+        let mut child = Sphere::new();
+        child.set_transform(Matrix::translation(1.0, 0.0, 0.0));
+        child.set_motion(Matrix::translation(1.0, 2.0, 0.0));
+        let mut inner = Group::new();
+        inner.add_child(Box::new(child));
+        inner.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        inner.set_receives_shadow(false);
+        let mut outer = Group::new();
+        outer.add_child(Box::new(inner));
+        outer.add_child(Box::new(Csg::difference(
+            Box::new(Cube::new()),
+            Box::new(Sphere::new()),
+        )));
+        outer.set_transform(Matrix::rotation_y(0.5));
+        outer.set_motion(Matrix::translation(0.0, 0.0, 3.0) * Matrix::rotation_y(0.5));
+        scene.world.add_shape(Box::new(outer));
+
+        let yaml = scene.to_yaml().unwrap();
+        let copy = Scene::parse(&yaml).unwrap();
+        let (a, b) = (
+            copy.world.shapes()[0].as_ref(),
+            scene.world.shapes()[0].as_ref(),
+        );
+        let leaf = |shape: &dyn Shape| {
+            let inner = downcast::<Group>(downcast::<Group>(shape).children()[0].as_ref());
+            (
+                *inner.children()[0].transform(),
+                *inner.children()[0].end_transform().unwrap(),
+            )
+        };
+        assert_eq!(leaf(a), leaf(b));
+        assert_eq!(yaml.matches("receives-shadow").count(), 1);
+        let inner = downcast::<Group>(downcast::<Group>(a).children()[0].as_ref());
+        assert!(!inner.children()[0].receives_shadow());
+        assert_eq!(a.end_transform(), b.end_transform());
+        let csg = downcast::<Csg>(downcast::<Group>(a).children()[1].as_ref());
+        assert_eq!(csg.operation(), CsgOperation::Difference);
+        assert_eq!(
+            csg.left().transform(),
+            downcast::<Group>(b).children()[1].transform()
+        );
+    }
+
+    #[test]
+    fn instances_share_one_exported_prototype() {
+        let mut scene = camera_only();
+        let mut prototype = Sphere::new();
+        prototype.set_transform(Matrix::scaling(0.5, 0.5, 0.5));
+        let prototype: Arc<dyn Shape> = Arc::new(prototype);
+        for x in [-1.0, 1.0] {
+            let mut instance = Instance::new(Arc::clone(&prototype));
+            instance.set_transform(Matrix::translation(x, 0.0, 0.0));
+            scene.world.add_shape(Box::new(instance));
+        }
+        scene.world.shapes_mut()[1].set_material(Material {
+            color: Color::new(1.0, 0.0, 0.0),
+            ..Material::default()
+        });
+
+        let yaml = scene.to_yaml().unwrap();
+        assert_eq!(yaml.matches("define: prototype-1").count(), 1);
+        let copy = Scene::parse(&yaml).unwrap();
+        let [a, b] = [0, 1].map(|i| downcast::<Instance>(copy.world.shapes()[i].as_ref()));
+        assert!(Arc::ptr_eq(a.prototype(), b.prototype()));
+        assert_eq!(*a.prototype().transform(), *prototype.transform());
+        assert!(a.material_override().is_none());
+        assert_eq!(b.material().color, Color::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn json_exports_load_like_yaml_exports() {
+        let scene = Scene::parse(SCENE).unwrap();
+        let json = scene.to_json().unwrap();
+        assert!(json.starts_with("[\n  {\n    \"add\": \"camera\","));
+        let copy = Scene::parse(&json).unwrap();
+        assert_same_document(
+            &document(&copy.to_yaml().unwrap()),
+            &document(&scene.to_yaml().unwrap()),
+        );
+    }
+
+    #[test]
+    fn saving_picks_the_format_from_the_extension() {
+        let dir = std::env::temp_dir().join(format!("rt-io-export-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let scene = camera_only();
+        for (name, first) in [("scene.json", "["), ("scene.yml", "- add: camera")] {
+            let path = dir.join(name);
+            scene.save_file(&path).unwrap();
+            let text = std::fs::read_to_string(&path).unwrap();
+            assert!(text.starts_with(first), "{}", text);
+            assert_eq!(Scene::parse(&text).unwrap().camera.hsize(), 10);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn scenes_that_cannot_be_written_back_are_rejected() {
+        let mut scene = camera_only();
+        scene.world.add_shape(Box::new(SdfShape::new(Sdf::custom(
+            |p| p.y(),
+            BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0)),
+        ))));
+        assert_eq!(
+            scene.to_yaml().unwrap_err().to_string(),
+            "cannot export custom distance fields"
+        );
+
+        let mut scene = camera_only();
+        let mut sphere = Sphere::new();
+        sphere.material_mut().normal_map = Some(Arc::new(NoiseNormalMap::new(1.0, 0.5, 0)));
+        scene.world.add_shape(Box::new(sphere));
+        assert_eq!(
+            scene.to_yaml().unwrap_err().to_string(),
+            "cannot export NoiseNormalMap normal maps"
+        );
+
+        let mut scene = camera_only();
+        scene
+            .camera
+            .set_transform(Matrix::scaling(1.0, 1.0 + EPSILON * 100.0, 1.0));
+        assert!(matches!(scene.to_json(), Err(ExportError::Unsupported(_))));
+    }
+}
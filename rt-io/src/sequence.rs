@@ -0,0 +1,153 @@
+use crate::{save_canvas, ImageError, Scene};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+const DEFAULT_DIGITS: usize = 4;
+
+pub fn frame_path(output_pattern: &str, frame: usize) -> PathBuf {
+    let number = frame + 1;
+    if let Some(end) = output_pattern.rfind('#') {
+        let start = output_pattern[..end]
+            .rfind(|c| c != '#')
+            .map_or(0, |i| i + 1);
+        let digits = end + 1 - start;
+        return PathBuf::from(format!(
+            "{}{:0width$}{}",
+            &output_pattern[..start],
+            number,
+            &output_pattern[end + 1..],
+            width = digits
+        ));
+    }
+    let path = PathBuf::from(output_pattern);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut name = format!("{}_{:0width$}", stem, number, width = DEFAULT_DIGITS);
+    if let Some(extension) = path.extension() {
+        name.push('.');
+        name.push_str(&extension.to_string_lossy());
+    }
+    path.with_file_name(name)
+}
+
+pub fn render_sequence<F>(
+    world_fn: F,
+    frames: usize,
+    output_pattern: &str,
+    threads: usize,
+) -> Result<Vec<PathBuf>, ImageError>
+where
+    F: Fn(usize) -> Scene + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let failure = Mutex::new(None);
+    std::thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, frames.max(1)) {
+            scope.spawn(|| loop {
+                let frame = next.fetch_add(1, Ordering::Relaxed);
+                if frame >= frames {
+                    break;
+                }
+                let scene = world_fn(frame);
+                let canvas = scene.camera.render(&scene.world);
+                if let Err(error) = save_canvas(&canvas, frame_path(output_pattern, frame)) {
+                    failure.lock().unwrap().get_or_insert(error);
+                    next.store(frames, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    match failure.into_inner().unwrap() {
+        Some(error) => Err(error),
+        None => Ok((0..frames)
+            .map(|frame| frame_path(output_pattern, frame))
+            .collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::consts::PI;
+    use rt_core::{Color, Matrix, Point, Scalar, Vector};
+    use rt_render::{view_transform, Animation, Camera, Track, World};
+    use rt_shapes::{Material, Shape, Sphere};
+
+    mod naming {
+        use super::*;
+
+        #[test]
+        fn hashes_are_replaced_by_the_padded_frame_number() {
+            assert_eq!(
+                frame_path("renders/spin_###.png", 0),
+                PathBuf::from("renders/spin_001.png")
+            );
+            assert_eq!(
+                frame_path("renders/spin_#.png", 11),
+                PathBuf::from("renders/spin_12.png")
+            );
+        }
+
+        #[test]
+        fn plain_paths_get_a_numbered_suffix() {
+            assert_eq!(frame_path("out.png", 0), PathBuf::from("out_0001.png"));
+            assert_eq!(
+                frame_path("renders/out", 41),
+                PathBuf::from("renders/out_0042")
+            );
+        }
+    }
+
+    fn slide(frame: usize) -> Scene {
+        let mut animation = Animation::new();
+        animation.set_transform(
+            "ball",
+            Track::new()
+                .key(0.0, Matrix::translation(-3.0, 0.0, 0.0))
+                .key(2.0, Matrix::translation(3.0, 0.0, 0.0)),
+        );
+        let mut ball = Sphere::new();
+        ball.set_transform(animation.transform_at("ball", frame as Scalar).unwrap());
+        ball.set_material(Material {
+            emissive: Color::white(),
+            ambient: 0.0,
+            diffuse: 0.0,
+            specular: 0.0,
+            ..Material::default()
+        });
+        let mut world = World::new();
+        world.add_shape(Box::new(ball));
+        let mut camera = Camera::new(9, 3, PI / 2.0);
+        camera.set_transform(view_transform(
+            Point::new(0.0, 0.0, -4.5),
+            Point::origin(),
+            Vector::new(0.0, 1.0, 0.0),
+        ));
+        Scene { camera, world }
+    }
+
+    #[test]
+    fn every_frame_is_rendered_to_its_own_file() {
+        let dir = std::env::temp_dir().join(format!("rt-io-sequence-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pattern = dir.join("slide_##.ppm");
+        let written = render_sequence(slide, 3, pattern.to_str().unwrap(), 2);
+        let frames: Vec<String> = (1..=3)
+            .map(|n| std::fs::read_to_string(dir.join(format!("slide_0{}.ppm", n))).unwrap())
+            .collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(written.unwrap().len(), 3);
+        for (frame, ppm) in frames.iter().enumerate() {
+            let expected = slide(frame).camera.render(&slide(frame).world);
+            assert_eq!(*ppm, expected.to_ppm());
+        }
+        assert_ne!(frames[0], frames[2]);
+    }
+
+    #[test]
+    fn write_failures_are_reported() {
+        let result = render_sequence(slide, 2, "frames/out_#.bmp", 1);
+        assert!(matches!(result, Err(ImageError::UnsupportedFormat(_))));
+    }
+}
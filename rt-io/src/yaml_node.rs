@@ -0,0 +1,484 @@
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Sequence(Vec<Node>),
+    Mapping(Vec<(String, Node)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Node {
+    pub(crate) value: Value,
+    pub(crate) line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NodeError {
+    pub(crate) line: usize,
+    pub(crate) message: String,
+}
+
+impl Node {
+    pub(crate) fn parse(source: &str) -> Result<Option<Node>, NodeError> {
+        let mut builder = Builder::default();
+        Parser::new_from_str(source)
+            .load(&mut builder, false)
+            .map_err(|error| NodeError {
+                line: error.marker().line(),
+                message: error.info().to_string(),
+            })?;
+        match builder.error {
+            Some(error) => Err(error),
+            None => Ok(builder.root),
+        }
+    }
+
+    pub(crate) fn describe(&self) -> &'static str {
+        match self.value {
+            Value::Null => "null",
+            Value::Bool(_) => "a boolean",
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Sequence(_) => "a list",
+            Value::Mapping(_) => "a mapping",
+        }
+    }
+
+    pub(crate) fn new(value: Value) -> Self {
+        Node { value, line: 0 }
+    }
+
+    pub(crate) fn to_yaml(&self) -> String {
+        let mut out = String::new();
+        match &self.value {
+            Value::Sequence(items) if !is_inline(self) => yaml_items(items, 0, &mut out),
+            Value::Mapping(entries) if !entries.is_empty() => {
+                yaml_entries(entries, 0, false, &mut out)
+            }
+            _ => out.push_str(&flow(self, yaml_scalar)),
+        }
+        format!("{}\n", out.trim_start_matches('\n'))
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        let mut out = String::new();
+        json_value(self, 0, &mut out);
+        out.push('\n');
+        out
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<&Node> {
+        match &self.value {
+            Value::Mapping(entries) => entries
+                .iter()
+                .rev()
+                .find(|(name, _)| name == key)
+                .map(|(_, node)| node),
+            _ => None,
+        }
+    }
+}
+
+enum Frame {
+    Sequence(usize, Vec<Node>),
+    Mapping(usize, Vec<(String, Node)>, Option<String>),
+}
+
+#[derive(Default)]
+struct Builder {
+    stack: Vec<Frame>,
+    root: Option<Node>,
+    error: Option<NodeError>,
+}
+
+impl Builder {
+    fn push(&mut self, node: Node) {
+        let line = node.line;
+        let accepted = match self.stack.last_mut() {
+            None => {
+                self.root = Some(node);
+                true
+            }
+            Some(Frame::Sequence(_, items)) => {
+                items.push(node);
+                true
+            }
+            Some(Frame::Mapping(_, entries, key)) => match (key.take(), node.value) {
+                (Some(name), value) => {
+                    entries.push((name, Node { value, line }));
+                    true
+                }
+                (None, Value::String(name)) => {
+                    *key = Some(name);
+                    true
+                }
+                (None, value @ (Value::Number(_) | Value::Bool(_) | Value::Null)) => {
+                    *key = Some(scalar_text(&value));
+                    true
+                }
+                (None, _) => false,
+            },
+        };
+        if !accepted {
+            self.fail(line, "mapping keys must be scalars");
+        }
+    }
+
+    fn fail(&mut self, line: usize, message: &str) {
+        if self.error.is_none() {
+            self.error = Some(NodeError {
+                line,
+                message: message.to_string(),
+            });
+        }
+    }
+}
+
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => "null".to_string(),
+    }
+}
+
+fn plain_scalar(text: String) -> Value {
+    match text.as_str() {
+        "" | "~" | "null" | "Null" | "NULL" => Value::Null,
+        "true" | "True" | "TRUE" => Value::Bool(true),
+        "false" | "False" | "FALSE" => Value::Bool(false),
+        ".inf" | ".Inf" | ".INF" | "+.inf" => Value::Number(f64::INFINITY),
+        "-.inf" | "-.Inf" | "-.INF" => Value::Number(f64::NEG_INFINITY),
+        _ => match text.parse::<f64>() {
+            Ok(number) => Value::Number(number),
+            Err(_) => Value::String(text),
+        },
+    }
+}
+
+fn is_scalar(node: &Node) -> bool {
+    !matches!(node.value, Value::Sequence(_) | Value::Mapping(_))
+}
+
+fn is_flat(node: &Node) -> bool {
+    match &node.value {
+        Value::Sequence(items) => items.iter().all(is_scalar),
+        _ => false,
+    }
+}
+
+fn is_inline(node: &Node) -> bool {
+    match &node.value {
+        Value::Sequence(items) if items.len() <= 4 => {
+            items.iter().all(is_scalar) || items.iter().all(is_flat)
+        }
+        Value::Sequence(items) => items.iter().all(is_scalar),
+        Value::Mapping(entries) => entries.is_empty(),
+        _ => true,
+    }
+}
+
+fn newline(indent: usize, out: &mut String) {
+    out.push('\n');
+    out.push_str(&" ".repeat(indent));
+}
+
+fn yaml_entries(entries: &[(String, Node)], indent: usize, inline_first: bool, out: &mut String) {
+    for (i, (key, value)) in entries.iter().enumerate() {
+        if i > 0 || !inline_first {
+            newline(indent, out);
+        }
+        out.push_str(&yaml_string(key));
+        out.push(':');
+        match &value.value {
+            _ if is_inline(value) => {
+                out.push(' ');
+                out.push_str(&flow(value, yaml_scalar));
+            }
+            Value::Mapping(entries) => yaml_entries(entries, indent + 2, false, out),
+            Value::Sequence(items) => yaml_items(items, indent + 2, out),
+            _ => unreachable!("scalars are written inline"),
+        }
+    }
+}
+
+fn yaml_items(items: &[Node], indent: usize, out: &mut String) {
+    for item in items {
+        newline(indent, out);
+        out.push_str("- ");
+        match &item.value {
+            Value::Mapping(entries) if !entries.is_empty() => {
+                yaml_entries(entries, indent + 2, true, out)
+            }
+            _ => out.push_str(&flow(item, yaml_scalar)),
+        }
+    }
+}
+
+fn flow(node: &Node, scalar: fn(&Value) -> String) -> String {
+    match &node.value {
+        Value::Sequence(items) => {
+            let items: Vec<String> = items.iter().map(|item| flow(item, scalar)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Mapping(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| {
+                    let key = scalar(&Value::String(key.clone()));
+                    format!("{}: {}", key, flow(value, scalar))
+                })
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
+        value => scalar(value),
+    }
+}
+
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Number(n) if n.is_nan() => ".nan".into(),
+        Value::Number(n) if n.is_infinite() => if *n > 0.0 { ".inf" } else { "-.inf" }.into(),
+        Value::String(text) => yaml_string(text),
+        value => json_scalar(value),
+    }
+}
+
+fn yaml_string(text: &str) -> String {
+    let plain = text
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_./".contains(c))
+        && text.starts_with(|c: char| c.is_ascii_alphabetic())
+        && plain_scalar(text.to_string()) == Value::String(text.to_string());
+    if plain {
+        text.to_string()
+    } else {
+        quoted(text)
+    }
+}
+
+fn json_value(node: &Node, indent: usize, out: &mut String) {
+    match &node.value {
+        _ if is_inline(node) => out.push_str(&flow(node, json_scalar)),
+        Value::Sequence(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                out.push_str(if i == 0 { "" } else { "," });
+                newline(indent + 2, out);
+                json_value(item, indent + 2, out);
+            }
+            newline(indent, out);
+            out.push(']');
+        }
+        Value::Mapping(entries) => {
+            out.push('{');
+            for (i, (key, value)) in entries.iter().enumerate() {
+                out.push_str(if i == 0 { "" } else { "," });
+                newline(indent + 2, out);
+                out.push_str(&quoted(key));
+                out.push_str(": ");
+                json_value(value, indent + 2, out);
+            }
+            newline(indent, out);
+            out.push('}');
+        }
+        _ => unreachable!("scalars are written inline"),
+    }
+}
+
+fn json_scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".into(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) if n.is_nan() => "null".into(),
+        Value::Number(n) if n.is_infinite() => if *n > 0.0 { "1e999" } else { "-1e999" }.into(),
+        Value::Number(n) => n.to_string(),
+        Value::String(text) => quoted(text),
+        Value::Sequence(_) | Value::Mapping(_) => unreachable!("collections are not scalars"),
+    }
+}
+
+fn quoted(text: &str) -> String {
+    let mut out = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl MarkedEventReceiver for Builder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let line = mark.line();
+        match event {
+            Event::Scalar(text, style, _, _) => {
+                let value = if style == TScalarStyle::Plain {
+                    plain_scalar(text)
+                } else {
+                    Value::String(text)
+                };
+                self.push(Node { value, line });
+            }
+            Event::SequenceStart(_, _) => self.stack.push(Frame::Sequence(line, Vec::new())),
+            Event::MappingStart(_, _) => self.stack.push(Frame::Mapping(line, Vec::new(), None)),
+            Event::SequenceEnd | Event::MappingEnd => {
+                let node = match self.stack.pop() {
+                    Some(Frame::Sequence(line, items)) => Node {
+                        value: Value::Sequence(items),
+                        line,
+                    },
+                    Some(Frame::Mapping(line, entries, _)) => Node {
+                        value: Value::Mapping(entries),
+                        line,
+                    },
+                    None => return,
+                };
+                self.push(node);
+            }
+            Event::Alias(_) => self.fail(line, "YAML aliases are not supported"),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Node {
+        Node::parse(source).unwrap().unwrap()
+    }
+
+    #[test]
+    fn scalars_are_typed() {
+        let node = parse("[1, 2.5, -3, true, ~, word, '4']");
+        let values: Vec<Value> = match node.value {
+            Value::Sequence(items) => items.into_iter().map(|n| n.value).collect(),
+            _ => panic!("expected a sequence"),
+        };
+        assert_eq!(
+            values,
+            vec![
+                Value::Number(1.0),
+                Value::Number(2.5),
+                Value::Number(-3.0),
+                Value::Bool(true),
+                Value::Null,
+                Value::String("word".into()),
+                Value::String("4".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn nodes_remember_their_line() {
+        let node = parse("- add: sphere\n  material:\n    color: [1, 0, 0]\n");
+        let item = match &node.value {
+            Value::Sequence(items) => &items[0],
+            _ => panic!("expected a sequence"),
+        };
+        assert_eq!(item.get("add").unwrap().line, 1);
+        assert_eq!(item.get("material").unwrap().get("color").unwrap().line, 3);
+    }
+
+    #[test]
+    fn json_documents_parse_as_yaml() {
+        let node = parse(r#"[{"add": "light", "at": [1, 2, 3]}]"#);
+        match &node.value {
+            Value::Sequence(items) => {
+                assert_eq!(
+                    items[0].get("add").unwrap().value,
+                    Value::String("light".into())
+                )
+            }
+            _ => panic!("expected a sequence"),
+        }
+    }
+
+    #[test]
+    fn syntax_errors_report_the_line() {
+        let error = Node::parse("- add: sphere\n- [unclosed\n").unwrap_err();
+        assert!(error.line >= 2, "{:?}", error);
+    }
+
+    #[test]
+    fn empty_documents_have_no_root() {
+        assert_eq!(Node::parse("").unwrap(), None);
+    }
+
+    fn without_lines(node: &Node) -> Node {
+        Node::new(match &node.value {
+            Value::Sequence(items) => Value::Sequence(items.iter().map(without_lines).collect()),
+            Value::Mapping(entries) => Value::Mapping(
+                entries
+                    .iter()
+                    .map(|(key, value)| (key.clone(), without_lines(value)))
+                    .collect(),
+            ),
+            value => value.clone(),
+        })
+    }
+
+    fn document() -> Node {
+        without_lines(&parse(
+            r#"
+- add: sphere
+  name: "needs: quoting"
+  tag: "true"
+  material:
+    color: [1, 0.5, -0.25]
+    empty: {}
+  transform:
+    - [translate, 1, 2, 3]
+    - [scale, .inf, -.inf, 1e-7]
+  children:
+    - add: cube
+      of: [{type: sphere, radius: 1}, {weird key: "line\nbreak"}]
+    - []
+- ~
+"#,
+        ))
+    }
+
+    #[test]
+    fn yaml_output_parses_back_to_the_same_document() {
+        let yaml = document().to_yaml();
+        assert_eq!(without_lines(&parse(&yaml)), document(), "{}", yaml);
+        assert!(yaml.contains("\n  children:\n    - add: cube\n"));
+        assert!(yaml.contains("\n  transform: [[translate, 1, 2, 3], [scale, .inf, -.inf, "));
+    }
+
+    #[test]
+    fn json_output_parses_back_to_the_same_document() {
+        let json = document().to_json();
+        assert_eq!(without_lines(&parse(&json)), document(), "{}", json);
+        assert!(json.starts_with("[\n  {\n    \"add\": \"sphere\","));
+    }
+
+    #[test]
+    fn scalars_written_alone_stay_scalars() {
+        for text in ["hello", "needs: quoting", "12", "false"] {
+            let node = Node::new(Value::String(text.into()));
+            assert_eq!(parse(&node.to_yaml()).value, node.value);
+            assert_eq!(parse(&node.to_json()).value, node.value);
+        }
+    }
+
+    #[test]
+    fn later_duplicate_keys_win() {
+        let node = parse("{a: 1, a: 2}");
+        assert_eq!(node.get("a").unwrap().value, Value::Number(2.0));
+    }
+}
@@ -0,0 +1,319 @@
+use crate::{view_transform, Camera};
+use rt_core::{Color, Matrix, Point, Scalar, Vector};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    Step,
+}
+
+impl Easing {
+    pub fn apply(self, t: Scalar) -> Scalar {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Easing::Step => {
+                if t < 1.0 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+}
+
+pub trait Keyable: Copy {
+    fn blend(self, other: Self, t: Scalar) -> Self;
+}
+
+impl Keyable for Scalar {
+    fn blend(self, other: Self, t: Scalar) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Keyable for Point {
+    fn blend(self, other: Self, t: Scalar) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Keyable for Vector {
+    fn blend(self, other: Self, t: Scalar) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Keyable for Color {
+    fn blend(self, other: Self, t: Scalar) -> Self {
+        self * (1.0 - t) + other * t
+    }
+}
+
+impl Keyable for Matrix<4, 4> {
+    fn blend(self, other: Self, t: Scalar) -> Self {
+        self.interpolate(&other, t)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe<T> {
+    pub time: Scalar,
+    pub value: T,
+    pub easing: Easing,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track<T> {
+    keys: Vec<Keyframe<T>>,
+}
+
+impl<T> Default for Track<T> {
+    fn default() -> Self {
+        Track { keys: Vec::new() }
+    }
+}
+
+impl<T: Keyable> Track<T> {
+    pub fn new() -> Self {
+        Track::default()
+    }
+
+    pub fn key(self, time: Scalar, value: T) -> Self {
+        self.eased(time, value, Easing::Linear)
+    }
+
+    pub fn eased(mut self, time: Scalar, value: T, easing: Easing) -> Self {
+        self.insert(Keyframe {
+            time,
+            value,
+            easing,
+        });
+        self
+    }
+
+    pub fn insert(&mut self, keyframe: Keyframe<T>) {
+        let index = self.keys.partition_point(|key| key.time <= keyframe.time);
+        if index > 0 && self.keys[index - 1].time == keyframe.time {
+            self.keys[index - 1] = keyframe;
+        } else {
+            self.keys.insert(index, keyframe);
+        }
+    }
+
+    pub fn keys(&self) -> &[Keyframe<T>] {
+        &self.keys
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn sample(&self, time: Scalar) -> Option<T> {
+        let first = self.keys.first()?;
+        let index = self.keys.partition_point(|key| key.time <= time);
+        if index == 0 {
+            return Some(first.value);
+        }
+        let from = &self.keys[index - 1];
+        let to = match self.keys.get(index) {
+            Some(to) => to,
+            None => return Some(from.value),
+        };
+        let local = (time - from.time) / (to.time - from.time);
+        Some(from.value.blend(to.value, to.easing.apply(local)))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Animation {
+    pub from: Track<Point>,
+    pub to: Track<Point>,
+    pub up: Track<Vector>,
+    pub field_of_view: Track<Scalar>,
+    pub aperture: Track<Scalar>,
+    pub focal_distance: Track<Scalar>,
+    transforms: BTreeMap<String, Track<Matrix<4, 4>>>,
+}
+
+impl Animation {
+    pub fn new() -> Self {
+        Animation::default()
+    }
+
+    pub fn set_transform(&mut self, name: &str, track: Track<Matrix<4, 4>>) {
+        self.transforms.insert(name.to_string(), track);
+    }
+
+    pub fn transform(&self, name: &str) -> Option<&Track<Matrix<4, 4>>> {
+        self.transforms.get(name)
+    }
+
+    pub fn transform_at(&self, name: &str, time: Scalar) -> Option<Matrix<4, 4>> {
+        self.transforms.get(name)?.sample(time)
+    }
+
+    pub fn apply_to_camera(&self, camera: &mut Camera, time: Scalar) {
+        if let (Some(from), Some(to)) = (self.from.sample(time), self.to.sample(time)) {
+            let up = self
+                .up
+                .sample(time)
+                .unwrap_or_else(|| Vector::new(0.0, 1.0, 0.0));
+            camera.set_transform(view_transform(from, to, up));
+        }
+        if let Some(field_of_view) = self.field_of_view.sample(time) {
+            camera.set_field_of_view(field_of_view);
+        }
+        if let Some(aperture) = self.aperture.sample(time) {
+            camera.set_aperture(aperture);
+        }
+        if let Some(distance) = self.focal_distance.sample(time) {
+            camera.set_focal_distance(distance);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::consts::PI;
+    use rt_core::EPSILON;
+
+    mod easing {
+        use super::*;
+
+        #[test]
+        fn every_curve_starts_at_zero_and_ends_at_one() {
+            for easing in [
+                Easing::Linear,
+                Easing::EaseIn,
+                Easing::EaseOut,
+                Easing::EaseInOut,
+                Easing::Step,
+            ] {
+                assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+                assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+            }
+        }
+
+        #[test]
+        fn curves_shape_the_middle_of_a_segment() {
+            assert_eq!(Easing::Linear.apply(0.25), 0.25);
+            assert_eq!(Easing::EaseIn.apply(0.5), 0.25);
+            assert_eq!(Easing::EaseOut.apply(0.5), 0.75);
+            assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+            assert!(Easing::EaseInOut.apply(0.25) < 0.25);
+            assert_eq!(Easing::Step.apply(0.99), 0.0);
+        }
+    }
+
+    mod tracks {
+        use super::*;
+
+        #[test]
+        fn an_empty_track_has_no_value() {
+            assert_eq!(Track::<Scalar>::new().sample(0.0), None);
+        }
+
+        #[test]
+        fn values_hold_outside_the_keyed_range() {
+            let track = Track::new().key(1.0, 2.0).key(3.0, 6.0);
+            assert_eq!(track.sample(0.0), Some(2.0));
+            assert_eq!(track.sample(2.0), Some(4.0));
+            assert_eq!(track.sample(5.0), Some(6.0));
+        }
+
+        #[test]
+        fn keys_are_kept_in_time_order() {
+            let track = Track::new().key(2.0, 20.0).key(0.0, 0.0).key(1.0, 10.0);
+            let times: Vec<Scalar> = track.keys().iter().map(|key| key.time).collect();
+            assert_eq!(times, vec![0.0, 1.0, 2.0]);
+            let replaced = track.key(1.0, 5.0);
+            assert_eq!(replaced.keys().len(), 3);
+            assert_eq!(replaced.sample(1.0), Some(5.0));
+        }
+
+        #[test]
+        fn a_key_eases_the_segment_leading_into_it() {
+            let track = Track::new()
+                .key(0.0, 0.0)
+                .eased(1.0, 1.0, Easing::EaseIn)
+                .eased(2.0, 0.0, Easing::Step);
+            assert_eq!(track.sample(0.5), Some(0.25));
+            assert_eq!(track.sample(1.5), Some(1.0));
+            assert_eq!(track.sample(2.0), Some(0.0));
+        }
+
+        #[test]
+        fn transforms_interpolate_rotation_smoothly() {
+            let track = Track::new()
+                .key(0.0, Matrix::identity())
+                .key(1.0, Matrix::rotation_y(PI / 2.0));
+            let halfway = track.sample(0.5).unwrap();
+            let p = halfway * Point::new(1.0, 0.0, 0.0);
+            assert_eq!(p, Point::new((PI / 4.0).cos(), 0.0, -(PI / 4.0).sin()));
+        }
+    }
+
+    mod camera {
+        use super::*;
+
+        #[test]
+        fn cameras_follow_their_keyframes() {
+            let mut animation = Animation::new();
+            animation.from = Track::new()
+                .key(0.0, Point::new(0.0, 0.0, -5.0))
+                .key(1.0, Point::new(0.0, 0.0, -10.0));
+            animation.to = Track::new().key(0.0, Point::origin());
+            animation.field_of_view = Track::new().key(0.0, PI / 2.0).key(1.0, PI / 4.0);
+
+            let mut camera = Camera::new(20, 10, PI / 3.0);
+            animation.apply_to_camera(&mut camera, 0.5);
+            assert_eq!(
+                *camera.transform(),
+                view_transform(
+                    Point::new(0.0, 0.0, -7.5),
+                    Point::origin(),
+                    Vector::new(0.0, 1.0, 0.0)
+                )
+            );
+            assert!((camera.field_of_view() - 3.0 * PI / 8.0).abs() < EPSILON);
+            assert_eq!(camera.aperture(), 0.0);
+        }
+
+        #[test]
+        fn unanimated_parameters_are_left_alone() {
+            let mut camera = Camera::new(20, 10, PI / 3.0);
+            camera.set_transform(Matrix::translation(1.0, 2.0, 3.0));
+            Animation::new().apply_to_camera(&mut camera, 0.5);
+            assert_eq!(*camera.transform(), Matrix::translation(1.0, 2.0, 3.0));
+            assert_eq!(camera.field_of_view(), PI / 3.0);
+        }
+    }
+
+    #[test]
+    fn named_transform_tracks() {
+        let mut animation = Animation::new();
+        animation.set_transform(
+            "spinner",
+            Track::new()
+                .key(0.0, Matrix::identity())
+                .key(10.0, Matrix::translation(10.0, 0.0, 0.0)),
+        );
+        assert_eq!(
+            animation.transform_at("spinner", 5.0),
+            Some(Matrix::translation(5.0, 0.0, 0.0))
+        );
+        assert_eq!(animation.transform_at("missing", 5.0), None);
+        assert_eq!(animation.transform("spinner").unwrap().keys().len(), 2);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{Camera, World};
+use rt_core::{Canvas, Color, Ray, Scalar};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aov {
+    Depth,
+    Normal,
+    Albedo,
+    ObjectId,
+}
+
+impl Aov {
+    pub const ALL: [Aov; 4] = [Aov::Depth, Aov::Normal, Aov::Albedo, Aov::ObjectId];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aov::Depth => "depth",
+            Aov::Normal => "normal",
+            Aov::Albedo => "albedo",
+            Aov::ObjectId => "object-id",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Aov> {
+        Aov::ALL.iter().copied().find(|aov| aov.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOutput {
+    pub beauty: Canvas,
+    pub aovs: Vec<(Aov, Canvas)>,
+}
+
+impl RenderOutput {
+    pub fn aov(&self, aov: Aov) -> Option<&Canvas> {
+        self.aovs
+            .iter()
+            .find(|(kind, _)| *kind == aov)
+            .map(|(_, canvas)| canvas)
+    }
+}
+
+impl World {
+    /// Misses are black in every buffer; object IDs count top-level shapes from 1.
+    pub fn aovs_at(&self, ray: &Ray, aovs: &[Aov]) -> Vec<Color> {
+        let xs = self.intersect(ray);
+        let comps = match xs.hit() {
+            Some(hit) => hit.prepare_computations(ray, &xs),
+            None => return vec![Color::black(); aovs.len()],
+        };
+        let grey = |value: Scalar| Color::new(value, value, value);
+        aovs.iter()
+            .map(|aov| match aov {
+                Aov::Depth => grey(comps.t * ray.direction.magnitude()),
+                Aov::Normal => Color::new(comps.normalv.x(), comps.normalv.y(), comps.normalv.z()),
+                Aov::Albedo => comps.material.color_at(comps.object, &comps.point),
+                Aov::ObjectId => {
+                    let index = self
+                        .shapes()
+                        .iter()
+                        .position(|shape| shape.includes(comps.object));
+                    grey(index.map_or(0.0, |index| (index + 1) as Scalar))
+                }
+            })
+            .collect()
+    }
+}
+
+impl Camera {
+    pub fn render_output(&self, world: &World, threads: usize, aovs: &[Aov]) -> RenderOutput {
+        RenderOutput {
+            beauty: self.render_parallel(world, threads),
+            aovs: self.render_aovs(world, aovs),
+        }
+    }
+
+    /// AOVs come from the primary ray through each pixel centre, without antialiasing.
+    pub fn render_aovs(&self, world: &World, aovs: &[Aov]) -> Vec<(Aov, Canvas)> {
+        let mut canvases = vec![Canvas::new(self.hsize(), self.vsize()); aovs.len()];
+        for y in 0..self.vsize() {
+            for x in 0..self.hsize() {
+                let colors = world.aovs_at(&self.ray_for_pixel(x, y), aovs);
+                for (canvas, color) in canvases.iter_mut().zip(colors) {
+                    canvas.write_pixel(x, y, color);
+                }
+            }
+        }
+        aovs.iter().copied().zip(canvases).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view_transform;
+    use rt_core::consts::PI;
+    use rt_core::{Matrix, Point, Vector};
+    use rt_shapes::{Group, Material, Shape, Sphere};
+
+    fn looking_at_origin() -> Camera {
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        c.set_transform(view_transform(
+            Point::new(0.0, 0.0, -5.0),
+            Point::origin(),
+            Vector::new(0.0, 1.0, 0.0),
+        ));
+        c
+    }
+
+    #[test]
+    fn aovs_are_named() {
+        for aov in Aov::ALL.iter().copied() {
+            assert_eq!(Aov::from_name(aov.name()), Some(aov));
+        }
+        assert_eq!(Aov::from_name("beauty"), None);
+    }
+
+    #[test]
+    fn primary_hits_fill_every_buffer() {
+        let w = World::default_world();
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(
+            w.aovs_at(&r, &Aov::ALL),
+            vec![
+                Color::new(4.0, 4.0, 4.0),
+                Color::new(0.0, 0.0, -1.0),
+                Color::new(0.8, 1.0, 0.6),
+                Color::white(),
+            ]
+        );
+    }
+
+    #[test]
+    fn misses_are_black() {
+        let w = World::default_world();
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0));
+        assert_eq!(w.aovs_at(&r, &Aov::ALL), vec![Color::black(); 4]);
+    }
+
+    #[test]
+    fn object_ids_belong_to_top_level_shapes() {
+        let mut w = World::new();
+        w.add_shape(Box::new(Sphere::new()));
+        let mut g = Group::new();
+        let mut child = Sphere::new();
+        child.set_transform(Matrix::translation(0.0, 0.0, -3.0));
+        child.set_material(Material {
+            color: Color::new(0.0, 0.0, 1.0),
+            ..Material::default()
+        });
+        g.add_child(Box::new(child));
+        w.add_shape(Box::new(g));
+        let r = Ray::new(Point::new(0.0, 0.0, -10.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(
+            w.aovs_at(&r, &[Aov::ObjectId, Aov::Albedo]),
+            vec![Color::new(2.0, 2.0, 2.0), Color::new(0.0, 0.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn render_output_keeps_the_beauty_pass() {
+        let w = World::default_world();
+        let c = looking_at_origin();
+        let output = c.render_output(&w, 2, &[Aov::Depth]);
+        assert_eq!(output.beauty, c.render(&w));
+        assert_eq!(output.aovs.len(), 1);
+        assert!(output.aov(Aov::Normal).is_none());
+        let depth = output.aov(Aov::Depth).unwrap();
+        assert_eq!(depth.pixel_at(5, 5), Color::new(4.0, 4.0, 4.0));
+        assert_eq!(depth.pixel_at(0, 0), Color::black());
+    }
+}
@@ -0,0 +1,98 @@
+use rt_core::{Canvas, Color, Point, Scalar, Vector};
+use rt_shapes::spherical_map;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Background {
+    Solid(Color),
+    Gradient { bottom: Color, top: Color },
+    Environment(Arc<Canvas>),
+}
+
+impl Background {
+    pub fn color_for(&self, direction: &Vector) -> Color {
+        let direction = direction.normalize();
+        match self {
+            Background::Solid(color) => *color,
+            Background::Gradient { bottom, top } => {
+                let t = (direction.y() + 1.0) / 2.0;
+                *bottom + (*top - *bottom) * t
+            }
+            Background::Environment(image) => {
+                let (u, v) = spherical_map(&(Point::origin() + direction));
+                sample_bilinear(image, u, 1.0 - v)
+            }
+        }
+    }
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Solid(Color::black())
+    }
+}
+
+fn sample_bilinear(image: &Canvas, u: Scalar, v: Scalar) -> Color {
+    let (width, height) = (image.width(), image.height());
+    let x = u * width as Scalar - 0.5;
+    let y = (v * height as Scalar - 0.5).clamp(0.0, (height - 1) as Scalar);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let column = |offset: Scalar| (x0 + offset).rem_euclid(width as Scalar) as usize;
+    let row = |offset: Scalar| ((y0 + offset) as usize).min(height - 1);
+    let pixel = |cx: Scalar, cy: Scalar| image.pixel_at(column(cx), row(cy));
+    let upper = pixel(0.0, 0.0) * (1.0 - fx) + pixel(1.0, 0.0) * fx;
+    let lower = pixel(0.0, 1.0) * (1.0 - fx) + pixel(1.0, 1.0) * fx;
+    upper * (1.0 - fy) + lower * fy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_default_background_is_black() {
+        let background = Background::default();
+        assert_eq!(
+            background.color_for(&Vector::new(0.0, 1.0, 0.0)),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn gradients_blend_from_bottom_to_top() {
+        let background = Background::Gradient {
+            bottom: Color::white(),
+            top: Color::new(0.0, 0.0, 1.0),
+        };
+        let at = |y| background.color_for(&Vector::new(1.0, y, 0.0));
+        assert_eq!(at(0.0), Color::new(0.5, 0.5, 1.0));
+        assert_eq!(
+            background.color_for(&Vector::new(0.0, 2.0, 0.0)),
+            Color::new(0.0, 0.0, 1.0)
+        );
+        assert_eq!(
+            background.color_for(&Vector::new(0.0, -1.0, 0.0)),
+            Color::white()
+        );
+    }
+
+    #[test]
+    fn environments_are_looked_up_by_direction() {
+        let mut image = Canvas::new(4, 2);
+        for x in 0..4 {
+            image.write_pixel(x, 0, Color::new(0.0, 0.0, 1.0));
+            image.write_pixel(x, 1, Color::new(0.0, 1.0, 0.0));
+        }
+        image.write_pixel(2, 0, Color::new(4.0, 0.0, 0.0));
+        let background = Background::Environment(Arc::new(image));
+        assert_eq!(
+            background.color_for(&Vector::new(0.0, 1.0, 0.0)),
+            Color::new(0.0, 0.0, 1.0) * 0.5 + Color::new(4.0, 0.0, 0.0) * 0.5
+        );
+        assert_eq!(
+            background.color_for(&Vector::new(0.0, -1.0, 0.0)),
+            Color::new(0.0, 1.0, 0.0)
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{Background, Light, Volume, World};
+use rt_shapes::{Shape, ShapeBuilder};
+
+#[derive(Debug, Default)]
+pub struct WorldBuilder {
+    world: World,
+}
+
+impl WorldBuilder {
+    pub fn shape<S: Shape>(mut self, shape: impl Into<ShapeBuilder<S>>) -> Self {
+        self.world.add_shape(shape.into().boxed());
+        self
+    }
+
+    pub fn light(mut self, light: impl Light) -> Self {
+        self.world.add_light(Box::new(light));
+        self
+    }
+
+    pub fn background(mut self, background: Background) -> Self {
+        self.world.set_background(background);
+        self
+    }
+
+    pub fn volume(mut self, volume: Volume) -> Self {
+        self.world.add_volume(volume);
+        self
+    }
+
+    pub fn build(self) -> World {
+        self.world
+    }
+}
+
+impl World {
+    pub fn builder() -> WorldBuilder {
+        WorldBuilder::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PointLight, MAX_DEPTH};
+    use rt_core::{Color, Point, Ray, Vector};
+    use rt_shapes::{Material, Plane, Sphere};
+
+    #[test]
+    fn an_empty_builder_makes_an_empty_world() {
+        let w = World::builder().build();
+        assert!(w.shapes().is_empty());
+        assert!(w.lights().is_empty());
+        assert_eq!(*w.background(), Background::default());
+    }
+
+    #[test]
+    fn builders_and_plain_shapes_can_be_mixed() {
+        let w = World::builder()
+            .shape(Sphere::new())
+            .shape(Plane::builder().translate(0.0, -1.0, 0.0))
+            .light(PointLight::new(
+                Point::new(-10.0, 10.0, -10.0),
+                Color::white(),
+            ))
+            .background(Background::Solid(Color::new(0.1, 0.1, 0.1)))
+            .build();
+        assert_eq!(w.shapes().len(), 2);
+        assert_eq!(w.shapes()[1].transform()[1][3], -1.0);
+        assert_eq!(w.lights().len(), 1);
+        let r = Ray::new(Point::new(0.0, 5.0, -5.0), Vector::new(0.0, 1.0, 0.0));
+        assert_eq!(w.color_at(&r, MAX_DEPTH), Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn the_default_world_shades_the_same_when_built() {
+        let built = World::builder()
+            .shape(
+                Sphere::builder().material(
+                    Material::builder()
+                        .color(Color::new(0.8, 1.0, 0.6))
+                        .diffuse(0.7)
+                        .specular(0.2),
+                ),
+            )
+            .shape(Sphere::builder().scale(0.5, 0.5, 0.5))
+            .light(PointLight::new(
+                Point::new(-10.0, 10.0, -10.0),
+                Color::white(),
+            ))
+            .build();
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(
+            built.color_at(&r, MAX_DEPTH),
+            World::default_world().color_at(&r, MAX_DEPTH)
+        );
+    }
+}
@@ -0,0 +1,1365 @@
+use crate::sampling::{mix, unit_hash, Rng, SEED_SPREAD};
+use crate::stats;
+use crate::{
+    Checkpoint, Integrator, RayKind, RenderMode, RenderProgress, RenderSettings, RenderStats,
+    Sampling, World,
+};
+use rt_core::{to_bits, Canvas, Color, Matrix, Point, Ray, Scalar, Vector, EPSILON};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+const TILE_SIZE: usize = 16;
+const SHUTTER_SALT: u64 = 0x2545_f491_4f6c_dd1d;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tile {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Tile {
+    pub fn pixels(&self) -> impl Iterator<Item = (usize, usize)> {
+        let Tile {
+            x,
+            y,
+            width,
+            height,
+        } = *self;
+        (y..y + height).flat_map(move |y| (x..x + width).map(move |x| (x, y)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Region {
+    u: Scalar,
+    v: Scalar,
+    size: Scalar,
+}
+
+impl Region {
+    fn corners(&self) -> [(Scalar, Scalar); 4] {
+        let (u0, v0, u1, v1) = (self.u, self.v, self.u + self.size, self.v + self.size);
+        [(u0, v0), (u1, v0), (u0, v1), (u1, v1)]
+    }
+
+    fn quadrants(&self) -> [Region; 4] {
+        let half = self.size / 2.0;
+        [(0.0, 0.0), (half, 0.0), (0.0, half), (half, half)].map(|(du, dv)| Region {
+            u: self.u + du,
+            v: self.v + dv,
+            size: half,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Camera {
+    hsize: usize,
+    vsize: usize,
+    field_of_view: Scalar,
+    transform: Matrix<4, 4>,
+    inverse_transform: Matrix<4, 4>,
+    half_width: Scalar,
+    half_height: Scalar,
+    pixel_size: Scalar,
+    sampling: Sampling,
+    settings: RenderSettings,
+    aperture: Scalar,
+    focal_distance: Scalar,
+    shutter: (Scalar, Scalar),
+}
+
+impl Camera {
+    pub fn new(hsize: usize, vsize: usize, field_of_view: Scalar) -> Self {
+        let half_view = (field_of_view / 2.0).tan();
+        let aspect = hsize as Scalar / vsize as Scalar;
+        let (half_width, half_height) = if aspect >= 1.0 {
+            (half_view, half_view / aspect)
+        } else {
+            (half_view * aspect, half_view)
+        };
+
+        Camera {
+            hsize,
+            vsize,
+            field_of_view,
+            transform: Matrix::identity(),
+            inverse_transform: Matrix::identity(),
+            half_width,
+            half_height,
+            pixel_size: half_width * 2.0 / hsize as Scalar,
+            sampling: Sampling::default(),
+            settings: RenderSettings::default(),
+            aperture: 0.0,
+            focal_distance: 1.0,
+            shutter: (0.0, 0.0),
+        }
+    }
+
+    pub fn hsize(&self) -> usize {
+        self.hsize
+    }
+
+    pub fn vsize(&self) -> usize {
+        self.vsize
+    }
+
+    pub fn field_of_view(&self) -> Scalar {
+        self.field_of_view
+    }
+
+    pub fn pixel_size(&self) -> Scalar {
+        self.pixel_size
+    }
+
+    pub fn transform(&self) -> &Matrix<4, 4> {
+        &self.transform
+    }
+
+    pub fn set_transform(&mut self, transform: Matrix<4, 4>) {
+        self.inverse_transform = transform
+            .inverse()
+            .expect("camera transform must be invertible");
+        self.transform = transform;
+    }
+
+    pub fn set_field_of_view(&mut self, field_of_view: Scalar) {
+        self.reshape(self.hsize, self.vsize, field_of_view);
+    }
+
+    pub fn set_size(&mut self, hsize: usize, vsize: usize) {
+        self.reshape(hsize, vsize, self.field_of_view);
+    }
+
+    fn reshape(&mut self, hsize: usize, vsize: usize, field_of_view: Scalar) {
+        *self = Camera {
+            transform: self.transform,
+            inverse_transform: self.inverse_transform,
+            sampling: self.sampling,
+            settings: self.settings,
+            aperture: self.aperture,
+            focal_distance: self.focal_distance,
+            shutter: self.shutter,
+            ..Camera::new(hsize, vsize, field_of_view)
+        };
+    }
+
+    pub fn sampling(&self) -> Sampling {
+        self.sampling
+    }
+
+    pub fn set_sampling(&mut self, sampling: Sampling) {
+        self.sampling = sampling;
+    }
+
+    pub fn settings(&self) -> RenderSettings {
+        self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: RenderSettings) {
+        self.settings = settings;
+    }
+
+    pub fn aperture(&self) -> Scalar {
+        self.aperture
+    }
+
+    pub fn set_aperture(&mut self, aperture: Scalar) {
+        assert!(aperture >= 0.0, "camera aperture must not be negative");
+        self.aperture = aperture;
+    }
+
+    pub fn focal_distance(&self) -> Scalar {
+        self.focal_distance
+    }
+
+    pub fn set_focal_distance(&mut self, focal_distance: Scalar) {
+        assert!(
+            focal_distance > 0.0,
+            "camera focal distance must be positive"
+        );
+        self.focal_distance = focal_distance;
+    }
+
+    pub fn shutter(&self) -> (Scalar, Scalar) {
+        self.shutter
+    }
+
+    pub fn set_shutter(&mut self, open: Scalar, close: Scalar) {
+        assert!(open <= close, "camera shutter must close after it opens");
+        self.shutter = (open, close);
+    }
+
+    pub fn position(&self) -> Point {
+        self.inverse_transform * Point::origin()
+    }
+
+    pub fn look_at(&mut self, to: Point) {
+        let from = self.position();
+        let up = self.inverse_transform * Vector::new(0.0, 1.0, 0.0);
+        let forward = to - from;
+        if forward.cross(&up).magnitude() <= EPSILON * forward.magnitude() {
+            return;
+        }
+        self.set_transform(view_transform(from, to, up));
+    }
+
+    pub fn focus_on(&mut self, point: &Point) {
+        let depth = -(self.transform * *point).z();
+        if depth > EPSILON {
+            self.set_focal_distance(depth);
+        }
+    }
+
+    /// Pinhole ray through canvas coordinates, where pixel centers lie at `px + 0.5`.
+    pub fn pixel_to_ray(&self, x: Scalar, y: Scalar) -> Ray {
+        let world_x = self.half_width - x * self.pixel_size;
+        let world_y = self.half_height - y * self.pixel_size;
+        let pixel = self.inverse_transform * Point::new(world_x, world_y, -1.0);
+        let origin = self.position();
+        Ray::new(origin, (pixel - origin).normalize()).with_time(self.shutter.0)
+    }
+
+    pub fn ray_for_pixel(&self, px: usize, py: usize) -> Ray {
+        self.ray_through(px, py, 0.5, 0.5)
+    }
+
+    pub fn ray_through(&self, px: usize, py: usize, u: Scalar, v: Scalar) -> Ray {
+        let xoffset = (px as Scalar + u) * self.pixel_size;
+        let yoffset = (py as Scalar + v) * self.pixel_size;
+        let world_x = self.half_width - xoffset;
+        let world_y = self.half_height - yoffset;
+
+        let focus = self.focal_distance;
+        let pixel = self.inverse_transform * Point::new(world_x * focus, world_y * focus, -focus);
+        let origin = self.inverse_transform * self.lens_point(px, py, u, v);
+        Ray::new(origin, (pixel - origin).normalize()).with_time(self.shutter_time(px, py, u, v))
+    }
+
+    fn lens_point(&self, px: usize, py: usize, u: Scalar, v: Scalar) -> Point {
+        if self.aperture == 0.0 {
+            return Point::origin();
+        }
+        let seed = self.sample_seed(px, py, u, v);
+        let radius = self.aperture / 2.0 * unit_hash(seed).sqrt();
+        let theta = 2.0 * rt_core::consts::PI * unit_hash(seed ^ 0x5851_f42d_4c95_7f2d);
+        Point::new(radius * theta.cos(), radius * theta.sin(), 0.0)
+    }
+
+    fn shutter_time(&self, px: usize, py: usize, u: Scalar, v: Scalar) -> Scalar {
+        let (open, close) = self.shutter;
+        if open == close {
+            return open;
+        }
+        open + (close - open) * unit_hash(self.sample_seed(px, py, u, v) ^ SHUTTER_SALT)
+    }
+
+    fn pixel_seed(&self, px: usize, py: usize) -> u64 {
+        let pixel = (px as u64) << 32 | py as u64;
+        mix(pixel ^ self.settings.seed.wrapping_mul(SEED_SPREAD))
+    }
+
+    fn sample_seed(&self, px: usize, py: usize, u: Scalar, v: Scalar) -> u64 {
+        mix(self.pixel_seed(px, py) ^ to_bits(u)) ^ to_bits(v)
+    }
+
+    pub fn render(&self, world: &World) -> Canvas {
+        let mut image = Canvas::new(self.hsize, self.vsize);
+        for y in 0..self.vsize {
+            for x in 0..self.hsize {
+                image.write_pixel(x, y, self.pixel_color(world, x, y));
+            }
+        }
+        image
+    }
+
+    pub fn render_parallel(&self, world: &World, threads: usize) -> Canvas {
+        self.render_with_progress(world, threads, |_| {})
+    }
+
+    pub fn render_with_progress<F>(&self, world: &World, threads: usize, on_progress: F) -> Canvas
+    where
+        F: FnMut(RenderProgress) + Send,
+    {
+        self.render_canvas(world, threads, None, on_progress)
+    }
+
+    pub fn render_with_stats<F>(
+        &self,
+        world: &World,
+        threads: usize,
+        on_progress: F,
+    ) -> (Canvas, RenderStats)
+    where
+        F: FnMut(RenderProgress) + Send,
+    {
+        let stats = Mutex::new(RenderStats::default());
+        let image = self.render_canvas(world, threads, Some(&stats), on_progress);
+        let mut stats = stats.into_inner().expect("a render thread panicked");
+        stats.tiles.sort_by_key(|&(tile, _)| self.tile_index(tile));
+        (image, stats)
+    }
+
+    fn render_canvas<F>(
+        &self,
+        world: &World,
+        threads: usize,
+        stats: Option<&Mutex<RenderStats>>,
+        mut on_progress: F,
+    ) -> Canvas
+    where
+        F: FnMut(RenderProgress) + Send,
+    {
+        let mut image = Canvas::new(self.hsize, self.vsize);
+        let never_cancelled = AtomicBool::new(false);
+        self.render_measured_tiles(
+            world,
+            threads,
+            &self.tiles(),
+            &never_cancelled,
+            stats,
+            |progress, colors| {
+                for ((x, y), color) in progress.tile.pixels().zip(colors) {
+                    image.write_pixel(x, y, color);
+                }
+                on_progress(progress);
+            },
+        );
+        image
+    }
+
+    pub fn render_tiles<F>(
+        &self,
+        world: &World,
+        threads: usize,
+        tiles: &[Tile],
+        cancel: &AtomicBool,
+        on_tile: F,
+    ) -> usize
+    where
+        F: FnMut(RenderProgress, Vec<Color>) + Send,
+    {
+        self.render_measured_tiles(world, threads, tiles, cancel, None, on_tile)
+    }
+
+    fn render_measured_tiles<F>(
+        &self,
+        world: &World,
+        threads: usize,
+        tiles: &[Tile],
+        cancel: &AtomicBool,
+        stats: Option<&Mutex<RenderStats>>,
+        on_tile: F,
+    ) -> usize
+    where
+        F: FnMut(RenderProgress, Vec<Color>) + Send,
+    {
+        let threads = if threads == 0 {
+            std::thread::available_parallelism().map_or(1, |n| n.get())
+        } else {
+            threads
+        };
+        let next_tile = AtomicUsize::new(0);
+        let started = Instant::now();
+        let reporter = Mutex::new((0, on_tile));
+        let report = |tile: Tile, colors: Vec<Color>| {
+            let mut reporter = reporter.lock().expect("progress callback panicked");
+            let (completed_tiles, on_tile) = &mut *reporter;
+            *completed_tiles += 1;
+            let progress = RenderProgress {
+                tile,
+                completed_tiles: *completed_tiles,
+                total_tiles: tiles.len(),
+                elapsed: started.elapsed(),
+            };
+            on_tile(progress, colors);
+        };
+
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| {
+                    while !cancel.load(Ordering::Relaxed) {
+                        let index = next_tile.fetch_add(1, Ordering::Relaxed);
+                        match tiles.get(index) {
+                            Some(&tile) => report(tile, self.measure_tile(world, tile, stats)),
+                            None => break,
+                        }
+                    }
+                });
+            }
+        });
+        let (completed_tiles, _) = reporter.into_inner().expect("progress callback panicked");
+        completed_tiles
+    }
+
+    pub fn render_into<F>(
+        &self,
+        world: &World,
+        threads: usize,
+        checkpoint: &mut Checkpoint,
+        cancel: &AtomicBool,
+        mut on_tile: F,
+    ) -> bool
+    where
+        F: FnMut(RenderProgress, &Checkpoint) + Send,
+    {
+        assert!(
+            checkpoint.matches(self),
+            "checkpoint was made for a different camera size"
+        );
+        let tiles = checkpoint.remaining_tiles(self);
+        self.render_tiles(world, threads, &tiles, cancel, |progress, colors| {
+            checkpoint.record(self, progress.tile, &colors);
+            on_tile(progress, checkpoint);
+        });
+        checkpoint.is_complete()
+    }
+
+    pub fn tiles(&self) -> Vec<Tile> {
+        (0..self.vsize)
+            .step_by(TILE_SIZE)
+            .flat_map(|y| {
+                (0..self.hsize).step_by(TILE_SIZE).map(move |x| Tile {
+                    x,
+                    y,
+                    width: TILE_SIZE.min(self.hsize - x),
+                    height: TILE_SIZE.min(self.vsize - y),
+                })
+            })
+            .collect()
+    }
+
+    pub(crate) fn tile_index(&self, tile: Tile) -> usize {
+        let columns = self.hsize.div_ceil(TILE_SIZE);
+        (tile.y / TILE_SIZE) * columns + tile.x / TILE_SIZE
+    }
+
+    fn measure_tile(
+        &self,
+        world: &World,
+        tile: Tile,
+        stats: Option<&Mutex<RenderStats>>,
+    ) -> Vec<Color> {
+        let stats = match stats {
+            Some(stats) => stats,
+            None => return self.render_tile(world, tile),
+        };
+        let started = Instant::now();
+        let (colors, mut counted) = RenderStats::measure(|| self.render_tile(world, tile));
+        counted.tiles.push((tile, started.elapsed()));
+        stats
+            .lock()
+            .expect("a render thread panicked")
+            .merge(counted);
+        colors
+    }
+
+    pub fn render_tile(&self, world: &World, tile: Tile) -> Vec<Color> {
+        let coherent = self.settings.integrator == Integrator::Whitted
+            && self.settings.mode == RenderMode::Shaded
+            && !matches!(self.sampling, Sampling::Adaptive { .. });
+        if self.settings.packets && coherent {
+            return self.render_tile_packets(world, tile);
+        }
+        tile.pixels()
+            .map(|(x, y)| self.pixel_color(world, x, y))
+            .collect()
+    }
+
+    fn render_tile_packets(&self, world: &World, tile: Tile) -> Vec<Color> {
+        let rays: Vec<(usize, Ray)> = tile
+            .pixels()
+            .enumerate()
+            .flat_map(|(index, (x, y))| {
+                self.sampling
+                    .offsets(self.pixel_seed(x, y))
+                    .into_iter()
+                    .map(move |(u, v)| (index, self.ray_through(x, y, u, v)))
+            })
+            .collect();
+        let mut totals = vec![(Color::black(), 0); tile.width * tile.height];
+        stats::count_rays(RayKind::Primary, rays.len());
+        for chunk in rays.chunks(4) {
+            let packet = std::array::from_fn(|i| chunk[i.min(chunk.len() - 1)].1);
+            let colors = world.colors_at(&packet, self.settings.max_depth);
+            for (&(index, _), color) in chunk.iter().zip(colors) {
+                let (sum, count) = &mut totals[index];
+                *sum = *sum + color;
+                *count += 1;
+            }
+        }
+        totals
+            .into_iter()
+            .map(|(sum, count)| sum / count as Scalar)
+            .collect()
+    }
+
+    fn pixel_color(&self, world: &World, x: usize, y: usize) -> Color {
+        if let RenderMode::Debug(channel) = self.settings.mode {
+            stats::count_rays(RayKind::Primary, 1);
+            return world.debug_color(&self.ray_for_pixel(x, y), channel, self.settings.max_depth);
+        }
+        if let Integrator::PathTracer {
+            samples_per_pixel,
+            max_bounces,
+        } = self.settings.integrator
+        {
+            return self.path_traced_color(world, x, y, samples_per_pixel, max_bounces);
+        }
+        match self.sampling {
+            Sampling::Adaptive {
+                max_depth,
+                threshold,
+            } => {
+                let region = Region {
+                    u: 0.0,
+                    v: 0.0,
+                    size: 1.0,
+                };
+                self.adaptive_sample(world, x, y, region, max_depth, threshold)
+                    .0
+            }
+            sampling => {
+                let offsets = sampling.offsets(self.pixel_seed(x, y));
+                stats::count_rays(RayKind::Primary, offsets.len());
+                let total = offsets.iter().fold(Color::black(), |sum, &(u, v)| {
+                    sum + world.color_at(&self.ray_through(x, y, u, v), self.settings.max_depth)
+                });
+                total / offsets.len() as Scalar
+            }
+        }
+    }
+
+    fn path_traced_color(
+        &self,
+        world: &World,
+        x: usize,
+        y: usize,
+        samples: usize,
+        max_bounces: usize,
+    ) -> Color {
+        let pixel_seed = self.pixel_seed(x, y);
+        stats::count_rays(RayKind::Primary, samples);
+        let total = (0..samples).fold(Color::black(), |sum, sample| {
+            let mut rng = Rng::new(pixel_seed ^ sample as u64);
+            let (u, v) = (rng.next_f64(), rng.next_f64());
+            sum + world.path_trace(&self.ray_through(x, y, u, v), max_bounces, &mut rng)
+        });
+        total / samples.max(1) as Scalar
+    }
+
+    fn adaptive_sample(
+        &self,
+        world: &World,
+        x: usize,
+        y: usize,
+        region: Region,
+        depth: usize,
+        threshold: Scalar,
+    ) -> (Color, usize) {
+        let corners: Vec<Color> = region
+            .corners()
+            .iter()
+            .map(|&(u, v)| world.color_at(&self.ray_through(x, y, u, v), self.settings.max_depth))
+            .collect();
+        stats::count_rays(RayKind::Primary, corners.len());
+        let average = corners.iter().fold(Color::black(), |sum, &c| sum + c) / 4.0;
+        let diverges = corners.iter().any(|&c| {
+            let d = c - average;
+            d.red().abs().max(d.green().abs()).max(d.blue().abs()) > threshold
+        });
+        if depth == 0 || !diverges {
+            return (average, corners.len());
+        }
+
+        region
+            .quadrants()
+            .iter()
+            .map(|&quadrant| self.adaptive_sample(world, x, y, quadrant, depth - 1, threshold))
+            .fold((Color::black(), corners.len()), |(sum, count), (c, n)| {
+                (sum + c / 4.0, count + n)
+            })
+    }
+}
+
+pub fn view_transform(from: Point, to: Point, up: Vector) -> Matrix<4, 4> {
+    let forward = (to - from).normalize();
+    let left = forward.cross(&up.normalize());
+    let true_up = left.cross(&forward);
+    let orientation = Matrix::from([
+        [left.x(), left.y(), left.z(), 0.0],
+        [true_up.x(), true_up.y(), true_up.z(), 0.0],
+        [-forward.x(), -forward.y(), -forward.z(), 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+    orientation * Matrix::translation(-from.x(), -from.y(), -from.z())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::consts::PI;
+    use rt_core::EPSILON;
+
+    mod view_transform {
+        use super::*;
+
+        #[test]
+        fn default_orientation() {
+            let t = view_transform(
+                Point::origin(),
+                Point::new(0.0, 0.0, -1.0),
+                Vector::new(0.0, 1.0, 0.0),
+            );
+            assert_eq!(t, Matrix::identity());
+        }
+
+        #[test]
+        fn looking_in_positive_z_direction() {
+            let t = view_transform(
+                Point::origin(),
+                Point::new(0.0, 0.0, 1.0),
+                Vector::new(0.0, 1.0, 0.0),
+            );
+            assert_eq!(t, Matrix::scaling(-1.0, 1.0, -1.0));
+        }
+
+        #[test]
+        fn moves_the_world() {
+            let t = view_transform(
+                Point::new(0.0, 0.0, 8.0),
+                Point::origin(),
+                Vector::new(0.0, 1.0, 0.0),
+            );
+            assert_eq!(t, Matrix::translation(0.0, 0.0, -8.0));
+        }
+
+        #[test]
+        fn arbitrary_view_transformation() {
+            let t = view_transform(
+                Point::new(1.0, 3.0, 2.0),
+                Point::new(4.0, -2.0, 8.0),
+                Vector::new(1.0, 1.0, 0.0),
+            );
+            assert_eq!(
+                t,
+                Matrix::from([
+                    [-0.50709, 0.50709, 0.67612, -2.36643],
+                    [0.76772, 0.60609, 0.12122, -2.82843],
+                    [-0.35857, 0.59761, -0.71714, 0.00000],
+                    [0.00000, 0.00000, 0.00000, 1.00000],
+                ])
+            );
+        }
+    }
+
+    mod construction {
+        use super::*;
+
+        #[test]
+        fn constructing_a_camera() {
+            let c = Camera::new(160, 120, PI / 2.0);
+            assert_eq!(c.hsize(), 160);
+            assert_eq!(c.vsize(), 120);
+            assert_eq!(c.field_of_view(), PI / 2.0);
+            assert_eq!(*c.transform(), Matrix::identity());
+        }
+
+        #[test]
+        fn pixel_size_for_a_horizontal_canvas() {
+            let c = Camera::new(200, 125, PI / 2.0);
+            assert!((c.pixel_size() - 0.01).abs() < EPSILON);
+        }
+
+        #[test]
+        fn resizing_keeps_the_view() {
+            let mut c = Camera::new(200, 125, PI / 2.0);
+            c.set_transform(Matrix::translation(0.0, -2.0, 5.0));
+            c.set_aperture(0.5);
+            c.set_size(400, 250);
+            assert_eq!((c.hsize(), c.vsize()), (400, 250));
+            assert!((c.pixel_size() - 0.005).abs() < EPSILON);
+            assert_eq!(*c.transform(), Matrix::translation(0.0, -2.0, 5.0));
+            assert_eq!(c.aperture(), 0.5);
+        }
+
+        #[test]
+        fn changing_the_field_of_view_keeps_the_view() {
+            let mut c = Camera::new(200, 125, PI / 3.0);
+            c.set_transform(Matrix::translation(0.0, -2.0, 5.0));
+            c.set_field_of_view(PI / 2.0);
+            assert_eq!(c.field_of_view(), PI / 2.0);
+            assert!((c.pixel_size() - 0.01).abs() < EPSILON);
+            assert_eq!(*c.transform(), Matrix::translation(0.0, -2.0, 5.0));
+        }
+
+        #[test]
+        fn pixel_size_for_a_vertical_canvas() {
+            let c = Camera::new(125, 200, PI / 2.0);
+            assert!((c.pixel_size() - 0.01).abs() < EPSILON);
+        }
+    }
+
+    mod rays {
+        use super::*;
+
+        #[test]
+        fn through_the_center_of_the_canvas() {
+            let c = Camera::new(201, 101, PI / 2.0);
+            let r = c.ray_for_pixel(100, 50);
+            assert_eq!(r.origin, Point::origin());
+            assert_eq!(r.direction, Vector::new(0.0, 0.0, -1.0));
+        }
+
+        #[test]
+        fn through_a_corner_of_the_canvas() {
+            let c = Camera::new(201, 101, PI / 2.0);
+            let r = c.ray_for_pixel(0, 0);
+            assert_eq!(r.origin, Point::origin());
+            assert_eq!(r.direction, Vector::new(0.66519, 0.33259, -0.66851));
+        }
+
+        #[test]
+        fn when_the_camera_is_transformed() {
+            let mut c = Camera::new(201, 101, PI / 2.0);
+            c.set_transform(Matrix::rotation_y(PI / 4.0) * Matrix::translation(0.0, -2.0, 5.0));
+            let r = c.ray_for_pixel(100, 50);
+            let h = Scalar::sqrt(2.0) / 2.0;
+            assert_eq!(r.origin, Point::new(0.0, 2.0, -5.0));
+            assert_eq!(r.direction, Vector::new(h, 0.0, -h));
+        }
+
+        #[test]
+        fn pixel_coordinates_map_to_pinhole_rays() {
+            let mut c = Camera::new(201, 101, PI / 2.0);
+            c.set_transform(Matrix::rotation_y(PI / 4.0) * Matrix::translation(0.0, -2.0, 5.0));
+            let expected = c.ray_for_pixel(0, 0);
+            c.set_aperture(0.5);
+            c.set_focal_distance(3.0);
+            let r = c.pixel_to_ray(0.5, 0.5);
+            assert_eq!(r.origin, c.position());
+            assert_eq!(r.origin, expected.origin);
+            assert_eq!(r.direction, expected.direction);
+        }
+    }
+
+    mod aiming {
+        use super::*;
+
+        fn camera() -> Camera {
+            let mut c = Camera::new(11, 11, PI / 2.0);
+            c.set_transform(view_transform(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::new(0.0, 1.0, 0.0),
+            ));
+            c
+        }
+
+        #[test]
+        fn looking_at_a_point_keeps_the_position() {
+            let mut c = camera();
+            c.look_at(Point::new(5.0, 0.0, 0.0));
+            assert_eq!(c.position(), Point::new(0.0, 0.0, -5.0));
+            let h = Scalar::sqrt(2.0) / 2.0;
+            assert_eq!(c.pixel_to_ray(5.5, 5.5).direction, Vector::new(h, 0.0, h));
+        }
+
+        #[test]
+        fn looking_along_the_up_vector_is_ignored() {
+            let mut c = camera();
+            let before = *c.transform();
+            c.look_at(Point::new(0.0, 3.0, -5.0));
+            c.look_at(Point::new(0.0, 0.0, -5.0));
+            assert_eq!(*c.transform(), before);
+        }
+
+        #[test]
+        fn focusing_on_a_point_uses_its_depth() {
+            let mut c = camera();
+            c.focus_on(&Point::new(2.0, 1.0, 3.0));
+            assert_eq!(c.focal_distance(), 8.0);
+            c.focus_on(&Point::new(0.0, 0.0, -6.0));
+            assert_eq!(c.focal_distance(), 8.0);
+        }
+    }
+
+    mod parallel {
+        use super::*;
+
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        #[test]
+        fn world_and_camera_are_thread_safe() {
+            assert_send_sync::<World>();
+            assert_send_sync::<Camera>();
+        }
+
+        #[test]
+        fn tiles_cover_the_canvas_exactly_once() {
+            let c = Camera::new(37, 20, PI / 2.0);
+            let mut covered = vec![0; 37 * 20];
+            for tile in c.tiles() {
+                for y in tile.y..tile.y + tile.height {
+                    for x in tile.x..tile.x + tile.width {
+                        covered[y * 37 + x] += 1;
+                    }
+                }
+            }
+            assert!(covered.iter().all(|&count| count == 1));
+        }
+
+        #[test]
+        fn tile_indices_follow_tile_order() {
+            let c = Camera::new(37, 20, PI / 2.0);
+            for (i, tile) in c.tiles().into_iter().enumerate() {
+                assert_eq!(c.tile_index(tile), i);
+            }
+        }
+
+        #[test]
+        fn parallel_render_matches_serial_render() {
+            let w = World::default_world();
+            let mut c = Camera::new(41, 23, PI / 3.0);
+            c.set_transform(view_transform(
+                Point::new(0.0, 1.5, -5.0),
+                Point::new(0.0, 1.0, 0.0),
+                Vector::new(0.0, 1.0, 0.0),
+            ));
+            let serial = c.render(&w);
+            assert_eq!(c.render_parallel(&w, 4), serial);
+            assert_eq!(c.render_parallel(&w, 0), serial);
+        }
+
+        #[test]
+        fn progress_is_reported_once_per_tile_in_order() {
+            let w = World::default_world();
+            let c = Camera::new(37, 20, PI / 2.0);
+            let mut reports = Vec::new();
+            let image = c.render_with_progress(&w, 3, |progress| reports.push(progress));
+            assert_eq!(image, c.render(&w));
+
+            let tiles = c.tiles();
+            assert_eq!(reports.len(), tiles.len());
+            for (i, report) in reports.iter().enumerate() {
+                assert_eq!(report.completed_tiles, i + 1);
+                assert_eq!(report.total_tiles, tiles.len());
+                assert!(tiles.contains(&report.tile));
+            }
+            assert!(reports.windows(2).all(|w| w[0].elapsed <= w[1].elapsed));
+            assert!(reports.last().unwrap().is_complete());
+        }
+
+        #[test]
+        fn rendering_selected_tiles() {
+            let w = World::default_world();
+            let c = Camera::new(37, 20, PI / 2.0);
+            let tiles = c.tiles();
+            let mut rendered = Vec::new();
+            let completed =
+                c.render_tiles(&w, 2, &tiles[1..3], &AtomicBool::new(false), |p, colors| {
+                    rendered.push((p.tile, colors))
+                });
+            assert_eq!(completed, 2);
+            for (tile, colors) in rendered {
+                assert!(tiles[1..3].contains(&tile));
+                assert_eq!(colors, c.render_tile(&w, tile));
+            }
+        }
+
+        #[test]
+        fn cancelling_stops_before_the_next_tile() {
+            let w = World::default_world();
+            let c = Camera::new(64, 64, PI / 2.0);
+            let cancel = AtomicBool::new(false);
+            let completed = c.render_tiles(&w, 1, &c.tiles(), &cancel, |progress, _| {
+                if progress.completed_tiles == 3 {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            });
+            assert_eq!(completed, 3);
+        }
+    }
+
+    mod depth_of_field {
+        use super::*;
+
+        #[test]
+        fn pinhole_camera_by_default() {
+            let c = Camera::new(201, 101, PI / 2.0);
+            assert_eq!(c.aperture(), 0.0);
+            assert_eq!(c.focal_distance(), 1.0);
+        }
+
+        #[test]
+        fn focal_distance_alone_does_not_change_pinhole_rays() {
+            let mut c = Camera::new(201, 101, PI / 2.0);
+            let before = c.ray_for_pixel(0, 0);
+            c.set_focal_distance(7.5);
+            assert_eq!(c.ray_for_pixel(0, 0), before);
+        }
+
+        #[test]
+        fn lens_origins_lie_within_the_aperture() {
+            let mut c = Camera::new(21, 21, PI / 2.0);
+            c.set_aperture(0.5);
+            c.set_focal_distance(4.0);
+            let mut moved = false;
+            for &(u, v) in Sampling::Jittered(4).offsets(3).iter() {
+                let r = c.ray_through(10, 10, u, v);
+                let offset = r.origin - Point::origin();
+                assert!(offset.magnitude() <= 0.25 + EPSILON);
+                assert!(offset.z().abs() < EPSILON);
+                moved |= offset.magnitude() > EPSILON;
+            }
+            assert!(moved);
+        }
+
+        #[test]
+        fn rays_through_a_pixel_meet_on_the_focal_plane() {
+            let mut pinhole = Camera::new(201, 101, PI / 2.0);
+            pinhole.set_transform(Matrix::translation(0.0, 0.0, -3.0));
+            let mut c = pinhole.clone();
+            c.set_aperture(1.0);
+            c.set_focal_distance(5.0);
+            let focal_z = 3.0 - 5.0;
+            let on_focal_plane = |r: Ray| r.position((focal_z - r.origin.z()) / r.direction.z());
+            for (u, v) in [(0.1, 0.9), (0.5, 0.5), (0.7, 0.2)] {
+                let lens = c.ray_through(20, 30, u, v);
+                let through = pinhole.ray_through(20, 30, u, v);
+                assert_eq!(on_focal_plane(lens), on_focal_plane(through));
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "aperture")]
+        fn negative_aperture_is_rejected() {
+            Camera::new(10, 10, PI / 2.0).set_aperture(-1.0);
+        }
+    }
+
+    mod packets {
+        use super::*;
+
+        fn packet_camera(sampling: Sampling) -> Camera {
+            let mut c = Camera::new(41, 23, PI / 3.0);
+            c.set_transform(view_transform(
+                Point::new(0.0, 1.5, -5.0),
+                Point::new(0.0, 1.0, 0.0),
+                Vector::new(0.0, 1.0, 0.0),
+            ));
+            c.set_sampling(sampling);
+            c
+        }
+
+        fn with_packets(camera: &Camera) -> Camera {
+            let mut c = camera.clone();
+            c.set_settings(RenderSettings {
+                packets: true,
+                ..camera.settings()
+            });
+            c
+        }
+
+        #[test]
+        fn packet_rendering_matches_single_rays() {
+            let w = World::default_world();
+            for sampling in [Sampling::Single, Sampling::Jittered(3)] {
+                let c = packet_camera(sampling);
+                assert_eq!(with_packets(&c).render(&w), c.render(&w), "{:?}", sampling);
+            }
+        }
+
+        #[test]
+        fn adaptive_sampling_falls_back_to_single_rays() {
+            let w = World::default_world();
+            let c = packet_camera(Sampling::Adaptive {
+                max_depth: 2,
+                threshold: 0.05,
+            });
+            assert_eq!(with_packets(&c).render(&w), c.render(&w));
+        }
+    }
+
+    mod motion_blur {
+        use super::*;
+        use rt_shapes::{Material, Shape, Sphere};
+
+        #[test]
+        fn shutter_is_closed_by_default() {
+            let c = Camera::new(11, 11, PI / 2.0);
+            assert_eq!(c.shutter(), (0.0, 0.0));
+            assert_eq!(c.ray_through(3, 4, 0.1, 0.7).time, 0.0);
+        }
+
+        #[test]
+        fn ray_times_fall_within_the_shutter() {
+            let mut c = Camera::new(11, 11, PI / 2.0);
+            c.set_shutter(0.25, 0.75);
+            let times: Vec<Scalar> = Sampling::Jittered(4)
+                .offsets(5)
+                .iter()
+                .map(|&(u, v)| c.ray_through(5, 5, u, v).time)
+                .collect();
+            assert!(times.iter().all(|t| (0.25..=0.75).contains(t)));
+            assert!(times.iter().any(|&t| t != times[0]));
+        }
+
+        #[test]
+        #[should_panic(expected = "shutter")]
+        fn a_shutter_must_close_after_it_opens() {
+            Camera::new(10, 10, PI / 2.0).set_shutter(1.0, 0.0);
+        }
+
+        #[test]
+        fn moving_objects_smear_across_their_path() {
+            let mut sphere = Sphere::new();
+            sphere.set_transform(Matrix::translation(-2.0, 0.0, 0.0));
+            sphere.set_motion(Matrix::translation(2.0, 0.0, 0.0));
+            sphere.set_material(Material {
+                emissive: Color::white(),
+                ambient: 0.0,
+                diffuse: 0.0,
+                specular: 0.0,
+                ..Material::default()
+            });
+            let mut w = World::new();
+            w.add_shape(Box::new(sphere));
+            let mut c = Camera::new(11, 11, PI / 2.0);
+            c.set_transform(view_transform(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::new(0.0, 1.0, 0.0),
+            ));
+            c.set_sampling(Sampling::Jittered(4));
+            assert_eq!(c.render(&w).pixel_at(5, 5), Color::black());
+
+            c.set_shutter(0.0, 1.0);
+            let blurred = c.render(&w).pixel_at(5, 5);
+            assert!(blurred.red() > 0.2 && blurred.red() < 0.8, "{:?}", blurred);
+        }
+    }
+
+    mod integrators {
+        use super::*;
+
+        fn camera(integrator: Integrator) -> Camera {
+            let mut c = Camera::new(11, 11, PI / 2.0);
+            c.set_transform(view_transform(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::new(0.0, 1.0, 0.0),
+            ));
+            c.set_settings(RenderSettings {
+                integrator,
+                ..RenderSettings::default()
+            });
+            c
+        }
+
+        #[test]
+        fn whitted_is_the_default_integrator() {
+            assert_eq!(
+                Camera::new(11, 11, PI / 2.0).settings(),
+                RenderSettings::default()
+            );
+            let image = camera(Integrator::Whitted).render(&World::default_world());
+            assert_eq!(image.pixel_at(5, 5), Color::new(0.38066, 0.47583, 0.2855));
+        }
+
+        #[test]
+        fn path_traced_renders_are_reproducible_in_parallel() {
+            let w = World::default_world();
+            let c = camera(Integrator::PathTracer {
+                samples_per_pixel: 4,
+                max_bounces: 3,
+            });
+            let serial = c.render(&w);
+            assert_eq!(c.render_parallel(&w, 3), serial);
+            assert_ne!(serial, camera(Integrator::Whitted).render(&w));
+        }
+
+        #[test]
+        fn path_tracing_drops_the_ambient_term() {
+            let w = World::default_world();
+            let c = camera(Integrator::PathTracer {
+                samples_per_pixel: 1,
+                max_bounces: 0,
+            });
+            assert_eq!(c.render(&w).pixel_at(0, 0), Color::black());
+            let center = c.render(&w).pixel_at(5, 5);
+            let whitted = camera(Integrator::Whitted).render(&w).pixel_at(5, 5);
+            assert!(center.red() < whitted.red());
+        }
+    }
+
+    mod antialiasing {
+        use super::*;
+
+        fn edge_camera() -> Camera {
+            let mut c = Camera::new(11, 11, PI / 2.0);
+            c.set_transform(view_transform(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::new(0.0, 1.0, 0.0),
+            ));
+            c
+        }
+
+        fn edge_pixel(c: &Camera, w: &World) -> (usize, usize) {
+            let image = c.render(w);
+            (0..c.hsize())
+                .map(|x| (x, 5))
+                .find(|&(x, y)| image.pixel_at(x, y) != Color::black())
+                .unwrap()
+        }
+
+        #[test]
+        fn camera_defaults_to_a_single_sample() {
+            assert_eq!(Camera::new(10, 10, PI / 2.0).sampling(), Sampling::Single);
+        }
+
+        #[test]
+        fn ray_through_offsets_within_the_pixel() {
+            let c = Camera::new(201, 101, PI / 2.0);
+            assert_eq!(c.ray_through(100, 50, 0.5, 0.5), c.ray_for_pixel(100, 50));
+            assert_ne!(c.ray_through(100, 50, 0.0, 0.0), c.ray_for_pixel(100, 50));
+        }
+
+        #[test]
+        fn one_by_one_grid_matches_single_sampling() {
+            let w = World::default_world();
+            let mut c = edge_camera();
+            let single = c.render(&w);
+            c.set_sampling(Sampling::Grid(1));
+            assert_eq!(c.render(&w), single);
+        }
+
+        #[test]
+        fn supersampling_softens_silhouette_edges() {
+            let w = World::default_world();
+            let mut c = edge_camera();
+            let (x, y) = edge_pixel(&c, &w);
+            let single = c.render(&w).pixel_at(x, y);
+            for sampling in [Sampling::Grid(4), Sampling::Jittered(4)] {
+                c.set_sampling(sampling);
+                let smoothed = c.render(&w).pixel_at(x, y);
+                assert_ne!(smoothed, single, "{:?}", sampling);
+                assert_ne!(smoothed, Color::black(), "{:?}", sampling);
+            }
+        }
+
+        #[test]
+        fn jittered_renders_are_reproducible() {
+            let w = World::default_world();
+            let mut c = edge_camera();
+            c.set_sampling(Sampling::Jittered(2));
+            assert_eq!(c.render(&w), c.render(&w));
+            assert_eq!(c.render_parallel(&w, 3), c.render(&w));
+        }
+
+        #[test]
+        fn adaptive_sampling_only_refines_divergent_pixels() {
+            let w = World::default_world();
+            let c = edge_camera();
+            let (x, y) = edge_pixel(&c, &w);
+            let whole = Region {
+                u: 0.0,
+                v: 0.0,
+                size: 1.0,
+            };
+            let (_, flat) = c.adaptive_sample(&w, 0, 0, whole, 3, 0.01);
+            let (_, edge) = c.adaptive_sample(&w, x, y, whole, 3, 0.01);
+            assert_eq!(flat, 4);
+            assert!(edge > 4, "{}", edge);
+        }
+
+        #[test]
+        fn adaptive_sampling_is_bounded_by_depth() {
+            let w = World::default_world();
+            let c = edge_camera();
+            let (x, y) = edge_pixel(&c, &w);
+            let whole = Region {
+                u: 0.0,
+                v: 0.0,
+                size: 1.0,
+            };
+            let (_, count) = c.adaptive_sample(&w, x, y, whole, 1, 0.0);
+            assert_eq!(count, 4 + 4 * 4);
+        }
+    }
+
+    #[test]
+    fn rendering_a_world() {
+        let w = World::default_world();
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        c.set_transform(view_transform(
+            Point::new(0.0, 0.0, -5.0),
+            Point::origin(),
+            Vector::new(0.0, 1.0, 0.0),
+        ));
+        let image = c.render(&w);
+        assert_eq!(image.pixel_at(5, 5), Color::new(0.38066, 0.47583, 0.2855));
+    }
+
+    mod statistics {
+        use super::*;
+        use rt_shapes::{Material, Plane, Shape};
+
+        fn camera() -> Camera {
+            let mut c = Camera::new(21, 19, PI / 3.0);
+            c.set_transform(view_transform(
+                Point::new(0.0, 1.5, -5.0),
+                Point::new(0.0, 1.0, 0.0),
+                Vector::new(0.0, 1.0, 0.0),
+            ));
+            c
+        }
+
+        #[test]
+        fn measured_renders_match_plain_renders() {
+            let w = World::default_world();
+            let c = camera();
+            let (image, stats) = c.render_with_stats(&w, 3, |_| {});
+            assert_eq!(image, c.render(&w));
+            assert_eq!(stats.rays(RayKind::Primary), 21 * 19);
+            assert!(stats.rays(RayKind::Shadow) > 0);
+            assert_eq!(stats.rays(RayKind::Reflection), 0);
+            assert!(stats.intersection_tests >= stats.total_rays());
+        }
+
+        #[test]
+        fn every_tile_is_timed_in_tile_order() {
+            let w = World::default_world();
+            let c = camera();
+            let (_, stats) = c.render_with_stats(&w, 4, |_| {});
+            let tiles: Vec<Tile> = stats.tiles.iter().map(|&(tile, _)| tile).collect();
+            assert_eq!(tiles, c.tiles());
+        }
+
+        #[test]
+        fn secondary_rays_are_counted_by_kind() {
+            let mut w = World::default_world();
+            let mut floor = Plane::new();
+            floor.set_transform(Matrix::translation(0.0, -1.0, 0.0));
+            floor.set_material(Material {
+                reflective: 0.5,
+                transparency: 0.5,
+                refractive_index: 1.5,
+                ..Material::default()
+            });
+            w.add_shape(Box::new(floor));
+            let (_, stats) = camera().render_with_stats(&w, 2, |_| {});
+            assert!(stats.rays(RayKind::Reflection) > 0);
+            assert!(stats.rays(RayKind::Refraction) > 0);
+            assert_eq!(stats.rays(RayKind::Bounce), 0);
+        }
+
+        #[test]
+        fn packets_cast_the_same_primary_rays() {
+            let w = World::default_world();
+            let mut c = camera();
+            c.set_sampling(Sampling::Grid(2));
+            let (_, single) = c.render_with_stats(&w, 1, |_| {});
+            c.set_settings(RenderSettings {
+                packets: true,
+                ..c.settings()
+            });
+            let (_, packets) = c.render_with_stats(&w, 1, |_| {});
+            assert_eq!(single.rays(RayKind::Primary), 4 * 21 * 19);
+            assert_eq!(
+                packets.rays(RayKind::Primary),
+                single.rays(RayKind::Primary)
+            );
+        }
+
+        #[test]
+        fn path_tracing_counts_bounces() {
+            let w = World::default_world();
+            let mut c = camera();
+            c.set_settings(RenderSettings {
+                integrator: Integrator::PathTracer {
+                    samples_per_pixel: 2,
+                    max_bounces: 3,
+                },
+                ..c.settings()
+            });
+            let (_, stats) = c.render_with_stats(&w, 2, |_| {});
+            assert_eq!(stats.rays(RayKind::Primary), 2 * 21 * 19);
+            assert!(stats.rays(RayKind::Bounce) > 0);
+        }
+    }
+
+    mod seeding {
+        use super::*;
+
+        fn seeded(seed: u64, sampling: Sampling, integrator: Integrator) -> Camera {
+            let mut c = Camera::new(24, 18, PI / 3.0);
+            c.set_transform(view_transform(
+                Point::new(0.0, 1.5, -5.0),
+                Point::new(0.0, 1.0, 0.0),
+                Vector::new(0.0, 1.0, 0.0),
+            ));
+            c.set_sampling(sampling);
+            c.set_aperture(0.2);
+            c.set_focal_distance(4.0);
+            c.set_settings(RenderSettings {
+                integrator,
+                seed,
+                ..c.settings()
+            });
+            c
+        }
+
+        fn bits(image: &Canvas) -> Vec<u64> {
+            let mut bits = Vec::new();
+            for y in 0..image.height() {
+                for x in 0..image.width() {
+                    let color = image.pixel_at(x, y);
+                    bits.extend([color.red(), color.green(), color.blue()].map(to_bits));
+                }
+            }
+            bits
+        }
+
+        fn path(samples_per_pixel: usize) -> Integrator {
+            Integrator::PathTracer {
+                samples_per_pixel,
+                max_bounces: 2,
+            }
+        }
+
+        #[test]
+        fn the_same_seed_reproduces_a_render_exactly() {
+            let w = World::default_world();
+            for integrator in [Integrator::Whitted, path(2)] {
+                let image = seeded(42, Sampling::Jittered(2), integrator).render(&w);
+                let again = seeded(42, Sampling::Jittered(2), integrator);
+                assert_eq!(bits(&again.render_parallel(&w, 3)), bits(&image));
+            }
+        }
+
+        #[test]
+        fn different_seeds_draw_different_samples() {
+            let w = World::default_world();
+            for integrator in [Integrator::Whitted, path(2)] {
+                let a = seeded(1, Sampling::Jittered(2), integrator).render(&w);
+                let b = seeded(2, Sampling::Jittered(2), integrator).render(&w);
+                assert_ne!(bits(&a), bits(&b));
+            }
+        }
+
+        #[test]
+        fn seeds_leave_deterministic_sampling_alone() {
+            let w = World::default_world();
+            let mut a = seeded(1, Sampling::Grid(2), Integrator::Whitted);
+            let mut b = seeded(2, Sampling::Grid(2), Integrator::Whitted);
+            a.set_aperture(0.0);
+            b.set_aperture(0.0);
+            assert_eq!(bits(&a.render(&w)), bits(&b.render(&w)));
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{Camera, Tile};
+use rt_core::{from_f64, to_f64, Canvas, Color};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"RTCP";
+const VERSION: u32 = 1;
+const PIXEL_BYTES: usize = 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    fingerprint: u64,
+    canvas: Canvas,
+    completed: Vec<bool>,
+}
+
+impl Checkpoint {
+    pub fn new(camera: &Camera, fingerprint: u64) -> Self {
+        Checkpoint {
+            fingerprint,
+            canvas: Canvas::new(camera.hsize(), camera.vsize()),
+            completed: vec![false; camera.tiles().len()],
+        }
+    }
+
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    pub fn into_canvas(self) -> Canvas {
+        self.canvas
+    }
+
+    pub fn matches(&self, camera: &Camera) -> bool {
+        self.canvas.width() == camera.hsize()
+            && self.canvas.height() == camera.vsize()
+            && self.completed.len() == camera.tiles().len()
+    }
+
+    pub fn completed_tiles(&self) -> usize {
+        self.completed.iter().filter(|&&done| done).count()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed.iter().all(|&done| done)
+    }
+
+    pub fn remaining_tiles(&self, camera: &Camera) -> Vec<Tile> {
+        camera
+            .tiles()
+            .into_iter()
+            .zip(&self.completed)
+            .filter(|(_, &done)| !done)
+            .map(|(tile, _)| tile)
+            .collect()
+    }
+
+    pub fn record(&mut self, camera: &Camera, tile: Tile, colors: &[Color]) {
+        let index = camera.tile_index(tile);
+        for ((x, y), &color) in tile.pixels().zip(colors) {
+            self.canvas.write_pixel(x, y, color);
+        }
+        self.completed[index] = true;
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&self.fingerprint.to_le_bytes())?;
+        for size in [
+            self.canvas.width(),
+            self.canvas.height(),
+            self.completed.len(),
+        ] {
+            writer.write_all(&(size as u64).to_le_bytes())?;
+        }
+        let flags: Vec<u8> = self.completed.iter().map(|&done| done as u8).collect();
+        writer.write_all(&flags)?;
+        for y in 0..self.canvas.height() {
+            for x in 0..self.canvas.width() {
+                let c = self.canvas.pixel_at(x, y);
+                for channel in [c.red(), c.green(), c.blue()] {
+                    writer.write_all(&to_f64(channel).to_le_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a render checkpoint"));
+        }
+        let mut word = [0; 4];
+        reader.read_exact(&mut word)?;
+        let version = u32::from_le_bytes(word);
+        if version != VERSION {
+            return Err(invalid_data(&format!(
+                "unsupported checkpoint version {}",
+                version
+            )));
+        }
+        let fingerprint = read_u64(reader)?;
+        let width = read_u64(reader)? as usize;
+        let height = read_u64(reader)? as usize;
+        let tiles = read_u64(reader)? as usize;
+
+        let mut flags = Vec::new();
+        reader.take(tiles as u64).read_to_end(&mut flags)?;
+        if flags.len() != tiles || flags.iter().any(|&flag| flag > 1) {
+            return Err(invalid_data("corrupt tile table"));
+        }
+
+        let payload = width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(PIXEL_BYTES))
+            .ok_or_else(|| invalid_data("checkpoint image is too large"))?;
+        let mut pixels = Vec::new();
+        reader.take(payload as u64).read_to_end(&mut pixels)?;
+        if pixels.len() != payload {
+            return Err(invalid_data("truncated pixel data"));
+        }
+
+        let mut canvas = Canvas::new(width, height);
+        for (i, mut pixel) in pixels.chunks_exact(PIXEL_BYTES).enumerate() {
+            let mut channels = [0.0; 3];
+            for channel in &mut channels {
+                *channel = from_f64(f64::from_bits(read_u64(&mut pixel)?));
+            }
+            let color = Color::new(channels[0], channels[1], channels[2]);
+            canvas.write_pixel(i % width, i / width, color);
+        }
+
+        Ok(Checkpoint {
+            fingerprint,
+            canvas,
+            completed: flags.into_iter().map(|flag| flag == 1).collect(),
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+        let mut file = io::BufWriter::new(std::fs::File::create(&partial)?);
+        self.write(&mut file)?;
+        file.into_inner()?.sync_all()?;
+        std::fs::rename(&partial, path)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read(&mut io::BufReader::new(std::fs::File::open(path)?))
+    }
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::World;
+    use rt_core::consts::PI;
+    use std::sync::atomic::AtomicBool;
+
+    fn camera() -> Camera {
+        Camera::new(37, 20, PI / 2.0)
+    }
+
+    #[test]
+    fn a_new_checkpoint_has_every_tile_remaining() {
+        let c = camera();
+        let checkpoint = Checkpoint::new(&c, 7);
+        assert!(checkpoint.matches(&c));
+        assert!(!checkpoint.matches(&Camera::new(38, 20, PI / 2.0)));
+        assert_eq!(checkpoint.completed_tiles(), 0);
+        assert_eq!(checkpoint.remaining_tiles(&c), c.tiles());
+    }
+
+    #[test]
+    fn recording_a_tile_fills_its_pixels() {
+        let c = camera();
+        let mut checkpoint = Checkpoint::new(&c, 7);
+        let tile = c.tiles()[1];
+        let colors = vec![Color::white(); tile.width * tile.height];
+        checkpoint.record(&c, tile, &colors);
+        assert_eq!(checkpoint.completed_tiles(), 1);
+        assert!(!checkpoint.remaining_tiles(&c).contains(&tile));
+        assert_eq!(checkpoint.canvas().pixel_at(tile.x, tile.y), Color::white());
+        assert_eq!(checkpoint.canvas().pixel_at(0, 0), Color::black());
+    }
+
+    #[test]
+    fn checkpoints_round_trip_through_bytes() {
+        let c = camera();
+        let mut checkpoint = Checkpoint::new(&c, 0xdead_beef);
+        let tile = c.tiles()[2];
+        let colors = vec![Color::new(0.1, 1.0 / 3.0, 7.5); tile.width * tile.height];
+        checkpoint.record(&c, tile, &colors);
+
+        let mut bytes = Vec::new();
+        checkpoint.write(&mut bytes).unwrap();
+        let restored = Checkpoint::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(restored, checkpoint);
+        assert_eq!(restored.fingerprint(), 0xdead_beef);
+    }
+
+    #[test]
+    fn reading_rejects_other_files() {
+        let error = Checkpoint::read(&mut &b"P3\n1 1\n255\n0 0 0\n"[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let mut bytes = Vec::new();
+        Checkpoint::new(&camera(), 1).write(&mut bytes).unwrap();
+        bytes.truncate(bytes.len() - 1);
+        assert!(Checkpoint::read(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn image_sizes_are_checked_against_the_payload() {
+        let header = |width: u64, height: u64| {
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend_from_slice(&VERSION.to_le_bytes());
+            for word in [0, width, height, 0] {
+                bytes.extend_from_slice(&word.to_le_bytes());
+            }
+            bytes
+        };
+        for (width, height) in [(1 << 40, 1 << 40), (1 << 30, 1 << 20), (2, 1)] {
+            let error = Checkpoint::read(&mut header(width, height).as_slice()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn resuming_produces_the_uninterrupted_image() {
+        let w = World::default_world();
+        let c = camera();
+        let mut checkpoint = Checkpoint::new(&c, 0);
+        let cancel = AtomicBool::new(false);
+        c.render_into(&w, 2, &mut checkpoint, &cancel, |progress, _| {
+            if progress.completed_tiles == 2 {
+                cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+            }
+        });
+        assert!(!checkpoint.is_complete());
+
+        let mut bytes = Vec::new();
+        checkpoint.write(&mut bytes).unwrap();
+        let mut resumed = Checkpoint::read(&mut bytes.as_slice()).unwrap();
+        c.render_into(&w, 2, &mut resumed, &AtomicBool::new(false), |_, _| {});
+        assert!(resumed.is_complete());
+        assert_eq!(resumed.into_canvas(), c.render(&w));
+    }
+}
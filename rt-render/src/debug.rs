@@ -0,0 +1,350 @@
+use crate::world::refraction_ray;
+use crate::{Camera, RayKind, RenderStats, World};
+use rt_core::{Color, Point, Ray, Scalar, Vector};
+use rt_shapes::count_traversal;
+
+const DEPTH_HALF_HEAT: Scalar = 10.0;
+const TRAVERSAL_HALF_HEAT: Scalar = 64.0;
+const SHADOW_HALF_HEAT: Scalar = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugChannel {
+    Normal,
+    Depth,
+    Traversal,
+    ShadowRays,
+}
+
+impl DebugChannel {
+    pub const ALL: [DebugChannel; 4] = [
+        DebugChannel::Normal,
+        DebugChannel::Depth,
+        DebugChannel::Traversal,
+        DebugChannel::ShadowRays,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DebugChannel::Normal => "normal",
+            DebugChannel::Depth => "depth",
+            DebugChannel::Traversal => "traversal",
+            DebugChannel::ShadowRays => "shadow-rays",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<DebugChannel> {
+        DebugChannel::ALL
+            .iter()
+            .copied()
+            .find(|channel| channel.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RayEvent {
+    Miss {
+        kind: RayKind,
+        depth: usize,
+        ray: Ray,
+    },
+    Hit {
+        kind: RayKind,
+        depth: usize,
+        ray: Ray,
+        t: Scalar,
+        shape: Option<usize>,
+        point: Point,
+        normal: Vector,
+        inside: bool,
+    },
+    Light {
+        light: usize,
+        intensity: Scalar,
+    },
+}
+
+fn heat(value: Scalar, half: Scalar) -> Color {
+    if value <= 0.0 {
+        return Color::black();
+    }
+    let v = value / (value + half);
+    if v < 0.5 {
+        Color::new(0.0, 2.0 * v, 1.0 - 2.0 * v)
+    } else {
+        Color::new(2.0 * v - 1.0, 2.0 - 2.0 * v, 0.0)
+    }
+}
+
+impl World {
+    /// Misses are black; the heat channels run from blue through green to red.
+    pub fn debug_color(&self, ray: &Ray, channel: DebugChannel, remaining: usize) -> Color {
+        match channel {
+            DebugChannel::Normal | DebugChannel::Depth => {
+                let xs = self.intersect(ray);
+                let hit = match xs.hit() {
+                    Some(hit) => hit,
+                    None => return Color::black(),
+                };
+                if channel == DebugChannel::Depth {
+                    return heat(hit.t, DEPTH_HALF_HEAT);
+                }
+                let n = hit.prepare_computations(ray, &xs).normalv;
+                Color::new(n.x() + 1.0, n.y() + 1.0, n.z() + 1.0) * 0.5
+            }
+            DebugChannel::Traversal => {
+                let (_, traversal) = count_traversal(|| self.intersect(ray).len());
+                let work = traversal.intersection_tests + traversal.node_visits;
+                heat(work as Scalar, TRAVERSAL_HALF_HEAT)
+            }
+            DebugChannel::ShadowRays => {
+                let (_, stats) = RenderStats::measure(|| self.color_at(ray, remaining));
+                heat(stats.rays(RayKind::Shadow) as Scalar, SHADOW_HALF_HEAT)
+            }
+        }
+    }
+
+    /// Follows the Whitted recursion of `color_at`: one event per ray, then one per light.
+    pub fn trace_single_ray(&self, camera: &Camera, x: usize, y: usize) -> Vec<RayEvent> {
+        let mut events = Vec::new();
+        let ray = camera.ray_for_pixel(x, y);
+        let remaining = camera.settings().max_depth;
+        self.trace_events(&ray, RayKind::Primary, 0, remaining, &mut events);
+        events
+    }
+
+    fn trace_events(
+        &self,
+        ray: &Ray,
+        kind: RayKind,
+        depth: usize,
+        remaining: usize,
+        events: &mut Vec<RayEvent>,
+    ) {
+        let xs = self.intersect(ray);
+        let hit = match xs.hit() {
+            Some(hit) => hit,
+            None => {
+                events.push(RayEvent::Miss {
+                    kind,
+                    depth,
+                    ray: *ray,
+                });
+                return;
+            }
+        };
+        let comps = hit.prepare_computations(ray, &xs);
+        events.push(RayEvent::Hit {
+            kind,
+            depth,
+            ray: *ray,
+            t: hit.t,
+            shape: self
+                .shapes()
+                .iter()
+                .position(|shape| shape.includes(hit.object)),
+            point: comps.point,
+            normal: comps.normalv,
+            inside: comps.inside,
+        });
+        for (light, source) in self.light_sources().enumerate() {
+            events.push(RayEvent::Light {
+                light,
+                intensity: self.surface_intensity(source, &comps),
+            });
+        }
+        if remaining == 0 {
+            return;
+        }
+        if comps.material.reflective > 0.0 {
+            let reflect_ray = Ray::new(comps.over_point, comps.reflectv).with_time(comps.time);
+            self.trace_events(
+                &reflect_ray,
+                RayKind::Reflection,
+                depth + 1,
+                remaining - 1,
+                events,
+            );
+        }
+        if comps.material.transparency > 0.0 {
+            if let Some(refract_ray) = refraction_ray(&comps) {
+                self.trace_events(
+                    &refract_ray,
+                    RayKind::Refraction,
+                    depth + 1,
+                    remaining - 1,
+                    events,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{view_transform, PointLight, RenderMode, RenderSettings};
+    use rt_core::consts::PI;
+    use rt_shapes::{Material, Shape, Sphere};
+
+    fn looking_at_origin() -> Camera {
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        c.set_transform(view_transform(
+            Point::new(0.0, 0.0, -5.0),
+            Point::origin(),
+            Vector::new(0.0, 1.0, 0.0),
+        ));
+        c
+    }
+
+    fn forward() -> Ray {
+        Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0))
+    }
+
+    #[test]
+    fn channels_are_named() {
+        for channel in DebugChannel::ALL {
+            assert_eq!(DebugChannel::from_name(channel.name()), Some(channel));
+        }
+        assert_eq!(DebugChannel::from_name("albedo"), None);
+    }
+
+    #[test]
+    fn heat_runs_from_blue_to_red() {
+        assert_eq!(heat(0.0, 1.0), Color::black());
+        assert_eq!(heat(1e-9, 1.0), Color::new(0.0, 0.0, 1.0));
+        assert_eq!(heat(1.0, 1.0), Color::new(0.0, 1.0, 0.0));
+        assert_eq!(heat(1e9, 1.0), Color::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn debug_channels_at_a_hit() {
+        let w = World::default_world();
+        let r = forward();
+        assert_eq!(
+            w.debug_color(&r, DebugChannel::Normal, 5),
+            Color::new(0.5, 0.5, 0.0)
+        );
+        assert_eq!(
+            w.debug_color(&r, DebugChannel::Depth, 5),
+            heat(4.0, DEPTH_HALF_HEAT)
+        );
+        assert_eq!(
+            w.debug_color(&r, DebugChannel::ShadowRays, 5),
+            heat(1.0, SHADOW_HALF_HEAT)
+        );
+        assert_ne!(
+            w.debug_color(&r, DebugChannel::Traversal, 5),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn misses_are_black_except_for_traversal() {
+        let w = World::default_world();
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0));
+        for channel in [
+            DebugChannel::Normal,
+            DebugChannel::Depth,
+            DebugChannel::ShadowRays,
+        ] {
+            assert_eq!(w.debug_color(&r, channel, 5), Color::black());
+        }
+        assert_ne!(
+            w.debug_color(&r, DebugChannel::Traversal, 5),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn debug_mode_renders_false_color() {
+        let w = World::default_world();
+        let mut c = looking_at_origin();
+        c.set_settings(RenderSettings {
+            mode: RenderMode::Debug(DebugChannel::Normal),
+            packets: true,
+            ..RenderSettings::default()
+        });
+        let image = c.render(&w);
+        assert_eq!(image.pixel_at(5, 5), Color::new(0.5, 0.5, 0.0));
+        assert_eq!(image.pixel_at(0, 0), Color::black());
+    }
+
+    #[test]
+    fn tracing_a_ray_that_misses() {
+        let w = World::default_world();
+        let events = w.trace_single_ray(&looking_at_origin(), 0, 0);
+        assert!(matches!(
+            events[..],
+            [RayEvent::Miss {
+                kind: RayKind::Primary,
+                depth: 0,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn tracing_a_ray_lists_hits_and_lights() {
+        let w = World::default_world();
+        let events = w.trace_single_ray(&looking_at_origin(), 5, 5);
+        assert_eq!(
+            events,
+            vec![
+                RayEvent::Hit {
+                    kind: RayKind::Primary,
+                    depth: 0,
+                    ray: forward(),
+                    t: 4.0,
+                    shape: Some(0),
+                    point: Point::new(0.0, 0.0, -1.0),
+                    normal: Vector::new(0.0, 0.0, -1.0),
+                    inside: false,
+                },
+                RayEvent::Light {
+                    light: 0,
+                    intensity: 1.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn tracing_follows_refraction() {
+        let mut w = World::new();
+        w.add_light(Box::new(PointLight::new(
+            Point::new(-10.0, 10.0, -10.0),
+            Color::white(),
+        )));
+        let mut glass = Sphere::new();
+        glass.set_material(Material {
+            transparency: 1.0,
+            refractive_index: 1.5,
+            ..Material::default()
+        });
+        w.add_shape(Box::new(glass));
+        let events = w.trace_single_ray(&looking_at_origin(), 5, 5);
+        let kinds: Vec<_> = events
+            .iter()
+            .map(|event| match event {
+                RayEvent::Hit {
+                    kind,
+                    depth,
+                    inside,
+                    ..
+                } => Some((*kind, *depth, *inside)),
+                RayEvent::Miss { kind, depth, .. } => Some((*kind, *depth, false)),
+                RayEvent::Light { .. } => None,
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Some((RayKind::Primary, 0, false)),
+                None,
+                Some((RayKind::Refraction, 1, true)),
+                None,
+                Some((RayKind::Refraction, 2, false)),
+            ]
+        );
+    }
+}
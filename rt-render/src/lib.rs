@@ -0,0 +1,32 @@
+mod animation;
+mod aov;
+mod background;
+mod builder;
+mod camera;
+mod checkpoint;
+mod debug;
+mod light;
+mod lighting;
+mod path_tracer;
+mod progress;
+mod sampling;
+mod settings;
+mod stats;
+mod volume;
+mod world;
+
+pub use animation::{Animation, Easing, Keyable, Keyframe, Track};
+pub use aov::{Aov, RenderOutput};
+pub use background::Background;
+pub use builder::WorldBuilder;
+pub use camera::{view_transform, Camera, Tile};
+pub use checkpoint::Checkpoint;
+pub use debug::{DebugChannel, RayEvent};
+pub use light::{AreaLight, Light, PointLight, ShapeLight, SpotLight};
+pub use lighting::lighting;
+pub use progress::RenderProgress;
+pub use sampling::Sampling;
+pub use settings::{Integrator, RenderMode, RenderSettings};
+pub use stats::{RayKind, RenderStats};
+pub use volume::{Medium, Volume};
+pub use world::{PickResult, World, MAX_DEPTH};
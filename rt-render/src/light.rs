@@ -0,0 +1,457 @@
+use crate::sampling::{mix, unit_hash, SEED_SPREAD};
+use rt_core::{to_bits, Color, Point, Scalar, Vector, EPSILON};
+use rt_shapes::Shape;
+
+const EMITTER_SAMPLES: usize = 16;
+
+pub trait Light: std::any::Any + std::fmt::Debug + Send + Sync {
+    fn intensity(&self) -> Color;
+    fn position(&self) -> Point;
+    fn sample_points(&self, target: &Point) -> Vec<Point>;
+
+    fn attenuation(&self, _point: &Point) -> Scalar {
+        1.0
+    }
+
+    fn set_seed(&mut self, _seed: u64) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointLight {
+    pub position: Point,
+    pub intensity: Color,
+}
+
+impl PointLight {
+    pub fn new(position: Point, intensity: Color) -> Self {
+        PointLight {
+            position,
+            intensity,
+        }
+    }
+}
+
+impl Light for PointLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn position(&self) -> Point {
+        self.position
+    }
+
+    fn sample_points(&self, _target: &Point) -> Vec<Point> {
+        vec![self.position]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AreaLight {
+    pub corner: Point,
+    pub uvec: Vector,
+    pub usteps: usize,
+    pub vvec: Vector,
+    pub vsteps: usize,
+    pub intensity: Color,
+    pub jitter: bool,
+    pub seed: u64,
+}
+
+impl AreaLight {
+    pub fn new(
+        corner: Point,
+        full_uvec: Vector,
+        usteps: usize,
+        full_vvec: Vector,
+        vsteps: usize,
+        intensity: Color,
+    ) -> Self {
+        assert!(
+            usteps > 0 && vsteps > 0,
+            "area lights need at least one step in each direction"
+        );
+        AreaLight {
+            corner,
+            uvec: full_uvec / usteps as Scalar,
+            usteps,
+            vvec: full_vvec / vsteps as Scalar,
+            vsteps,
+            intensity,
+            jitter: false,
+            seed: 0,
+        }
+    }
+
+    pub fn samples(&self) -> usize {
+        self.usteps * self.vsteps
+    }
+
+    pub fn point_on_light(&self, u: usize, v: usize, target: &Point) -> Point {
+        let (du, dv) = if self.jitter {
+            let seed = cell_seed(u, v, target, self.seed);
+            (unit_hash(seed), unit_hash(seed ^ 0x5851_f42d_4c95_7f2d))
+        } else {
+            (0.5, 0.5)
+        };
+        self.corner + self.uvec * (u as Scalar + du) + self.vvec * (v as Scalar + dv)
+    }
+}
+
+impl Light for AreaLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn position(&self) -> Point {
+        self.corner
+            + self.uvec * (self.usteps as Scalar / 2.0)
+            + self.vvec * (self.vsteps as Scalar / 2.0)
+    }
+
+    fn sample_points(&self, target: &Point) -> Vec<Point> {
+        (0..self.vsteps)
+            .flat_map(|v| (0..self.usteps).map(move |u| (u, v)))
+            .map(|(u, v)| self.point_on_light(u, v, target))
+            .collect()
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpotLight {
+    pub position: Point,
+    pub direction: Vector,
+    pub inner_angle: Scalar,
+    pub outer_angle: Scalar,
+    pub intensity: Color,
+}
+
+impl SpotLight {
+    pub fn new(
+        position: Point,
+        direction: Vector,
+        inner_angle: Scalar,
+        outer_angle: Scalar,
+        intensity: Color,
+    ) -> Self {
+        assert!(
+            inner_angle <= outer_angle,
+            "spot light inner angle must not exceed the outer angle"
+        );
+        SpotLight {
+            position,
+            direction: direction.normalize(),
+            inner_angle,
+            outer_angle,
+            intensity,
+        }
+    }
+}
+
+impl Light for SpotLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn position(&self) -> Point {
+        self.position
+    }
+
+    fn sample_points(&self, _target: &Point) -> Vec<Point> {
+        vec![self.position]
+    }
+
+    fn attenuation(&self, point: &Point) -> Scalar {
+        let cos_angle = self.direction.dot(&(*point - self.position).normalize());
+        let cos_inner = self.inner_angle.cos();
+        let cos_outer = self.outer_angle.cos();
+        if cos_angle >= cos_inner {
+            1.0
+        } else if cos_angle <= cos_outer {
+            0.0
+        } else {
+            let t = (cos_angle - cos_outer) / (cos_inner - cos_outer);
+            t * t * (3.0 - 2.0 * t)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeLight {
+    pub samples: Vec<(Point, Vector)>,
+    pub intensity: Color,
+}
+
+impl ShapeLight {
+    pub fn new(samples: Vec<(Point, Vector)>, intensity: Color) -> Self {
+        assert!(!samples.is_empty(), "shape lights need at least one sample");
+        ShapeLight { samples, intensity }
+    }
+
+    pub fn from_shapes(shapes: &[&dyn Shape], intensity: Color, seed: u64) -> Option<Self> {
+        let mut samples = Vec::new();
+        for (k, shape) in shapes.iter().enumerate() {
+            let first = k * EMITTER_SAMPLES / shapes.len();
+            let count = (k + 1) * EMITTER_SAMPLES / shapes.len() - first;
+            for j in 0..count {
+                let seed = mix((first + j) as u64 ^ seed.wrapping_mul(SEED_SPREAD));
+                let u = (j as Scalar + unit_hash(seed)) / count as Scalar;
+                let v = unit_hash(seed ^ 0x5851_f42d_4c95_7f2d);
+                if let Some((point, normal)) = shape.sample_point(u, v) {
+                    samples.push((point + normal * (EPSILON * 10.0), normal));
+                }
+            }
+        }
+        if samples.is_empty() {
+            None
+        } else {
+            Some(ShapeLight::new(samples, intensity))
+        }
+    }
+}
+
+impl Light for ShapeLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn position(&self) -> Point {
+        let sum = self
+            .samples
+            .iter()
+            .fold(Vector::new(0.0, 0.0, 0.0), |sum, (point, _)| {
+                sum + (*point - Point::origin())
+            });
+        Point::origin() + sum / self.samples.len() as Scalar
+    }
+
+    fn sample_points(&self, target: &Point) -> Vec<Point> {
+        let facing: Vec<Point> = self
+            .samples
+            .iter()
+            .filter(|(point, normal)| normal.dot(&(*target - *point)) > 0.0)
+            .map(|(point, _)| *point)
+            .collect();
+        if facing.is_empty() {
+            self.samples.iter().map(|(point, _)| *point).collect()
+        } else {
+            facing
+        }
+    }
+}
+
+fn cell_seed(u: usize, v: usize, target: &Point, seed: u64) -> u64 {
+    let cell = ((u as u64) << 32 | v as u64) ^ seed.wrapping_mul(SEED_SPREAD);
+    [target.x(), target.y(), target.z()]
+        .iter()
+        .fold(cell, |seed, coordinate| mix(seed ^ to_bits(*coordinate)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_light_has_position_and_intensity() {
+        let light = PointLight::new(Point::origin(), Color::white());
+        assert_eq!(light.position, Point::origin());
+        assert_eq!(light.intensity, Color::white());
+    }
+
+    #[test]
+    fn point_light_is_a_single_sample() {
+        let light = PointLight::new(Point::new(1.0, 2.0, 3.0), Color::white());
+        assert_eq!(
+            light.sample_points(&Point::origin()),
+            vec![Point::new(1.0, 2.0, 3.0)]
+        );
+    }
+
+    mod shape {
+        use super::*;
+        use rt_core::Matrix;
+        use rt_shapes::Sphere;
+
+        #[test]
+        fn samples_sit_just_above_the_surface() {
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::translation(0.0, 3.0, 0.0));
+            let light = ShapeLight::from_shapes(&[&s], Color::white(), 0).unwrap();
+            assert_eq!(light.samples.len(), EMITTER_SAMPLES);
+            for (point, normal) in &light.samples {
+                let offset = *point - Point::new(0.0, 3.0, 0.0);
+                assert!(offset.magnitude() > 1.0);
+                assert_eq!(*normal, offset.normalize());
+            }
+        }
+
+        #[test]
+        fn only_samples_facing_the_target_are_used() {
+            let light = ShapeLight::new(
+                vec![
+                    (Point::new(0.0, 1.0, 0.0), Vector::new(0.0, 1.0, 0.0)),
+                    (Point::new(0.0, -1.0, 0.0), Vector::new(0.0, -1.0, 0.0)),
+                ],
+                Color::white(),
+            );
+            assert_eq!(light.position(), Point::origin());
+            assert_eq!(
+                light.sample_points(&Point::new(0.0, 5.0, 0.0)),
+                vec![Point::new(0.0, 1.0, 0.0)]
+            );
+            assert_eq!(light.sample_points(&Point::origin()).len(), 2);
+        }
+
+        #[test]
+        fn shapes_without_a_surface_sampler_give_no_light() {
+            let plane = rt_shapes::Plane::new();
+            assert_eq!(ShapeLight::from_shapes(&[&plane], Color::white(), 0), None);
+        }
+    }
+
+    mod area {
+        use super::*;
+
+        fn light() -> AreaLight {
+            AreaLight::new(
+                Point::origin(),
+                Vector::new(2.0, 0.0, 0.0),
+                4,
+                Vector::new(0.0, 0.0, 1.0),
+                2,
+                Color::white(),
+            )
+        }
+
+        #[test]
+        fn creating_an_area_light() {
+            let light = light();
+            assert_eq!(light.corner, Point::origin());
+            assert_eq!(light.uvec, Vector::new(0.5, 0.0, 0.0));
+            assert_eq!(light.usteps, 4);
+            assert_eq!(light.vvec, Vector::new(0.0, 0.0, 0.5));
+            assert_eq!(light.vsteps, 2);
+            assert_eq!(light.samples(), 8);
+            assert_eq!(light.position(), Point::new(1.0, 0.0, 0.5));
+        }
+
+        #[test]
+        fn finding_a_single_point_on_an_area_light() {
+            let light = light();
+            let cases = [
+                (0, 0, Point::new(0.25, 0.0, 0.25)),
+                (1, 0, Point::new(0.75, 0.0, 0.25)),
+                (0, 1, Point::new(0.25, 0.0, 0.75)),
+                (2, 0, Point::new(1.25, 0.0, 0.25)),
+                (3, 1, Point::new(1.75, 0.0, 0.75)),
+            ];
+            for (u, v, expected) in cases {
+                assert_eq!(light.point_on_light(u, v, &Point::origin()), expected);
+            }
+        }
+
+        #[test]
+        fn sampling_covers_every_cell() {
+            assert_eq!(light().sample_points(&Point::origin()).len(), 8);
+        }
+
+        #[test]
+        fn jittered_points_stay_inside_their_cell() {
+            let mut light = light();
+            light.jitter = true;
+            let target = Point::new(0.3, -2.0, 5.0);
+            for v in 0..light.vsteps {
+                for u in 0..light.usteps {
+                    let p = light.point_on_light(u, v, &target);
+                    assert!(p.x() >= u as Scalar * 0.5 && p.x() <= (u + 1) as Scalar * 0.5);
+                    assert!(p.z() >= v as Scalar * 0.5 && p.z() <= (v + 1) as Scalar * 0.5);
+                    assert_eq!(p, light.point_on_light(u, v, &target));
+                }
+            }
+        }
+
+        #[test]
+        fn jitter_varies_between_target_points() {
+            let mut light = light();
+            light.jitter = true;
+            let a = light.point_on_light(0, 0, &Point::new(1.0, 0.0, 0.0));
+            let b = light.point_on_light(0, 0, &Point::new(2.0, 0.0, 0.0));
+            assert_ne!(a, b);
+        }
+
+        #[test]
+        fn the_seed_picks_the_jitter() {
+            let seeded = |seed| {
+                let mut light = light();
+                light.jitter = true;
+                light.set_seed(seed);
+                light.sample_points(&Point::new(0.3, -2.0, 5.0))
+            };
+            assert_eq!(seeded(7), seeded(7));
+            assert_ne!(seeded(7), seeded(8));
+        }
+    }
+
+    mod spot {
+        use super::*;
+        use rt_core::consts::PI;
+
+        fn light() -> SpotLight {
+            SpotLight::new(
+                Point::new(0.0, 10.0, 0.0),
+                Vector::new(0.0, -2.0, 0.0),
+                PI / 8.0,
+                PI / 4.0,
+                Color::white(),
+            )
+        }
+
+        #[test]
+        fn creating_a_spot_light_normalizes_its_direction() {
+            let light = light();
+            assert_eq!(light.direction, Vector::new(0.0, -1.0, 0.0));
+            assert_eq!(light.position(), Point::new(0.0, 10.0, 0.0));
+            assert_eq!(light.sample_points(&Point::origin()), vec![light.position]);
+        }
+
+        #[test]
+        fn full_intensity_inside_the_inner_cone() {
+            let light = light();
+            assert_eq!(light.attenuation(&Point::origin()), 1.0);
+            assert_eq!(light.attenuation(&Point::new(3.0, 0.0, 0.0)), 1.0);
+        }
+
+        #[test]
+        fn no_intensity_outside_the_outer_cone() {
+            let light = light();
+            assert_eq!(light.attenuation(&Point::new(10.5, 0.0, 0.0)), 0.0);
+            assert_eq!(light.attenuation(&Point::new(0.0, 20.0, 0.0)), 0.0);
+        }
+
+        #[test]
+        fn smooth_falloff_between_the_cones() {
+            let light = light();
+            let angle = (PI / 8.0 + PI / 4.0) / 2.0;
+            let halfway = Point::new(10.0 * angle.tan(), 0.0, 0.0);
+            let a = light.attenuation(&halfway);
+            assert!(a > 0.0 && a < 1.0, "{}", a);
+            let nearer = light.attenuation(&Point::new(halfway.x() * 0.9, 0.0, 0.0));
+            assert!(nearer > a);
+        }
+
+        #[test]
+        #[should_panic(expected = "inner angle")]
+        fn inner_angle_must_be_within_the_outer_angle() {
+            SpotLight::new(
+                Point::origin(),
+                Vector::new(0.0, -1.0, 0.0),
+                PI / 4.0,
+                PI / 8.0,
+                Color::white(),
+            );
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use crate::Light;
+use rt_core::{Color, Point, Scalar, Vector};
+use rt_shapes::{Material, Shape};
+
+pub fn lighting(
+    material: &Material,
+    object: &dyn Shape,
+    light: &dyn Light,
+    point: &Point,
+    eyev: &Vector,
+    normalv: &Vector,
+    intensity: Scalar,
+) -> Color {
+    let effective_color = material.color_at(object, point) * light.intensity();
+    let ambient = effective_color * material.ambient;
+    if intensity <= 0.0 {
+        return ambient;
+    }
+
+    let samples = light.sample_points(point);
+    let lit = samples.iter().fold(Color::black(), |sum, sample| {
+        let lightv = (*sample - *point).normalize();
+        let light_dot_normal = lightv.dot(normalv);
+        if light_dot_normal < 0.0 {
+            return sum;
+        }
+
+        let diffuse = effective_color * material.diffuse * light_dot_normal;
+        let reflectv = (-lightv).reflect(normalv);
+        let reflect_dot_eye = reflectv.dot(eyev);
+        let specular = if reflect_dot_eye <= 0.0 {
+            Color::black()
+        } else {
+            light.intensity() * material.specular * reflect_dot_eye.powf(material.shininess)
+        };
+        sum + diffuse + specular
+    });
+
+    ambient + lit / samples.len() as Scalar * intensity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AreaLight, PointLight, World};
+    use rt_core::consts::FRAC_1_SQRT_2;
+    use rt_shapes::{Sphere, Stripe};
+    use std::sync::Arc;
+
+    fn setup() -> (Material, Point) {
+        (Material::default(), Point::origin())
+    }
+
+    #[test]
+    fn eye_between_light_and_surface() {
+        let (m, position) = setup();
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white());
+        assert_eq!(
+            lighting(&m, &Sphere::new(), &light, &position, &eyev, &normalv, 1.0),
+            Color::new(1.9, 1.9, 1.9)
+        );
+    }
+
+    #[test]
+    fn eye_offset_45_degrees() {
+        let (m, position) = setup();
+        let h = Scalar::sqrt(2.0) / 2.0;
+        let eyev = Vector::new(0.0, h, -h);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white());
+        assert_eq!(
+            lighting(&m, &Sphere::new(), &light, &position, &eyev, &normalv, 1.0),
+            Color::new(1.0, 1.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn light_offset_45_degrees() {
+        let (m, position) = setup();
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point::new(0.0, 10.0, -10.0), Color::white());
+        assert_eq!(
+            lighting(&m, &Sphere::new(), &light, &position, &eyev, &normalv, 1.0),
+            Color::new(0.7364, 0.7364, 0.7364)
+        );
+    }
+
+    #[test]
+    fn eye_in_the_path_of_the_reflection_vector() {
+        let (m, position) = setup();
+        let h = Scalar::sqrt(2.0) / 2.0;
+        let eyev = Vector::new(0.0, -h, -h);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point::new(0.0, 10.0, -10.0), Color::white());
+        assert_eq!(
+            lighting(&m, &Sphere::new(), &light, &position, &eyev, &normalv, 1.0),
+            Color::new(1.6364, 1.6364, 1.6364)
+        );
+    }
+
+    #[test]
+    fn light_behind_the_surface() {
+        let (m, position) = setup();
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point::new(0.0, 0.0, 10.0), Color::white());
+        assert_eq!(
+            lighting(&m, &Sphere::new(), &light, &position, &eyev, &normalv, 1.0),
+            Color::new(0.1, 0.1, 0.1)
+        );
+    }
+
+    #[test]
+    fn surface_in_shadow() {
+        let (m, position) = setup();
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white());
+        assert_eq!(
+            lighting(&m, &Sphere::new(), &light, &position, &eyev, &normalv, 0.0),
+            Color::new(0.1, 0.1, 0.1)
+        );
+    }
+
+    #[test]
+    fn lighting_with_a_pattern_applied() {
+        let m = Material {
+            pattern: Some(Arc::new(Stripe::new(Color::white(), Color::black()))),
+            ambient: 1.0,
+            diffuse: 0.0,
+            specular: 0.0,
+            ..Material::default()
+        };
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white());
+        let object = Sphere::new();
+        let c1 = lighting(
+            &m,
+            &object,
+            &light,
+            &Point::new(0.9, 0.0, 0.0),
+            &eyev,
+            &normalv,
+            1.0,
+        );
+        let c2 = lighting(
+            &m,
+            &object,
+            &light,
+            &Point::new(1.1, 0.0, 0.0),
+            &eyev,
+            &normalv,
+            1.0,
+        );
+        assert_eq!(c1, Color::white());
+        assert_eq!(c2, Color::black());
+    }
+
+    #[test]
+    fn light_intensity_attenuates_the_color() {
+        let mut w = World::default_world();
+        w.lights_mut()[0] = Box::new(PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white()));
+        let shape = w.shapes()[0].as_ref();
+        let mut m = shape.material().clone();
+        m.ambient = 0.1;
+        m.diffuse = 0.9;
+        m.specular = 0.0;
+        m.color = Color::white();
+        let point = Point::new(0.0, 0.0, -1.0);
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        for (intensity, expected) in [(1.0, 1.0), (0.5, 0.55), (0.0, 0.1)] {
+            assert_eq!(
+                lighting(
+                    &m,
+                    shape,
+                    w.lights()[0].as_ref(),
+                    &point,
+                    &eyev,
+                    &normalv,
+                    intensity
+                ),
+                Color::new(expected, expected, expected)
+            );
+        }
+    }
+
+    #[test]
+    fn lighting_samples_the_area_light() {
+        let corner = Point::new(-0.5, -0.5, -5.0);
+        let light = AreaLight::new(
+            corner,
+            Vector::new(1.0, 0.0, 0.0),
+            2,
+            Vector::new(0.0, 1.0, 0.0),
+            2,
+            Color::white(),
+        );
+        let mut shape = Sphere::new();
+        shape.set_material(Material {
+            ambient: 0.1,
+            diffuse: 0.9,
+            specular: 0.0,
+            color: Color::white(),
+            ..Material::default()
+        });
+        let eye = Point::new(0.0, 0.0, -5.0);
+        let cases = [
+            (Point::new(0.0, 0.0, -1.0), 0.9965),
+            (Point::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2), 0.62318),
+        ];
+        for (point, expected) in cases {
+            let eyev = (eye - point).normalize();
+            let normalv = point - Point::origin();
+            let result = lighting(
+                shape.material(),
+                &shape,
+                &light,
+                &point,
+                &eyev,
+                &normalv,
+                1.0,
+            );
+            assert!(
+                (result.red() - expected).abs() < 0.0001,
+                "{:?} {:?}",
+                point,
+                result
+            );
+        }
+    }
+}
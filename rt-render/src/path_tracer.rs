@@ -0,0 +1,305 @@
+use crate::sampling::Rng;
+use crate::stats;
+use crate::{lighting, RayKind, World};
+use rt_core::{Color, Ray, Scalar, Vector};
+use rt_shapes::{Computations, Material};
+
+const ROULETTE_START: usize = 3;
+
+impl World {
+    pub(crate) fn path_trace(&self, ray: &Ray, max_bounces: usize, rng: &mut Rng) -> Color {
+        let mut ray = *ray;
+        let mut radiance = Color::black();
+        let mut throughput = Color::white();
+
+        for bounce in 0..=max_bounces {
+            if bounce > 0 {
+                stats::count_rays(RayKind::Bounce, 1);
+            }
+            let xs = self.intersect(&ray);
+            let comps = match xs.hit() {
+                Some(hit) => hit.prepare_computations(&ray, &xs),
+                None => {
+                    radiance = radiance + throughput * self.background().color_for(&ray.direction);
+                    break;
+                }
+            };
+            let material = comps.material;
+            radiance = radiance + throughput * (material.emissive + self.direct_light(&comps));
+            if bounce == max_bounces {
+                break;
+            }
+
+            let (next, weight) = match scatter(&comps, rng) {
+                Some(scattered) => scattered,
+                None => break,
+            };
+            ray = next.with_time(ray.time);
+            throughput = throughput * weight;
+
+            if bounce >= ROULETTE_START {
+                let survival = max_channel(throughput).clamp(0.05, 0.95);
+                if rng.next_f64() >= survival {
+                    break;
+                }
+                throughput = throughput / survival;
+            }
+        }
+        radiance
+    }
+
+    fn direct_light(&self, comps: &Computations) -> Color {
+        let material = Material {
+            ambient: 0.0,
+            ..comps.material.clone()
+        };
+        self.lights().iter().fold(Color::black(), |sum, light| {
+            let intensity = self.surface_intensity(light.as_ref(), comps);
+            if intensity <= 0.0 {
+                return sum;
+            }
+            sum + lighting(
+                &material,
+                comps.object,
+                light.as_ref(),
+                &comps.over_point,
+                &comps.eyev,
+                &comps.normalv,
+                intensity,
+            )
+        })
+    }
+}
+
+fn scatter(comps: &Computations, rng: &mut Rng) -> Option<(Ray, Color)> {
+    let material = comps.material;
+    let albedo = material.color_at(comps.object, &comps.over_point) * material.diffuse;
+    let (reflect, refract) = if material.reflective > 0.0 && material.transparency > 0.0 {
+        let reflectance = comps.schlick();
+        (
+            material.reflective * reflectance,
+            material.transparency * (1.0 - reflectance),
+        )
+    } else {
+        (material.reflective, material.transparency)
+    };
+    let diffuse = max_channel(albedo);
+    let total = reflect + refract + diffuse;
+    if total <= 0.0 {
+        return None;
+    }
+
+    let choice = rng.next_f64() * total;
+    if choice < reflect {
+        let ray = Ray::new(comps.over_point, comps.reflectv);
+        Some((ray, Color::white() * (total / reflect)))
+    } else if choice < reflect + refract {
+        let direction = refraction(comps).unwrap_or(comps.reflectv);
+        let origin = if direction.dot(&comps.normalv) < 0.0 {
+            comps.under_point
+        } else {
+            comps.over_point
+        };
+        Some((
+            Ray::new(origin, direction),
+            Color::white() * (total / refract),
+        ))
+    } else {
+        let direction = cosine_hemisphere(&comps.normalv, rng.next_f64(), rng.next_f64());
+        let ray = Ray::new(comps.over_point, direction);
+        Some((ray, albedo * (total / diffuse)))
+    }
+}
+
+pub(crate) fn refraction(comps: &Computations) -> Option<Vector> {
+    let n_ratio = comps.n1 / comps.n2;
+    let cos_i = comps.eyev.dot(&comps.normalv);
+    let sin2_t = n_ratio * n_ratio * (1.0 - cos_i * cos_i);
+    if sin2_t > 1.0 {
+        return None;
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some(comps.normalv * (n_ratio * cos_i - cos_t) - comps.eyev * n_ratio)
+}
+
+pub(crate) fn cosine_hemisphere(normal: &Vector, u: Scalar, v: Scalar) -> Vector {
+    let axis = if normal.x().abs() > 0.9 {
+        Vector::new(0.0, 1.0, 0.0)
+    } else {
+        Vector::new(1.0, 0.0, 0.0)
+    };
+    let tangent = normal.cross(&axis).normalize();
+    let bitangent = normal.cross(&tangent);
+    let radius = u.sqrt();
+    let theta = 2.0 * rt_core::consts::PI * v;
+    (tangent * (radius * theta.cos())
+        + bitangent * (radius * theta.sin())
+        + *normal * (1.0 - u).max(0.0).sqrt())
+    .normalize()
+}
+
+fn max_channel(color: Color) -> Scalar {
+    color.red().max(color.green()).max(color.blue())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Background, PointLight};
+    use rt_core::{Matrix, Point};
+    use rt_shapes::{Plane, Shape, Sphere};
+
+    fn trace(world: &World, ray: &Ray, max_bounces: usize, seed: u64) -> Color {
+        world.path_trace(ray, max_bounces, &mut Rng::new(seed))
+    }
+
+    fn down_at(x: Scalar, z: Scalar) -> Ray {
+        Ray::new(Point::new(x, 1.0, z), Vector::new(0.0, -1.0, 0.0))
+    }
+
+    fn lit_floor(color: Color) -> World {
+        let mut floor = Plane::new();
+        floor.set_material(Material {
+            color,
+            specular: 0.0,
+            ..Material::default()
+        });
+        let mut w = World::new();
+        w.add_shape(Box::new(floor));
+        w.add_light(Box::new(PointLight::new(
+            Point::new(0.0, 10.0, 0.0),
+            Color::white(),
+        )));
+        w
+    }
+
+    #[test]
+    fn rays_that_miss_are_black() {
+        let w = World::default_world();
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0));
+        assert_eq!(trace(&w, &r, 4, 0), Color::black());
+    }
+
+    #[test]
+    fn escaping_paths_pick_up_the_background() {
+        let mut w = World::new();
+        let mut mirror = Plane::new();
+        mirror.set_material(Material {
+            diffuse: 0.0,
+            specular: 0.0,
+            reflective: 1.0,
+            ..Material::default()
+        });
+        w.add_shape(Box::new(mirror));
+        w.set_background(Background::Solid(Color::new(0.3, 0.6, 0.9)));
+        assert_eq!(
+            trace(&w, &down_at(0.0, 0.0), 2, 0),
+            Color::new(0.3, 0.6, 0.9)
+        );
+    }
+
+    #[test]
+    fn emissive_surfaces_glow_without_lights() {
+        let mut s = Sphere::new();
+        s.set_material(Material {
+            emissive: Color::new(1.0, 0.5, 0.25),
+            ..Material::default()
+        });
+        let mut w = World::new();
+        w.add_shape(Box::new(s));
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(trace(&w, &r, 4, 0), Color::new(1.0, 0.5, 0.25));
+    }
+
+    #[test]
+    fn direct_light_has_no_ambient_term() {
+        let w = lit_floor(Color::new(0.5, 0.5, 0.5));
+        assert_eq!(
+            trace(&w, &down_at(0.0, 0.0), 4, 0),
+            Color::new(0.45, 0.45, 0.45)
+        );
+    }
+
+    #[test]
+    fn indirect_light_bleeds_color_between_surfaces() {
+        let mut w = lit_floor(Color::white());
+        let mut wall = Plane::new();
+        wall.set_transform(Matrix::rotation_z(rt_core::consts::FRAC_PI_2).translate(1.0, 0.0, 0.0));
+        wall.set_material(Material {
+            color: Color::new(1.0, 0.0, 0.0),
+            specular: 0.0,
+            ..Material::default()
+        });
+        w.add_shape(Box::new(wall));
+
+        let direct = trace(&w, &down_at(0.5, 0.0), 0, 0);
+        let samples = 256;
+        let average = (0..samples).fold(Color::black(), |sum, seed| {
+            sum + trace(&w, &down_at(0.5, 0.0), 4, seed)
+        }) / samples as Scalar;
+        let bounced = average - direct;
+        assert!(bounced.red() > 0.05, "{:?}", bounced);
+        assert!(bounced.red() > bounced.blue() * 2.0, "{:?}", bounced);
+    }
+
+    #[test]
+    fn paths_are_reproducible_for_a_seed() {
+        let w = World::default_world();
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(trace(&w, &r, 8, 42), trace(&w, &r, 8, 42));
+    }
+
+    #[test]
+    fn perfect_mirrors_reflect_without_loss() {
+        let mut w = World::new();
+        let mut mirror = Plane::new();
+        mirror.set_material(Material {
+            diffuse: 0.0,
+            specular: 0.0,
+            ambient: 0.0,
+            reflective: 1.0,
+            ..Material::default()
+        });
+        w.add_shape(Box::new(mirror));
+        let mut glow = Sphere::new();
+        glow.set_transform(Matrix::translation(0.0, 3.0, 0.0));
+        glow.set_material(Material {
+            emissive: Color::new(0.2, 0.4, 0.6),
+            ..Material::default()
+        });
+        w.add_shape(Box::new(glow));
+        for seed in 0..8 {
+            assert_eq!(
+                trace(&w, &down_at(0.0, 0.0), 1, seed),
+                Color::new(0.2, 0.4, 0.6)
+            );
+        }
+    }
+
+    mod hemisphere {
+        use super::*;
+
+        #[test]
+        fn samples_lie_above_the_surface() {
+            let normal = Vector::new(0.3, -0.5, 0.8).normalize();
+            let mut rng = Rng::new(9);
+            for _ in 0..200 {
+                let d = cosine_hemisphere(&normal, rng.next_f64(), rng.next_f64());
+                assert!(d.dot(&normal) >= 0.0);
+                assert!((d.magnitude() - 1.0).abs() < rt_core::EPSILON);
+            }
+        }
+
+        #[test]
+        fn samples_are_cosine_weighted() {
+            let normal = Vector::new(0.0, 1.0, 0.0);
+            let mut rng = Rng::new(1);
+            let n = 4000;
+            let mean_cos = (0..n)
+                .map(|_| cosine_hemisphere(&normal, rng.next_f64(), rng.next_f64()).y())
+                .sum::<Scalar>()
+                / n as Scalar;
+            assert!((mean_cos - 2.0 / 3.0).abs() < 0.02, "{}", mean_cos);
+        }
+    }
+}
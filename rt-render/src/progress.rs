@@ -0,0 +1,68 @@
+use crate::Tile;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderProgress {
+    pub tile: Tile,
+    pub completed_tiles: usize,
+    pub total_tiles: usize,
+    pub elapsed: Duration,
+}
+
+impl RenderProgress {
+    pub fn fraction(&self) -> f64 {
+        if self.total_tiles == 0 {
+            1.0
+        } else {
+            self.completed_tiles as f64 / self.total_tiles as f64
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed_tiles >= self.total_tiles
+    }
+
+    pub fn eta(&self) -> Option<Duration> {
+        if self.completed_tiles == 0 {
+            return None;
+        }
+        let remaining = self.total_tiles.saturating_sub(self.completed_tiles);
+        Some(
+            self.elapsed
+                .mul_f64(remaining as f64 / self.completed_tiles as f64),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(completed_tiles: usize, total_tiles: usize, seconds: u64) -> RenderProgress {
+        RenderProgress {
+            tile: Tile {
+                x: 0,
+                y: 0,
+                width: 16,
+                height: 16,
+            },
+            completed_tiles,
+            total_tiles,
+            elapsed: Duration::from_secs(seconds),
+        }
+    }
+
+    #[test]
+    fn fraction_of_tiles_completed() {
+        assert_eq!(progress(1, 4, 1).fraction(), 0.25);
+        assert!(!progress(1, 4, 1).is_complete());
+        assert!(progress(4, 4, 1).is_complete());
+    }
+
+    #[test]
+    fn eta_extrapolates_the_tile_rate() {
+        assert_eq!(progress(1, 4, 2).eta(), Some(Duration::from_secs(6)));
+        assert_eq!(progress(4, 4, 8).eta(), Some(Duration::ZERO));
+        assert_eq!(progress(0, 4, 8).eta(), None);
+    }
+}
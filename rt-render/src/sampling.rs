@@ -0,0 +1,129 @@
+use rt_core::Scalar;
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Sampling {
+    #[default]
+    Single,
+    Grid(usize),
+    Jittered(usize),
+    Adaptive {
+        max_depth: usize,
+        threshold: Scalar,
+    },
+}
+
+impl Sampling {
+    pub fn offsets(&self, seed: u64) -> Vec<(Scalar, Scalar)> {
+        match *self {
+            Sampling::Single | Sampling::Adaptive { .. } => vec![(0.5, 0.5)],
+            Sampling::Grid(n) => strata(n)
+                .map(|(i, j)| {
+                    (
+                        (i as Scalar + 0.5) / n as Scalar,
+                        (j as Scalar + 0.5) / n as Scalar,
+                    )
+                })
+                .collect(),
+            Sampling::Jittered(n) => strata(n)
+                .map(|(i, j)| {
+                    let cell = mix(seed ^ ((i as u64) << 32 | j as u64));
+                    let du = unit_hash(cell);
+                    let dv = unit_hash(cell ^ 0x5851_f42d_4c95_7f2d);
+                    (
+                        (i as Scalar + du) / n as Scalar,
+                        (j as Scalar + dv) / n as Scalar,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+fn strata(n: usize) -> impl Iterator<Item = (usize, usize)> {
+    assert!(n > 0, "supersampling needs at least one sample per axis");
+    (0..n).flat_map(move |j| (0..n).map(move |i| (i, j)))
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng { state: mix(seed) }
+    }
+
+    pub(crate) fn next_f64(&mut self) -> Scalar {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        unit_hash(self.state)
+    }
+}
+
+pub(crate) const SEED_SPREAD: u64 = 0x9e37_79b9_7f4a_7c15;
+
+pub(crate) fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+pub(crate) fn unit_hash(seed: u64) -> Scalar {
+    (mix(seed) >> 11) as Scalar / (1u64 << 53) as Scalar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_sampling_uses_the_pixel_center() {
+        assert_eq!(Sampling::default().offsets(0), vec![(0.5, 0.5)]);
+    }
+
+    #[test]
+    fn grid_sampling_uses_subpixel_centers() {
+        assert_eq!(
+            Sampling::Grid(2).offsets(0),
+            vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+        );
+        assert_eq!(Sampling::Grid(1).offsets(0), Sampling::Single.offsets(0));
+    }
+
+    #[test]
+    fn jittered_samples_stay_in_their_stratum() {
+        let offsets = Sampling::Jittered(3).offsets(17);
+        assert_eq!(offsets.len(), 9);
+        for (k, (u, v)) in offsets.into_iter().enumerate() {
+            let (i, j) = ((k % 3) as Scalar, (k / 3) as Scalar);
+            assert!(u >= i / 3.0 && u < (i + 1.0) / 3.0);
+            assert!(v >= j / 3.0 && v < (j + 1.0) / 3.0);
+        }
+    }
+
+    #[test]
+    fn jitter_is_deterministic_per_seed() {
+        let a = Sampling::Jittered(2).offsets(5);
+        assert_eq!(a, Sampling::Jittered(2).offsets(5));
+        assert_ne!(a, Sampling::Jittered(2).offsets(6));
+    }
+
+    #[test]
+    fn rng_streams_are_reproducible_per_seed() {
+        let draw = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..8).map(|_| rng.next_f64()).collect::<Vec<_>>()
+        };
+        assert_eq!(draw(3), draw(3));
+        assert_ne!(draw(3), draw(4));
+        assert!(draw(3).iter().all(|value| (0.0..1.0).contains(value)));
+    }
+
+    #[test]
+    fn unit_hash_is_in_the_unit_interval() {
+        for seed in 0..1000 {
+            let value = unit_hash(seed);
+            assert!((0.0..1.0).contains(&value));
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{DebugChannel, MAX_DEPTH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Integrator {
+    #[default]
+    Whitted,
+    PathTracer {
+        samples_per_pixel: usize,
+        max_bounces: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderMode {
+    #[default]
+    Shaded,
+    Debug(DebugChannel),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderSettings {
+    pub integrator: Integrator,
+    pub mode: RenderMode,
+    pub max_depth: usize,
+    pub packets: bool,
+    pub seed: u64,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            integrator: Integrator::default(),
+            mode: RenderMode::default(),
+            max_depth: MAX_DEPTH,
+            packets: false,
+            seed: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_settings_use_whitted_shading() {
+        let settings = RenderSettings::default();
+        assert_eq!(settings.integrator, Integrator::Whitted);
+        assert_eq!(settings.mode, RenderMode::Shaded);
+        assert_eq!(settings.max_depth, MAX_DEPTH);
+        assert!(!settings.packets);
+        assert_eq!(settings.seed, 0);
+    }
+}
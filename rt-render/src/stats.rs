@@ -0,0 +1,196 @@
+use crate::Tile;
+use rt_shapes::count_traversal;
+use std::cell::Cell;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RayKind {
+    Primary,
+    Reflection,
+    Refraction,
+    Shadow,
+    Bounce,
+}
+
+impl RayKind {
+    pub const ALL: [RayKind; 5] = [
+        RayKind::Primary,
+        RayKind::Reflection,
+        RayKind::Refraction,
+        RayKind::Shadow,
+        RayKind::Bounce,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RayKind::Primary => "primary",
+            RayKind::Reflection => "reflection",
+            RayKind::Refraction => "refraction",
+            RayKind::Shadow => "shadow",
+            RayKind::Bounce => "bounce",
+        }
+    }
+}
+
+type RayCounts = [u64; RayKind::ALL.len()];
+
+thread_local! {
+    static COUNTING: Cell<Option<RayCounts>> = const { Cell::new(None) };
+}
+
+pub(crate) fn count_rays(kind: RayKind, rays: usize) {
+    COUNTING.with(|counting| {
+        if let Some(mut counts) = counting.get() {
+            counts[kind as usize] += rays as u64;
+            counting.set(Some(counts));
+        }
+    });
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderStats {
+    rays: RayCounts,
+    pub intersection_tests: u64,
+    pub node_visits: u64,
+    pub tiles: Vec<(Tile, Duration)>,
+}
+
+impl RenderStats {
+    pub fn rays(&self, kind: RayKind) -> u64 {
+        self.rays[kind as usize]
+    }
+
+    pub fn total_rays(&self) -> u64 {
+        self.rays.iter().sum()
+    }
+
+    pub fn render_time(&self) -> Duration {
+        self.tiles.iter().map(|(_, elapsed)| *elapsed).sum()
+    }
+
+    pub fn slowest_tile(&self) -> Option<(Tile, Duration)> {
+        self.tiles
+            .iter()
+            .copied()
+            .max_by_key(|(_, elapsed)| *elapsed)
+    }
+
+    pub(crate) fn measure<R>(f: impl FnOnce() -> R) -> (R, RenderStats) {
+        let outer = COUNTING.with(|counting| counting.replace(Some(RayCounts::default())));
+        let (result, traversal) = count_traversal(f);
+        let rays = COUNTING
+            .with(|counting| counting.replace(outer))
+            .unwrap_or_default();
+        if let Some(mut outer) = outer {
+            for (total, count) in outer.iter_mut().zip(rays) {
+                *total += count;
+            }
+            COUNTING.with(|counting| counting.set(Some(outer)));
+        }
+        let stats = RenderStats {
+            rays,
+            intersection_tests: traversal.intersection_tests,
+            node_visits: traversal.node_visits,
+            tiles: Vec::new(),
+        };
+        (result, stats)
+    }
+
+    pub(crate) fn merge(&mut self, other: RenderStats) {
+        for (total, count) in self.rays.iter_mut().zip(other.rays) {
+            *total += count;
+        }
+        self.intersection_tests += other.intersection_tests;
+        self.node_visits += other.node_visits;
+        self.tiles.extend(other.tiles);
+    }
+}
+
+impl fmt::Display for RenderStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let row = |f: &mut fmt::Formatter<'_>, label: &str, value: String| {
+            writeln!(f, "  {:<20}{:>16}", label, value)
+        };
+        writeln!(f, "rays")?;
+        for kind in RayKind::ALL {
+            row(f, kind.name(), self.rays(kind).to_string())?;
+        }
+        row(f, "total", self.total_rays().to_string())?;
+        writeln!(f, "traversal")?;
+        row(f, "intersection tests", self.intersection_tests.to_string())?;
+        row(f, "bvh node visits", self.node_visits.to_string())?;
+        if self.total_rays() > 0 {
+            let per_ray = self.intersection_tests as f64 / self.total_rays() as f64;
+            row(f, "tests per ray", format!("{:.2}", per_ray))?;
+        }
+        writeln!(f, "tiles")?;
+        row(f, "count", self.tiles.len().to_string())?;
+        if let Some((tile, slowest)) = self.slowest_tile() {
+            let mean = self.render_time() / self.tiles.len() as u32;
+            row(f, "mean time", format!("{:.2?}", mean))?;
+            row(f, "slowest time", format!("{:.2?}", slowest))?;
+            row(f, "slowest tile", format!("{},{}", tile.x, tile.y))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(x: usize) -> Tile {
+        Tile {
+            x,
+            y: 0,
+            width: 16,
+            height: 16,
+        }
+    }
+
+    #[test]
+    fn rays_are_only_counted_while_measuring() {
+        count_rays(RayKind::Primary, 3);
+        let ((), stats) = RenderStats::measure(|| {
+            count_rays(RayKind::Primary, 2);
+            count_rays(RayKind::Shadow, 5);
+        });
+        assert_eq!(stats.rays(RayKind::Primary), 2);
+        assert_eq!(stats.rays(RayKind::Shadow), 5);
+        assert_eq!(stats.total_rays(), 7);
+    }
+
+    #[test]
+    fn merging_adds_counts_and_collects_tiles() {
+        let mut total = RenderStats::default();
+        for x in [0, 16] {
+            let ((), mut stats) = RenderStats::measure(|| count_rays(RayKind::Bounce, 4));
+            stats
+                .tiles
+                .push((tile(x), Duration::from_millis(x as u64 + 1)));
+            total.merge(stats);
+        }
+        assert_eq!(total.rays(RayKind::Bounce), 8);
+        assert_eq!(total.render_time(), Duration::from_millis(18));
+        assert_eq!(
+            total.slowest_tile(),
+            Some((tile(16), Duration::from_millis(17)))
+        );
+    }
+
+    #[test]
+    fn summary_lists_every_counter() {
+        let ((), mut stats) = RenderStats::measure(|| count_rays(RayKind::Reflection, 12));
+        stats.tiles.push((tile(0), Duration::from_millis(4)));
+        let summary = stats.to_string();
+        for kind in RayKind::ALL {
+            assert!(summary.contains(kind.name()));
+        }
+        assert!(summary.contains("bvh node visits"));
+        assert!(summary
+            .lines()
+            .any(|line| line.trim_start().starts_with("reflection") && line.ends_with("12")));
+        assert!(summary.contains("slowest tile"));
+    }
+}
@@ -0,0 +1,225 @@
+use crate::World;
+use rt_core::{Color, Point, Ray, Scalar};
+use rt_shapes::Shape;
+
+const DEFAULT_STEPS: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Medium {
+    pub density: Scalar,
+    pub albedo: Color,
+    pub steps: usize,
+}
+
+impl Medium {
+    pub fn new(density: Scalar, albedo: Color) -> Self {
+        assert!(density >= 0.0, "medium density must not be negative");
+        Medium {
+            density,
+            albedo,
+            steps: DEFAULT_STEPS,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Volume {
+    Fog {
+        medium: Medium,
+        distance: Scalar,
+    },
+    Bounded {
+        medium: Medium,
+        boundary: Box<dyn Shape>,
+    },
+}
+
+impl Volume {
+    pub fn fog(medium: Medium, distance: Scalar) -> Self {
+        Volume::Fog { medium, distance }
+    }
+
+    pub fn bounded(medium: Medium, boundary: Box<dyn Shape>) -> Self {
+        Volume::Bounded { medium, boundary }
+    }
+
+    pub fn medium(&self) -> &Medium {
+        match self {
+            Volume::Fog { medium, .. } | Volume::Bounded { medium, .. } => medium,
+        }
+    }
+
+    fn intervals(&self, ray: &Ray, end: Scalar) -> Vec<(Scalar, Scalar)> {
+        let spans = match self {
+            Volume::Fog { distance, .. } => vec![(0.0, distance / ray.direction.magnitude())],
+            Volume::Bounded { boundary, .. } => {
+                let ts: Vec<Scalar> = boundary.intersect(ray).iter().map(|i| i.t).collect();
+                ts.chunks_exact(2).map(|pair| (pair[0], pair[1])).collect()
+            }
+        };
+        spans
+            .into_iter()
+            .map(|(t0, t1)| (t0.max(0.0), t1.min(end)))
+            .filter(|(t0, t1)| t1 > t0)
+            .collect()
+    }
+}
+
+impl World {
+    pub(crate) fn through_volumes(&self, ray: &Ray, distance: Scalar, color: Color) -> Color {
+        let spans: Vec<Vec<(Scalar, Scalar)>> = self
+            .volumes()
+            .iter()
+            .map(|volume| volume.intervals(ray, distance))
+            .collect();
+        let end = spans
+            .iter()
+            .flatten()
+            .fold(0.0, |end: Scalar, &(_, t1)| end.max(t1));
+        let steps = self
+            .volumes()
+            .iter()
+            .map(|volume| volume.medium().steps)
+            .max()
+            .unwrap_or(0);
+        if end <= 0.0 || steps == 0 {
+            return color;
+        }
+
+        let scale = ray.direction.magnitude();
+        let dt = end / steps as Scalar;
+        let mut transmittance = 1.0;
+        let mut scattered = Color::black();
+        for step in 0..steps {
+            let t = (step as Scalar + 0.5) * dt;
+            let (extinction, scattering) = self.volumes().iter().zip(&spans).fold(
+                (0.0, Color::black()),
+                |(extinction, scattering), (volume, spans)| {
+                    if spans.iter().any(|&(t0, t1)| t >= t0 && t < t1) {
+                        let medium = volume.medium();
+                        (
+                            extinction + medium.density,
+                            scattering + medium.albedo * medium.density,
+                        )
+                    } else {
+                        (extinction, scattering)
+                    }
+                },
+            );
+            if extinction <= 0.0 {
+                continue;
+            }
+            let step_transmittance = (-extinction * dt * scale).exp();
+            let incoming = self.incoming_light(&ray.position(t), ray.time);
+            scattered = scattered
+                + scattering * incoming * (transmittance * (1.0 - step_transmittance) / extinction);
+            transmittance *= step_transmittance;
+        }
+        scattered + color * transmittance
+    }
+
+    pub(crate) fn transmittance(&self, from: &Point, to: &Point) -> Scalar {
+        let ray = Ray::new(*from, *to - *from);
+        let optical_depth = self.volumes().iter().fold(0.0, |depth, volume| {
+            let length: Scalar = volume
+                .intervals(&ray, 1.0)
+                .iter()
+                .map(|(t0, t1)| t1 - t0)
+                .sum();
+            depth + volume.medium().density * length * ray.direction.magnitude()
+        });
+        (-optical_depth).exp()
+    }
+
+    fn incoming_light(&self, point: &Point, time: Scalar) -> Color {
+        self.light_sources().fold(Color::black(), |sum, light| {
+            sum + light.intensity() * self.intensity_at_time(light, point, time)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Background, PointLight, MAX_DEPTH};
+    use rt_core::{Matrix, Vector};
+    use rt_shapes::{Material, Sphere};
+
+    fn glowing_sphere() -> Sphere {
+        let mut s = Sphere::new();
+        s.set_material(Material {
+            emissive: Color::white(),
+            ambient: 0.0,
+            diffuse: 0.0,
+            specular: 0.0,
+            ..Material::default()
+        });
+        s
+    }
+
+    fn forward() -> Ray {
+        Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0))
+    }
+
+    #[test]
+    fn absorbing_fog_attenuates_surfaces_by_distance() {
+        let mut w = World::new();
+        w.add_shape(Box::new(glowing_sphere()));
+        w.add_volume(Volume::fog(Medium::new(0.1, Color::black()), 100.0));
+        let expected = Color::white() * Scalar::exp(-0.4);
+        assert_eq!(w.color_at(&forward(), MAX_DEPTH), expected);
+    }
+
+    #[test]
+    fn escaping_rays_fade_into_fog_at_its_distance() {
+        let mut w = World::new();
+        w.set_background(Background::Solid(Color::white()));
+        w.add_volume(Volume::fog(Medium::new(0.05, Color::white()), 20.0));
+        let expected = Color::white() * Scalar::exp(-1.0);
+        assert_eq!(w.color_at(&forward(), MAX_DEPTH), expected);
+    }
+
+    #[test]
+    fn lit_fog_scatters_light_toward_the_eye() {
+        let mut w = World::new();
+        w.add_light(Box::new(PointLight::new(
+            Point::new(0.0, 5.0, 0.0),
+            Color::white(),
+        )));
+        w.add_volume(Volume::fog(
+            Medium::new(0.1, Color::new(1.0, 0.5, 0.0)),
+            10.0,
+        ));
+        let haze = w.color_at(&forward(), MAX_DEPTH);
+        assert!(haze.red() > 0.1, "{:?}", haze);
+        assert_eq!(haze.green(), haze.red() * 0.5);
+        assert_eq!(haze.blue(), 0.0);
+    }
+
+    #[test]
+    fn bounded_volumes_only_affect_rays_that_cross_them() {
+        let mut w = World::new();
+        w.set_background(Background::Solid(Color::white()));
+        let mut boundary = Sphere::new();
+        boundary.set_transform(Matrix::translation(0.0, 0.0, 2.0));
+        w.add_volume(Volume::bounded(
+            Medium::new(0.5, Color::white()),
+            Box::new(boundary),
+        ));
+        assert_eq!(
+            w.color_at(&forward(), MAX_DEPTH),
+            Color::white() * Scalar::exp(-1.0)
+        );
+        let above = Ray::new(Point::new(0.0, 2.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(w.color_at(&above, MAX_DEPTH), Color::white());
+    }
+
+    #[test]
+    fn volumes_attenuate_light_reaching_a_point() {
+        let mut w = World::new();
+        let light = PointLight::new(Point::new(0.0, 4.0, 0.0), Color::white());
+        assert_eq!(w.intensity_at(&light, &Point::origin()), 1.0);
+        w.add_volume(Volume::fog(Medium::new(0.25, Color::white()), 100.0));
+        assert!((w.intensity_at(&light, &Point::origin()) - Scalar::exp(-1.0)).abs() < 1e-9);
+    }
+}
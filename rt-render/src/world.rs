@@ -0,0 +1,905 @@
+use crate::path_tracer;
+use crate::stats;
+use crate::{lighting, Background, Camera, Light, PointLight, RayKind, ShapeLight, Volume};
+use rt_core::{Color, Matrix, Point, Ray, Scalar};
+use rt_shapes::{Computations, Group, Intersection, Intersections, Material, Shape, Sphere};
+use std::any::Any;
+use std::sync::OnceLock;
+
+pub const MAX_DEPTH: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickResult {
+    pub shape: usize,
+    pub point: Point,
+    pub distance: Scalar,
+}
+
+#[derive(Debug, Default)]
+pub struct World {
+    shapes: Vec<Box<dyn Shape>>,
+    lights: Vec<Box<dyn Light>>,
+    emitters: OnceLock<Vec<ShapeLight>>,
+    background: Background,
+    volumes: Vec<Volume>,
+    seed: u64,
+}
+
+impl World {
+    pub fn new() -> Self {
+        World::default()
+    }
+
+    pub fn default_world() -> Self {
+        let mut outer = Sphere::new();
+        outer.set_material(Material {
+            color: Color::new(0.8, 1.0, 0.6),
+            diffuse: 0.7,
+            specular: 0.2,
+            ..Material::default()
+        });
+        let mut inner = Sphere::new();
+        inner.set_transform(Matrix::scaling(0.5, 0.5, 0.5));
+
+        let mut world = World::new();
+        world.add_shape(Box::new(outer));
+        world.add_shape(Box::new(inner));
+        world.add_light(Box::new(PointLight::new(
+            Point::new(-10.0, 10.0, -10.0),
+            Color::white(),
+        )));
+        world
+    }
+
+    pub fn add_shape(&mut self, shape: Box<dyn Shape>) {
+        self.shapes.push(shape);
+        self.emitters.take();
+    }
+
+    pub fn add_light(&mut self, mut light: Box<dyn Light>) {
+        light.set_seed(self.seed);
+        self.lights.push(light);
+    }
+
+    pub fn shapes(&self) -> &[Box<dyn Shape>] {
+        &self.shapes
+    }
+
+    pub fn shapes_mut(&mut self) -> &mut [Box<dyn Shape>] {
+        self.emitters.take();
+        &mut self.shapes
+    }
+
+    pub fn lights(&self) -> &[Box<dyn Light>] {
+        &self.lights
+    }
+
+    pub fn lights_mut(&mut self) -> &mut [Box<dyn Light>] {
+        &mut self.lights
+    }
+
+    pub fn background(&self) -> &Background {
+        &self.background
+    }
+
+    pub fn set_background(&mut self, background: Background) {
+        self.background = background;
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        for light in &mut self.lights {
+            light.set_seed(seed);
+        }
+        self.emitters.take();
+    }
+
+    pub fn emitters(&self) -> &[ShapeLight] {
+        self.emitters
+            .get_or_init(|| collect_emitters(&self.shapes, self.seed))
+    }
+
+    pub fn add_volume(&mut self, volume: Volume) {
+        self.volumes.push(volume);
+    }
+
+    pub fn volumes(&self) -> &[Volume] {
+        &self.volumes
+    }
+
+    pub(crate) fn light_sources(&self) -> impl Iterator<Item = &dyn Light> {
+        let lights = self.lights.iter().map(|light| light.as_ref());
+        lights.chain(self.emitters().iter().map(|light| light as &dyn Light))
+    }
+
+    pub fn intersect(&self, ray: &Ray) -> Intersections<'_> {
+        self.shapes
+            .iter()
+            .flat_map(|shape| shape.intersect(ray))
+            .collect()
+    }
+
+    pub fn pick(&self, camera: &Camera, x: Scalar, y: Scalar) -> Option<PickResult> {
+        let ray = camera.pixel_to_ray(x, y);
+        let xs = self.intersect(&ray);
+        let hit = xs.hit()?;
+        let shape = self
+            .shapes
+            .iter()
+            .position(|shape| shape.includes(hit.object))?;
+        Some(PickResult {
+            shape,
+            point: ray.position(hit.t),
+            distance: hit.t,
+        })
+    }
+
+    pub fn intersect_packet(&self, rays: &[Ray; 4]) -> [Intersections<'_>; 4] {
+        let mut lanes: [Vec<Intersection>; 4] = Default::default();
+        for shape in &self.shapes {
+            for (lane, hits) in lanes.iter_mut().zip(shape.intersect_packet(rays)) {
+                lane.extend(hits);
+            }
+        }
+        lanes.map(Intersections::new)
+    }
+
+    pub fn shade_hit(&self, comps: &Computations, remaining: usize) -> Color {
+        let material = comps.material;
+        let surface = self
+            .light_sources()
+            .fold(material.emissive, |color, light| {
+                let intensity = self.surface_intensity(light, comps);
+                color
+                    + lighting(
+                        material,
+                        comps.object,
+                        light,
+                        &comps.over_point,
+                        &comps.eyev,
+                        &comps.normalv,
+                        intensity,
+                    )
+            });
+        let reflected = self.reflected_color(comps, remaining);
+        let refracted = self.refracted_color(comps, remaining);
+
+        if material.reflective > 0.0 && material.transparency > 0.0 {
+            let reflectance = comps.schlick();
+            surface + reflected * reflectance + refracted * (1.0 - reflectance)
+        } else {
+            surface + reflected + refracted
+        }
+    }
+
+    pub fn color_at(&self, ray: &Ray, remaining: usize) -> Color {
+        self.color_of(ray, &self.intersect(ray), remaining)
+    }
+
+    pub fn colors_at(&self, rays: &[Ray; 4], remaining: usize) -> [Color; 4] {
+        let packet = self.intersect_packet(rays);
+        std::array::from_fn(|i| self.color_of(&rays[i], &packet[i], remaining))
+    }
+
+    fn color_of(&self, ray: &Ray, xs: &Intersections, remaining: usize) -> Color {
+        let (color, distance) = match xs.hit() {
+            Some(hit) => (
+                self.shade_hit(&hit.prepare_computations(ray, xs), remaining),
+                hit.t,
+            ),
+            None => (self.background.color_for(&ray.direction), Scalar::INFINITY),
+        };
+        if self.volumes.is_empty() {
+            color
+        } else {
+            self.through_volumes(ray, distance, color)
+        }
+    }
+
+    pub fn reflected_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let reflective = comps.material.reflective;
+        if remaining == 0 || reflective == 0.0 {
+            return Color::black();
+        }
+        let reflect_ray = Ray::new(comps.over_point, comps.reflectv).with_time(comps.time);
+        stats::count_rays(RayKind::Reflection, 1);
+        self.color_at(&reflect_ray, remaining - 1) * reflective
+    }
+
+    pub fn refracted_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let transparency = comps.material.transparency;
+        if remaining == 0 || transparency == 0.0 {
+            return Color::black();
+        }
+        match refraction_ray(comps) {
+            Some(refract_ray) => {
+                stats::count_rays(RayKind::Refraction, 1);
+                self.color_at(&refract_ray, remaining - 1) * transparency
+            }
+            None => Color::black(),
+        }
+    }
+
+    pub fn surface_intensity(&self, light: &dyn Light, comps: &Computations) -> Scalar {
+        if comps.object.receives_shadow() {
+            self.intensity_at_time(light, &comps.over_point, comps.time)
+        } else {
+            light.attenuation(&comps.over_point)
+        }
+    }
+
+    pub fn intensity_at(&self, light: &dyn Light, point: &Point) -> Scalar {
+        self.intensity_at_time(light, point, 0.0)
+    }
+
+    pub fn intensity_at_time(&self, light: &dyn Light, point: &Point, time: Scalar) -> Scalar {
+        let attenuation = light.attenuation(point);
+        if attenuation <= 0.0 {
+            return 0.0;
+        }
+        let samples = light.sample_points(point);
+        let lit: Scalar = samples
+            .iter()
+            .filter(|sample| !self.is_shadowed_at_time(sample, point, time))
+            .map(|sample| {
+                if self.volumes.is_empty() {
+                    1.0
+                } else {
+                    self.transmittance(point, sample)
+                }
+            })
+            .sum();
+        attenuation * lit / samples.len() as Scalar
+    }
+
+    pub fn is_shadowed(&self, light_position: &Point, point: &Point) -> bool {
+        self.is_shadowed_at_time(light_position, point, 0.0)
+    }
+
+    pub fn is_shadowed_at_time(&self, light_position: &Point, point: &Point, time: Scalar) -> bool {
+        let v = *light_position - *point;
+        let distance = v.magnitude();
+        let ray = Ray::new(*point, v.normalize()).with_time(time);
+        stats::count_rays(RayKind::Shadow, 1);
+        matches!(self.intersect(&ray).shadow_hit(), Some(hit) if hit.t < distance)
+    }
+}
+
+pub(crate) fn refraction_ray(comps: &Computations) -> Option<Ray> {
+    path_tracer::refraction(comps)
+        .map(|direction| Ray::new(comps.under_point, direction).with_time(comps.time))
+}
+
+fn collect_emitters(shapes: &[Box<dyn Shape>], seed: u64) -> Vec<ShapeLight> {
+    let mut leaves = Vec::new();
+    for shape in shapes {
+        emissive_leaves(shape.as_ref(), &mut leaves);
+    }
+    let mut by_color: Vec<(Color, Vec<&dyn Shape>)> = Vec::new();
+    for leaf in leaves {
+        let emissive = leaf.material().emissive;
+        match by_color.iter_mut().find(|(color, _)| *color == emissive) {
+            Some((_, members)) => members.push(leaf),
+            None => by_color.push((emissive, vec![leaf])),
+        }
+    }
+    by_color
+        .into_iter()
+        .filter_map(|(color, members)| ShapeLight::from_shapes(&members, color, seed))
+        .collect()
+}
+
+fn emissive_leaves<'a>(shape: &'a dyn Shape, leaves: &mut Vec<&'a dyn Shape>) {
+    if let Some(group) = (shape as &dyn Any).downcast_ref::<Group>() {
+        for child in group.children() {
+            emissive_leaves(child.as_ref(), leaves);
+        }
+    } else if shape.material().emissive != Color::black() {
+        leaves.push(shape);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::consts::{FRAC_1_SQRT_2, PI};
+    use rt_core::Vector;
+    use rt_shapes::{Intersection, Plane};
+
+    mod creation {
+        use super::*;
+
+        #[test]
+        fn creating_a_world() {
+            let w = World::new();
+            assert!(w.shapes().is_empty());
+            assert!(w.lights().is_empty());
+        }
+
+        #[test]
+        fn the_default_world() {
+            let w = World::default_world();
+            assert_eq!(w.lights().len(), 1);
+            assert_eq!(w.lights()[0].position(), Point::new(-10.0, 10.0, -10.0));
+            assert_eq!(w.lights()[0].intensity(), Color::white());
+            assert_eq!(w.shapes().len(), 2);
+            assert_eq!(w.shapes()[0].material().color, Color::new(0.8, 1.0, 0.6));
+            assert_eq!(*w.shapes()[1].transform(), Matrix::scaling(0.5, 0.5, 0.5));
+        }
+    }
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn intersect_a_world_with_a_ray() {
+            let w = World::default_world();
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let ts: Vec<Scalar> = w.intersect(&r).iter().map(|i| i.t).collect();
+            assert_eq!(ts, vec![4.0, 4.5, 5.5, 6.0]);
+        }
+
+        #[test]
+        fn picking_finds_the_top_level_shape_under_a_pixel() {
+            let mut w = World::default_world();
+            let mut g = Group::new();
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::translation(0.0, 3.0, 0.0));
+            g.add_child(Box::new(s));
+            w.add_shape(Box::new(g));
+            let mut camera = Camera::new(11, 11, PI / 2.0);
+            camera.set_transform(crate::view_transform(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::new(0.0, 1.0, 0.0),
+            ));
+            let pick = w.pick(&camera, 5.5, 5.5).unwrap();
+            assert_eq!(pick.shape, 0);
+            assert_eq!(pick.point, Point::new(0.0, 0.0, -1.0));
+            assert_eq!(pick.distance, 4.0);
+            let above = camera.pixel_to_ray(5.5, 2.2);
+            let pick = w.pick(&camera, 5.5, 2.2).unwrap();
+            assert_eq!(pick.shape, 2);
+            assert_eq!(pick.point, above.position(pick.distance));
+            assert_eq!(w.pick(&camera, 0.0, 11.0), None);
+        }
+    }
+
+    mod shading {
+        use super::*;
+
+        #[test]
+        fn shading_an_intersection() {
+            let w = World::default_world();
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let shape = w.shapes()[0].as_ref();
+            let i = Intersection::new(4.0, shape);
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert_eq!(
+                w.shade_hit(&comps, MAX_DEPTH),
+                Color::new(0.38066, 0.47583, 0.2855)
+            );
+        }
+
+        #[test]
+        fn shading_an_intersection_from_the_inside() {
+            let mut w = World::default_world();
+            w.lights_mut()[0] =
+                Box::new(PointLight::new(Point::new(0.0, 0.25, 0.0), Color::white()));
+            let r = Ray::new(Point::origin(), Vector::new(0.0, 0.0, 1.0));
+            let shape = w.shapes()[1].as_ref();
+            let i = Intersection::new(0.5, shape);
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert_eq!(
+                w.shade_hit(&comps, MAX_DEPTH),
+                Color::new(0.90498, 0.90498, 0.90498)
+            );
+        }
+
+        #[test]
+        fn shade_hit_is_given_an_intersection_in_shadow() {
+            let mut w = World::new();
+            w.add_light(Box::new(PointLight::new(
+                Point::new(0.0, 0.0, -10.0),
+                Color::white(),
+            )));
+            w.add_shape(Box::new(Sphere::new()));
+            let mut s2 = Sphere::new();
+            s2.set_transform(Matrix::translation(0.0, 0.0, 10.0));
+            w.add_shape(Box::new(s2));
+            let r = Ray::new(Point::new(0.0, 0.0, 5.0), Vector::new(0.0, 0.0, 1.0));
+            let shape = w.shapes()[1].as_ref();
+            let i = Intersection::new(4.0, shape);
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert_eq!(w.shade_hit(&comps, MAX_DEPTH), Color::new(0.1, 0.1, 0.1));
+        }
+
+        #[test]
+        fn shading_with_multiple_lights_adds_contributions() {
+            let mut w = World::default_world();
+            w.add_light(Box::new(PointLight::new(
+                Point::new(-10.0, 10.0, -10.0),
+                Color::white(),
+            )));
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let shape = w.shapes()[0].as_ref();
+            let i = Intersection::new(4.0, shape);
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert_eq!(
+                w.shade_hit(&comps, MAX_DEPTH),
+                Color::new(0.38066, 0.47583, 0.2855) * 2.0
+            );
+        }
+    }
+
+    mod color_at {
+        use super::*;
+
+        #[test]
+        fn ray_misses() {
+            let w = World::default_world();
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0));
+            assert_eq!(w.color_at(&r, MAX_DEPTH), Color::black());
+        }
+
+        #[test]
+        fn ray_misses_into_the_background() {
+            let mut w = World::default_world();
+            w.set_background(Background::Gradient {
+                bottom: Color::black(),
+                top: Color::new(0.2, 0.4, 1.0),
+            });
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0));
+            assert_eq!(w.color_at(&r, MAX_DEPTH), Color::new(0.2, 0.4, 1.0));
+        }
+
+        #[test]
+        fn ray_hits() {
+            let w = World::default_world();
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert_eq!(
+                w.color_at(&r, MAX_DEPTH),
+                Color::new(0.38066, 0.47583, 0.2855)
+            );
+        }
+
+        #[test]
+        fn intersection_behind_the_ray() {
+            let mut w = World::default_world();
+            for shape in w.shapes_mut() {
+                shape.material_mut().ambient = 1.0;
+            }
+            let r = Ray::new(Point::new(0.0, 0.0, 0.75), Vector::new(0.0, 0.0, -1.0));
+            let inner_color = w.shapes()[1].material().color;
+            assert_eq!(w.color_at(&r, MAX_DEPTH), inner_color);
+        }
+
+        #[test]
+        fn a_packet_shades_each_ray_independently() {
+            let w = World::default_world();
+            let origin = Point::new(0.0, 0.0, -5.0);
+            let rays = [
+                Ray::new(origin, Vector::new(0.0, 0.0, 1.0)),
+                Ray::new(origin, Vector::new(0.0, 1.0, 0.0)),
+                Ray::new(origin, Vector::new(0.1, 0.0, 1.0).normalize()),
+                Ray::new(origin, Vector::new(0.0, -0.1, 1.0).normalize()),
+            ];
+            let colors = w.colors_at(&rays, MAX_DEPTH);
+            for (ray, color) in rays.iter().zip(&colors) {
+                assert_eq!(*color, w.color_at(ray, MAX_DEPTH));
+            }
+        }
+    }
+
+    mod shadows {
+        use super::*;
+
+        fn light_position() -> Point {
+            Point::new(-10.0, 10.0, -10.0)
+        }
+
+        #[test]
+        fn nothing_is_collinear_with_point_and_light() {
+            let w = World::default_world();
+            assert!(!w.is_shadowed(&light_position(), &Point::new(0.0, 10.0, 0.0)));
+        }
+
+        #[test]
+        fn object_between_point_and_light() {
+            let w = World::default_world();
+            assert!(w.is_shadowed(&light_position(), &Point::new(10.0, -10.0, 10.0)));
+        }
+
+        #[test]
+        fn shapes_that_cast_no_shadow_do_not_occlude() {
+            let mut w = World::default_world();
+            for shape in w.shapes_mut() {
+                shape.set_casts_shadow(false);
+            }
+            assert!(!w.is_shadowed(&light_position(), &Point::new(10.0, -10.0, 10.0)));
+        }
+
+        #[test]
+        fn shapes_that_receive_no_shadow_are_fully_lit() {
+            let mut w = World::new();
+            w.add_light(Box::new(PointLight::new(
+                Point::new(0.0, 0.0, -10.0),
+                Color::white(),
+            )));
+            w.add_shape(Box::new(Sphere::new()));
+            let mut s2 = Sphere::new();
+            s2.set_transform(Matrix::translation(0.0, 0.0, 10.0));
+            s2.set_receives_shadow(false);
+            w.add_shape(Box::new(s2));
+            let r = Ray::new(Point::new(0.0, 0.0, 5.0), Vector::new(0.0, 0.0, 1.0));
+            let i = Intersection::new(4.0, w.shapes()[1].as_ref());
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert_eq!(w.shade_hit(&comps, MAX_DEPTH), Color::new(1.9, 1.9, 1.9));
+        }
+
+        #[test]
+        fn object_behind_the_light() {
+            let w = World::default_world();
+            assert!(!w.is_shadowed(&light_position(), &Point::new(-20.0, 20.0, -20.0)));
+        }
+
+        #[test]
+        fn object_behind_the_point() {
+            let w = World::default_world();
+            assert!(!w.is_shadowed(&light_position(), &Point::new(-2.0, 2.0, -2.0)));
+        }
+
+        #[test]
+        fn area_light_intensity_counts_unshadowed_samples() {
+            let w = World::default_world();
+            let light = crate::AreaLight::new(
+                Point::new(-0.5, -0.5, -5.0),
+                Vector::new(1.0, 0.0, 0.0),
+                2,
+                Vector::new(0.0, 1.0, 0.0),
+                2,
+                Color::white(),
+            );
+            let cases = [
+                (Point::new(0.0, 0.0, 2.0), 0.0),
+                (Point::new(1.0, -1.0, 2.0), 0.25),
+                (Point::new(1.5, 0.0, 2.0), 0.5),
+                (Point::new(1.25, 1.25, 3.0), 0.75),
+                (Point::new(0.0, 0.0, -2.0), 1.0),
+            ];
+            for (point, expected) in cases {
+                assert_eq!(w.intensity_at(&light, &point), expected, "{:?}", point);
+            }
+        }
+
+        #[test]
+        fn spot_light_only_reaches_points_inside_its_cone() {
+            let w = World::new();
+            let light = crate::SpotLight::new(
+                Point::new(0.0, 10.0, 0.0),
+                Vector::new(0.0, -1.0, 0.0),
+                rt_core::consts::PI / 8.0,
+                rt_core::consts::PI / 6.0,
+                Color::white(),
+            );
+            assert_eq!(w.intensity_at(&light, &Point::origin()), 1.0);
+            assert_eq!(w.intensity_at(&light, &Point::new(10.0, 0.0, 0.0)), 0.0);
+        }
+
+        #[test]
+        fn spot_light_cone_is_still_shadowed_by_objects() {
+            let w = World::default_world();
+            let light = crate::SpotLight::new(
+                Point::new(0.0, 10.0, 0.0),
+                Vector::new(0.0, -1.0, 0.0),
+                rt_core::consts::PI / 8.0,
+                rt_core::consts::PI / 6.0,
+                Color::white(),
+            );
+            assert_eq!(w.intensity_at(&light, &Point::new(0.0, -2.0, 0.0)), 0.0);
+        }
+
+        #[test]
+        fn point_light_intensity_is_all_or_nothing() {
+            let w = World::default_world();
+            let light = w.lights()[0].as_ref();
+            assert_eq!(w.intensity_at(light, &Point::new(0.0, 1.0001, 0.0)), 1.0);
+            assert_eq!(w.intensity_at(light, &Point::new(-1.0001, 0.0, 0.0)), 1.0);
+            assert_eq!(w.intensity_at(light, &Point::new(0.0, 0.0, -1.0001)), 1.0);
+            assert_eq!(w.intensity_at(light, &Point::new(0.0, 0.0, 1.0001)), 0.0);
+            assert_eq!(w.intensity_at(light, &Point::new(1.0001, 0.0, 0.0)), 0.0);
+            assert_eq!(w.intensity_at(light, &Point::new(0.0, -1.0001, 0.0)), 0.0);
+            assert_eq!(w.intensity_at(light, &Point::origin()), 0.0);
+        }
+    }
+
+    mod reflection {
+        use super::*;
+
+        fn reflective_plane(w: &mut World) {
+            let mut shape = Plane::new();
+            shape.material_mut().reflective = 0.5;
+            shape.set_transform(Matrix::translation(0.0, -1.0, 0.0));
+            w.add_shape(Box::new(shape));
+        }
+
+        fn ray_toward_plane() -> Ray {
+            Ray::new(
+                Point::new(0.0, 0.0, -3.0),
+                Vector::new(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+            )
+        }
+
+        #[test]
+        fn reflected_color_for_a_nonreflective_material() {
+            let mut w = World::default_world();
+            w.shapes_mut()[1].material_mut().ambient = 1.0;
+            let r = Ray::new(Point::origin(), Vector::new(0.0, 0.0, 1.0));
+            let i = Intersection::new(1.0, w.shapes()[1].as_ref());
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert_eq!(w.reflected_color(&comps, MAX_DEPTH), Color::black());
+        }
+
+        #[test]
+        fn reflected_color_for_a_reflective_material() {
+            let mut w = World::default_world();
+            reflective_plane(&mut w);
+            let r = ray_toward_plane();
+            let i = Intersection::new(Scalar::sqrt(2.0), w.shapes()[2].as_ref());
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert_eq!(
+                w.reflected_color(&comps, MAX_DEPTH),
+                Color::new(0.19033, 0.23792, 0.14275)
+            );
+        }
+
+        #[test]
+        fn shade_hit_with_a_reflective_material() {
+            let mut w = World::default_world();
+            reflective_plane(&mut w);
+            let r = ray_toward_plane();
+            let i = Intersection::new(Scalar::sqrt(2.0), w.shapes()[2].as_ref());
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert_eq!(
+                w.shade_hit(&comps, MAX_DEPTH),
+                Color::new(0.87676, 0.92434, 0.82917)
+            );
+        }
+
+        #[test]
+        fn color_at_with_mutually_reflective_surfaces() {
+            let mut w = World::new();
+            w.add_light(Box::new(PointLight::new(Point::origin(), Color::white())));
+            for (y, rotation) in [(-1.0, 0.0), (1.0, rt_core::consts::PI)] {
+                let mut plane = Plane::new();
+                plane.material_mut().reflective = 1.0;
+                plane.set_transform(Matrix::rotation_x(rotation).translate(0.0, y, 0.0));
+                w.add_shape(Box::new(plane));
+            }
+            let r = Ray::new(Point::origin(), Vector::new(0.0, 1.0, 0.0));
+            let color = w.color_at(&r, MAX_DEPTH);
+            assert!(color.red() > 0.0);
+        }
+
+        #[test]
+        fn reflected_color_at_the_maximum_recursive_depth() {
+            let mut w = World::default_world();
+            reflective_plane(&mut w);
+            let r = ray_toward_plane();
+            let i = Intersection::new(Scalar::sqrt(2.0), w.shapes()[2].as_ref());
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert_eq!(w.reflected_color(&comps, 0), Color::black());
+        }
+    }
+
+    mod refraction {
+        use super::*;
+        use rt_shapes::Intersections;
+
+        fn add_glass_floor_and_ball(w: &mut World, reflective: Scalar) {
+            let mut floor = Plane::new();
+            floor.set_transform(Matrix::translation(0.0, -1.0, 0.0));
+            floor.material_mut().reflective = reflective;
+            floor.material_mut().transparency = 0.5;
+            floor.material_mut().refractive_index = 1.5;
+            w.add_shape(Box::new(floor));
+
+            let mut ball = Sphere::new();
+            ball.material_mut().color = Color::new(1.0, 0.0, 0.0);
+            ball.material_mut().ambient = 0.5;
+            ball.set_transform(Matrix::translation(0.0, -3.5, -0.5));
+            w.add_shape(Box::new(ball));
+        }
+
+        #[test]
+        fn refracted_color_with_an_opaque_surface() {
+            let w = World::default_world();
+            let shape = w.shapes()[0].as_ref();
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let xs = Intersections::new(vec![
+                Intersection::new(4.0, shape),
+                Intersection::new(6.0, shape),
+            ]);
+            let comps = xs[0].prepare_computations(&r, &xs);
+            assert_eq!(w.refracted_color(&comps, MAX_DEPTH), Color::black());
+        }
+
+        #[test]
+        fn refracted_color_at_the_maximum_recursive_depth() {
+            let mut w = World::default_world();
+            w.shapes_mut()[0].material_mut().transparency = 1.0;
+            w.shapes_mut()[0].material_mut().refractive_index = 1.5;
+            let shape = w.shapes()[0].as_ref();
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let xs = Intersections::new(vec![
+                Intersection::new(4.0, shape),
+                Intersection::new(6.0, shape),
+            ]);
+            let comps = xs[0].prepare_computations(&r, &xs);
+            assert_eq!(w.refracted_color(&comps, 0), Color::black());
+        }
+
+        #[test]
+        fn refracted_color_under_total_internal_reflection() {
+            let mut w = World::default_world();
+            w.shapes_mut()[0].material_mut().transparency = 1.0;
+            w.shapes_mut()[0].material_mut().refractive_index = 1.5;
+            let shape = w.shapes()[0].as_ref();
+            let r = Ray::new(
+                Point::new(0.0, 0.0, FRAC_1_SQRT_2),
+                Vector::new(0.0, 1.0, 0.0),
+            );
+            let xs = Intersections::new(vec![
+                Intersection::new(-FRAC_1_SQRT_2, shape),
+                Intersection::new(FRAC_1_SQRT_2, shape),
+            ]);
+            let comps = xs[1].prepare_computations(&r, &xs);
+            assert_eq!(w.refracted_color(&comps, MAX_DEPTH), Color::black());
+        }
+
+        #[test]
+        fn shade_hit_with_a_transparent_material() {
+            let mut w = World::default_world();
+            add_glass_floor_and_ball(&mut w, 0.0);
+            let r = Ray::new(
+                Point::new(0.0, 0.0, -3.0),
+                Vector::new(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+            );
+            let xs = Intersections::new(vec![Intersection::new(
+                Scalar::sqrt(2.0),
+                w.shapes()[2].as_ref(),
+            )]);
+            let comps = xs[0].prepare_computations(&r, &xs);
+            assert_eq!(
+                w.shade_hit(&comps, MAX_DEPTH),
+                Color::new(0.93642, 0.68642, 0.68642)
+            );
+        }
+
+        #[test]
+        fn shade_hit_with_a_reflective_transparent_material() {
+            let mut w = World::default_world();
+            add_glass_floor_and_ball(&mut w, 0.5);
+            let r = Ray::new(
+                Point::new(0.0, 0.0, -3.0),
+                Vector::new(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+            );
+            let xs = Intersections::new(vec![Intersection::new(
+                Scalar::sqrt(2.0),
+                w.shapes()[2].as_ref(),
+            )]);
+            let comps = xs[0].prepare_computations(&r, &xs);
+            assert_eq!(
+                w.shade_hit(&comps, MAX_DEPTH),
+                Color::new(0.93391, 0.69643, 0.69243)
+            );
+        }
+    }
+
+    mod emitters {
+        use super::*;
+        use crate::AreaLight;
+        use rt_shapes::Cube;
+
+        fn glowing(emissive: Color) -> Material {
+            Material {
+                emissive,
+                ambient: 0.0,
+                diffuse: 0.0,
+                specular: 0.0,
+                ..Material::default()
+            }
+        }
+
+        fn panel_over_floor() -> World {
+            let mut floor = Plane::new();
+            floor.material_mut().specular = 0.0;
+            let mut panel = Cube::new();
+            panel.set_transform(Matrix::scaling(1.0, 0.01, 1.0).translate(0.0, 2.0, 0.0));
+            panel.set_material(glowing(Color::white()));
+            let mut w = World::new();
+            w.add_shape(Box::new(floor));
+            w.add_shape(Box::new(panel));
+            w
+        }
+
+        #[test]
+        fn emissive_shapes_inside_groups_become_lights() {
+            let mut g = Group::new();
+            let mut a = Sphere::new();
+            a.set_material(glowing(Color::new(1.0, 0.5, 0.0)));
+            g.add_child(Box::new(a));
+            g.add_child(Box::new(Sphere::new()));
+            let mut w = World::new();
+            w.add_shape(Box::new(g));
+            assert_eq!(w.emitters().len(), 1);
+            assert_eq!(w.emitters()[0].intensity(), Color::new(1.0, 0.5, 0.0));
+            assert_eq!(w.emitters()[0].samples.len(), 16);
+
+            let mut b = Cube::new();
+            b.set_material(glowing(Color::white()));
+            w.add_shape(Box::new(b));
+            assert_eq!(w.emitters().len(), 2);
+        }
+
+        #[test]
+        fn an_emissive_shape_glows_without_lights() {
+            let mut s = Sphere::new();
+            s.set_material(glowing(Color::new(0.2, 0.4, 0.6)));
+            let mut w = World::new();
+            w.add_shape(Box::new(s));
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert_eq!(w.color_at(&r, MAX_DEPTH), Color::new(0.2, 0.4, 0.6));
+        }
+
+        #[test]
+        fn an_emissive_panel_lights_the_floor_below_it() {
+            let mut w = panel_over_floor();
+            let r = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+            let lit = w.color_at(&r, MAX_DEPTH);
+            assert!(lit.red() > 0.5, "{:?}", lit);
+
+            w.shapes_mut()[1].material_mut().emissive = Color::black();
+            assert!(w.emitters().is_empty());
+            assert_eq!(w.color_at(&r, MAX_DEPTH), Color::black());
+        }
+
+        #[test]
+        fn the_seed_reaches_area_lights_and_emitters() {
+            let mut light = AreaLight::new(
+                Point::new(-1.0, 5.0, -1.0),
+                Vector::new(2.0, 0.0, 0.0),
+                4,
+                Vector::new(0.0, 0.0, 2.0),
+                4,
+                Color::white(),
+            );
+            light.jitter = true;
+            let mut w = panel_over_floor();
+            w.add_light(Box::new(light));
+            let samples = |w: &World| {
+                let target = Point::origin();
+                (
+                    w.lights()[0].sample_points(&target),
+                    w.emitters()[0].samples.clone(),
+                )
+            };
+            let unseeded = samples(&w);
+            w.set_seed(3);
+            let seeded = samples(&w);
+            assert_ne!(seeded.0, unseeded.0);
+            assert_ne!(seeded.1, unseeded.1);
+            w.set_seed(0);
+            assert_eq!(samples(&w), unseeded);
+
+            let mut again = panel_over_floor();
+            again.set_seed(3);
+            again.add_light(Box::new(light));
+            assert_eq!(samples(&again), seeded);
+        }
+    }
+}
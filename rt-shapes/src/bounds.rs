@@ -0,0 +1,448 @@
+use rt_core::{Matrix, Point, Ray, Scalar, EPSILON};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    pub fn new(min: Point, max: Point) -> Self {
+        BoundingBox { min, max }
+    }
+
+    pub fn empty() -> Self {
+        BoundingBox::new(
+            Point::new(Scalar::INFINITY, Scalar::INFINITY, Scalar::INFINITY),
+            Point::new(
+                Scalar::NEG_INFINITY,
+                Scalar::NEG_INFINITY,
+                Scalar::NEG_INFINITY,
+            ),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x() > self.max.x() || self.min.y() > self.max.y() || self.min.z() > self.max.z()
+    }
+
+    pub fn add_point(&mut self, point: &Point) {
+        self.min = Point::new(
+            self.min.x().min(point.x()),
+            self.min.y().min(point.y()),
+            self.min.z().min(point.z()),
+        );
+        self.max = Point::new(
+            self.max.x().max(point.x()),
+            self.max.y().max(point.y()),
+            self.max.z().max(point.z()),
+        );
+    }
+
+    pub fn merge(&mut self, other: &BoundingBox) {
+        if !other.is_empty() {
+            self.add_point(&other.min);
+            self.add_point(&other.max);
+        }
+    }
+
+    pub fn contains_point(&self, point: &Point) -> bool {
+        (self.min.x()..=self.max.x()).contains(&point.x())
+            && (self.min.y()..=self.max.y()).contains(&point.y())
+            && (self.min.z()..=self.max.z()).contains(&point.z())
+    }
+
+    pub fn contains_box(&self, other: &BoundingBox) -> bool {
+        self.contains_point(&other.min) && self.contains_point(&other.max)
+    }
+
+    pub fn transform(&self, matrix: &Matrix<4, 4>) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        let (min, max) = (
+            [self.min.x(), self.min.y(), self.min.z()],
+            [self.max.x(), self.max.y(), self.max.z()],
+        );
+        let mut new_min = [0.0; 3];
+        let mut new_max = [0.0; 3];
+        for i in 0..3 {
+            new_min[i] = matrix[i][3];
+            new_max[i] = matrix[i][3];
+            for j in 0..3 {
+                if matrix[i][j] == 0.0 {
+                    continue;
+                }
+                let a = matrix[i][j] * min[j];
+                let b = matrix[i][j] * max[j];
+                new_min[i] += a.min(b);
+                new_max[i] += a.max(b);
+            }
+        }
+        BoundingBox::new(
+            Point::new(new_min[0], new_min[1], new_min[2]),
+            Point::new(new_max[0], new_max[1], new_max[2]),
+        )
+    }
+
+    pub fn intersects(&self, ray: &Ray) -> bool {
+        self.intersection_range(ray).is_some()
+    }
+
+    pub fn intersection_range(&self, ray: &Ray) -> Option<(Scalar, Scalar)> {
+        if self.is_empty() {
+            return None;
+        }
+        let (xtmin, xtmax) = check_axis(
+            ray.origin.x(),
+            ray.direction.x(),
+            self.min.x(),
+            self.max.x(),
+        );
+        let (ytmin, ytmax) = check_axis(
+            ray.origin.y(),
+            ray.direction.y(),
+            self.min.y(),
+            self.max.y(),
+        );
+        let (ztmin, ztmax) = check_axis(
+            ray.origin.z(),
+            ray.direction.z(),
+            self.min.z(),
+            self.max.z(),
+        );
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+        if tmin <= tmax {
+            Some((tmin, tmax))
+        } else {
+            None
+        }
+    }
+
+    pub fn split(&self) -> (BoundingBox, BoundingBox) {
+        let dx = self.max.x() - self.min.x();
+        let dy = self.max.y() - self.min.y();
+        let dz = self.max.z() - self.min.z();
+        let greatest = dx.max(dy).max(dz);
+
+        let (mut x0, mut y0, mut z0) = (self.min.x(), self.min.y(), self.min.z());
+        let (mut x1, mut y1, mut z1) = (self.max.x(), self.max.y(), self.max.z());
+        if greatest == dx {
+            x0 += dx / 2.0;
+            x1 = x0;
+        } else if greatest == dy {
+            y0 += dy / 2.0;
+            y1 = y0;
+        } else {
+            z0 += dz / 2.0;
+            z1 = z0;
+        }
+
+        (
+            BoundingBox::new(self.min, Point::new(x1, y1, z1)),
+            BoundingBox::new(Point::new(x0, y0, z0), self.max),
+        )
+    }
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        BoundingBox::empty()
+    }
+}
+
+pub(crate) fn check_axis(
+    origin: Scalar,
+    direction: Scalar,
+    min: Scalar,
+    max: Scalar,
+) -> (Scalar, Scalar) {
+    let tmin_numerator = min - origin;
+    let tmax_numerator = max - origin;
+    let (tmin, tmax) = if direction.abs() >= EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (
+            tmin_numerator * Scalar::INFINITY,
+            tmax_numerator * Scalar::INFINITY,
+        )
+    };
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::consts::{FRAC_1_SQRT_2, PI, SQRT_2};
+    use rt_core::Vector;
+
+    #[test]
+    fn creating_an_empty_bounding_box() {
+        let b = BoundingBox::empty();
+        assert!(b.is_empty());
+        assert_eq!(b.min.x(), Scalar::INFINITY);
+        assert_eq!(b.max.x(), Scalar::NEG_INFINITY);
+    }
+
+    #[test]
+    fn adding_points_to_an_empty_bounding_box() {
+        let mut b = BoundingBox::empty();
+        b.add_point(&Point::new(-5.0, 2.0, 0.0));
+        b.add_point(&Point::new(7.0, 0.0, -3.0));
+        assert_eq!(b.min, Point::new(-5.0, 0.0, -3.0));
+        assert_eq!(b.max, Point::new(7.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn merging_bounding_boxes() {
+        let mut b1 = BoundingBox::new(Point::new(-5.0, -2.0, 0.0), Point::new(7.0, 4.0, 4.0));
+        let b2 = BoundingBox::new(Point::new(8.0, -7.0, -2.0), Point::new(14.0, 2.0, 8.0));
+        b1.merge(&b2);
+        assert_eq!(b1.min, Point::new(-5.0, -7.0, -2.0));
+        assert_eq!(b1.max, Point::new(14.0, 4.0, 8.0));
+        b1.merge(&BoundingBox::empty());
+        assert_eq!(b1.max, Point::new(14.0, 4.0, 8.0));
+    }
+
+    #[test]
+    fn box_contains_a_point() {
+        let b = BoundingBox::new(Point::new(5.0, -2.0, 0.0), Point::new(11.0, 4.0, 7.0));
+        let cases = [
+            (Point::new(5.0, -2.0, 0.0), true),
+            (Point::new(11.0, 4.0, 7.0), true),
+            (Point::new(8.0, 1.0, 3.0), true),
+            (Point::new(3.0, 0.0, 3.0), false),
+            (Point::new(8.0, -4.0, 3.0), false),
+            (Point::new(8.0, 1.0, -1.0), false),
+            (Point::new(13.0, 1.0, 3.0), false),
+            (Point::new(8.0, 5.0, 3.0), false),
+            (Point::new(8.0, 1.0, 8.0), false),
+        ];
+        for (point, expected) in cases {
+            assert_eq!(b.contains_point(&point), expected);
+        }
+    }
+
+    #[test]
+    fn box_contains_a_box() {
+        let b = BoundingBox::new(Point::new(5.0, -2.0, 0.0), Point::new(11.0, 4.0, 7.0));
+        let cases = [
+            (Point::new(5.0, -2.0, 0.0), Point::new(11.0, 4.0, 7.0), true),
+            (Point::new(6.0, -1.0, 1.0), Point::new(10.0, 3.0, 6.0), true),
+            (
+                Point::new(4.0, -3.0, -1.0),
+                Point::new(10.0, 3.0, 6.0),
+                false,
+            ),
+            (
+                Point::new(6.0, -1.0, 1.0),
+                Point::new(12.0, 5.0, 8.0),
+                false,
+            ),
+        ];
+        for (min, max, expected) in cases {
+            assert_eq!(b.contains_box(&BoundingBox::new(min, max)), expected);
+        }
+    }
+
+    mod transformation {
+        use super::*;
+
+        #[test]
+        fn transforming_a_bounding_box() {
+            let b = BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+            let m = Matrix::rotation_x(PI / 4.0) * Matrix::rotation_y(PI / 4.0);
+            let b2 = b.transform(&m);
+            let k = 1.0 + FRAC_1_SQRT_2;
+            assert_eq!(b2.min, Point::new(-SQRT_2, -k, -k));
+            assert_eq!(b2.max, Point::new(SQRT_2, k, k));
+        }
+
+        #[test]
+        fn transforming_an_infinite_bounding_box() {
+            let b = BoundingBox::new(
+                Point::new(Scalar::NEG_INFINITY, 0.0, Scalar::NEG_INFINITY),
+                Point::new(Scalar::INFINITY, 0.0, Scalar::INFINITY),
+            );
+            let b2 = b.transform(&Matrix::translation(0.0, 2.0, 0.0));
+            assert_eq!(b2.min.x(), Scalar::NEG_INFINITY);
+            assert_eq!(b2.min.y(), 2.0);
+            assert_eq!(b2.max.z(), Scalar::INFINITY);
+
+            let b3 = b.transform(&Matrix::rotation_x(PI / 2.0));
+            assert_eq!(b3.min.y(), Scalar::NEG_INFINITY);
+            assert_eq!(b3.max.y(), Scalar::INFINITY);
+            assert!(!b3.min.x().is_nan() && !b3.min.z().is_nan());
+        }
+    }
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn ray_with_a_cubic_bounding_box_at_the_origin() {
+            let b = BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+            let cases = [
+                (Point::new(5.0, 0.5, 0.0), Vector::new(-1.0, 0.0, 0.0), true),
+                (Point::new(-5.0, 0.5, 0.0), Vector::new(1.0, 0.0, 0.0), true),
+                (Point::new(0.5, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0), true),
+                (Point::new(0.5, -5.0, 0.0), Vector::new(0.0, 1.0, 0.0), true),
+                (Point::new(0.5, 0.0, 5.0), Vector::new(0.0, 0.0, -1.0), true),
+                (Point::new(0.5, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0), true),
+                (Point::new(0.0, 0.5, 0.0), Vector::new(0.0, 0.0, 1.0), true),
+                (
+                    Point::new(-2.0, 0.0, 0.0),
+                    Vector::new(2.0, 4.0, 6.0),
+                    false,
+                ),
+                (
+                    Point::new(0.0, -2.0, 0.0),
+                    Vector::new(6.0, 2.0, 4.0),
+                    false,
+                ),
+                (
+                    Point::new(0.0, 0.0, -2.0),
+                    Vector::new(4.0, 6.0, 2.0),
+                    false,
+                ),
+                (
+                    Point::new(2.0, 0.0, 2.0),
+                    Vector::new(0.0, 0.0, -1.0),
+                    false,
+                ),
+                (
+                    Point::new(0.0, 2.0, 2.0),
+                    Vector::new(0.0, -1.0, 0.0),
+                    false,
+                ),
+                (
+                    Point::new(2.0, 2.0, 0.0),
+                    Vector::new(-1.0, 0.0, 0.0),
+                    false,
+                ),
+            ];
+            for (origin, direction, expected) in cases {
+                let r = Ray::new(origin, direction.normalize());
+                assert_eq!(b.intersects(&r), expected);
+            }
+        }
+
+        #[test]
+        fn ray_with_a_non_cubic_bounding_box() {
+            let b = BoundingBox::new(Point::new(5.0, -2.0, 0.0), Point::new(11.0, 4.0, 7.0));
+            let cases = [
+                (
+                    Point::new(15.0, 1.0, 2.0),
+                    Vector::new(-1.0, 0.0, 0.0),
+                    true,
+                ),
+                (
+                    Point::new(-5.0, -1.0, 4.0),
+                    Vector::new(1.0, 0.0, 0.0),
+                    true,
+                ),
+                (Point::new(7.0, 6.0, 5.0), Vector::new(0.0, -1.0, 0.0), true),
+                (Point::new(9.0, -5.0, 6.0), Vector::new(0.0, 1.0, 0.0), true),
+                (
+                    Point::new(8.0, 2.0, 12.0),
+                    Vector::new(0.0, 0.0, -1.0),
+                    true,
+                ),
+                (Point::new(6.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0), true),
+                (Point::new(8.0, 1.0, 3.5), Vector::new(0.0, 0.0, 1.0), true),
+                (
+                    Point::new(9.0, -1.0, -8.0),
+                    Vector::new(2.0, 4.0, 6.0),
+                    false,
+                ),
+                (
+                    Point::new(8.0, 3.0, -4.0),
+                    Vector::new(6.0, 2.0, 4.0),
+                    false,
+                ),
+                (
+                    Point::new(9.0, -1.0, -2.0),
+                    Vector::new(4.0, 6.0, 2.0),
+                    false,
+                ),
+                (
+                    Point::new(4.0, 0.0, 9.0),
+                    Vector::new(0.0, 0.0, -1.0),
+                    false,
+                ),
+                (
+                    Point::new(8.0, 6.0, -1.0),
+                    Vector::new(0.0, -1.0, 0.0),
+                    false,
+                ),
+                (
+                    Point::new(12.0, 5.0, 4.0),
+                    Vector::new(-1.0, 0.0, 0.0),
+                    false,
+                ),
+            ];
+            for (origin, direction, expected) in cases {
+                let r = Ray::new(origin, direction.normalize());
+                assert_eq!(b.intersects(&r), expected);
+            }
+        }
+
+        #[test]
+        fn ray_never_intersects_an_empty_box() {
+            let r = Ray::new(Point::origin(), Vector::new(0.0, 0.0, 1.0));
+            assert!(!BoundingBox::empty().intersects(&r));
+        }
+
+        #[test]
+        fn intersection_range_spans_entry_to_exit() {
+            let b = BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert_eq!(b.intersection_range(&r), Some((4.0, 6.0)));
+            let r = Ray::new(Point::new(0.0, 2.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert_eq!(b.intersection_range(&r), None);
+        }
+    }
+
+    mod splitting {
+        use super::*;
+
+        #[test]
+        fn splitting_a_perfect_cube() {
+            let b = BoundingBox::new(Point::new(-1.0, -4.0, -5.0), Point::new(9.0, 6.0, 5.0));
+            let (left, right) = b.split();
+            assert_eq!(left.min, Point::new(-1.0, -4.0, -5.0));
+            assert_eq!(left.max, Point::new(4.0, 6.0, 5.0));
+            assert_eq!(right.min, Point::new(4.0, -4.0, -5.0));
+            assert_eq!(right.max, Point::new(9.0, 6.0, 5.0));
+        }
+
+        #[test]
+        fn splitting_an_x_wide_box() {
+            let b = BoundingBox::new(Point::new(-1.0, -2.0, -3.0), Point::new(9.0, 5.5, 3.0));
+            let (left, right) = b.split();
+            assert_eq!(left.max, Point::new(4.0, 5.5, 3.0));
+            assert_eq!(right.min, Point::new(4.0, -2.0, -3.0));
+        }
+
+        #[test]
+        fn splitting_a_y_wide_box() {
+            let b = BoundingBox::new(Point::new(-1.0, -2.0, -3.0), Point::new(5.0, 8.0, 3.0));
+            let (left, right) = b.split();
+            assert_eq!(left.max, Point::new(5.0, 3.0, 3.0));
+            assert_eq!(right.min, Point::new(-1.0, 3.0, -3.0));
+        }
+
+        #[test]
+        fn splitting_a_z_wide_box() {
+            let b = BoundingBox::new(Point::new(-1.0, -2.0, -3.0), Point::new(5.0, 3.0, 7.0));
+            let (left, right) = b.split();
+            assert_eq!(left.max, Point::new(5.0, 3.0, 2.0));
+            assert_eq!(right.min, Point::new(-1.0, -2.0, 2.0));
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use crate::{Group, Material, NormalMap, Pattern, Shape};
+use rt_core::{Color, Matrix, Scalar};
+use std::any::Any;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct ShapeBuilder<S> {
+    shape: S,
+    transform: Matrix<4, 4>,
+    material: Option<Material>,
+    casts_shadow: Option<bool>,
+    receives_shadow: Option<bool>,
+}
+
+impl<S: Shape> ShapeBuilder<S> {
+    pub fn new(shape: S) -> Self {
+        ShapeBuilder {
+            shape,
+            transform: Matrix::identity(),
+            material: None,
+            casts_shadow: None,
+            receives_shadow: None,
+        }
+    }
+
+    pub fn transform(mut self, transform: Matrix<4, 4>) -> Self {
+        self.transform = transform * self.transform;
+        self
+    }
+
+    pub fn translate(self, x: Scalar, y: Scalar, z: Scalar) -> Self {
+        self.transform(Matrix::translation(x, y, z))
+    }
+
+    pub fn scale(self, x: Scalar, y: Scalar, z: Scalar) -> Self {
+        self.transform(Matrix::scaling(x, y, z))
+    }
+
+    pub fn rotate_x(self, radians: Scalar) -> Self {
+        self.transform(Matrix::rotation_x(radians))
+    }
+
+    pub fn rotate_y(self, radians: Scalar) -> Self {
+        self.transform(Matrix::rotation_y(radians))
+    }
+
+    pub fn rotate_z(self, radians: Scalar) -> Self {
+        self.transform(Matrix::rotation_z(radians))
+    }
+
+    pub fn shear(
+        self,
+        xy: Scalar,
+        xz: Scalar,
+        yx: Scalar,
+        yz: Scalar,
+        zx: Scalar,
+        zy: Scalar,
+    ) -> Self {
+        self.transform(Matrix::shearing(xy, xz, yx, yz, zx, zy))
+    }
+
+    /// On a group this is also given to every child still using the default material.
+    pub fn material(mut self, material: impl Into<Material>) -> Self {
+        self.material = Some(material.into());
+        self
+    }
+
+    pub fn casts_shadow(mut self, casts: bool) -> Self {
+        self.casts_shadow = Some(casts);
+        self
+    }
+
+    pub fn receives_shadow(mut self, receives: bool) -> Self {
+        self.receives_shadow = Some(receives);
+        self
+    }
+
+    pub fn build(mut self) -> S {
+        if self.transform != Matrix::identity() {
+            self.shape.set_transform(self.transform);
+        }
+        if let Some(material) = self.material {
+            if let Some(group) = (&mut self.shape as &mut dyn Any).downcast_mut::<Group>() {
+                group.inherit_material(&material);
+            }
+            self.shape.set_material(material);
+        }
+        if let Some(casts) = self.casts_shadow {
+            self.shape.set_casts_shadow(casts);
+        }
+        if let Some(receives) = self.receives_shadow {
+            self.shape.set_receives_shadow(receives);
+        }
+        self.shape
+    }
+
+    pub fn boxed(self) -> Box<dyn Shape> {
+        Box::new(self.build())
+    }
+}
+
+impl ShapeBuilder<Group> {
+    pub fn child<C: Shape>(mut self, child: impl Into<ShapeBuilder<C>>) -> Self {
+        self.shape.add_child(child.into().boxed());
+        self
+    }
+}
+
+impl<S: Shape> From<S> for ShapeBuilder<S> {
+    fn from(shape: S) -> Self {
+        ShapeBuilder::new(shape)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MaterialBuilder {
+    material: Material,
+}
+
+impl MaterialBuilder {
+    pub fn color(mut self, color: Color) -> Self {
+        self.material.color = color;
+        self
+    }
+
+    pub fn pattern(mut self, pattern: impl Pattern) -> Self {
+        self.material.pattern = Some(Arc::new(pattern));
+        self
+    }
+
+    pub fn normal_map(mut self, normal_map: impl NormalMap) -> Self {
+        self.material.normal_map = Some(Arc::new(normal_map));
+        self
+    }
+
+    pub fn ambient(mut self, ambient: Scalar) -> Self {
+        self.material.ambient = ambient;
+        self
+    }
+
+    pub fn diffuse(mut self, diffuse: Scalar) -> Self {
+        self.material.diffuse = diffuse;
+        self
+    }
+
+    pub fn specular(mut self, specular: Scalar) -> Self {
+        self.material.specular = specular;
+        self
+    }
+
+    pub fn shininess(mut self, shininess: Scalar) -> Self {
+        self.material.shininess = shininess;
+        self
+    }
+
+    pub fn reflective(mut self, reflective: Scalar) -> Self {
+        self.material.reflective = reflective;
+        self
+    }
+
+    pub fn transparency(mut self, transparency: Scalar) -> Self {
+        self.material.transparency = transparency;
+        self
+    }
+
+    pub fn refractive_index(mut self, refractive_index: Scalar) -> Self {
+        self.material.refractive_index = refractive_index;
+        self
+    }
+
+    pub fn emissive(mut self, emissive: Color) -> Self {
+        self.material.emissive = emissive;
+        self
+    }
+
+    pub fn build(self) -> Material {
+        self.material
+    }
+}
+
+impl From<MaterialBuilder> for Material {
+    fn from(builder: MaterialBuilder) -> Self {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cube, Sphere, Stripe};
+    use rt_core::consts::PI;
+    use rt_core::{Point, Ray, Vector};
+
+    #[test]
+    fn transforms_apply_in_call_order() {
+        let s = Sphere::builder()
+            .rotate_x(PI / 2.0)
+            .scale(5.0, 5.0, 5.0)
+            .translate(10.0, 5.0, 7.0)
+            .build();
+        assert_eq!(
+            *s.transform(),
+            Matrix::translation(10.0, 5.0, 7.0)
+                * Matrix::scaling(5.0, 5.0, 5.0)
+                * Matrix::rotation_x(PI / 2.0)
+        );
+    }
+
+    #[test]
+    fn defaults_match_the_plain_constructors() {
+        let s = Sphere::builder().build();
+        assert_eq!(*s.transform(), Matrix::identity());
+        assert_eq!(*s.material(), Material::default());
+        assert_eq!(Material::builder().build(), Material::default());
+    }
+
+    #[test]
+    fn materials_can_be_given_as_builders() {
+        let s = Cube::builder()
+            .material(
+                Material::builder()
+                    .color(Color::new(1.0, 0.0, 0.0))
+                    .reflective(0.3),
+            )
+            .casts_shadow(false)
+            .build();
+        assert_eq!(
+            *s.material(),
+            Material {
+                color: Color::new(1.0, 0.0, 0.0),
+                reflective: 0.3,
+                ..Material::default()
+            }
+        );
+        assert!(!s.casts_shadow());
+        assert!(s.receives_shadow());
+    }
+
+    #[test]
+    fn patterns_are_shared_behind_the_material() {
+        let m = Material::builder()
+            .pattern(Stripe::new(Color::white(), Color::black()))
+            .build();
+        let s = Sphere::new();
+        assert_eq!(m.color_at(&s, &Point::new(1.5, 0.0, 0.0)), Color::black());
+    }
+
+    #[test]
+    fn group_children_follow_the_group_transform() {
+        let g = Group::builder()
+            .child(Sphere::builder().translate(5.0, 0.0, 0.0))
+            .child(Cube::new())
+            .scale(2.0, 2.0, 2.0)
+            .build();
+        assert_eq!(g.len(), 2);
+        assert_eq!(
+            *g.children()[0].transform(),
+            Matrix::scaling(2.0, 2.0, 2.0) * Matrix::translation(5.0, 0.0, 0.0)
+        );
+        let r = Ray::new(Point::new(10.0, 0.0, -10.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(g.intersect(&r).len(), 2);
+    }
+
+    #[test]
+    fn group_settings_reach_children_added_later() {
+        let red = Material::builder().color(Color::new(1.0, 0.0, 0.0)).build();
+        let blue = Material::builder().color(Color::new(0.0, 0.0, 1.0)).build();
+        let g = Group::builder()
+            .material(red.clone())
+            .casts_shadow(false)
+            .child(Sphere::new())
+            .child(Group::builder().child(Cube::new()))
+            .child(Sphere::builder().material(blue.clone()))
+            .build();
+        let children = g.children();
+        assert_eq!(*g.material(), red);
+        assert_eq!(*children[0].material(), red);
+        let nested = (children[1].as_ref() as &dyn Any)
+            .downcast_ref::<Group>()
+            .unwrap();
+        assert_eq!(*nested.children()[0].material(), red);
+        assert_eq!(*children[2].material(), blue);
+        assert!(children.iter().all(|child| !child.casts_shadow()));
+        assert!(!nested.children()[0].casts_shadow());
+    }
+}
@@ -0,0 +1,328 @@
+use crate::shape;
+use crate::{BoundingBox, Intersection, Intersections, Shape, ShapeData};
+use rt_core::{Matrix, Point, Ray, Scalar, Vector, EPSILON};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipPlane {
+    pub point: Point,
+    pub normal: Vector,
+}
+
+impl ClipPlane {
+    pub fn new(point: Point, normal: Vector) -> Self {
+        ClipPlane {
+            point,
+            normal: normal.normalize(),
+        }
+    }
+
+    pub fn distance(&self, point: &Point) -> Scalar {
+        (*point - self.point).dot(&self.normal)
+    }
+}
+
+#[derive(Debug)]
+pub struct Clip {
+    data: ShapeData,
+    shape: Box<dyn Shape>,
+    planes: Vec<ClipPlane>,
+    pub capped: bool,
+}
+
+impl Clip {
+    pub fn new(shape: Box<dyn Shape>, planes: Vec<ClipPlane>) -> Self {
+        Clip {
+            data: ShapeData::default(),
+            shape,
+            planes,
+            capped: false,
+        }
+    }
+
+    pub fn to_box(shape: Box<dyn Shape>, min: Point, max: Point) -> Self {
+        let axes = [
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, 0.0, 1.0),
+        ];
+        let planes = axes
+            .iter()
+            .map(|&axis| ClipPlane::new(max, axis))
+            .chain(axes.iter().map(|&axis| ClipPlane::new(min, -axis)))
+            .collect();
+        Clip::new(shape, planes)
+    }
+
+    pub fn shape(&self) -> &dyn Shape {
+        self.shape.as_ref()
+    }
+
+    pub fn planes(&self) -> &[ClipPlane] {
+        &self.planes
+    }
+
+    pub fn add_plane(&mut self, plane: ClipPlane) {
+        self.planes.push(plane);
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.distance(point) <= EPSILON)
+    }
+
+    fn clip_range(&self, ray: &Ray) -> Option<(Scalar, Scalar)> {
+        let mut range = (Scalar::NEG_INFINITY, Scalar::INFINITY);
+        for plane in &self.planes {
+            let rate = ray.direction.dot(&plane.normal);
+            let distance = plane.distance(&ray.origin);
+            if rate.abs() < EPSILON {
+                if distance > 0.0 {
+                    return None;
+                }
+                continue;
+            }
+            let t = -distance / rate;
+            if rate < 0.0 {
+                range.0 = range.0.max(t);
+            } else {
+                range.1 = range.1.min(t);
+            }
+        }
+        if range.0 <= range.1 {
+            Some(range)
+        } else {
+            None
+        }
+    }
+
+    fn filter_intersections<'a>(
+        &'a self,
+        xs: Intersections<'a>,
+        range: (Scalar, Scalar),
+    ) -> Intersections<'a> {
+        let (t0, t1) = range;
+        let inside_at = |t: Scalar| xs.iter().filter(|i| i.t < t).count() % 2 == 1;
+        let mut result: Vec<Intersection<'a>> = xs
+            .iter()
+            .filter(|i| (t0..=t1).contains(&i.t))
+            .copied()
+            .collect();
+        if self.capped {
+            for t in [t0, t1] {
+                if t.is_finite() && inside_at(t) {
+                    result.push(Intersection::new(t, self));
+                }
+            }
+        }
+        Intersections::new(result)
+    }
+
+    fn clipped_bounds(&self, mut bounds: BoundingBox) -> BoundingBox {
+        let mut min = [bounds.min.x(), bounds.min.y(), bounds.min.z()];
+        let mut max = [bounds.max.x(), bounds.max.y(), bounds.max.z()];
+        for plane in &self.planes {
+            let normal = [plane.normal.x(), plane.normal.y(), plane.normal.z()];
+            let point = [plane.point.x(), plane.point.y(), plane.point.z()];
+            for axis in 0..3 {
+                if (normal[axis] - 1.0).abs() < EPSILON {
+                    max[axis] = max[axis].min(point[axis]);
+                } else if (normal[axis] + 1.0).abs() < EPSILON {
+                    min[axis] = min[axis].max(point[axis]);
+                }
+            }
+        }
+        bounds.min = Point::new(min[0], min[1], min[2]);
+        bounds.max = Point::new(max[0], max[1], max[2]);
+        if bounds.is_empty() {
+            BoundingBox::empty()
+        } else {
+            bounds
+        }
+    }
+}
+
+impl Shape for Clip {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn set_transform(&mut self, transform: Matrix<4, 4>) {
+        let rebase = transform * *self.inverse_transform();
+        shape::rebase(self.shape.as_mut(), &rebase);
+        self.data.set_transform(transform);
+    }
+
+    fn set_motion(&mut self, end: Matrix<4, 4>) {
+        let inverse = *self.inverse_transform();
+        shape::rebase_motion(self.shape.as_mut(), &inverse, &end);
+        self.data.set_motion(end);
+    }
+
+    fn set_casts_shadow(&mut self, casts: bool) {
+        self.data.casts_shadow = casts;
+        self.shape.set_casts_shadow(casts);
+    }
+
+    fn set_receives_shadow(&mut self, receives: bool) {
+        self.data.receives_shadow = receives;
+        self.shape.set_receives_shadow(receives);
+    }
+
+    fn intersect(&self, ray: &Ray) -> Intersections<'_> {
+        if !self.bounds().intersects(ray) {
+            return Intersections::default();
+        }
+        let local = match self.transform_at(ray.time).inverse() {
+            Some(inverse) => ray.transform(&inverse),
+            None => return Intersections::default(),
+        };
+        match self.clip_range(&local) {
+            Some(range) => self.filter_intersections(self.shape.intersect(ray), range),
+            None => Intersections::default(),
+        }
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        self.intersect(&ray.transform(self.transform()))
+    }
+
+    fn local_normal_at(&self, point: &Point, _hit: &Intersection) -> Vector {
+        self.planes
+            .iter()
+            .min_by(|a, b| a.distance(point).abs().total_cmp(&b.distance(point).abs()))
+            .map_or(Vector::new(0.0, 1.0, 0.0), |plane| plane.normal)
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        self.clipped_bounds(self.shape.bounds().transform(self.inverse_transform()))
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        if self.end_transform().is_some() {
+            return self.shape.bounds();
+        }
+        self.local_bounds().transform(self.transform())
+    }
+
+    fn divide(&mut self, threshold: usize) {
+        self.shape.divide(threshold);
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        shape::same_shape(self, other) || self.shape.includes(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cube, Group, Sphere};
+    use rt_core::consts::PI;
+
+    fn hemisphere(capped: bool) -> Clip {
+        let mut clip = Clip::new(
+            Box::new(Sphere::new()),
+            vec![ClipPlane::new(Point::origin(), Vector::new(0.0, 1.0, 0.0))],
+        );
+        clip.capped = capped;
+        clip
+    }
+
+    fn ts(xs: &Intersections) -> Vec<Scalar> {
+        xs.iter().map(|i| i.t).collect()
+    }
+
+    #[test]
+    fn a_box_is_six_planes_facing_out() {
+        let clip = Clip::to_box(
+            Box::new(Sphere::new()),
+            Point::new(-1.0, -2.0, -3.0),
+            Point::new(1.0, 2.0, 3.0),
+        );
+        assert_eq!(clip.planes().len(), 6);
+        assert!(clip.contains(&Point::new(0.5, -1.5, 2.5)));
+        assert!(!clip.contains(&Point::new(0.0, 0.0, 3.5)));
+    }
+
+    #[test]
+    fn hits_outside_the_region_are_discarded() {
+        let clip = hemisphere(false);
+        let r = Ray::new(Point::new(0.0, 0.5, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(clip.intersect(&r).len(), 0);
+        let r = Ray::new(Point::new(0.0, -5.0, 0.0), Vector::new(0.0, 1.0, 0.0));
+        let xs = clip.intersect(&r);
+        assert_eq!(ts(&xs), vec![4.0]);
+        assert!(shape::same_shape(xs[0].object, clip.shape()));
+    }
+
+    #[test]
+    fn a_ray_parallel_to_a_plane_outside_the_region_misses() {
+        let clip = hemisphere(true);
+        let r = Ray::new(Point::new(0.0, 0.5, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(clip.clip_range(&r), None);
+    }
+
+    #[test]
+    fn capped_solids_are_closed_where_they_are_cut() {
+        let clip = hemisphere(true);
+        let r = Ray::new(Point::new(0.0, -5.0, 0.0), Vector::new(0.0, 1.0, 0.0));
+        let xs = clip.intersect(&r);
+        assert_eq!(ts(&xs), vec![4.0, 5.0]);
+        assert!(shape::same_shape(xs[1].object, &clip));
+        let n = xs[1].object.normal_at(&r.position(5.0), &xs[1]);
+        assert_eq!(n, Vector::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn caps_are_only_added_inside_the_solid() {
+        let clip = hemisphere(true);
+        let r = Ray::new(Point::new(2.0, -5.0, 0.0), Vector::new(0.0, 1.0, 0.0));
+        assert_eq!(clip.intersect(&r).len(), 0);
+    }
+
+    #[test]
+    fn a_clip_is_transformed_with_its_shape() {
+        let mut clip = hemisphere(true);
+        clip.set_transform(Matrix::translation(0.0, 1.0, 0.0) * Matrix::rotation_x(PI / 2.0));
+        let r = Ray::new(Point::new(0.0, 1.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        let xs = clip.intersect(&r);
+        assert_eq!(ts(&xs), vec![4.0, 5.0]);
+        let n = xs[1].object.normal_at(&r.position(5.0), &xs[1]);
+        assert_eq!(n, Vector::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn bounds_are_trimmed_to_axis_aligned_planes() {
+        let clip = Clip::to_box(
+            Box::new(Cube::new()),
+            Point::new(-0.5, -2.0, -2.0),
+            Point::new(0.5, 0.25, 2.0),
+        );
+        let bounds = clip.bounds();
+        assert_eq!(bounds.min, Point::new(-0.5, -1.0, -1.0));
+        assert_eq!(bounds.max, Point::new(0.5, 0.25, 1.0));
+    }
+
+    #[test]
+    fn clipping_a_group_keeps_hits_on_every_child() {
+        let mut g = Group::new();
+        let mut s = Sphere::new();
+        s.set_transform(Matrix::translation(3.0, 0.0, 0.0));
+        g.add_child(Box::new(Sphere::new()));
+        g.add_child(Box::new(s));
+        let clip = Clip::to_box(
+            Box::new(g),
+            Point::new(-5.0, -5.0, -5.0),
+            Point::new(5.0, 5.0, 0.5),
+        );
+        let r = Ray::new(Point::new(-5.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0));
+        assert_eq!(ts(&clip.intersect(&r)), vec![4.0, 6.0, 7.0, 9.0]);
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(ts(&clip.intersect(&r)), vec![4.0]);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::cylinder::check_cap;
+use crate::{BoundingBox, Intersection, Intersections, Shape, ShapeBuilder, ShapeData};
+use rt_core::{Point, Ray, Scalar, Vector, EPSILON};
+
+#[derive(Debug)]
+pub struct Cone {
+    data: ShapeData,
+    pub minimum: Scalar,
+    pub maximum: Scalar,
+    pub closed: bool,
+}
+
+impl Default for Cone {
+    fn default() -> Self {
+        Cone {
+            data: ShapeData::default(),
+            minimum: Scalar::NEG_INFINITY,
+            maximum: Scalar::INFINITY,
+            closed: false,
+        }
+    }
+}
+
+impl Cone {
+    pub fn new() -> Self {
+        Cone::default()
+    }
+
+    pub fn builder() -> ShapeBuilder<Cone> {
+        ShapeBuilder::new(Cone::new())
+    }
+
+    pub fn truncated(minimum: Scalar, maximum: Scalar, closed: bool) -> Self {
+        Cone {
+            minimum,
+            maximum,
+            closed,
+            ..Cone::default()
+        }
+    }
+
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        if !self.closed || ray.direction.y().abs() < EPSILON {
+            return;
+        }
+        for y in [self.minimum, self.maximum] {
+            let t = (y - ray.origin.y()) / ray.direction.y();
+            if check_cap(ray, t, y.abs()) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+}
+
+impl Shape for Cone {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let (o, d) = (ray.origin, ray.direction);
+        let a = d.x().powi(2) - d.y().powi(2) + d.z().powi(2);
+        let b = 2.0 * o.x() * d.x() - 2.0 * o.y() * d.y() + 2.0 * o.z() * d.z();
+        let c = o.x().powi(2) - o.y().powi(2) + o.z().powi(2);
+
+        let mut ts = Vec::new();
+        if a.abs() < EPSILON {
+            if b.abs() >= EPSILON {
+                ts.push(-c / (2.0 * b));
+            }
+        } else {
+            let discriminant = b * b - 4.0 * a * c;
+            if discriminant < -EPSILON {
+                return Intersections::default();
+            }
+            let root = discriminant.max(0.0).sqrt();
+            ts.push((-b - root) / (2.0 * a));
+            ts.push((-b + root) / (2.0 * a));
+        }
+
+        let mut xs: Vec<Intersection> = ts
+            .into_iter()
+            .filter(|t| {
+                let y = o.y() + t * d.y();
+                self.minimum < y && y < self.maximum
+            })
+            .map(|t| Intersection::new(t, self))
+            .collect();
+        self.intersect_caps(ray, &mut xs);
+        Intersections::new(xs)
+    }
+
+    fn local_normal_at(&self, point: &Point, _hit: &Intersection) -> Vector {
+        let dist = point.x().powi(2) + point.z().powi(2);
+        if dist < self.maximum.powi(2) && point.y() >= self.maximum - EPSILON {
+            Vector::new(0.0, 1.0, 0.0)
+        } else if dist < self.minimum.powi(2) && point.y() <= self.minimum + EPSILON {
+            Vector::new(0.0, -1.0, 0.0)
+        } else {
+            let y = dist.sqrt();
+            let y = if point.y() > 0.0 { -y } else { y };
+            Vector::new(point.x(), y, point.z())
+        }
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        let limit = self.minimum.abs().max(self.maximum.abs());
+        BoundingBox::new(
+            Point::new(-limit, self.minimum, -limit),
+            Point::new(limit, self.maximum, limit),
+        )
+    }
+
+    fn local_sample_point(&self, u: Scalar, v: Scalar) -> Option<(Point, Vector)> {
+        if !self.minimum.is_finite() || !self.maximum.is_finite() {
+            return None;
+        }
+        let y = self.minimum + u * (self.maximum - self.minimum);
+        let theta = 2.0 * rt_core::consts::PI * v;
+        let point = Point::new(y.abs() * theta.cos(), y, y.abs() * theta.sin());
+        let radial = if y > 0.0 { -y.abs() } else { y.abs() };
+        Some((point, Vector::new(point.x(), radial, point.z())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn intersecting_a_cone_with_a_ray() {
+            let shape = Cone::new();
+            let cases = [
+                (
+                    Point::new(0.0, 0.0, -5.0),
+                    Vector::new(0.0, 0.0, 1.0),
+                    5.0,
+                    5.0,
+                ),
+                (
+                    Point::new(0.0, 0.0, -5.0),
+                    Vector::new(1.0, 1.0, 1.0),
+                    8.66025,
+                    8.66025,
+                ),
+                (
+                    Point::new(1.0, 1.0, -5.0),
+                    Vector::new(-0.5, -1.0, 1.0),
+                    4.55006,
+                    49.44994,
+                ),
+            ];
+            for (origin, direction, t0, t1) in cases {
+                let xs = shape.local_intersect(&Ray::new(origin, direction.normalize()));
+                assert_eq!(xs.len(), 2);
+                assert!((xs[0].t - t0).abs() < 1e-4);
+                assert!((xs[1].t - t1).abs() < 1e-4);
+            }
+        }
+
+        #[test]
+        fn ray_parallel_to_one_of_its_halves() {
+            let shape = Cone::new();
+            let direction = Vector::new(0.0, 1.0, 1.0).normalize();
+            let xs = shape.local_intersect(&Ray::new(Point::new(0.0, 0.0, -1.0), direction));
+            assert_eq!(xs.len(), 1);
+            assert!((xs[0].t - 0.35355).abs() < EPSILON);
+        }
+
+        #[test]
+        fn ray_through_the_apex_along_the_surface_misses() {
+            let shape = Cone::new();
+            let direction = Vector::new(0.0, 1.0, 1.0).normalize();
+            let xs = shape.local_intersect(&Ray::new(Point::origin(), direction));
+            assert!(xs.iter().all(|i| i.t.abs() < EPSILON));
+        }
+
+        #[test]
+        fn intersecting_a_cones_end_caps() {
+            let shape = Cone::truncated(-0.5, 0.5, true);
+            let cases = [
+                (Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0), 0),
+                (Point::new(0.0, 0.0, -0.25), Vector::new(0.0, 1.0, 1.0), 2),
+                (Point::new(0.0, 0.0, -0.25), Vector::new(0.0, 1.0, 0.0), 4),
+            ];
+            for (origin, direction, count) in cases {
+                let xs = shape.local_intersect(&Ray::new(origin, direction.normalize()));
+                assert_eq!(xs.len(), count);
+            }
+        }
+    }
+
+    #[test]
+    fn normal_on_a_cone() {
+        let shape = Cone::new();
+        let hit = Intersection::new(0.0, &shape);
+        let cases = [
+            (Point::origin(), Vector::new(0.0, 0.0, 0.0)),
+            (
+                Point::new(1.0, 1.0, 1.0),
+                Vector::new(1.0, -(Scalar::sqrt(2.0)), 1.0),
+            ),
+            (Point::new(-1.0, -1.0, 0.0), Vector::new(-1.0, 1.0, 0.0)),
+        ];
+        for (point, normal) in cases {
+            assert_eq!(shape.local_normal_at(&point, &hit), normal);
+        }
+    }
+
+    #[test]
+    fn normal_on_the_caps_of_a_cone() {
+        let shape = Cone::truncated(-1.0, 2.0, true);
+        let hit = Intersection::new(0.0, &shape);
+        assert_eq!(
+            shape.local_normal_at(&Point::new(0.5, 2.0, 0.0), &hit),
+            Vector::new(0.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            shape.local_normal_at(&Point::new(0.5, -1.0, 0.0), &hit),
+            Vector::new(0.0, -1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn bounded_cone_has_a_bounding_box() {
+        let b = Cone::truncated(-5.0, 3.0, false).local_bounds();
+        assert_eq!(b.min, Point::new(-5.0, -5.0, -5.0));
+        assert_eq!(b.max, Point::new(5.0, 3.0, 5.0));
+    }
+
+    mod sampling {
+        use super::*;
+
+        #[test]
+        fn samples_lie_on_the_surface() {
+            let c = Cone::truncated(-1.0, 2.0, false);
+            for (u, v) in [(0.0, 0.0), (0.5, 0.3), (0.9, 0.8)] {
+                let (point, normal) = c.sample_point(u, v).unwrap();
+                let radius = (point.x().powi(2) + point.z().powi(2)).sqrt();
+                assert!((radius - point.y().abs()).abs() < EPSILON);
+                let expected = c.local_normal_at(&point, &Intersection::new(0.0, &c));
+                assert_eq!(normal, expected.normalize());
+            }
+            assert_eq!(Cone::new().sample_point(0.5, 0.5), None);
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use crate::shape;
+use crate::{BoundingBox, Intersection, Intersections, Shape, ShapeData};
+use rt_core::{Matrix, Point, Ray, Vector};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOperation {
+    pub fn allows(&self, left_hit: bool, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => (left_hit && !in_right) || (!left_hit && !in_left),
+            CsgOperation::Intersection => (left_hit && in_right) || (!left_hit && in_left),
+            CsgOperation::Difference => (left_hit && !in_right) || (!left_hit && in_left),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Csg {
+    data: ShapeData,
+    operation: CsgOperation,
+    left: Box<dyn Shape>,
+    right: Box<dyn Shape>,
+    bounds: BoundingBox,
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, left: Box<dyn Shape>, right: Box<dyn Shape>) -> Self {
+        let mut csg = Csg {
+            data: ShapeData::default(),
+            operation,
+            left,
+            right,
+            bounds: BoundingBox::empty(),
+        };
+        csg.update_bounds();
+        csg
+    }
+
+    pub fn union(left: Box<dyn Shape>, right: Box<dyn Shape>) -> Self {
+        Csg::new(CsgOperation::Union, left, right)
+    }
+
+    pub fn intersection(left: Box<dyn Shape>, right: Box<dyn Shape>) -> Self {
+        Csg::new(CsgOperation::Intersection, left, right)
+    }
+
+    pub fn difference(left: Box<dyn Shape>, right: Box<dyn Shape>) -> Self {
+        Csg::new(CsgOperation::Difference, left, right)
+    }
+
+    pub fn operation(&self) -> CsgOperation {
+        self.operation
+    }
+
+    pub fn left(&self) -> &dyn Shape {
+        self.left.as_ref()
+    }
+
+    pub fn right(&self) -> &dyn Shape {
+        self.right.as_ref()
+    }
+
+    pub fn filter_intersections<'a>(&self, xs: Intersections<'a>) -> Intersections<'a> {
+        let mut in_left = false;
+        let mut in_right = false;
+        let mut result = Vec::new();
+        for i in xs {
+            let left_hit = self.left.includes(i.object);
+            if self.operation.allows(left_hit, in_left, in_right) {
+                result.push(i);
+            }
+            if left_hit {
+                in_left = !in_left;
+            } else {
+                in_right = !in_right;
+            }
+        }
+        Intersections::new(result)
+    }
+
+    fn update_bounds(&mut self) {
+        self.bounds = self.left.bounds();
+        self.bounds.merge(&self.right.bounds());
+    }
+}
+
+impl Shape for Csg {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn set_transform(&mut self, transform: Matrix<4, 4>) {
+        let rebase = transform * *self.inverse_transform();
+        shape::rebase(self.left.as_mut(), &rebase);
+        shape::rebase(self.right.as_mut(), &rebase);
+        self.data.set_transform(transform);
+        self.update_bounds();
+    }
+
+    fn set_motion(&mut self, end: Matrix<4, 4>) {
+        let inverse = *self.inverse_transform();
+        shape::rebase_motion(self.left.as_mut(), &inverse, &end);
+        shape::rebase_motion(self.right.as_mut(), &inverse, &end);
+        self.data.set_motion(end);
+        self.update_bounds();
+    }
+
+    fn set_casts_shadow(&mut self, casts: bool) {
+        self.data.casts_shadow = casts;
+        self.left.set_casts_shadow(casts);
+        self.right.set_casts_shadow(casts);
+    }
+
+    fn set_receives_shadow(&mut self, receives: bool) {
+        self.data.receives_shadow = receives;
+        self.left.set_receives_shadow(receives);
+        self.right.set_receives_shadow(receives);
+    }
+
+    fn intersect(&self, ray: &Ray) -> Intersections<'_> {
+        if !self.bounds().intersects(ray) {
+            return Intersections::default();
+        }
+        let xs = self
+            .left
+            .intersect(ray)
+            .into_iter()
+            .chain(self.right.intersect(ray))
+            .collect();
+        self.filter_intersections(xs)
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        self.intersect(&ray.transform(self.transform()))
+    }
+
+    fn local_normal_at(&self, _point: &Point, _hit: &Intersection) -> Vector {
+        panic!("CSG shapes have no surface; normals are computed on their children")
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        self.bounds().transform(self.inverse_transform())
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    fn divide(&mut self, threshold: usize) {
+        self.left.divide(threshold);
+        self.right.divide(threshold);
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.left.includes(other) || self.right.includes(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::same_shape;
+    use crate::shape::tests::TestShape;
+    use crate::{Cube, Group, Sphere};
+    use rt_core::Scalar;
+
+    #[test]
+    fn csg_is_created_with_an_operation_and_two_shapes() {
+        let c = Csg::union(Box::new(Sphere::new()), Box::new(Cube::new()));
+        assert_eq!(c.operation(), CsgOperation::Union);
+        assert_eq!(*c.left().transform(), Matrix::identity());
+        assert_eq!(*c.right().transform(), Matrix::identity());
+    }
+
+    #[test]
+    fn evaluating_the_rule_for_a_csg_operation() {
+        use CsgOperation::*;
+        let cases = [
+            (Union, true, true, true, false),
+            (Union, true, true, false, true),
+            (Union, true, false, true, false),
+            (Union, true, false, false, true),
+            (Union, false, true, true, false),
+            (Union, false, true, false, false),
+            (Union, false, false, true, true),
+            (Union, false, false, false, true),
+            (Intersection, true, true, true, true),
+            (Intersection, true, true, false, false),
+            (Intersection, true, false, true, true),
+            (Intersection, true, false, false, false),
+            (Intersection, false, true, true, true),
+            (Intersection, false, true, false, true),
+            (Intersection, false, false, true, false),
+            (Intersection, false, false, false, false),
+            (Difference, true, true, true, false),
+            (Difference, true, true, false, true),
+            (Difference, true, false, true, false),
+            (Difference, true, false, false, true),
+            (Difference, false, true, true, true),
+            (Difference, false, true, false, true),
+            (Difference, false, false, true, false),
+            (Difference, false, false, false, false),
+        ];
+        for (op, lhit, inl, inr, expected) in cases {
+            assert_eq!(
+                op.allows(lhit, inl, inr),
+                expected,
+                "{:?}",
+                (op, lhit, inl, inr)
+            );
+        }
+    }
+
+    #[test]
+    fn filtering_a_list_of_intersections() {
+        let cases = [
+            (CsgOperation::Union, 0, 3),
+            (CsgOperation::Intersection, 1, 2),
+            (CsgOperation::Difference, 0, 1),
+        ];
+        for (operation, x0, x1) in cases {
+            let c = Csg::new(operation, Box::new(Sphere::new()), Box::new(Cube::new()));
+            let (s1, s2) = (c.left(), c.right());
+            let xs = Intersections::new(vec![
+                Intersection::new(1.0, s1),
+                Intersection::new(2.0, s2),
+                Intersection::new(3.0, s1),
+                Intersection::new(4.0, s2),
+            ]);
+            let result = c.filter_intersections(xs.clone());
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0], xs[x0]);
+            assert_eq!(result[1], xs[x1]);
+        }
+    }
+
+    #[test]
+    fn includes_looks_through_nested_groups() {
+        let mut g = Group::new();
+        g.add_child(Box::new(Sphere::new()));
+        let c = Csg::difference(Box::new(g), Box::new(Cube::new()));
+        let left = (c.left() as &dyn std::any::Any)
+            .downcast_ref::<Group>()
+            .unwrap()
+            .children()[0]
+            .as_ref();
+        assert!(c.left().includes(left));
+        assert!(!c.right().includes(left));
+        assert!(c.includes(left));
+    }
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn ray_misses_a_csg_object() {
+            let c = Csg::union(Box::new(Sphere::new()), Box::new(Cube::new()));
+            let r = Ray::new(Point::new(0.0, 2.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert!(c.intersect(&r).is_empty());
+        }
+
+        #[test]
+        fn ray_hits_a_csg_object() {
+            let s1 = Sphere::new();
+            let mut s2 = Sphere::new();
+            s2.set_transform(Matrix::translation(0.0, 0.0, 0.5));
+            let c = Csg::union(Box::new(s1), Box::new(s2));
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let xs = c.intersect(&r);
+            assert_eq!(xs.len(), 2);
+            assert_eq!(xs[0].t, 4.0);
+            assert!(same_shape(xs[0].object, c.left()));
+            assert_eq!(xs[1].t, 6.5);
+            assert!(same_shape(xs[1].object, c.right()));
+        }
+
+        #[test]
+        fn ray_misses_the_bounding_box_and_skips_children() {
+            let c = Csg::difference(Box::<TestShape>::default(), Box::<TestShape>::default());
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0));
+            c.intersect(&r);
+            for child in [c.left(), c.right()] {
+                let child = (child as &dyn std::any::Any)
+                    .downcast_ref::<TestShape>()
+                    .unwrap();
+                assert!(child.saved_ray.lock().unwrap().is_none());
+            }
+        }
+
+        #[test]
+        fn transforming_a_csg_transforms_its_children() {
+            let mut c = Csg::union(Box::new(Sphere::new()), Box::new(Cube::new()));
+            c.set_transform(Matrix::translation(0.0, 0.0, 10.0));
+            assert_eq!(*c.left().transform(), Matrix::translation(0.0, 0.0, 10.0));
+            assert_eq!(*c.right().transform(), Matrix::translation(0.0, 0.0, 10.0));
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let ts: Vec<Scalar> = c.intersect(&r).iter().map(|i| i.t).collect();
+            assert_eq!(ts, vec![14.0, 16.0]);
+        }
+    }
+
+    #[test]
+    fn csg_has_a_bounding_box_that_contains_its_children() {
+        let mut right = Sphere::new();
+        right.set_transform(Matrix::translation(2.0, 3.0, 4.0));
+        let c = Csg::difference(Box::new(Sphere::new()), Box::new(right));
+        let b = c.bounds();
+        assert_eq!(b.min, Point::new(-1.0, -1.0, -1.0));
+        assert_eq!(b.max, Point::new(3.0, 4.0, 5.0));
+    }
+
+    #[test]
+    fn bounds_are_kept_in_step_with_transforms_and_motion() {
+        let mut c = Csg::union(Box::new(Sphere::new()), Box::new(Cube::new()));
+        c.set_transform(Matrix::translation(0.0, 0.0, 10.0));
+        assert_eq!(c.bounds().min, Point::new(-1.0, -1.0, 9.0));
+        c.set_motion(Matrix::translation(4.0, 0.0, 10.0));
+        assert_eq!(c.bounds().min, Point::new(-1.0, -1.0, 9.0));
+        assert_eq!(c.bounds().max, Point::new(5.0, 1.0, 11.0));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::bounds::check_axis;
+use crate::{BoundingBox, Intersection, Intersections, Shape, ShapeBuilder, ShapeData};
+use rt_core::{Point, Ray, Scalar, Vector};
+
+#[derive(Debug, Default)]
+pub struct Cube {
+    data: ShapeData,
+}
+
+impl Cube {
+    pub fn new() -> Self {
+        Cube::default()
+    }
+
+    pub fn builder() -> ShapeBuilder<Cube> {
+        ShapeBuilder::new(Cube::new())
+    }
+}
+
+impl Shape for Cube {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let (xtmin, xtmax) = check_axis(ray.origin.x(), ray.direction.x(), -1.0, 1.0);
+        let (ytmin, ytmax) = check_axis(ray.origin.y(), ray.direction.y(), -1.0, 1.0);
+        let (ztmin, ztmax) = check_axis(ray.origin.z(), ray.direction.z(), -1.0, 1.0);
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+        if tmin > tmax {
+            return Intersections::default();
+        }
+        Intersections::new(vec![
+            Intersection::new(tmin, self),
+            Intersection::new(tmax, self),
+        ])
+    }
+
+    fn local_normal_at(&self, point: &Point, _hit: &Intersection) -> Vector {
+        let (x, y, z) = (point.x().abs(), point.y().abs(), point.z().abs());
+        let maxc = x.max(y).max(z);
+        if maxc == x {
+            Vector::new(point.x(), 0.0, 0.0)
+        } else if maxc == y {
+            Vector::new(0.0, point.y(), 0.0)
+        } else {
+            Vector::new(0.0, 0.0, point.z())
+        }
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+    }
+
+    fn local_sample_point(&self, u: Scalar, v: Scalar) -> Option<(Point, Vector)> {
+        let scaled = (u * 6.0).min(5.999_999);
+        let face = scaled as usize;
+        let a = (scaled - face as Scalar) * 2.0 - 1.0;
+        let b = v * 2.0 - 1.0;
+        let sign = if face.is_multiple_of(2) { 1.0 } else { -1.0 };
+        Some(match face / 2 {
+            0 => (Point::new(sign, a, b), Vector::new(sign, 0.0, 0.0)),
+            1 => (Point::new(a, sign, b), Vector::new(0.0, sign, 0.0)),
+            _ => (Point::new(a, b, sign), Vector::new(0.0, 0.0, sign)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ray_intersects_a_cube() {
+        let c = Cube::new();
+        let cases = [
+            (
+                Point::new(5.0, 0.5, 0.0),
+                Vector::new(-1.0, 0.0, 0.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point::new(-5.0, 0.5, 0.0),
+                Vector::new(1.0, 0.0, 0.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point::new(0.5, 5.0, 0.0),
+                Vector::new(0.0, -1.0, 0.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point::new(0.5, -5.0, 0.0),
+                Vector::new(0.0, 1.0, 0.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point::new(0.5, 0.0, 5.0),
+                Vector::new(0.0, 0.0, -1.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point::new(0.5, 0.0, -5.0),
+                Vector::new(0.0, 0.0, 1.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point::new(0.0, 0.5, 0.0),
+                Vector::new(0.0, 0.0, 1.0),
+                -1.0,
+                1.0,
+            ),
+        ];
+        for (origin, direction, t1, t2) in cases {
+            let xs = c.local_intersect(&Ray::new(origin, direction));
+            assert_eq!(xs.len(), 2);
+            assert_eq!(xs[0].t, t1);
+            assert_eq!(xs[1].t, t2);
+        }
+    }
+
+    #[test]
+    fn ray_misses_a_cube() {
+        let c = Cube::new();
+        let cases = [
+            (
+                Point::new(-2.0, 0.0, 0.0),
+                Vector::new(0.2673, 0.5345, 0.8018),
+            ),
+            (
+                Point::new(0.0, -2.0, 0.0),
+                Vector::new(0.8018, 0.2673, 0.5345),
+            ),
+            (
+                Point::new(0.0, 0.0, -2.0),
+                Vector::new(0.5345, 0.8018, 0.2673),
+            ),
+            (Point::new(2.0, 0.0, 2.0), Vector::new(0.0, 0.0, -1.0)),
+            (Point::new(0.0, 2.0, 2.0), Vector::new(0.0, -1.0, 0.0)),
+            (Point::new(2.0, 2.0, 0.0), Vector::new(-1.0, 0.0, 0.0)),
+        ];
+        for (origin, direction) in cases {
+            assert!(c.local_intersect(&Ray::new(origin, direction)).is_empty());
+        }
+    }
+
+    #[test]
+    fn normal_on_the_surface_of_a_cube() {
+        let c = Cube::new();
+        let hit = Intersection::new(0.0, &c);
+        let cases = [
+            (Point::new(1.0, 0.5, -0.8), Vector::new(1.0, 0.0, 0.0)),
+            (Point::new(-1.0, -0.2, 0.9), Vector::new(-1.0, 0.0, 0.0)),
+            (Point::new(-0.4, 1.0, -0.1), Vector::new(0.0, 1.0, 0.0)),
+            (Point::new(0.3, -1.0, -0.7), Vector::new(0.0, -1.0, 0.0)),
+            (Point::new(-0.6, 0.3, 1.0), Vector::new(0.0, 0.0, 1.0)),
+            (Point::new(0.4, 0.4, -1.0), Vector::new(0.0, 0.0, -1.0)),
+            (Point::new(1.0, 1.0, 1.0), Vector::new(1.0, 0.0, 0.0)),
+            (Point::new(-1.0, -1.0, -1.0), Vector::new(-1.0, 0.0, 0.0)),
+        ];
+        for (point, normal) in cases {
+            assert_eq!(c.local_normal_at(&point, &hit), normal);
+        }
+    }
+
+    #[test]
+    fn cube_has_a_bounding_box() {
+        let b = Cube::new().local_bounds();
+        assert_eq!(b.min, Point::new(-1.0, -1.0, -1.0));
+        assert_eq!(b.max, Point::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn samples_cover_every_face() {
+        let c = Cube::new();
+        let mut normals = Vec::new();
+        for face in 0..6 {
+            let u = (face as Scalar + 0.5) / 6.0;
+            let (point, normal) = c.sample_point(u, 0.3).unwrap();
+            let largest = point.x().abs().max(point.y().abs()).max(point.z().abs());
+            assert_eq!(largest, 1.0);
+            assert_eq!(
+                normal,
+                c.local_normal_at(&point, &Intersection::new(0.0, &c))
+            );
+            normals.push(normal);
+        }
+        normals.dedup();
+        assert_eq!(normals.len(), 6);
+    }
+}
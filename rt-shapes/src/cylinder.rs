@@ -0,0 +1,299 @@
+use crate::{BoundingBox, Intersection, Intersections, Shape, ShapeBuilder, ShapeData};
+use rt_core::{Point, Ray, Scalar, Vector, EPSILON};
+
+#[derive(Debug)]
+pub struct Cylinder {
+    data: ShapeData,
+    pub minimum: Scalar,
+    pub maximum: Scalar,
+    pub closed: bool,
+}
+
+impl Default for Cylinder {
+    fn default() -> Self {
+        Cylinder {
+            data: ShapeData::default(),
+            minimum: Scalar::NEG_INFINITY,
+            maximum: Scalar::INFINITY,
+            closed: false,
+        }
+    }
+}
+
+impl Cylinder {
+    pub fn new() -> Self {
+        Cylinder::default()
+    }
+
+    pub fn builder() -> ShapeBuilder<Cylinder> {
+        ShapeBuilder::new(Cylinder::new())
+    }
+
+    pub fn truncated(minimum: Scalar, maximum: Scalar, closed: bool) -> Self {
+        Cylinder {
+            minimum,
+            maximum,
+            closed,
+            ..Cylinder::default()
+        }
+    }
+
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        if !self.closed || ray.direction.y().abs() < EPSILON {
+            return;
+        }
+        for y in [self.minimum, self.maximum] {
+            let t = (y - ray.origin.y()) / ray.direction.y();
+            if check_cap(ray, t, 1.0) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+}
+
+pub(crate) fn check_cap(ray: &Ray, t: Scalar, radius: Scalar) -> bool {
+    let x = ray.origin.x() + t * ray.direction.x();
+    let z = ray.origin.z() + t * ray.direction.z();
+    x * x + z * z <= radius * radius + EPSILON
+}
+
+impl Shape for Cylinder {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let mut xs = Vec::new();
+        let a = ray.direction.x().powi(2) + ray.direction.z().powi(2);
+        if a.abs() >= EPSILON {
+            let b =
+                2.0 * ray.origin.x() * ray.direction.x() + 2.0 * ray.origin.z() * ray.direction.z();
+            let c = ray.origin.x().powi(2) + ray.origin.z().powi(2) - 1.0;
+            let discriminant = b * b - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return Intersections::default();
+            }
+
+            let root = discriminant.sqrt();
+            for t in [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)] {
+                let y = ray.origin.y() + t * ray.direction.y();
+                if self.minimum < y && y < self.maximum {
+                    xs.push(Intersection::new(t, self));
+                }
+            }
+        }
+        self.intersect_caps(ray, &mut xs);
+        Intersections::new(xs)
+    }
+
+    fn local_normal_at(&self, point: &Point, _hit: &Intersection) -> Vector {
+        let dist = point.x().powi(2) + point.z().powi(2);
+        if dist < 1.0 && point.y() >= self.maximum - EPSILON {
+            Vector::new(0.0, 1.0, 0.0)
+        } else if dist < 1.0 && point.y() <= self.minimum + EPSILON {
+            Vector::new(0.0, -1.0, 0.0)
+        } else {
+            Vector::new(point.x(), 0.0, point.z())
+        }
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Point::new(-1.0, self.minimum, -1.0),
+            Point::new(1.0, self.maximum, 1.0),
+        )
+    }
+
+    fn local_sample_point(&self, u: Scalar, v: Scalar) -> Option<(Point, Vector)> {
+        if !self.minimum.is_finite() || !self.maximum.is_finite() {
+            return None;
+        }
+        let y = self.minimum + u * (self.maximum - self.minimum);
+        let theta = 2.0 * rt_core::consts::PI * v;
+        let (x, z) = (theta.cos(), theta.sin());
+        Some((Point::new(x, y, z), Vector::new(x, 0.0, z)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(xs: &Intersections) -> Vec<Scalar> {
+        xs.iter().map(|i| i.t).collect()
+    }
+
+    #[test]
+    fn default_cylinder_is_infinite_and_open() {
+        let c = Cylinder::new();
+        assert_eq!(c.minimum, Scalar::NEG_INFINITY);
+        assert_eq!(c.maximum, Scalar::INFINITY);
+        assert!(!c.closed);
+    }
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn ray_misses_a_cylinder() {
+            let c = Cylinder::new();
+            let cases = [
+                (Point::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0)),
+                (Point::origin(), Vector::new(0.0, 1.0, 0.0)),
+                (Point::new(0.0, 0.0, -5.0), Vector::new(1.0, 1.0, 1.0)),
+            ];
+            for (origin, direction) in cases {
+                let r = Ray::new(origin, direction.normalize());
+                assert!(c.local_intersect(&r).is_empty());
+            }
+        }
+
+        #[test]
+        fn ray_strikes_a_cylinder() {
+            let c = Cylinder::new();
+            let cases = [
+                (
+                    Point::new(1.0, 0.0, -5.0),
+                    Vector::new(0.0, 0.0, 1.0),
+                    5.0,
+                    5.0,
+                ),
+                (
+                    Point::new(0.0, 0.0, -5.0),
+                    Vector::new(0.0, 0.0, 1.0),
+                    4.0,
+                    6.0,
+                ),
+                (
+                    Point::new(0.5, 0.0, -5.0),
+                    Vector::new(0.1, 1.0, 1.0),
+                    6.80798,
+                    7.08872,
+                ),
+            ];
+            for (origin, direction, t0, t1) in cases {
+                let xs = c.local_intersect(&Ray::new(origin, direction.normalize()));
+                assert_eq!(xs.len(), 2);
+                assert!((xs[0].t - t0).abs() < EPSILON);
+                assert!((xs[1].t - t1).abs() < EPSILON);
+            }
+        }
+
+        #[test]
+        fn intersecting_a_truncated_cylinder() {
+            let c = Cylinder::truncated(1.0, 2.0, false);
+            let cases = [
+                (Point::new(0.0, 1.5, 0.0), Vector::new(0.1, 1.0, 0.0), 0),
+                (Point::new(0.0, 3.0, -5.0), Vector::new(0.0, 0.0, 1.0), 0),
+                (Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0), 0),
+                (Point::new(0.0, 2.0, -5.0), Vector::new(0.0, 0.0, 1.0), 0),
+                (Point::new(0.0, 1.0, -5.0), Vector::new(0.0, 0.0, 1.0), 0),
+                (Point::new(0.0, 1.5, -2.0), Vector::new(0.0, 0.0, 1.0), 2),
+            ];
+            for (origin, direction, count) in cases {
+                let xs = c.local_intersect(&Ray::new(origin, direction.normalize()));
+                assert_eq!(xs.len(), count);
+            }
+        }
+
+        #[test]
+        fn intersecting_the_caps_of_a_closed_cylinder() {
+            let c = Cylinder::truncated(1.0, 2.0, true);
+            let cases = [
+                (Point::new(0.0, 3.0, 0.0), Vector::new(0.0, -1.0, 0.0), 2),
+                (Point::new(0.0, 3.0, -2.0), Vector::new(0.0, -1.0, 2.0), 2),
+                (Point::new(0.0, 4.0, -2.0), Vector::new(0.0, -1.0, 1.0), 2),
+                (Point::new(0.0, 0.0, -2.0), Vector::new(0.0, 1.0, 2.0), 2),
+                (Point::new(0.0, -1.0, -2.0), Vector::new(0.0, 1.0, 1.0), 2),
+            ];
+            for (origin, direction, count) in cases {
+                let xs = c.local_intersect(&Ray::new(origin, direction.normalize()));
+                assert_eq!(xs.len(), count);
+            }
+        }
+
+        #[test]
+        fn ray_along_the_axis_of_a_closed_cylinder_hits_both_caps() {
+            let c = Cylinder::truncated(1.0, 2.0, true);
+            let r = Ray::new(Point::new(0.0, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+            assert_eq!(ts(&c.local_intersect(&r)), vec![3.0, 4.0]);
+        }
+    }
+
+    mod normal {
+        use super::*;
+
+        #[test]
+        fn on_the_surface_of_a_cylinder() {
+            let c = Cylinder::new();
+            let hit = Intersection::new(0.0, &c);
+            let cases = [
+                (Point::new(1.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0)),
+                (Point::new(0.0, 5.0, -1.0), Vector::new(0.0, 0.0, -1.0)),
+                (Point::new(0.0, -2.0, 1.0), Vector::new(0.0, 0.0, 1.0)),
+                (Point::new(-1.0, 1.0, 0.0), Vector::new(-1.0, 0.0, 0.0)),
+            ];
+            for (point, normal) in cases {
+                assert_eq!(c.local_normal_at(&point, &hit), normal);
+            }
+        }
+
+        #[test]
+        fn on_the_end_caps_of_a_cylinder() {
+            let c = Cylinder::truncated(1.0, 2.0, true);
+            let hit = Intersection::new(0.0, &c);
+            let cases = [
+                (Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0)),
+                (Point::new(0.5, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0)),
+                (Point::new(0.0, 1.0, 0.5), Vector::new(0.0, -1.0, 0.0)),
+                (Point::new(0.0, 2.0, 0.0), Vector::new(0.0, 1.0, 0.0)),
+                (Point::new(0.5, 2.0, 0.0), Vector::new(0.0, 1.0, 0.0)),
+                (Point::new(0.0, 2.0, 0.5), Vector::new(0.0, 1.0, 0.0)),
+            ];
+            for (point, normal) in cases {
+                assert_eq!(c.local_normal_at(&point, &hit), normal);
+            }
+        }
+    }
+
+    mod bounds {
+        use super::*;
+
+        #[test]
+        fn unbounded_cylinder_has_an_infinite_bounding_box() {
+            let b = Cylinder::new().local_bounds();
+            assert_eq!(b.min.x(), -1.0);
+            assert_eq!(b.min.y(), Scalar::NEG_INFINITY);
+            assert_eq!(b.max.y(), Scalar::INFINITY);
+            assert_eq!(b.max.z(), 1.0);
+        }
+
+        #[test]
+        fn bounded_cylinder_has_a_bounding_box() {
+            let b = Cylinder::truncated(-5.0, 3.0, false).local_bounds();
+            assert_eq!(b.min, Point::new(-1.0, -5.0, -1.0));
+            assert_eq!(b.max, Point::new(1.0, 3.0, 1.0));
+        }
+    }
+
+    mod sampling {
+        use super::*;
+
+        #[test]
+        fn infinite_cylinders_cannot_be_sampled() {
+            assert_eq!(Cylinder::new().sample_point(0.5, 0.5), None);
+        }
+
+        #[test]
+        fn samples_lie_on_the_side() {
+            let c = Cylinder::truncated(1.0, 2.0, true);
+            let (point, normal) = c.sample_point(0.5, 0.25).unwrap();
+            assert_eq!(point, Point::new(0.0, 1.5, 1.0));
+            assert_eq!(normal, Vector::new(0.0, 0.0, 1.0));
+        }
+    }
+}
@@ -0,0 +1,520 @@
+use crate::shape::{self, rebase};
+use crate::stats;
+use crate::{BoundingBox, Intersection, Intersections, Material, Shape, ShapeBuilder, ShapeData};
+use rt_core::{Matrix, Point, Ray, Vector};
+use std::any::Any;
+
+type Children = Vec<Box<dyn Shape>>;
+
+#[derive(Debug, Default)]
+pub struct Group {
+    data: ShapeData,
+    children: Children,
+    bounds: BoundingBox,
+}
+
+impl Group {
+    pub fn new() -> Self {
+        Group::default()
+    }
+
+    pub fn builder() -> ShapeBuilder<Group> {
+        ShapeBuilder::new(Group::new())
+    }
+
+    pub fn add_child(&mut self, mut child: Box<dyn Shape>) {
+        rebase(child.as_mut(), self.transform());
+        if let Some(end) = self.end_transform() {
+            shape::rebase_motion(child.as_mut(), self.inverse_transform(), end);
+        }
+        self.bounds.merge(&child.bounds());
+        self.children.push(child);
+    }
+
+    pub fn children(&self) -> &[Box<dyn Shape>] {
+        &self.children
+    }
+
+    pub fn inherit_material(&mut self, material: &Material) {
+        for child in &mut self.children {
+            if let Some(group) = (child.as_mut() as &mut dyn Any).downcast_mut::<Group>() {
+                group.inherit_material(material);
+            }
+            if *child.material() == Material::default() {
+                child.set_material(material.clone());
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    fn update_bounds(&mut self) {
+        self.bounds = BoundingBox::empty();
+        for child in &self.children {
+            self.bounds.merge(&child.bounds());
+        }
+    }
+
+    fn partition_children(&mut self) -> (Children, Children) {
+        let (left_bounds, right_bounds) = self.bounds.split();
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        let mut remaining = Vec::new();
+        for child in self.children.drain(..) {
+            let bounds = child.bounds();
+            if left_bounds.contains_box(&bounds) {
+                left.push(child);
+            } else if right_bounds.contains_box(&bounds) {
+                right.push(child);
+            } else {
+                remaining.push(child);
+            }
+        }
+        self.children = remaining;
+        (left, right)
+    }
+
+    fn add_subgroup(&mut self, shapes: Children) {
+        let mut subgroup = Group::new();
+        for shape in shapes {
+            subgroup.add_child(shape);
+        }
+        self.children.push(Box::new(subgroup));
+    }
+}
+
+impl Shape for Group {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn set_transform(&mut self, transform: Matrix<4, 4>) {
+        let rebase = transform * *self.inverse_transform();
+        for child in &mut self.children {
+            shape::rebase(child.as_mut(), &rebase);
+        }
+        self.data.set_transform(transform);
+        self.update_bounds();
+    }
+
+    fn set_motion(&mut self, end: Matrix<4, 4>) {
+        let inverse = *self.inverse_transform();
+        for child in &mut self.children {
+            shape::rebase_motion(child.as_mut(), &inverse, &end);
+        }
+        self.data.set_motion(end);
+        self.update_bounds();
+    }
+
+    fn set_casts_shadow(&mut self, casts: bool) {
+        self.data.casts_shadow = casts;
+        for child in &mut self.children {
+            child.set_casts_shadow(casts);
+        }
+    }
+
+    fn set_receives_shadow(&mut self, receives: bool) {
+        self.data.receives_shadow = receives;
+        for child in &mut self.children {
+            child.set_receives_shadow(receives);
+        }
+    }
+
+    fn intersect(&self, ray: &Ray) -> Intersections<'_> {
+        stats::record(|stats| stats.node_visits += 1);
+        if !self.bounds.intersects(ray) {
+            return Intersections::default();
+        }
+        self.children
+            .iter()
+            .flat_map(|child| child.intersect(ray))
+            .collect()
+    }
+
+    fn intersect_packet(&self, rays: &[Ray; 4]) -> [Intersections<'_>; 4] {
+        stats::record(|stats| stats.node_visits += 1);
+        let live = rays.each_ref().map(|ray| self.bounds.intersects(ray));
+        if !live.contains(&true) {
+            return Default::default();
+        }
+        let mut lanes: [Vec<Intersection>; 4] = Default::default();
+        for child in &self.children {
+            let packet = child.intersect_packet(rays);
+            for ((lane, hits), &live) in lanes.iter_mut().zip(packet).zip(&live) {
+                if live {
+                    lane.extend(hits);
+                }
+            }
+        }
+        lanes.map(Intersections::new)
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        self.intersect(&ray.transform(self.transform()))
+    }
+
+    fn local_normal_at(&self, _point: &Point, _hit: &Intersection) -> Vector {
+        panic!("groups have no surface; normals are computed on their children")
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        self.bounds.transform(self.inverse_transform())
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    fn divide(&mut self, threshold: usize) {
+        if threshold <= self.children.len() {
+            let (left, right) = self.partition_children();
+            if !left.is_empty() {
+                self.add_subgroup(left);
+            }
+            if !right.is_empty() {
+                self.add_subgroup(right);
+            }
+        }
+        for child in &mut self.children {
+            child.divide(threshold);
+        }
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.children.iter().any(|child| child.includes(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::TestShape;
+    use crate::Sphere;
+    use rt_core::consts::PI;
+    use rt_core::Scalar;
+
+    #[test]
+    fn creating_a_new_group() {
+        let g = Group::new();
+        assert_eq!(*g.transform(), Matrix::identity());
+        assert!(g.is_empty());
+    }
+
+    #[test]
+    fn adding_a_child_to_a_group() {
+        let mut g = Group::new();
+        g.add_child(Box::<TestShape>::default());
+        assert_eq!(g.len(), 1);
+        assert_eq!(*g.children()[0].transform(), Matrix::identity());
+    }
+
+    #[test]
+    fn shadow_flags_propagate_to_children() {
+        let mut inner = Group::new();
+        inner.add_child(Box::<TestShape>::default());
+        let mut g = Group::new();
+        g.add_child(Box::new(inner));
+        g.set_casts_shadow(false);
+        g.set_receives_shadow(false);
+        let inner = (g.children()[0].as_ref() as &dyn std::any::Any)
+            .downcast_ref::<Group>()
+            .unwrap();
+        assert!(!inner.casts_shadow());
+        assert!(!inner.children()[0].casts_shadow());
+        assert!(!inner.children()[0].receives_shadow());
+    }
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn ray_with_an_empty_group() {
+            let r = Ray::new(Point::origin(), Vector::new(0.0, 0.0, 1.0));
+            assert!(Group::new().intersect(&r).is_empty());
+        }
+
+        #[test]
+        fn ray_with_a_nonempty_group() {
+            let mut g = Group::new();
+            g.add_child(Box::new(Sphere::new()));
+            let mut s2 = Sphere::new();
+            s2.set_transform(Matrix::translation(0.0, 0.0, -3.0));
+            g.add_child(Box::new(s2));
+            let mut s3 = Sphere::new();
+            s3.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+            g.add_child(Box::new(s3));
+
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let xs = g.intersect(&r);
+            let children = g.children();
+            assert_eq!(xs.len(), 4);
+            assert!(crate::shape::same_shape(xs[0].object, children[1].as_ref()));
+            assert!(crate::shape::same_shape(xs[1].object, children[1].as_ref()));
+            assert!(crate::shape::same_shape(xs[2].object, children[0].as_ref()));
+            assert!(crate::shape::same_shape(xs[3].object, children[0].as_ref()));
+        }
+
+        #[test]
+        fn ray_with_a_transformed_group() {
+            let mut g = Group::new();
+            g.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+            g.add_child(Box::new(s));
+            let r = Ray::new(Point::new(10.0, 0.0, -10.0), Vector::new(0.0, 0.0, 1.0));
+            assert_eq!(g.intersect(&r).len(), 2);
+        }
+
+        #[test]
+        fn transforming_a_group_after_adding_children() {
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+            let mut g = Group::new();
+            g.add_child(Box::new(s));
+            g.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+            let r = Ray::new(Point::new(10.0, 0.0, -10.0), Vector::new(0.0, 0.0, 1.0));
+            assert_eq!(g.intersect(&r).len(), 2);
+            assert_eq!(
+                *g.children()[0].transform(),
+                Matrix::scaling(2.0, 2.0, 2.0) * Matrix::translation(5.0, 0.0, 0.0)
+            );
+        }
+
+        #[test]
+        fn local_intersect_matches_intersect() {
+            let mut g = Group::new();
+            g.set_transform(Matrix::translation(0.0, 0.0, 3.0));
+            g.add_child(Box::new(Sphere::new()));
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let local = r.transform(g.inverse_transform());
+            let ts: Vec<Scalar> = g.local_intersect(&local).iter().map(|i| i.t).collect();
+            assert_eq!(ts, vec![7.0, 9.0]);
+        }
+    }
+
+    mod parent_chain {
+        use super::*;
+
+        fn nested(scaling: Matrix<4, 4>) -> Group {
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+            let mut g2 = Group::new();
+            g2.set_transform(scaling);
+            g2.add_child(Box::new(s));
+            let mut g1 = Group::new();
+            g1.set_transform(Matrix::rotation_y(PI / 2.0));
+            g1.add_child(Box::new(g2));
+            g1
+        }
+
+        fn innermost(g1: &Group) -> &dyn Shape {
+            let g2 = g1.children()[0].as_ref() as &dyn std::any::Any;
+            g2.downcast_ref::<Group>().unwrap().children()[0].as_ref()
+        }
+
+        #[test]
+        fn converting_a_point_from_world_to_object_space() {
+            let g1 = nested(Matrix::scaling(2.0, 2.0, 2.0));
+            let s = innermost(&g1);
+            assert_eq!(
+                s.world_to_object(&Point::new(-2.0, 0.0, -10.0)),
+                Point::new(0.0, 0.0, -1.0)
+            );
+        }
+
+        #[test]
+        fn converting_a_normal_from_object_to_world_space() {
+            let g1 = nested(Matrix::scaling(1.0, 2.0, 3.0));
+            let s = innermost(&g1);
+            let k = Scalar::sqrt(3.0) / 3.0;
+            assert_eq!(
+                s.normal_to_world(&Vector::new(k, k, k)),
+                Vector::new(2.0, 3.0, -6.0) / 7.0
+            );
+        }
+
+        #[test]
+        fn finding_the_normal_on_a_child_object() {
+            let g1 = nested(Matrix::scaling(1.0, 2.0, 3.0));
+            let s = innermost(&g1);
+            let hit = Intersection::new(0.0, s);
+            let r3 = Scalar::sqrt(3.0);
+            assert_eq!(
+                s.normal_at(&Point::new(r3, 2.0 / r3, -5.0 - 1.0 / r3), &hit),
+                Vector::new(2.0, 3.0, -6.0) / 7.0
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "groups have no surface")]
+    fn groups_have_no_local_normal() {
+        let g = Group::new();
+        g.local_normal_at(&Point::origin(), &Intersection::new(0.0, &g));
+    }
+
+    mod bounds {
+        use super::*;
+        use crate::Cylinder;
+
+        fn sphere_at(x: Scalar, y: Scalar, z: Scalar) -> Box<dyn Shape> {
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::translation(x, y, z));
+            Box::new(s)
+        }
+
+        fn as_group(shape: &dyn Shape) -> &Group {
+            (shape as &dyn std::any::Any).downcast_ref().unwrap()
+        }
+
+        #[test]
+        fn group_has_a_bounding_box_that_contains_its_children() {
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::translation(2.0, 5.0, -3.0) * Matrix::scaling(2.0, 2.0, 2.0));
+            let mut c = Cylinder::truncated(-2.0, 2.0, false);
+            c.set_transform(Matrix::translation(-4.0, -1.0, 4.0) * Matrix::scaling(0.5, 1.0, 0.5));
+            let mut g = Group::new();
+            g.add_child(Box::new(s));
+            g.add_child(Box::new(c));
+            let b = g.bounds();
+            assert_eq!(b.min, Point::new(-4.5, -3.0, -5.0));
+            assert_eq!(b.max, Point::new(4.0, 7.0, 4.5));
+        }
+
+        #[test]
+        fn bounds_follow_the_group_transform() {
+            let mut g = Group::new();
+            g.add_child(Box::new(Sphere::new()));
+            g.set_transform(Matrix::translation(10.0, 0.0, 0.0));
+            assert_eq!(g.bounds().min, Point::new(9.0, -1.0, -1.0));
+            assert_eq!(g.local_bounds().min, Point::new(-1.0, -1.0, -1.0));
+        }
+
+        #[test]
+        fn ray_misses_the_bounding_box_and_skips_children() {
+            let mut g = Group::new();
+            g.add_child(Box::<TestShape>::default());
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0));
+            g.intersect(&r);
+            let child = (g.children()[0].as_ref() as &dyn std::any::Any)
+                .downcast_ref::<TestShape>()
+                .unwrap();
+            assert!(child.saved_ray.lock().unwrap().is_none());
+        }
+
+        #[test]
+        fn ray_hits_the_bounding_box_and_tests_children() {
+            let mut g = Group::new();
+            g.add_child(Box::<TestShape>::default());
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            g.intersect(&r);
+            let child = (g.children()[0].as_ref() as &dyn std::any::Any)
+                .downcast_ref::<TestShape>()
+                .unwrap();
+            assert!(child.saved_ray.lock().unwrap().is_some());
+        }
+
+        #[test]
+        fn partitioning_a_groups_children() {
+            let mut g = Group::new();
+            g.add_child(sphere_at(-2.0, 0.0, 0.0));
+            g.add_child(sphere_at(2.0, 0.0, 0.0));
+            g.add_child(Box::new(Sphere::new()));
+            let (left, right) = g.partition_children();
+            assert_eq!(g.len(), 1);
+            assert_eq!(left.len(), 1);
+            assert_eq!(right.len(), 1);
+            assert_eq!(left[0].bounds().min, Point::new(-3.0, -1.0, -1.0));
+            assert_eq!(right[0].bounds().min, Point::new(1.0, -1.0, -1.0));
+        }
+
+        #[test]
+        fn subdividing_a_group_partitions_its_children() {
+            let mut g = Group::new();
+            g.add_child(sphere_at(-2.0, -2.0, 0.0));
+            g.add_child(sphere_at(-2.0, 2.0, 0.0));
+            let mut s3 = Sphere::new();
+            s3.set_transform(Matrix::scaling(4.0, 4.0, 4.0));
+            g.add_child(Box::new(s3));
+            g.divide(1);
+
+            assert_eq!(g.len(), 2);
+            let subgroup = as_group(g.children()[1].as_ref());
+            assert_eq!(subgroup.len(), 2);
+            assert_eq!(as_group(subgroup.children()[0].as_ref()).len(), 1);
+            assert_eq!(as_group(subgroup.children()[1].as_ref()).len(), 1);
+        }
+
+        #[test]
+        fn subdividing_a_group_with_too_few_children() {
+            let mut subgroup = Group::new();
+            subgroup.add_child(sphere_at(-2.0, 0.0, 0.0));
+            subgroup.add_child(sphere_at(2.0, 1.0, 0.0));
+            subgroup.add_child(sphere_at(2.0, -1.0, 0.0));
+            let mut g = Group::new();
+            g.add_child(Box::new(subgroup));
+            g.add_child(Box::new(Sphere::new()));
+            g.divide(3);
+
+            assert_eq!(g.len(), 2);
+            let subgroup = as_group(g.children()[0].as_ref());
+            assert_eq!(subgroup.len(), 2);
+            assert_eq!(as_group(subgroup.children()[0].as_ref()).len(), 1);
+            assert_eq!(as_group(subgroup.children()[1].as_ref()).len(), 2);
+        }
+
+        #[test]
+        fn subdivided_group_intersects_like_the_original() {
+            let mut g = Group::new();
+            for i in 0..20 {
+                g.add_child(sphere_at(i as Scalar * 3.0 - 30.0, 0.0, 0.0));
+            }
+            let r = Ray::new(
+                Point::new(-30.0, 0.0, -5.0),
+                Vector::new(1.0, 0.0, 0.8).normalize(),
+            );
+            let before: Vec<Scalar> = g.intersect(&r).iter().map(|i| i.t).collect();
+            g.divide(2);
+            let after: Vec<Scalar> = g.intersect(&r).iter().map(|i| i.t).collect();
+            assert!(!before.is_empty());
+            assert_eq!(before, after);
+        }
+
+        #[test]
+        fn packets_match_single_rays_through_a_hierarchy() {
+            let mut g = Group::new();
+            for i in 0..20 {
+                g.add_child(sphere_at(i as Scalar * 3.0 - 30.0, 0.0, 0.0));
+            }
+            g.divide(2);
+            let rays = [-30.0, -3.0, 0.5, 40.0]
+                .map(|x| Ray::new(Point::new(x, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0)));
+            for (ray, hits) in rays.iter().zip(g.intersect_packet(&rays)) {
+                let expected: Vec<Scalar> = g.intersect(ray).iter().map(|i| i.t).collect();
+                let actual: Vec<Scalar> = hits.iter().map(|i| i.t).collect();
+                assert_eq!(actual, expected);
+            }
+        }
+
+        #[test]
+        fn a_packet_that_misses_the_bounds_finds_nothing() {
+            let mut g = Group::new();
+            g.add_child(Box::new(Sphere::new()));
+            let miss = Ray::new(Point::new(5.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert!(g
+                .intersect_packet(&[miss; 4])
+                .iter()
+                .all(|xs| xs.is_empty()));
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{BoundingBox, Intersection, Intersections, Material, Shape, ShapeData};
+use rt_core::{Point, Ray, Scalar, Vector};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct Instance {
+    data: ShapeData,
+    prototype: Arc<dyn Shape>,
+    material: Option<Material>,
+}
+
+impl Instance {
+    pub fn new(prototype: Arc<dyn Shape>) -> Self {
+        Instance {
+            data: ShapeData::default(),
+            prototype,
+            material: None,
+        }
+    }
+
+    pub fn prototype(&self) -> &Arc<dyn Shape> {
+        &self.prototype
+    }
+
+    pub fn material_override(&self) -> Option<&Material> {
+        self.material.as_ref()
+    }
+
+    fn inner_hit<'a>(&'a self, hit: &Intersection<'a>) -> Intersection<'a> {
+        Intersection {
+            object: hit.inner.unwrap_or(self.prototype.as_ref()),
+            inner: None,
+            ..*hit
+        }
+    }
+}
+
+impl Shape for Instance {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn material(&self) -> &Material {
+        self.material
+            .as_ref()
+            .unwrap_or_else(|| self.prototype.material())
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        let prototype = &self.prototype;
+        self.material
+            .get_or_insert_with(|| prototype.material().clone())
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = Some(material);
+    }
+
+    fn material_at<'a>(&'a self, hit: &Intersection<'a>) -> &'a Material {
+        match &self.material {
+            Some(material) => material,
+            None => {
+                let inner = self.inner_hit(hit);
+                inner.object.material_at(&inner)
+            }
+        }
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        self.prototype
+            .intersect(ray)
+            .into_iter()
+            .map(|hit| hit.within(self))
+            .collect()
+    }
+
+    fn intersect_packet(&self, rays: &[Ray; 4]) -> [Intersections<'_>; 4] {
+        if self.end_transform().is_some() {
+            return rays.each_ref().map(|ray| self.intersect(ray));
+        }
+        let local = rays
+            .each_ref()
+            .map(|ray| ray.transform(self.inverse_transform()));
+        self.prototype
+            .intersect_packet(&local)
+            .map(|hits| hits.into_iter().map(|hit| hit.within(self)).collect())
+    }
+
+    fn local_normal_at(&self, point: &Point, hit: &Intersection) -> Vector {
+        let inner = self.inner_hit(hit);
+        inner.object.normal_at(point, &inner)
+    }
+
+    fn tangent_at(&self, point: &Point, hit: &Intersection) -> Vector {
+        let local_point = self.inverse_transform_at(hit.time) * *point;
+        let inner = self.inner_hit(hit);
+        (self.transform_at(hit.time) * inner.object.tangent_at(&local_point, &inner)).normalize()
+    }
+
+    fn world_to_object(&self, point: &Point) -> Point {
+        self.prototype
+            .world_to_object(&(*self.inverse_transform() * *point))
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        self.prototype.bounds()
+    }
+
+    fn local_sample_point(&self, u: Scalar, v: Scalar) -> Option<(Point, Vector)> {
+        self.prototype.sample_point(u, v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Group, Solid, Sphere};
+    use rt_core::{Color, Matrix, Scalar};
+
+    fn placed(prototype: &Arc<dyn Shape>, x: Scalar) -> Instance {
+        let mut instance = Instance::new(Arc::clone(prototype));
+        instance.set_transform(Matrix::translation(x, 0.0, 0.0));
+        instance
+    }
+
+    fn along_z(x: Scalar) -> Ray {
+        Ray::new(Point::new(x, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0))
+    }
+
+    #[test]
+    fn instances_share_their_prototype() {
+        let prototype: Arc<dyn Shape> = Arc::new(Sphere::new());
+        let a = placed(&prototype, -3.0);
+        let b = placed(&prototype, 3.0);
+        assert!(Arc::ptr_eq(a.prototype(), b.prototype()));
+        assert_eq!(Arc::strong_count(&prototype), 3);
+    }
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn hits_are_reported_on_the_instance() {
+            let prototype: Arc<dyn Shape> = Arc::new(Sphere::new());
+            let instance = placed(&prototype, 3.0);
+            let xs = instance.intersect(&along_z(3.0));
+            assert_eq!(xs.len(), 2);
+            assert_eq!((xs[0].t, xs[1].t), (4.0, 6.0));
+            assert!(crate::shape::same_shape(xs[0].object, &instance));
+            assert!(crate::shape::same_shape(
+                xs[0].inner.unwrap(),
+                prototype.as_ref()
+            ));
+            assert!(instance.intersect(&along_z(0.0)).is_empty());
+        }
+
+        #[test]
+        fn packets_match_single_rays() {
+            let mut group = Group::new();
+            group.add_child(Box::new(Sphere::new()));
+            let prototype: Arc<dyn Shape> = Arc::new(group);
+            let instance = placed(&prototype, 3.0);
+            let rays = [along_z(3.0), along_z(0.0), along_z(3.5), along_z(2.2)];
+            for (ray, hits) in rays.iter().zip(instance.intersect_packet(&rays)) {
+                let expected: Vec<Scalar> = instance.intersect(ray).iter().map(|i| i.t).collect();
+                let actual: Vec<Scalar> = hits.iter().map(|i| i.t).collect();
+                assert_eq!(actual, expected);
+                assert!(hits
+                    .iter()
+                    .all(|hit| crate::shape::same_shape(hit.object, &instance)));
+            }
+        }
+
+        #[test]
+        fn grouped_prototypes_remember_the_leaf_that_was_hit() {
+            let mut group = Group::new();
+            let mut sphere = Sphere::new();
+            sphere.set_transform(Matrix::translation(0.0, 0.0, 2.0));
+            group.add_child(Box::new(sphere));
+            let prototype: Arc<dyn Shape> = Arc::new(group);
+            let instance = placed(&prototype, 3.0);
+
+            let r = Ray::new(Point::new(3.0, 0.0, 5.0), Vector::new(0.0, 0.0, -1.0));
+            let xs = instance.intersect(&r);
+            assert_eq!(xs[0].t, 2.0);
+            let point = r.position(xs[0].t);
+            assert_eq!(
+                instance.normal_at(&point, &xs[0]),
+                Vector::new(0.0, 0.0, 1.0)
+            );
+        }
+    }
+
+    #[test]
+    fn normals_follow_the_instance_transform() {
+        let prototype: Arc<dyn Shape> = Arc::new(Sphere::new());
+        let mut instance = Instance::new(prototype);
+        instance.set_transform(Matrix::scaling(1.0, 0.5, 1.0));
+        let r = Ray::new(Point::new(0.0, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let xs = instance.intersect(&r);
+        assert_eq!(xs[0].t, 4.5);
+        let normal = instance.normal_at(&r.position(xs[0].t), &xs[0]);
+        assert_eq!(normal, Vector::new(0.0, 1.0, 0.0));
+    }
+
+    mod material {
+        use super::*;
+
+        fn red() -> Material {
+            Material {
+                color: Color::new(1.0, 0.0, 0.0),
+                ..Material::default()
+            }
+        }
+
+        #[test]
+        fn instances_use_the_material_of_the_leaf_they_hit() {
+            let mut sphere = Sphere::new();
+            sphere.set_material(red());
+            let mut group = Group::new();
+            group.add_child(Box::new(sphere));
+            let prototype: Arc<dyn Shape> = Arc::new(group);
+            let instance = placed(&prototype, 0.0);
+            let xs = instance.intersect(&along_z(0.0));
+            assert_eq!(
+                instance.material_at(&xs[0]).color,
+                Color::new(1.0, 0.0, 0.0)
+            );
+            assert_eq!(instance.material_override(), None);
+        }
+
+        #[test]
+        fn an_override_replaces_every_leaf_material() {
+            let mut sphere = Sphere::new();
+            sphere.set_material(red());
+            let prototype: Arc<dyn Shape> = Arc::new(sphere);
+            let mut instance = placed(&prototype, 0.0);
+            instance.material_mut().pattern = Some(Arc::new(Solid::new(Color::white())));
+            let xs = instance.intersect(&along_z(0.0));
+            let material = instance.material_at(&xs[0]);
+            assert_eq!(material.color, Color::new(1.0, 0.0, 0.0));
+            assert!(material.pattern.is_some());
+            assert!(prototype.material().pattern.is_none());
+        }
+    }
+
+    #[test]
+    fn bounds_are_the_prototype_bounds_in_instance_space() {
+        let prototype: Arc<dyn Shape> = Arc::new(Sphere::new());
+        let b = placed(&prototype, 3.0).bounds();
+        assert_eq!(b.min, Point::new(2.0, -1.0, -1.0));
+        assert_eq!(b.max, Point::new(4.0, 1.0, 1.0));
+    }
+}
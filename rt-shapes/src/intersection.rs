@@ -0,0 +1,506 @@
+use crate::normal_map::perturb_normal;
+use crate::{Material, Shape};
+use rt_core::{Point, Ray, Scalar, Vector, EPSILON};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Intersection<'a> {
+    pub t: Scalar,
+    pub object: &'a dyn Shape,
+    pub uv: Option<(Scalar, Scalar)>,
+    pub inner: Option<&'a dyn Shape>,
+    pub time: Scalar,
+}
+
+impl<'a> Intersection<'a> {
+    pub fn new(t: Scalar, object: &'a dyn Shape) -> Self {
+        Intersection {
+            t,
+            object,
+            uv: None,
+            inner: None,
+            time: 0.0,
+        }
+    }
+
+    pub fn with_uv(t: Scalar, object: &'a dyn Shape, u: Scalar, v: Scalar) -> Self {
+        Intersection {
+            t,
+            object,
+            uv: Some((u, v)),
+            inner: None,
+            time: 0.0,
+        }
+    }
+
+    pub fn within(self, object: &'a dyn Shape) -> Self {
+        Intersection {
+            object,
+            inner: Some(self.object),
+            ..self
+        }
+    }
+
+    pub fn casts_shadow(&self) -> bool {
+        self.object.casts_shadow() && self.inner.is_none_or(|inner| inner.casts_shadow())
+    }
+
+    pub fn prepare_computations(&self, ray: &Ray, xs: &Intersections<'a>) -> Computations<'a> {
+        let point = ray.position(self.t);
+        let eyev = -ray.direction;
+        let mut geometric_normal = self.object.normal_at(&point, self);
+        let mut normalv = perturb_normal(self.object, &point, self, geometric_normal);
+        let inside = geometric_normal.dot(&eyev) < 0.0;
+        if inside {
+            geometric_normal = -geometric_normal;
+            normalv = -normalv;
+        }
+        let reflectv = ray.direction.reflect(&normalv);
+        let (n1, n2) = self.refractive_indices(xs);
+
+        Computations {
+            t: self.t,
+            object: self.object,
+            material: self.object.material_at(self),
+            point,
+            eyev,
+            normalv,
+            inside,
+            reflectv,
+            over_point: point + geometric_normal * EPSILON,
+            under_point: point - geometric_normal * EPSILON,
+            n1,
+            n2,
+            time: ray.time,
+        }
+    }
+
+    fn refractive_indices(&self, xs: &Intersections<'a>) -> (Scalar, Scalar) {
+        let mut containers: Vec<&Intersection<'a>> = Vec::new();
+        let index_of = |containers: &[&Intersection<'a>]| {
+            containers
+                .last()
+                .map_or(1.0, |i| i.object.material_at(i).refractive_index)
+        };
+
+        let mut n1 = 1.0;
+        for i in xs.iter() {
+            let is_hit = i == self;
+            if is_hit {
+                n1 = index_of(&containers);
+            }
+            match containers
+                .iter()
+                .position(|c| crate::shape::same_shape(c.object, i.object))
+            {
+                Some(index) => {
+                    containers.remove(index);
+                }
+                None => containers.push(i),
+            }
+            if is_hit {
+                return (n1, index_of(&containers));
+            }
+        }
+        (n1, index_of(&containers))
+    }
+}
+
+impl PartialEq for Intersection<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.t == other.t && crate::shape::same_shape(self.object, other.object)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Intersections<'a> {
+    data: Vec<Intersection<'a>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Computations<'a> {
+    pub t: Scalar,
+    pub object: &'a dyn Shape,
+    pub material: &'a Material,
+    pub point: Point,
+    pub eyev: Vector,
+    pub normalv: Vector,
+    pub inside: bool,
+    pub reflectv: Vector,
+    pub over_point: Point,
+    pub under_point: Point,
+    pub n1: Scalar,
+    pub n2: Scalar,
+    pub time: Scalar,
+}
+
+impl Computations<'_> {
+    pub fn schlick(&self) -> Scalar {
+        let mut cos = self.eyev.dot(&self.normalv);
+        if self.n1 > self.n2 {
+            let n = self.n1 / self.n2;
+            let sin2_t = n * n * (1.0 - cos * cos);
+            if sin2_t > 1.0 {
+                return 1.0;
+            }
+            cos = (1.0 - sin2_t).sqrt();
+        }
+        let r0 = ((self.n1 - self.n2) / (self.n1 + self.n2)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+    }
+}
+
+impl<'a> Intersections<'a> {
+    pub fn new(mut data: Vec<Intersection<'a>>) -> Self {
+        data.sort_by(|a, b| a.t.total_cmp(&b.t));
+        Intersections { data }
+    }
+
+    pub fn hit(&self) -> Option<&Intersection<'a>> {
+        self.data.iter().find(|i| i.t >= 0.0)
+    }
+
+    pub fn shadow_hit(&self) -> Option<&Intersection<'a>> {
+        self.data.iter().find(|i| i.t >= 0.0 && i.casts_shadow())
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Intersection<'a>> {
+        self.data.iter()
+    }
+}
+
+impl<'a> From<Vec<Intersection<'a>>> for Intersections<'a> {
+    fn from(data: Vec<Intersection<'a>>) -> Self {
+        Intersections::new(data)
+    }
+}
+
+impl<'a> std::iter::FromIterator<Intersection<'a>> for Intersections<'a> {
+    fn from_iter<I: IntoIterator<Item = Intersection<'a>>>(iter: I) -> Self {
+        Intersections::new(iter.into_iter().collect())
+    }
+}
+
+impl<'a> std::ops::Index<usize> for Intersections<'a> {
+    type Output = Intersection<'a>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+impl<'a> IntoIterator for Intersections<'a> {
+    type Item = Intersection<'a>;
+    type IntoIter = std::vec::IntoIter<Intersection<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sphere;
+    use rt_core::Matrix;
+
+    #[test]
+    fn intersection_encapsulates_t_and_object() {
+        let s = Sphere::new();
+        let i = Intersection::new(3.5, &s);
+        assert_eq!(i.t, 3.5);
+        assert!(crate::shape::same_shape(i.object, &s));
+        assert_eq!(i.uv, None);
+    }
+
+    #[test]
+    fn intersection_can_encapsulate_u_and_v() {
+        let s = Sphere::new();
+        let i = Intersection::with_uv(3.5, &s, 0.2, 0.4);
+        assert_eq!(i.uv, Some((0.2, 0.4)));
+    }
+
+    #[test]
+    fn aggregating_intersections() {
+        let s = Sphere::new();
+        let xs = Intersections::new(vec![Intersection::new(1.0, &s), Intersection::new(2.0, &s)]);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 1.0);
+        assert_eq!(xs[1].t, 2.0);
+    }
+
+    #[test]
+    fn intersections_with_different_objects_are_not_equal() {
+        let a = Sphere::new();
+        let b = Sphere::new();
+        assert_ne!(Intersection::new(1.0, &a), Intersection::new(1.0, &b));
+        assert_eq!(Intersection::new(1.0, &a), Intersection::new(1.0, &a));
+    }
+
+    #[test]
+    fn intersections_are_sorted_by_t() {
+        let s = Sphere::new();
+        let xs = Intersections::new(vec![
+            Intersection::new(5.0, &s),
+            Intersection::new(-3.0, &s),
+            Intersection::new(2.0, &s),
+        ]);
+        let ts: Vec<Scalar> = xs.iter().map(|i| i.t).collect();
+        assert_eq!(ts, vec![-3.0, 2.0, 5.0]);
+    }
+
+    #[test]
+    fn collecting_intersections_sorts_them() {
+        let s = Sphere::new();
+        let xs: Intersections = vec![Intersection::new(2.0, &s), Intersection::new(1.0, &s)]
+            .into_iter()
+            .collect();
+        assert_eq!(xs[0].t, 1.0);
+        assert_eq!(xs[1].t, 2.0);
+    }
+
+    mod hit {
+        use super::*;
+
+        #[test]
+        fn all_intersections_have_positive_t() {
+            let s = Sphere::new();
+            let i1 = Intersection::new(1.0, &s);
+            let xs = Intersections::new(vec![Intersection::new(2.0, &s), i1]);
+            assert_eq!(xs.hit(), Some(&i1));
+        }
+
+        #[test]
+        fn some_intersections_have_negative_t() {
+            let s = Sphere::new();
+            let i2 = Intersection::new(1.0, &s);
+            let xs = Intersections::new(vec![i2, Intersection::new(-1.0, &s)]);
+            assert_eq!(xs.hit(), Some(&i2));
+        }
+
+        #[test]
+        fn all_intersections_have_negative_t() {
+            let s = Sphere::new();
+            let xs = Intersections::new(vec![
+                Intersection::new(-2.0, &s),
+                Intersection::new(-1.0, &s),
+            ]);
+            assert_eq!(xs.hit(), None);
+        }
+
+        #[test]
+        fn hit_is_always_the_lowest_nonnegative_intersection() {
+            let s = Sphere::new();
+            let i4 = Intersection::new(2.0, &s);
+            let xs = Intersections::new(vec![
+                Intersection::new(5.0, &s),
+                Intersection::new(7.0, &s),
+                Intersection::new(-3.0, &s),
+                i4,
+            ]);
+            assert_eq!(xs.hit(), Some(&i4));
+        }
+
+        #[test]
+        fn shadow_hit_skips_shapes_that_cast_no_shadow() {
+            let mut pane = Sphere::new();
+            pane.set_casts_shadow(false);
+            let s = Sphere::new();
+            let i3 = Intersection::new(3.0, &s);
+            let xs = Intersections::new(vec![
+                Intersection::new(1.0, &pane),
+                i3,
+                Intersection::new(-1.0, &s),
+            ]);
+            assert!(crate::shape::same_shape(xs.hit().unwrap().object, &pane));
+            assert_eq!(xs.shadow_hit(), Some(&i3));
+        }
+
+        #[test]
+        fn shadow_hit_checks_the_instanced_shape() {
+            let mut pane = Sphere::new();
+            pane.set_casts_shadow(false);
+            let s = Sphere::new();
+            let xs = Intersections::new(vec![Intersection::new(1.0, &pane).within(&s)]);
+            assert!(xs.hit().is_some());
+            assert_eq!(xs.shadow_hit(), None);
+        }
+    }
+
+    mod computations {
+        use super::*;
+
+        #[test]
+        fn precomputing_the_state_of_an_intersection() {
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let shape = Sphere::new();
+            let i = Intersection::new(4.0, &shape);
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert_eq!(comps.t, 4.0);
+            assert!(crate::shape::same_shape(comps.object, &shape));
+            assert_eq!(comps.point, Point::new(0.0, 0.0, -1.0));
+            assert_eq!(comps.eyev, Vector::new(0.0, 0.0, -1.0));
+            assert_eq!(comps.normalv, Vector::new(0.0, 0.0, -1.0));
+        }
+
+        #[test]
+        fn hit_occurs_on_the_outside() {
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let shape = Sphere::new();
+            let i = Intersection::new(4.0, &shape);
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert!(!comps.inside);
+        }
+
+        #[test]
+        fn hit_occurs_on_the_inside() {
+            let r = Ray::new(Point::origin(), Vector::new(0.0, 0.0, 1.0));
+            let shape = Sphere::new();
+            let i = Intersection::new(1.0, &shape);
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert_eq!(comps.point, Point::new(0.0, 0.0, 1.0));
+            assert_eq!(comps.eyev, Vector::new(0.0, 0.0, -1.0));
+            assert!(comps.inside);
+            assert_eq!(comps.normalv, Vector::new(0.0, 0.0, -1.0));
+        }
+
+        #[test]
+        fn precomputing_the_reflection_vector() {
+            let shape = crate::Plane::new();
+            let h = Scalar::sqrt(2.0) / 2.0;
+            let r = Ray::new(Point::new(0.0, 1.0, -1.0), Vector::new(0.0, -h, h));
+            let i = Intersection::new(Scalar::sqrt(2.0), &shape);
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert_eq!(comps.reflectv, Vector::new(0.0, h, h));
+        }
+
+        #[test]
+        fn hit_offsets_the_over_point() {
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let mut shape = Sphere::new();
+            shape.set_transform(Matrix::translation(0.0, 0.0, 1.0));
+            let i = Intersection::new(5.0, &shape);
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert!(comps.over_point.z() < -EPSILON / 2.0);
+            assert!(comps.point.z() > comps.over_point.z());
+        }
+
+        #[test]
+        fn under_point_is_offset_below_the_surface() {
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let mut shape = Sphere::new();
+            shape.set_transform(Matrix::translation(0.0, 0.0, 1.0));
+            let i = Intersection::new(5.0, &shape);
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            assert!(comps.under_point.z() > EPSILON / 2.0);
+            assert!(comps.point.z() < comps.under_point.z());
+        }
+
+        #[test]
+        fn normal_map_perturbs_normalv_but_not_the_offsets() {
+            let mut shape = crate::Plane::new();
+            shape.set_material(crate::Material {
+                normal_map: Some(std::sync::Arc::new(crate::PatternNormalMap::new(Box::new(
+                    crate::Solid::new(rt_core::Color::new(1.0, 0.5, 1.0)),
+                )))),
+                ..crate::Material::default()
+            });
+            let r = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+            let i = Intersection::new(1.0, &shape);
+            let comps = i.prepare_computations(&r, &vec![i].into());
+            let h = rt_core::consts::FRAC_1_SQRT_2;
+            assert_eq!(comps.normalv, Vector::new(h, h, 0.0));
+            assert_eq!(comps.over_point, Point::new(0.0, EPSILON, 0.0));
+            assert!(!comps.inside);
+        }
+    }
+
+    mod refraction {
+        use super::*;
+        use rt_core::consts::FRAC_1_SQRT_2;
+
+        fn glass_sphere() -> Sphere {
+            let mut s = Sphere::new();
+            s.material_mut().transparency = 1.0;
+            s.material_mut().refractive_index = 1.5;
+            s
+        }
+
+        #[test]
+        fn finding_n1_and_n2_at_various_intersections() {
+            let mut a = glass_sphere();
+            a.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+            let mut b = glass_sphere();
+            b.set_transform(Matrix::translation(0.0, 0.0, -0.25));
+            b.material_mut().refractive_index = 2.0;
+            let mut c = glass_sphere();
+            c.set_transform(Matrix::translation(0.0, 0.0, 0.25));
+            c.material_mut().refractive_index = 2.5;
+
+            let r = Ray::new(Point::new(0.0, 0.0, -4.0), Vector::new(0.0, 0.0, 1.0));
+            let xs = Intersections::new(vec![
+                Intersection::new(2.0, &a),
+                Intersection::new(2.75, &b),
+                Intersection::new(3.25, &c),
+                Intersection::new(4.75, &b),
+                Intersection::new(5.25, &c),
+                Intersection::new(6.0, &a),
+            ]);
+            let expected = [
+                (1.0, 1.5),
+                (1.5, 2.0),
+                (2.0, 2.5),
+                (2.5, 2.5),
+                (2.5, 1.5),
+                (1.5, 1.0),
+            ];
+            for (i, (n1, n2)) in expected.iter().enumerate() {
+                let comps = xs[i].prepare_computations(&r, &xs);
+                assert_eq!((comps.n1, comps.n2), (*n1, *n2), "intersection {}", i);
+            }
+        }
+
+        #[test]
+        fn schlick_under_total_internal_reflection() {
+            let shape = glass_sphere();
+            let r = Ray::new(
+                Point::new(0.0, 0.0, FRAC_1_SQRT_2),
+                Vector::new(0.0, 1.0, 0.0),
+            );
+            let xs = Intersections::new(vec![
+                Intersection::new(-FRAC_1_SQRT_2, &shape),
+                Intersection::new(FRAC_1_SQRT_2, &shape),
+            ]);
+            let comps = xs[1].prepare_computations(&r, &xs);
+            assert_eq!(comps.schlick(), 1.0);
+        }
+
+        #[test]
+        fn schlick_with_a_perpendicular_viewing_angle() {
+            let shape = glass_sphere();
+            let r = Ray::new(Point::origin(), Vector::new(0.0, 1.0, 0.0));
+            let xs = Intersections::new(vec![
+                Intersection::new(-1.0, &shape),
+                Intersection::new(1.0, &shape),
+            ]);
+            let comps = xs[1].prepare_computations(&r, &xs);
+            assert!((comps.schlick() - 0.04).abs() < EPSILON);
+        }
+
+        #[test]
+        fn schlick_with_small_angle_and_n2_greater_than_n1() {
+            let shape = glass_sphere();
+            let r = Ray::new(Point::new(0.0, 0.99, -2.0), Vector::new(0.0, 0.0, 1.0));
+            let xs = Intersections::new(vec![Intersection::new(1.8589, &shape)]);
+            let comps = xs[0].prepare_computations(&r, &xs);
+            assert!((comps.schlick() - 0.48873).abs() < 1e-4);
+        }
+    }
+}
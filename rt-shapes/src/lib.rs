@@ -0,0 +1,52 @@
+mod bounds;
+mod builder;
+mod clip;
+mod cone;
+mod csg;
+mod cube;
+mod cylinder;
+mod group;
+mod instance;
+mod intersection;
+mod material;
+mod normal_map;
+mod pattern;
+mod plane;
+mod quadric;
+mod sdf;
+mod shape;
+mod smooth_triangle;
+mod sphere;
+mod stats;
+mod texture;
+mod torus;
+mod triangle;
+
+pub use bounds::BoundingBox;
+pub use builder::{MaterialBuilder, ShapeBuilder};
+pub use clip::{Clip, ClipPlane};
+pub use cone::Cone;
+pub use csg::{Csg, CsgOperation};
+pub use cube::Cube;
+pub use cylinder::Cylinder;
+pub use group::Group;
+pub use instance::Instance;
+pub use intersection::{Computations, Intersection, Intersections};
+pub use material::Material;
+pub use normal_map::{NoiseNormalMap, NormalMap, PatternNormalMap};
+pub use pattern::{
+    Blend, Checker, Gradient, Noise, Pattern, PatternData, Perturb, Ring, Solid, Stripe,
+};
+pub use plane::Plane;
+pub use quadric::Quadric;
+pub use sdf::{CustomSdf, Sdf, SdfShape};
+pub use shape::{Shape, ShapeData};
+pub use smooth_triangle::SmoothTriangle;
+pub use sphere::Sphere;
+pub use stats::{count_traversal, TraversalStats};
+pub use texture::{
+    cylindrical_map, planar_map, spherical_map, CubeFace, CubeMap, TextureMap, UvAlignCheck,
+    UvChecker, UvImage, UvMapping, UvPattern, VertexUvMap,
+};
+pub use torus::Torus;
+pub use triangle::Triangle;
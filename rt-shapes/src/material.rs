@@ -0,0 +1,126 @@
+use crate::{MaterialBuilder, NormalMap, Pattern, Shape};
+use rt_core::{Color, Point, Scalar};
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct Material {
+    pub color: Color,
+    pub pattern: Option<Arc<dyn Pattern>>,
+    pub normal_map: Option<Arc<dyn NormalMap>>,
+    pub ambient: Scalar,
+    pub diffuse: Scalar,
+    pub specular: Scalar,
+    pub shininess: Scalar,
+    pub reflective: Scalar,
+    pub transparency: Scalar,
+    pub refractive_index: Scalar,
+    pub emissive: Color,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material {
+            color: Color::white(),
+            pattern: None,
+            normal_map: None,
+            ambient: 0.1,
+            diffuse: 0.9,
+            specular: 0.9,
+            shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
+            emissive: Color::black(),
+        }
+    }
+}
+
+impl Material {
+    pub fn builder() -> MaterialBuilder {
+        MaterialBuilder::default()
+    }
+
+    pub fn color_at(&self, object: &dyn Shape, world_point: &Point) -> Color {
+        match &self.pattern {
+            Some(pattern) => pattern.pattern_at_shape(object, world_point),
+            None => self.color,
+        }
+    }
+}
+
+impl PartialEq for Material {
+    fn eq(&self, other: &Self) -> bool {
+        same_arc(&self.pattern, &other.pattern)
+            && same_arc(&self.normal_map, &other.normal_map)
+            && self.color == other.color
+            && self.ambient == other.ambient
+            && self.diffuse == other.diffuse
+            && self.specular == other.specular
+            && self.shininess == other.shininess
+            && self.reflective == other.reflective
+            && self.transparency == other.transparency
+            && self.refractive_index == other.refractive_index
+            && self.emissive == other.emissive
+    }
+}
+
+fn same_arc<T: ?Sized>(a: &Option<Arc<T>>, b: &Option<Arc<T>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_material() {
+        let m = Material::default();
+        assert_eq!(m.color, Color::white());
+        assert!(m.pattern.is_none());
+        assert!(m.normal_map.is_none());
+        assert_eq!(m.ambient, 0.1);
+        assert_eq!(m.diffuse, 0.9);
+        assert_eq!(m.specular, 0.9);
+        assert_eq!(m.shininess, 200.0);
+        assert_eq!(m.reflective, 0.0);
+        assert_eq!(m.transparency, 0.0);
+        assert_eq!(m.refractive_index, 1.0);
+        assert_eq!(m.emissive, Color::black());
+    }
+
+    mod pattern {
+        use super::*;
+        use crate::{Sphere, Stripe};
+
+        #[test]
+        fn color_comes_from_the_pattern_when_present() {
+            let m = Material {
+                pattern: Some(Arc::new(Stripe::new(Color::white(), Color::black()))),
+                ..Material::default()
+            };
+            let s = Sphere::new();
+            assert_eq!(m.color_at(&s, &Point::new(0.9, 0.0, 0.0)), Color::white());
+            assert_eq!(m.color_at(&s, &Point::new(1.1, 0.0, 0.0)), Color::black());
+        }
+
+        #[test]
+        fn materials_sharing_a_pattern_are_equal() {
+            let pattern: Arc<dyn Pattern> = Arc::new(Stripe::new(Color::white(), Color::black()));
+            let a = Material {
+                pattern: Some(pattern.clone()),
+                ..Material::default()
+            };
+            let b = Material {
+                pattern: Some(Arc::new(Stripe::new(Color::white(), Color::black()))),
+                ..Material::default()
+            };
+            assert_eq!(a, a.clone());
+            assert_ne!(a, b);
+            assert_ne!(a, Material::default());
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{Intersection, Pattern, Shape};
+use rt_core::{Perlin, Point, Scalar, Vector};
+
+pub trait NormalMap: std::any::Any + std::fmt::Debug + Send + Sync {
+    fn tangent_normal_at(&self, point: &Point) -> Vector;
+}
+
+#[derive(Debug)]
+pub struct PatternNormalMap {
+    pattern: Box<dyn Pattern>,
+}
+
+impl PatternNormalMap {
+    pub fn new(pattern: Box<dyn Pattern>) -> Self {
+        PatternNormalMap { pattern }
+    }
+
+    pub fn pattern(&self) -> &dyn Pattern {
+        self.pattern.as_ref()
+    }
+}
+
+impl NormalMap for PatternNormalMap {
+    fn tangent_normal_at(&self, point: &Point) -> Vector {
+        let c = self.pattern.pattern_at(point);
+        Vector::new(
+            c.red() * 2.0 - 1.0,
+            c.green() * 2.0 - 1.0,
+            c.blue() * 2.0 - 1.0,
+        )
+        .normalize()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NoiseNormalMap {
+    noise: Perlin,
+    frequency: Scalar,
+    strength: Scalar,
+}
+
+impl NoiseNormalMap {
+    pub fn new(frequency: Scalar, strength: Scalar, seed: u64) -> Self {
+        NoiseNormalMap {
+            noise: Perlin::new(seed),
+            frequency,
+            strength,
+        }
+    }
+
+    pub fn frequency(&self) -> Scalar {
+        self.frequency
+    }
+
+    pub fn strength(&self) -> Scalar {
+        self.strength
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.noise.seed()
+    }
+}
+
+impl NormalMap for NoiseNormalMap {
+    fn tangent_normal_at(&self, point: &Point) -> Vector {
+        let sample = |offset: Scalar| {
+            self.noise.noise(&Point::new(
+                point.x() * self.frequency + offset,
+                point.y() * self.frequency,
+                point.z() * self.frequency,
+            ))
+        };
+        Vector::new(
+            sample(0.0) * self.strength,
+            sample(17.3) * self.strength,
+            1.0,
+        )
+        .normalize()
+    }
+}
+
+pub(crate) fn perturb_normal(
+    object: &dyn Shape,
+    point: &Point,
+    hit: &Intersection,
+    normal: Vector,
+) -> Vector {
+    let map = match &object.material_at(hit).normal_map {
+        Some(map) => map,
+        None => return normal,
+    };
+    let tangent = object.tangent_at(point, hit);
+    let tangent = (tangent - normal * normal.dot(&tangent)).normalize();
+    let bitangent = tangent.cross(&normal);
+    let local = map.tangent_normal_at(&object.world_to_object(point));
+    (tangent * local.x() + bitangent * local.y() + normal * local.z()).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Material, Plane, Solid, Sphere};
+    use rt_core::Color;
+    use std::sync::Arc;
+
+    fn solid_map(color: Color) -> Arc<dyn NormalMap> {
+        Arc::new(PatternNormalMap::new(Box::new(Solid::new(color))))
+    }
+
+    fn plane_with(map: Arc<dyn NormalMap>) -> Plane {
+        let mut p = Plane::new();
+        p.set_material(Material {
+            normal_map: Some(map),
+            ..Material::default()
+        });
+        p
+    }
+
+    fn perturbed(object: &dyn Shape, point: Point) -> Vector {
+        let hit = Intersection::new(0.0, object);
+        perturb_normal(object, &point, &hit, object.normal_at(&point, &hit))
+    }
+
+    #[test]
+    fn flat_color_decodes_to_the_surface_normal() {
+        let map = PatternNormalMap::new(Box::new(Solid::new(Color::new(0.5, 0.5, 1.0))));
+        assert_eq!(
+            map.tangent_normal_at(&Point::origin()),
+            Vector::new(0.0, 0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn shapes_without_a_normal_map_keep_their_normal() {
+        let s = Sphere::new();
+        assert_eq!(
+            perturbed(&s, Point::new(0.0, 0.0, -1.0)),
+            Vector::new(0.0, 0.0, -1.0)
+        );
+    }
+
+    #[test]
+    fn flat_normal_map_leaves_the_normal_unchanged() {
+        let p = plane_with(solid_map(Color::new(0.5, 0.5, 1.0)));
+        assert_eq!(
+            perturbed(&p, Point::new(3.0, 0.0, 2.0)),
+            Vector::new(0.0, 1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn normal_map_tilts_the_normal_in_tangent_space() {
+        let p = plane_with(solid_map(Color::new(1.0, 0.5, 0.5)));
+        assert_eq!(perturbed(&p, Point::origin()), Vector::new(1.0, 0.0, 0.0));
+        let p = plane_with(solid_map(Color::new(0.5, 1.0, 0.5)));
+        assert_eq!(perturbed(&p, Point::origin()), Vector::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn normal_map_follows_the_object_transform() {
+        let mut p = plane_with(solid_map(Color::new(1.0, 0.5, 0.5)));
+        p.set_transform(rt_core::Matrix::rotation_z(rt_core::consts::PI / 2.0));
+        assert_eq!(perturbed(&p, Point::origin()), Vector::new(0.0, 1.0, 0.0));
+    }
+
+    mod noise {
+        use super::*;
+
+        #[test]
+        fn noise_normals_stay_above_the_surface() {
+            let map = NoiseNormalMap::new(4.0, 0.8, 3);
+            for i in 0..50 {
+                let i = i as Scalar;
+                let n = map.tangent_normal_at(&Point::new(i * 0.13, i * 0.07, -i * 0.21));
+                assert!(n.z() > 0.0);
+                assert!((n.magnitude() - 1.0).abs() < rt_core::EPSILON);
+            }
+        }
+
+        #[test]
+        fn noise_normals_are_reproducible_for_a_seed() {
+            let a = NoiseNormalMap::new(4.0, 0.8, 3);
+            let b = NoiseNormalMap::new(4.0, 0.8, 3);
+            let point = Point::new(0.3, 1.7, -2.2);
+            assert_eq!(a.tangent_normal_at(&point), b.tangent_normal_at(&point));
+        }
+
+        #[test]
+        fn zero_strength_is_a_flat_surface() {
+            let map = NoiseNormalMap::new(4.0, 0.0, 3);
+            assert_eq!(
+                map.tangent_normal_at(&Point::new(0.3, 1.7, -2.2)),
+                Vector::new(0.0, 0.0, 1.0)
+            );
+        }
+    }
+}
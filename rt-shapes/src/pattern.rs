@@ -0,0 +1,578 @@
+use crate::Shape;
+use rt_core::{Color, Matrix, Perlin, Point, Scalar, Vector};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternData {
+    transform: Matrix<4, 4>,
+    inverse_transform: Matrix<4, 4>,
+}
+
+impl PatternData {
+    pub fn set_transform(&mut self, transform: Matrix<4, 4>) {
+        self.inverse_transform = transform
+            .inverse()
+            .expect("pattern transform must be invertible");
+        self.transform = transform;
+    }
+}
+
+impl Default for PatternData {
+    fn default() -> Self {
+        PatternData {
+            transform: Matrix::identity(),
+            inverse_transform: Matrix::identity(),
+        }
+    }
+}
+
+pub trait Pattern: std::any::Any + std::fmt::Debug + Send + Sync {
+    fn data(&self) -> &PatternData;
+    fn data_mut(&mut self) -> &mut PatternData;
+    fn local_pattern_at(&self, point: &Point) -> Color;
+
+    fn transform(&self) -> &Matrix<4, 4> {
+        &self.data().transform
+    }
+
+    fn inverse_transform(&self) -> &Matrix<4, 4> {
+        &self.data().inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix<4, 4>) {
+        self.data_mut().set_transform(transform);
+    }
+
+    fn pattern_at(&self, point: &Point) -> Color {
+        self.local_pattern_at(&(*self.inverse_transform() * *point))
+    }
+
+    fn pattern_at_shape(&self, shape: &dyn Shape, world_point: &Point) -> Color {
+        self.pattern_at(&shape.world_to_object(world_point))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Solid {
+    data: PatternData,
+    color: Color,
+}
+
+impl Solid {
+    pub fn new(color: Color) -> Self {
+        Solid {
+            data: PatternData::default(),
+            color,
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+}
+
+impl Pattern for Solid {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, _point: &Point) -> Color {
+        self.color
+    }
+}
+
+macro_rules! implement_two_tone_pattern {
+    ($($name:ident),*) => {
+        $(
+            #[derive(Debug)]
+            pub struct $name {
+                data: PatternData,
+                a: Box<dyn Pattern>,
+                b: Box<dyn Pattern>,
+            }
+
+            impl $name {
+                pub fn new(a: Color, b: Color) -> Self {
+                    $name::nested(Box::new(Solid::new(a)), Box::new(Solid::new(b)))
+                }
+
+                pub fn nested(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> Self {
+                    $name {
+                        data: PatternData::default(),
+                        a,
+                        b,
+                    }
+                }
+
+                pub fn a(&self) -> &dyn Pattern {
+                    self.a.as_ref()
+                }
+
+                pub fn b(&self) -> &dyn Pattern {
+                    self.b.as_ref()
+                }
+            }
+        )*
+    };
+}
+
+implement_two_tone_pattern!(Stripe, Gradient, Ring, Checker, Blend);
+
+fn is_even(value: Scalar) -> bool {
+    (value.floor() as i64).rem_euclid(2) == 0
+}
+
+impl Pattern for Stripe {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, point: &Point) -> Color {
+        if is_even(point.x()) {
+            self.a.pattern_at(point)
+        } else {
+            self.b.pattern_at(point)
+        }
+    }
+}
+
+impl Pattern for Gradient {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, point: &Point) -> Color {
+        let a = self.a.pattern_at(point);
+        let b = self.b.pattern_at(point);
+        a + (b - a) * (point.x() - point.x().floor())
+    }
+}
+
+impl Pattern for Ring {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, point: &Point) -> Color {
+        if is_even((point.x().powi(2) + point.z().powi(2)).sqrt()) {
+            self.a.pattern_at(point)
+        } else {
+            self.b.pattern_at(point)
+        }
+    }
+}
+
+impl Pattern for Checker {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, point: &Point) -> Color {
+        if is_even(point.x().floor() + point.y().floor() + point.z().floor()) {
+            self.a.pattern_at(point)
+        } else {
+            self.b.pattern_at(point)
+        }
+    }
+}
+
+impl Pattern for Blend {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, point: &Point) -> Color {
+        (self.a.pattern_at(point) + self.b.pattern_at(point)) / 2.0
+    }
+}
+
+#[derive(Debug)]
+pub struct Noise {
+    data: PatternData,
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+    noise: Perlin,
+}
+
+impl Noise {
+    pub fn new(a: Color, b: Color, seed: u64) -> Self {
+        Noise::nested(Box::new(Solid::new(a)), Box::new(Solid::new(b)), seed)
+    }
+
+    pub fn nested(a: Box<dyn Pattern>, b: Box<dyn Pattern>, seed: u64) -> Self {
+        Noise {
+            data: PatternData::default(),
+            a,
+            b,
+            noise: Perlin::new(seed),
+        }
+    }
+
+    pub fn a(&self) -> &dyn Pattern {
+        self.a.as_ref()
+    }
+
+    pub fn b(&self) -> &dyn Pattern {
+        self.b.as_ref()
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.noise.seed()
+    }
+}
+
+impl Pattern for Noise {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, point: &Point) -> Color {
+        let t = ((self.noise.noise(point) + 1.0) / 2.0).clamp(0.0, 1.0);
+        let a = self.a.pattern_at(point);
+        let b = self.b.pattern_at(point);
+        a + (b - a) * t
+    }
+}
+
+#[derive(Debug)]
+pub struct Perturb {
+    data: PatternData,
+    pattern: Box<dyn Pattern>,
+    scale: Scalar,
+    noise: Perlin,
+}
+
+impl Perturb {
+    pub fn new(pattern: Box<dyn Pattern>, scale: Scalar, seed: u64) -> Self {
+        Perturb {
+            data: PatternData::default(),
+            pattern,
+            scale,
+            noise: Perlin::new(seed),
+        }
+    }
+
+    pub fn pattern(&self) -> &dyn Pattern {
+        self.pattern.as_ref()
+    }
+
+    pub fn scale(&self) -> Scalar {
+        self.scale
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.noise.seed()
+    }
+}
+
+impl Pattern for Perturb {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, point: &Point) -> Color {
+        let offset = |dx: Scalar, dy: Scalar, dz: Scalar| {
+            self.noise
+                .noise(&Point::new(point.x() + dx, point.y() + dy, point.z() + dz))
+        };
+        let jitter = Vector::new(
+            offset(0.0, 0.0, 0.0),
+            offset(31.7, 0.0, 0.0),
+            offset(0.0, 0.0, 47.3),
+        );
+        self.pattern.pattern_at(&(*point + jitter * self.scale))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::Sphere;
+
+    #[derive(Debug, Default)]
+    pub(crate) struct TestPattern {
+        data: PatternData,
+    }
+
+    impl Pattern for TestPattern {
+        fn data(&self) -> &PatternData {
+            &self.data
+        }
+
+        fn data_mut(&mut self) -> &mut PatternData {
+            &mut self.data
+        }
+
+        fn local_pattern_at(&self, point: &Point) -> Color {
+            Color::new(point.x(), point.y(), point.z())
+        }
+    }
+
+    fn white() -> Color {
+        Color::white()
+    }
+
+    fn black() -> Color {
+        Color::black()
+    }
+
+    mod transformation {
+        use super::*;
+
+        #[test]
+        fn default_pattern_transformation() {
+            assert_eq!(*TestPattern::default().transform(), Matrix::identity());
+        }
+
+        #[test]
+        fn assigning_a_transformation() {
+            let mut p = TestPattern::default();
+            p.set_transform(Matrix::translation(1.0, 2.0, 3.0));
+            assert_eq!(*p.transform(), Matrix::translation(1.0, 2.0, 3.0));
+        }
+
+        #[test]
+        fn pattern_with_an_object_transformation() {
+            let mut shape = Sphere::new();
+            shape.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+            let p = TestPattern::default();
+            assert_eq!(
+                p.pattern_at_shape(&shape, &Point::new(2.0, 3.0, 4.0)),
+                Color::new(1.0, 1.5, 2.0)
+            );
+        }
+
+        #[test]
+        fn pattern_with_a_pattern_transformation() {
+            let mut p = TestPattern::default();
+            p.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+            assert_eq!(
+                p.pattern_at_shape(&Sphere::new(), &Point::new(2.0, 3.0, 4.0)),
+                Color::new(1.0, 1.5, 2.0)
+            );
+        }
+
+        #[test]
+        fn pattern_with_both_an_object_and_a_pattern_transformation() {
+            let mut shape = Sphere::new();
+            shape.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+            let mut p = TestPattern::default();
+            p.set_transform(Matrix::translation(0.5, 1.0, 1.5));
+            assert_eq!(
+                p.pattern_at_shape(&shape, &Point::new(2.5, 3.0, 3.5)),
+                Color::new(0.75, 0.5, 0.25)
+            );
+        }
+    }
+
+    mod stripe {
+        use super::*;
+
+        #[test]
+        fn constant_in_y_and_z() {
+            let p = Stripe::new(white(), black());
+            for point in [
+                Point::origin(),
+                Point::new(0.0, 1.0, 0.0),
+                Point::new(0.0, 2.0, 0.0),
+                Point::new(0.0, 0.0, 1.0),
+                Point::new(0.0, 0.0, 2.0),
+            ] {
+                assert_eq!(p.pattern_at(&point), white());
+            }
+        }
+
+        #[test]
+        fn alternates_in_x() {
+            let p = Stripe::new(white(), black());
+            let cases = [
+                (0.0, white()),
+                (0.9, white()),
+                (1.0, black()),
+                (-0.1, black()),
+                (-1.0, black()),
+                (-1.1, white()),
+            ];
+            for (x, color) in cases {
+                assert_eq!(p.pattern_at(&Point::new(x, 0.0, 0.0)), color);
+            }
+        }
+    }
+
+    #[test]
+    fn gradient_linearly_interpolates_between_colors() {
+        let p = Gradient::new(white(), black());
+        let cases = [
+            (0.0, white()),
+            (0.25, Color::new(0.75, 0.75, 0.75)),
+            (0.5, Color::new(0.5, 0.5, 0.5)),
+            (0.75, Color::new(0.25, 0.25, 0.25)),
+        ];
+        for (x, color) in cases {
+            assert_eq!(p.pattern_at(&Point::new(x, 0.0, 0.0)), color);
+        }
+    }
+
+    #[test]
+    fn ring_extends_in_both_x_and_z() {
+        let p = Ring::new(white(), black());
+        assert_eq!(p.pattern_at(&Point::origin()), white());
+        assert_eq!(p.pattern_at(&Point::new(1.0, 0.0, 0.0)), black());
+        assert_eq!(p.pattern_at(&Point::new(0.0, 0.0, 1.0)), black());
+        assert_eq!(p.pattern_at(&Point::new(0.708, 0.0, 0.708)), black());
+    }
+
+    #[test]
+    fn checkers_repeat_in_each_dimension() {
+        let p = Checker::new(white(), black());
+        for (x, y, z) in [(1.0, 0.0, 0.0), (0.0, 1.0, 0.0), (0.0, 0.0, 1.0)] {
+            assert_eq!(p.pattern_at(&Point::origin()), white());
+            assert_eq!(
+                p.pattern_at(&Point::new(x * 0.99, y * 0.99, z * 0.99)),
+                white()
+            );
+            assert_eq!(
+                p.pattern_at(&Point::new(x * 1.01, y * 1.01, z * 1.01)),
+                black()
+            );
+        }
+    }
+
+    mod nesting {
+        use super::*;
+
+        #[test]
+        fn stripes_of_checkers() {
+            let mut checker = Checker::new(Color::new(1.0, 0.0, 0.0), Color::new(0.0, 0.0, 1.0));
+            checker.set_transform(Matrix::scaling(0.25, 0.25, 0.25));
+            let p = Stripe::nested(Box::new(checker), Box::new(Solid::new(black())));
+            assert_eq!(
+                p.pattern_at(&Point::new(0.1, 0.1, 0.1)),
+                Color::new(1.0, 0.0, 0.0)
+            );
+            assert_eq!(
+                p.pattern_at(&Point::new(0.3, 0.1, 0.1)),
+                Color::new(0.0, 0.0, 1.0)
+            );
+            assert_eq!(p.pattern_at(&Point::new(1.5, 0.1, 0.1)), black());
+        }
+
+        #[test]
+        fn sub_patterns_see_the_parent_pattern_space() {
+            let mut p =
+                Stripe::nested(Box::<TestPattern>::default(), Box::new(Solid::new(black())));
+            p.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+            assert_eq!(
+                p.pattern_at(&Point::new(1.0, 1.0, 1.0)),
+                Color::new(0.5, 0.5, 0.5)
+            );
+        }
+
+        #[test]
+        fn blending_averages_two_patterns() {
+            let mut vertical = Stripe::new(white(), black());
+            vertical.set_transform(Matrix::rotation_y(rt_core::consts::PI / 2.0));
+            let p = Blend::nested(Box::new(Stripe::new(white(), black())), Box::new(vertical));
+            assert_eq!(p.pattern_at(&Point::new(0.5, 0.0, -0.5)), white());
+            assert_eq!(
+                p.pattern_at(&Point::new(0.5, 0.0, 0.5)),
+                Color::new(0.5, 0.5, 0.5)
+            );
+            assert_eq!(p.pattern_at(&Point::new(1.5, 0.0, 0.5)), black());
+        }
+    }
+
+    mod noise {
+        use super::*;
+
+        fn sample_points() -> Vec<Point> {
+            (0..50)
+                .map(|i| {
+                    Point::new(
+                        i as Scalar * 0.31,
+                        i as Scalar * 0.17 - 2.0,
+                        i as Scalar * 0.07,
+                    )
+                })
+                .collect()
+        }
+
+        #[test]
+        fn noise_blends_between_its_colors() {
+            let p = Noise::new(white(), black(), 3);
+            for point in sample_points() {
+                let c = p.pattern_at(&point);
+                assert!((0.0..=1.0).contains(&c.red()));
+                assert_eq!(c.red(), c.green());
+                assert_eq!(c.red(), c.blue());
+            }
+        }
+
+        #[test]
+        fn noise_is_reproducible_for_a_seed() {
+            let a = Noise::new(white(), black(), 11);
+            let b = Noise::new(white(), black(), 11);
+            for point in sample_points() {
+                assert_eq!(a.pattern_at(&point), b.pattern_at(&point));
+            }
+        }
+
+        #[test]
+        fn perturb_with_zero_scale_is_the_inner_pattern() {
+            let p = Perturb::new(Box::new(Stripe::new(white(), black())), 0.0, 5);
+            let stripe = Stripe::new(white(), black());
+            for point in sample_points() {
+                assert_eq!(p.pattern_at(&point), stripe.pattern_at(&point));
+            }
+        }
+
+        #[test]
+        fn perturb_jitters_the_sample_point() {
+            let p = Perturb::new(Box::<TestPattern>::default(), 0.5, 5);
+            let moved = sample_points()
+                .iter()
+                .any(|point| p.pattern_at(point) != TestPattern::default().pattern_at(point));
+            assert!(moved);
+        }
+
+        #[test]
+        fn perturb_is_reproducible_for_a_seed() {
+            let a = Perturb::new(Box::new(Ring::new(white(), black())), 0.3, 9);
+            let b = Perturb::new(Box::new(Ring::new(white(), black())), 0.3, 9);
+            for point in sample_points() {
+                assert_eq!(a.pattern_at(&point), b.pattern_at(&point));
+            }
+        }
+    }
+}
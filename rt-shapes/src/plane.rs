@@ -0,0 +1,123 @@
+use crate::{BoundingBox, Intersection, Intersections, Shape, ShapeBuilder, ShapeData};
+use rt_core::{Point, Ray, Scalar, Vector, EPSILON};
+
+#[derive(Debug, Default)]
+pub struct Plane {
+    data: ShapeData,
+}
+
+impl Plane {
+    pub fn new() -> Self {
+        Plane::default()
+    }
+
+    pub fn builder() -> ShapeBuilder<Plane> {
+        ShapeBuilder::new(Plane::new())
+    }
+}
+
+impl Shape for Plane {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        if ray.direction.y().abs() < EPSILON {
+            return Intersections::default();
+        }
+        let t = -ray.origin.y() / ray.direction.y();
+        Intersections::new(vec![Intersection::new(t, self)])
+    }
+
+    fn local_normal_at(&self, _point: &Point, _hit: &Intersection) -> Vector {
+        Vector::new(0.0, 1.0, 0.0)
+    }
+
+    fn local_tangent_at(&self, _point: &Point, _hit: &Intersection) -> Vector {
+        Vector::new(1.0, 0.0, 0.0)
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Point::new(Scalar::NEG_INFINITY, 0.0, Scalar::NEG_INFINITY),
+            Point::new(Scalar::INFINITY, 0.0, Scalar::INFINITY),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_is_constant_everywhere() {
+        let p = Plane::new();
+        let hit = Intersection::new(0.0, &p);
+        for point in [
+            Point::origin(),
+            Point::new(10.0, 0.0, -10.0),
+            Point::new(-5.0, 0.0, 150.0),
+        ] {
+            assert_eq!(p.local_normal_at(&point, &hit), Vector::new(0.0, 1.0, 0.0));
+        }
+    }
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn ray_parallel_to_the_plane() {
+            let r = Ray::new(Point::new(0.0, 10.0, 0.0), Vector::new(0.0, 0.0, 1.0));
+            assert!(Plane::new().local_intersect(&r).is_empty());
+        }
+
+        #[test]
+        fn coplanar_ray() {
+            let r = Ray::new(Point::origin(), Vector::new(0.0, 0.0, 1.0));
+            assert!(Plane::new().local_intersect(&r).is_empty());
+        }
+
+        #[test]
+        fn ray_from_above() {
+            let p = Plane::new();
+            let r = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+            let xs = p.local_intersect(&r);
+            assert_eq!(xs.len(), 1);
+            assert_eq!(xs[0], Intersection::new(1.0, &p));
+        }
+
+        #[test]
+        fn ray_from_below() {
+            let p = Plane::new();
+            let r = Ray::new(Point::new(0.0, -1.0, 0.0), Vector::new(0.0, 1.0, 0.0));
+            let xs = p.local_intersect(&r);
+            assert_eq!(xs.len(), 1);
+            assert_eq!(xs[0], Intersection::new(1.0, &p));
+        }
+    }
+
+    #[test]
+    fn plane_has_an_infinite_flat_bounding_box() {
+        let b = Plane::new().local_bounds();
+        assert_eq!(b.min.x(), Scalar::NEG_INFINITY);
+        assert_eq!(b.min.y(), 0.0);
+        assert_eq!(b.min.z(), Scalar::NEG_INFINITY);
+        assert_eq!(b.max.x(), Scalar::INFINITY);
+        assert_eq!(b.max.y(), 0.0);
+        assert_eq!(b.max.z(), Scalar::INFINITY);
+    }
+
+    #[test]
+    fn tangent_runs_along_the_x_axis() {
+        let p = Plane::new();
+        let hit = Intersection::new(0.0, &p);
+        assert_eq!(
+            p.tangent_at(&Point::new(10.0, 0.0, -10.0), &hit),
+            Vector::new(1.0, 0.0, 0.0)
+        );
+    }
+}
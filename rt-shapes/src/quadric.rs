@@ -0,0 +1,226 @@
+use crate::{BoundingBox, Intersection, Intersections, Shape, ShapeData};
+use rt_core::{solve_quadratic, Matrix, Point, Ray, Scalar, Tuple, Vector, EPSILON};
+
+#[derive(Debug)]
+pub struct Quadric {
+    data: ShapeData,
+    coefficients: Matrix<4, 4>,
+    clip: BoundingBox,
+}
+
+impl Quadric {
+    pub fn new(coefficients: Matrix<4, 4>) -> Self {
+        let symmetric = Matrix::from(std::array::from_fn(|row| {
+            std::array::from_fn(|col| (coefficients[row][col] + coefficients[col][row]) / 2.0)
+        }));
+        Quadric {
+            data: ShapeData::default(),
+            coefficients: symmetric,
+            clip: BoundingBox::new(
+                Point::new(
+                    Scalar::NEG_INFINITY,
+                    Scalar::NEG_INFINITY,
+                    Scalar::NEG_INFINITY,
+                ),
+                Point::new(Scalar::INFINITY, Scalar::INFINITY, Scalar::INFINITY),
+            ),
+        }
+    }
+
+    pub fn ellipsoid(a: Scalar, b: Scalar, c: Scalar) -> Self {
+        Quadric::new(diagonal(1.0 / (a * a), 1.0 / (b * b), 1.0 / (c * c), -1.0)).clipped(
+            BoundingBox::new(Point::new(-a, -b, -c), Point::new(a, b, c)),
+        )
+    }
+
+    pub fn paraboloid() -> Self {
+        let mut coefficients = diagonal(1.0, 0.0, 1.0, 0.0);
+        coefficients[1][3] = -0.5;
+        coefficients[3][1] = -0.5;
+        Quadric::new(coefficients)
+    }
+
+    pub fn hyperboloid() -> Self {
+        Quadric::new(diagonal(1.0, -1.0, 1.0, -1.0))
+    }
+
+    pub fn hyperboloid_of_two_sheets() -> Self {
+        Quadric::new(diagonal(1.0, -1.0, 1.0, 1.0))
+    }
+
+    pub fn clipped(mut self, clip: BoundingBox) -> Self {
+        self.clip = clip;
+        self
+    }
+
+    pub fn coefficients(&self) -> &Matrix<4, 4> {
+        &self.coefficients
+    }
+
+    pub fn clip(&self) -> &BoundingBox {
+        &self.clip
+    }
+
+    fn form(&self, u: Tuple<4>, v: Tuple<4>) -> Scalar {
+        let qv = self.coefficients * v;
+        (0..4).map(|i| u[i] * qv[i]).sum()
+    }
+
+    fn within_clip(&self, point: &Point) -> bool {
+        let min = self.clip.min;
+        let max = self.clip.max;
+        point.x() >= min.x() - EPSILON
+            && point.x() <= max.x() + EPSILON
+            && point.y() >= min.y() - EPSILON
+            && point.y() <= max.y() + EPSILON
+            && point.z() >= min.z() - EPSILON
+            && point.z() <= max.z() + EPSILON
+    }
+}
+
+fn diagonal(x: Scalar, y: Scalar, z: Scalar, w: Scalar) -> Matrix<4, 4> {
+    Matrix::from([
+        [x, 0.0, 0.0, 0.0],
+        [0.0, y, 0.0, 0.0],
+        [0.0, 0.0, z, 0.0],
+        [0.0, 0.0, 0.0, w],
+    ])
+}
+
+impl Shape for Quadric {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let o: Tuple<4> = ray.origin.into();
+        let d: Tuple<4> = ray.direction.into();
+        let a = self.form(d, d);
+        let b = 2.0 * self.form(d, o);
+        let c = self.form(o, o);
+        Intersections::new(
+            solve_quadratic(a, b, c)
+                .into_iter()
+                .filter(|&t| self.within_clip(&ray.position(t)))
+                .map(|t| Intersection::new(t, self))
+                .collect(),
+        )
+    }
+
+    fn local_normal_at(&self, point: &Point, _hit: &Intersection) -> Vector {
+        let gradient = self.coefficients * Tuple::<4>::from(*point);
+        Vector::new(gradient[0], gradient[1], gradient[2])
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        self.clip
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(xs: &Intersections) -> Vec<Scalar> {
+        xs.iter().map(|i| i.t).collect()
+    }
+
+    fn assert_ts(xs: &Intersections, expected: &[Scalar]) {
+        let actual = ts(xs);
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < EPSILON, "{:?}", actual);
+        }
+    }
+
+    #[test]
+    fn coefficients_are_made_symmetric() {
+        let mut m = diagonal(1.0, 1.0, 1.0, -1.0);
+        m[0][3] = 2.0;
+        let q = Quadric::new(m);
+        assert_eq!(q.coefficients()[0][3], 1.0);
+        assert_eq!(q.coefficients()[3][0], 1.0);
+    }
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn a_unit_quadric_matches_a_sphere() {
+            let q = Quadric::new(diagonal(1.0, 1.0, 1.0, -1.0));
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert_eq!(ts(&q.local_intersect(&r)), vec![4.0, 6.0]);
+        }
+
+        #[test]
+        fn ellipsoids_stretch_along_their_axes() {
+            let q = Quadric::ellipsoid(1.0, 2.0, 3.0);
+            let along_y = Ray::new(Point::new(0.0, -5.0, 0.0), Vector::new(0.0, 1.0, 0.0));
+            let along_z = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert_ts(&q.local_intersect(&along_y), &[3.0, 7.0]);
+            assert_ts(&q.local_intersect(&along_z), &[2.0, 8.0]);
+        }
+
+        #[test]
+        fn a_ray_along_the_paraboloid_axis_hits_its_vertex_once() {
+            let q = Quadric::paraboloid();
+            let r = Ray::new(Point::new(0.0, -5.0, 0.0), Vector::new(0.0, 1.0, 0.0));
+            assert_eq!(ts(&q.local_intersect(&r)), vec![5.0]);
+        }
+
+        #[test]
+        fn hyperboloids_have_a_waist() {
+            let one = Quadric::hyperboloid();
+            let r = Ray::new(Point::new(-5.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0));
+            assert_eq!(ts(&one.local_intersect(&r)), vec![4.0, 6.0]);
+            let two = Quadric::hyperboloid_of_two_sheets();
+            assert!(two.local_intersect(&r).is_empty());
+            let up = Ray::new(Point::new(0.0, -5.0, 0.0), Vector::new(0.0, 1.0, 0.0));
+            assert_eq!(ts(&two.local_intersect(&up)), vec![4.0, 6.0]);
+        }
+
+        #[test]
+        fn hits_outside_the_clip_box_are_discarded() {
+            let q = Quadric::hyperboloid().clipped(BoundingBox::new(
+                Point::new(-5.0, -1.0, -5.0),
+                Point::new(5.0, 1.0, 5.0),
+            ));
+            let inside = Ray::new(Point::new(-5.0, 0.5, 0.0), Vector::new(1.0, 0.0, 0.0));
+            let outside = Ray::new(Point::new(-5.0, 2.0, 0.0), Vector::new(1.0, 0.0, 0.0));
+            assert_eq!(q.local_intersect(&inside).len(), 2);
+            assert!(q.local_intersect(&outside).is_empty());
+        }
+    }
+
+    #[test]
+    fn normals_follow_the_gradient() {
+        let q = Quadric::paraboloid();
+        let hit = Intersection::new(0.0, &q);
+        assert_eq!(
+            q.local_normal_at(&Point::origin(), &hit),
+            Vector::new(0.0, -0.5, 0.0)
+        );
+        assert_eq!(
+            q.local_normal_at(&Point::new(1.0, 1.0, 0.0), &hit),
+            Vector::new(1.0, -0.5, 0.0)
+        );
+    }
+
+    #[test]
+    fn a_nan_ray_finds_nothing() {
+        let nan = Scalar::NAN;
+        let r = Ray::new(Point::new(nan, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert!(Quadric::paraboloid().local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn ellipsoids_are_bounded_by_their_radii() {
+        let b = Quadric::ellipsoid(1.0, 2.0, 3.0).local_bounds();
+        assert_eq!(b.min, Point::new(-1.0, -2.0, -3.0));
+        assert_eq!(b.max, Point::new(1.0, 2.0, 3.0));
+    }
+}
@@ -0,0 +1,391 @@
+use crate::{BoundingBox, Intersection, Intersections, Shape, ShapeData};
+use rt_core::{Point, Ray, Scalar, Vector};
+use std::sync::Arc;
+
+const MAX_STEPS: usize = 512;
+const MIN_STEP: Scalar = 1e-4;
+const MARCH_MARGIN: Scalar = 1e-3;
+const REFINE_STEPS: usize = 24;
+const GRADIENT_DELTA: Scalar = 1e-5;
+
+type DistanceFn = dyn Fn(&Point) -> Scalar + Send + Sync;
+
+#[derive(Clone)]
+pub struct CustomSdf {
+    function: Arc<DistanceFn>,
+    bounds: BoundingBox,
+}
+
+impl std::fmt::Debug for CustomSdf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomSdf")
+            .field("bounds", &self.bounds)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Sdf {
+    Sphere { radius: Scalar },
+    Cuboid { half_extents: Vector },
+    Torus { major: Scalar, minor: Scalar },
+    Capsule { a: Point, b: Point, radius: Scalar },
+    Custom(CustomSdf),
+    Translate(Box<Sdf>, Vector),
+    Union(Box<Sdf>, Box<Sdf>, Scalar),
+    Subtract(Box<Sdf>, Box<Sdf>, Scalar),
+    Intersect(Box<Sdf>, Box<Sdf>, Scalar),
+}
+
+impl Sdf {
+    pub fn sphere(radius: Scalar) -> Self {
+        Sdf::Sphere { radius }
+    }
+
+    pub fn cuboid(half_extents: Vector) -> Self {
+        Sdf::Cuboid { half_extents }
+    }
+
+    pub fn torus(major: Scalar, minor: Scalar) -> Self {
+        Sdf::Torus { major, minor }
+    }
+
+    pub fn capsule(a: Point, b: Point, radius: Scalar) -> Self {
+        Sdf::Capsule { a, b, radius }
+    }
+
+    pub fn custom<F>(function: F, bounds: BoundingBox) -> Self
+    where
+        F: Fn(&Point) -> Scalar + Send + Sync + 'static,
+    {
+        Sdf::Custom(CustomSdf {
+            function: Arc::new(function),
+            bounds,
+        })
+    }
+
+    pub fn translate(self, x: Scalar, y: Scalar, z: Scalar) -> Self {
+        Sdf::Translate(Box::new(self), Vector::new(x, y, z))
+    }
+
+    pub fn union(self, other: Sdf) -> Self {
+        self.smooth_union(other, 0.0)
+    }
+
+    pub fn smooth_union(self, other: Sdf, blend: Scalar) -> Self {
+        Sdf::Union(Box::new(self), Box::new(other), blend)
+    }
+
+    pub fn subtract(self, other: Sdf) -> Self {
+        self.smooth_subtract(other, 0.0)
+    }
+
+    pub fn smooth_subtract(self, other: Sdf, blend: Scalar) -> Self {
+        Sdf::Subtract(Box::new(self), Box::new(other), blend)
+    }
+
+    pub fn intersect(self, other: Sdf) -> Self {
+        self.smooth_intersect(other, 0.0)
+    }
+
+    pub fn smooth_intersect(self, other: Sdf, blend: Scalar) -> Self {
+        Sdf::Intersect(Box::new(self), Box::new(other), blend)
+    }
+
+    pub fn distance(&self, p: &Point) -> Scalar {
+        match self {
+            Sdf::Sphere { radius } => (*p - Point::origin()).magnitude() - radius,
+            Sdf::Cuboid { half_extents } => {
+                let q = [
+                    p.x().abs() - half_extents.x(),
+                    p.y().abs() - half_extents.y(),
+                    p.z().abs() - half_extents.z(),
+                ];
+                let outside = q.iter().map(|c| c.max(0.0).powi(2)).sum::<Scalar>().sqrt();
+                outside + q[0].max(q[1]).max(q[2]).min(0.0)
+            }
+            Sdf::Torus { major, minor } => {
+                let ring = (p.x().powi(2) + p.z().powi(2)).sqrt() - major;
+                (ring.powi(2) + p.y().powi(2)).sqrt() - minor
+            }
+            Sdf::Capsule { a, b, radius } => {
+                let pa = *p - *a;
+                let ba = *b - *a;
+                let h = (pa.dot(&ba) / ba.dot(&ba)).clamp(0.0, 1.0);
+                (pa - ba * h).magnitude() - radius
+            }
+            Sdf::Custom(custom) => (custom.function)(p),
+            Sdf::Translate(sdf, offset) => sdf.distance(&(*p - *offset)),
+            Sdf::Union(a, b, k) => smooth_min(a.distance(p), b.distance(p), *k),
+            Sdf::Subtract(a, b, k) => -smooth_min(-a.distance(p), b.distance(p), *k),
+            Sdf::Intersect(a, b, k) => -smooth_min(-a.distance(p), -b.distance(p), *k),
+        }
+    }
+
+    pub fn bounds(&self) -> BoundingBox {
+        match self {
+            Sdf::Sphere { radius } => cube_bounds(*radius),
+            Sdf::Cuboid { half_extents } => BoundingBox::new(
+                Point::origin() - *half_extents,
+                Point::origin() + *half_extents,
+            ),
+            Sdf::Torus { major, minor } => BoundingBox::new(
+                Point::new(-(major + minor), -minor, -(major + minor)),
+                Point::new(major + minor, *minor, major + minor),
+            ),
+            Sdf::Capsule { a, b, radius } => {
+                let mut bounds = BoundingBox::empty();
+                bounds.add_point(a);
+                bounds.add_point(b);
+                grow(&bounds, *radius)
+            }
+            Sdf::Custom(custom) => custom.bounds,
+            Sdf::Translate(sdf, offset) => {
+                let bounds = sdf.bounds();
+                BoundingBox::new(bounds.min + *offset, bounds.max + *offset)
+            }
+            Sdf::Union(a, b, k) => {
+                let mut bounds = a.bounds();
+                bounds.merge(&b.bounds());
+                grow(&bounds, k / 4.0)
+            }
+            Sdf::Subtract(a, _, _) => a.bounds(),
+            Sdf::Intersect(a, b, _) => {
+                let (a, b) = (a.bounds(), b.bounds());
+                BoundingBox::new(
+                    Point::new(
+                        a.min.x().max(b.min.x()),
+                        a.min.y().max(b.min.y()),
+                        a.min.z().max(b.min.z()),
+                    ),
+                    Point::new(
+                        a.max.x().min(b.max.x()),
+                        a.max.y().min(b.max.y()),
+                        a.max.z().min(b.max.z()),
+                    ),
+                )
+            }
+        }
+    }
+}
+
+fn smooth_min(a: Scalar, b: Scalar, k: Scalar) -> Scalar {
+    if k <= 0.0 {
+        return a.min(b);
+    }
+    let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+    b + (a - b) * h - k * h * (1.0 - h)
+}
+
+fn cube_bounds(half: Scalar) -> BoundingBox {
+    BoundingBox::new(
+        Point::new(-half, -half, -half),
+        Point::new(half, half, half),
+    )
+}
+
+fn grow(bounds: &BoundingBox, amount: Scalar) -> BoundingBox {
+    let margin = Vector::new(amount, amount, amount);
+    BoundingBox::new(bounds.min - margin, bounds.max + margin)
+}
+
+#[derive(Debug)]
+pub struct SdfShape {
+    data: ShapeData,
+    sdf: Sdf,
+    bounds: BoundingBox,
+}
+
+impl SdfShape {
+    pub fn new(sdf: Sdf) -> Self {
+        SdfShape {
+            data: ShapeData::default(),
+            bounds: sdf.bounds(),
+            sdf,
+        }
+    }
+
+    pub fn sdf(&self) -> &Sdf {
+        &self.sdf
+    }
+
+    fn refine(&self, ray: &Ray, mut outside: Scalar, mut inside: Scalar, sign: Scalar) -> Scalar {
+        for _ in 0..REFINE_STEPS {
+            let middle = (outside + inside) / 2.0;
+            if self.sdf.distance(&ray.position(middle)) * sign > 0.0 {
+                outside = middle;
+            } else {
+                inside = middle;
+            }
+        }
+        (outside + inside) / 2.0
+    }
+}
+
+impl Shape for SdfShape {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let (start, end) = match grow(&self.bounds, MARCH_MARGIN).intersection_range(ray) {
+            Some(range) => range,
+            None => return Intersections::default(),
+        };
+        let speed = ray.direction.magnitude();
+        let mut hits = Vec::new();
+        let mut t = start;
+        let mut distance = self.sdf.distance(&ray.position(t));
+        for _ in 0..MAX_STEPS {
+            let next = t + distance.abs().max(MIN_STEP) / speed;
+            if next > end {
+                break;
+            }
+            let next_distance = self.sdf.distance(&ray.position(next));
+            if next_distance.signum() != distance.signum() {
+                let hit = self.refine(ray, t, next, distance.signum());
+                hits.push(Intersection::new(hit, self));
+            }
+            t = next;
+            distance = next_distance;
+        }
+        Intersections::new(hits)
+    }
+
+    fn local_normal_at(&self, point: &Point, _hit: &Intersection) -> Vector {
+        let gradient = |axis: Vector| {
+            self.sdf.distance(&(*point + axis * GRADIENT_DELTA))
+                - self.sdf.distance(&(*point - axis * GRADIENT_DELTA))
+        };
+        Vector::new(
+            gradient(Vector::new(1.0, 0.0, 0.0)),
+            gradient(Vector::new(0.0, 1.0, 0.0)),
+            gradient(Vector::new(0.0, 0.0, 1.0)),
+        )
+        .normalize()
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::{Matrix, EPSILON};
+
+    fn ts(xs: &Intersections) -> Vec<Scalar> {
+        xs.iter().map(|i| i.t).collect()
+    }
+
+    fn assert_close(actual: &[Scalar], expected: &[Scalar]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < EPSILON, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    mod distances {
+        use super::*;
+
+        #[test]
+        fn primitive_distances() {
+            let p = Point::new(0.0, 3.0, 0.0);
+            assert_eq!(Sdf::sphere(1.0).distance(&p), 2.0);
+            assert_eq!(Sdf::cuboid(Vector::new(1.0, 1.0, 1.0)).distance(&p), 2.0);
+            assert_eq!(Sdf::torus(2.0, 0.5).distance(&p), Scalar::sqrt(13.0) - 0.5);
+            let capsule = Sdf::capsule(Point::origin(), Point::new(0.0, 2.0, 0.0), 0.5);
+            assert_eq!(capsule.distance(&p), 0.5);
+            assert_eq!(Sdf::sphere(1.0).distance(&Point::origin()), -1.0);
+        }
+
+        #[test]
+        fn hard_combinators_match_csg() {
+            let a = || Sdf::sphere(1.0);
+            let b = || Sdf::sphere(1.0).translate(1.5, 0.0, 0.0);
+            let p = Point::new(0.25, 0.0, 0.0);
+            assert_eq!(a().union(b()).distance(&p), -0.75);
+            assert_eq!(a().intersect(b()).distance(&p), 0.25);
+            assert_eq!(a().subtract(b()).distance(&p), -0.25);
+        }
+
+        #[test]
+        fn smooth_union_fills_the_gap_between_shapes() {
+            let a = Sdf::sphere(1.0).translate(-1.2, 0.0, 0.0);
+            let b = Sdf::sphere(1.0).translate(1.2, 0.0, 0.0);
+            let gap = Point::origin();
+            assert!(a.clone().union(b.clone()).distance(&gap) > 0.0);
+            assert!(a.smooth_union(b, 1.0).distance(&gap) < 0.0);
+        }
+
+        #[test]
+        fn custom_functions_are_called() {
+            let plane = Sdf::custom(|p| p.y(), cube_bounds(1.0));
+            assert_eq!(plane.distance(&Point::new(3.0, -0.5, 2.0)), -0.5);
+            assert_eq!(plane.bounds(), cube_bounds(1.0));
+        }
+    }
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn sphere_tracing_finds_entry_and_exit() {
+            let s = SdfShape::new(Sdf::sphere(1.0));
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert_close(&ts(&s.local_intersect(&r)), &[4.0, 6.0]);
+        }
+
+        #[test]
+        fn rays_can_start_inside() {
+            let s = SdfShape::new(Sdf::cuboid(Vector::new(1.0, 2.0, 1.0)));
+            let r = Ray::new(Point::origin(), Vector::new(0.0, 1.0, 0.0));
+            assert_close(&ts(&s.local_intersect(&r)), &[-2.0, 2.0]);
+        }
+
+        #[test]
+        fn rays_through_a_torus_hole_cross_four_surfaces() {
+            let s = SdfShape::new(Sdf::torus(2.0, 0.5));
+            let r = Ray::new(Point::new(-5.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0));
+            assert_close(&ts(&s.local_intersect(&r)), &[2.5, 3.5, 6.5, 7.5]);
+            let above = Ray::new(Point::new(-5.0, 1.0, 0.0), Vector::new(1.0, 0.0, 0.0));
+            assert!(s.local_intersect(&above).is_empty());
+        }
+
+        #[test]
+        fn unnormalized_directions_keep_ray_units() {
+            let mut s = SdfShape::new(Sdf::sphere(1.0));
+            s.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert_close(&ts(&s.intersect(&r)), &[3.0, 7.0]);
+        }
+
+        #[test]
+        fn normals_follow_the_distance_gradient() {
+            let s = SdfShape::new(Sdf::sphere(1.0));
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let xs = s.local_intersect(&r);
+            let k = Scalar::sqrt(3.0) / 3.0;
+            let p = Point::new(k, k, k);
+            assert_eq!(s.local_normal_at(&p, &xs[0]), Vector::new(k, k, k));
+        }
+    }
+
+    #[test]
+    fn bounds_cover_blended_shapes() {
+        let s = SdfShape::new(
+            Sdf::sphere(1.0)
+                .translate(-1.0, 0.0, 0.0)
+                .smooth_union(Sdf::sphere(1.0).translate(1.0, 0.0, 0.0), 0.4),
+        );
+        assert_eq!(
+            s.local_bounds(),
+            BoundingBox::new(Point::new(-2.1, -1.1, -1.1), Point::new(2.1, 1.1, 1.1))
+        );
+    }
+}
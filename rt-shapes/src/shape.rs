@@ -0,0 +1,508 @@
+use crate::stats;
+use crate::{BoundingBox, Intersection, Intersections, Material};
+use rt_core::{Matrix, Point, Ray, Scalar, Vector};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeData {
+    transform: Matrix<4, 4>,
+    inverse_transform: Matrix<4, 4>,
+    material: Material,
+    motion: Option<Matrix<4, 4>>,
+    pub(crate) casts_shadow: bool,
+    pub(crate) receives_shadow: bool,
+}
+
+impl ShapeData {
+    pub fn set_transform(&mut self, transform: Matrix<4, 4>) {
+        self.inverse_transform = transform
+            .inverse()
+            .expect("shape transform must be invertible");
+        self.transform = transform;
+    }
+
+    pub fn set_motion(&mut self, end: Matrix<4, 4>) {
+        end.inverse()
+            .expect("shape end transform must be invertible");
+        self.motion = Some(end);
+    }
+}
+
+impl Default for ShapeData {
+    fn default() -> Self {
+        ShapeData {
+            transform: Matrix::identity(),
+            inverse_transform: Matrix::identity(),
+            material: Material::default(),
+            motion: None,
+            casts_shadow: true,
+            receives_shadow: true,
+        }
+    }
+}
+
+const MOTION_BOUND_STEPS: usize = 8;
+
+pub trait Shape: std::any::Any + std::fmt::Debug + Send + Sync {
+    fn data(&self) -> &ShapeData;
+    fn data_mut(&mut self) -> &mut ShapeData;
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_>;
+    fn local_normal_at(&self, point: &Point, hit: &Intersection) -> Vector;
+    fn local_bounds(&self) -> BoundingBox;
+
+    fn transform(&self) -> &Matrix<4, 4> {
+        &self.data().transform
+    }
+
+    fn inverse_transform(&self) -> &Matrix<4, 4> {
+        &self.data().inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix<4, 4>) {
+        self.data_mut().set_transform(transform);
+    }
+
+    fn end_transform(&self) -> Option<&Matrix<4, 4>> {
+        self.data().motion.as_ref()
+    }
+
+    fn set_motion(&mut self, end: Matrix<4, 4>) {
+        self.data_mut().set_motion(end);
+    }
+
+    fn transform_at(&self, time: Scalar) -> Matrix<4, 4> {
+        match self.end_transform() {
+            Some(end) => self.transform().interpolate(end, time.clamp(0.0, 1.0)),
+            None => *self.transform(),
+        }
+    }
+
+    fn inverse_transform_at(&self, time: Scalar) -> Matrix<4, 4> {
+        match self.end_transform() {
+            Some(_) => self
+                .transform_at(time)
+                .inverse()
+                .expect("interpolated transform must be invertible"),
+            None => *self.inverse_transform(),
+        }
+    }
+
+    fn material(&self) -> &Material {
+        &self.data().material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.data_mut().material
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.data_mut().material = material;
+    }
+
+    fn casts_shadow(&self) -> bool {
+        self.data().casts_shadow
+    }
+
+    fn set_casts_shadow(&mut self, casts: bool) {
+        self.data_mut().casts_shadow = casts;
+    }
+
+    fn receives_shadow(&self) -> bool {
+        self.data().receives_shadow
+    }
+
+    fn set_receives_shadow(&mut self, receives: bool) {
+        self.data_mut().receives_shadow = receives;
+    }
+
+    fn material_at<'a>(&'a self, _hit: &Intersection<'a>) -> &'a Material {
+        self.material()
+    }
+
+    fn intersect(&self, ray: &Ray) -> Intersections<'_> {
+        stats::record(|stats| stats.intersection_tests += 1);
+        if self.end_transform().is_none() {
+            return self.local_intersect(&ray.transform(self.inverse_transform()));
+        }
+        let inverse = match self.transform_at(ray.time).inverse() {
+            Some(inverse) => inverse,
+            None => return Intersections::default(),
+        };
+        self.local_intersect(&ray.transform(&inverse))
+            .into_iter()
+            .map(|hit| Intersection {
+                time: ray.time,
+                ..hit
+            })
+            .collect()
+    }
+
+    fn intersect_packet(&self, rays: &[Ray; 4]) -> [Intersections<'_>; 4] {
+        rays.each_ref().map(|ray| self.intersect(ray))
+    }
+
+    fn world_to_object(&self, point: &Point) -> Point {
+        *self.inverse_transform() * *point
+    }
+
+    fn normal_to_world(&self, normal: &Vector) -> Vector {
+        (self.inverse_transform().transpose() * *normal).normalize()
+    }
+
+    fn normal_at(&self, point: &Point, hit: &Intersection) -> Vector {
+        let inverse = self.inverse_transform_at(hit.time);
+        let local_normal = self.local_normal_at(&(inverse * *point), hit);
+        (inverse.transpose() * local_normal).normalize()
+    }
+
+    fn local_tangent_at(&self, point: &Point, hit: &Intersection) -> Vector {
+        perpendicular_to(&self.local_normal_at(point, hit))
+    }
+
+    fn tangent_at(&self, point: &Point, hit: &Intersection) -> Vector {
+        let local_point = self.inverse_transform_at(hit.time) * *point;
+        (self.transform_at(hit.time) * self.local_tangent_at(&local_point, hit)).normalize()
+    }
+
+    fn local_sample_point(&self, _u: Scalar, _v: Scalar) -> Option<(Point, Vector)> {
+        None
+    }
+
+    fn sample_point(&self, u: Scalar, v: Scalar) -> Option<(Point, Vector)> {
+        let (point, normal) = self.local_sample_point(u, v)?;
+        Some((*self.transform() * point, self.normal_to_world(&normal)))
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let local = self.local_bounds();
+        let mut bounds = local.transform(self.transform());
+        if self.end_transform().is_some() {
+            for step in 1..=MOTION_BOUND_STEPS {
+                let time = step as Scalar / MOTION_BOUND_STEPS as Scalar;
+                bounds.merge(&local.transform(&self.transform_at(time)));
+            }
+        }
+        bounds
+    }
+
+    fn divide(&mut self, _threshold: usize) {}
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        std::ptr::eq(
+            self as *const Self as *const (),
+            other as *const dyn Shape as *const (),
+        )
+    }
+}
+
+pub(crate) fn perpendicular_to(vector: &Vector) -> Vector {
+    let axis = if vector.x().abs() > 0.9 {
+        Vector::new(0.0, 1.0, 0.0)
+    } else {
+        Vector::new(1.0, 0.0, 0.0)
+    };
+    vector.cross(&axis).normalize()
+}
+
+pub(crate) fn rebase(child: &mut dyn Shape, matrix: &Matrix<4, 4>) {
+    if let Some(end) = child.end_transform().copied() {
+        child.set_motion(*matrix * end);
+    }
+    child.set_transform(*matrix * *child.transform());
+}
+
+pub(crate) fn rebase_motion(
+    child: &mut dyn Shape,
+    parent_inverse: &Matrix<4, 4>,
+    end: &Matrix<4, 4>,
+) {
+    let child_end = *child.end_transform().unwrap_or(child.transform());
+    child.set_motion(*end * *parent_inverse * child_end);
+}
+
+pub(crate) fn same_shape(a: &dyn Shape, b: &dyn Shape) -> bool {
+    std::ptr::eq(
+        a as *const dyn Shape as *const (),
+        b as *const dyn Shape as *const (),
+    )
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use rt_core::consts::{FRAC_1_SQRT_2, PI};
+    use std::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    pub(crate) struct TestShape {
+        data: ShapeData,
+        pub(crate) saved_ray: Mutex<Option<Ray>>,
+    }
+
+    impl Shape for TestShape {
+        fn data(&self) -> &ShapeData {
+            &self.data
+        }
+
+        fn data_mut(&mut self) -> &mut ShapeData {
+            &mut self.data
+        }
+
+        fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+            *self.saved_ray.lock().unwrap() = Some(*ray);
+            Intersections::default()
+        }
+
+        fn local_normal_at(&self, point: &Point, _hit: &Intersection) -> Vector {
+            Vector::new(point.x(), point.y(), point.z())
+        }
+
+        fn local_bounds(&self) -> BoundingBox {
+            BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+        }
+    }
+
+    mod transformation {
+        use super::*;
+
+        #[test]
+        fn default_transformation() {
+            assert_eq!(*TestShape::default().transform(), Matrix::identity());
+        }
+
+        #[test]
+        fn assigning_a_transformation() {
+            let mut s = TestShape::default();
+            s.set_transform(Matrix::translation(2.0, 3.0, 4.0));
+            assert_eq!(*s.transform(), Matrix::translation(2.0, 3.0, 4.0));
+            assert_eq!(
+                *s.inverse_transform(),
+                Matrix::translation(-2.0, -3.0, -4.0)
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "invertible")]
+        fn non_invertible_transformation() {
+            TestShape::default().set_transform(Matrix::scaling(0.0, 1.0, 1.0));
+        }
+    }
+
+    mod material {
+        use super::*;
+        use rt_core::Color;
+
+        #[test]
+        fn default_material() {
+            assert_eq!(*TestShape::default().material(), Material::default());
+        }
+
+        #[test]
+        fn assigning_a_material() {
+            let mut s = TestShape::default();
+            let m = Material {
+                color: Color::new(1.0, 0.0, 0.0),
+                ambient: 1.0,
+                ..Material::default()
+            };
+            s.set_material(m.clone());
+            assert_eq!(*s.material(), m);
+        }
+    }
+
+    mod shadows {
+        use super::*;
+
+        #[test]
+        fn shapes_cast_and_receive_shadows_by_default() {
+            let s = TestShape::default();
+            assert!(s.casts_shadow());
+            assert!(s.receives_shadow());
+        }
+
+        #[test]
+        fn shadow_flags_are_independent() {
+            let mut s = TestShape::default();
+            s.set_casts_shadow(false);
+            assert!(!s.casts_shadow());
+            assert!(s.receives_shadow());
+            s.set_receives_shadow(false);
+            s.set_casts_shadow(true);
+            assert!(s.casts_shadow());
+            assert!(!s.receives_shadow());
+        }
+    }
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn intersecting_a_scaled_shape() {
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let mut s = TestShape::default();
+            s.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+            s.intersect(&r);
+            let saved = s.saved_ray.lock().unwrap().unwrap();
+            assert_eq!(saved.origin, Point::new(0.0, 0.0, -2.5));
+            assert_eq!(saved.direction, Vector::new(0.0, 0.0, 0.5));
+        }
+
+        #[test]
+        fn intersecting_a_translated_shape() {
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let mut s = TestShape::default();
+            s.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+            s.intersect(&r);
+            let saved = s.saved_ray.lock().unwrap().unwrap();
+            assert_eq!(saved.origin, Point::new(-5.0, 0.0, -5.0));
+            assert_eq!(saved.direction, Vector::new(0.0, 0.0, 1.0));
+        }
+    }
+
+    mod normal {
+        use super::*;
+
+        fn normal_at(s: &dyn Shape, point: Point) -> Vector {
+            s.normal_at(&point, &Intersection::new(0.0, s))
+        }
+
+        #[test]
+        fn normal_on_a_translated_shape() {
+            let mut s = TestShape::default();
+            s.set_transform(Matrix::translation(0.0, 1.0, 0.0));
+            assert_eq!(
+                normal_at(&s, Point::new(0.0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2)),
+                Vector::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2)
+            );
+        }
+
+        #[test]
+        fn normal_on_a_transformed_shape() {
+            let mut s = TestShape::default();
+            s.set_transform(Matrix::scaling(1.0, 0.5, 1.0) * Matrix::rotation_z(PI / 5.0));
+            let h = Scalar::sqrt(2.0) / 2.0;
+            assert_eq!(
+                normal_at(&s, Point::new(0.0, h, -h)),
+                Vector::new(0.0, 0.97014, -0.24254)
+            );
+        }
+    }
+
+    mod bounds {
+        use super::*;
+
+        #[test]
+        fn querying_a_shapes_bounding_box_in_its_parents_space() {
+            let mut s = TestShape::default();
+            s.set_transform(Matrix::translation(1.0, -3.0, 5.0) * Matrix::scaling(0.5, 2.0, 4.0));
+            let b = s.bounds();
+            assert_eq!(b.min, Point::new(0.5, -5.0, 1.0));
+            assert_eq!(b.max, Point::new(1.5, -1.0, 9.0));
+        }
+    }
+
+    mod motion {
+        use super::*;
+        use crate::{Group, Sphere};
+
+        fn moving_sphere() -> Sphere {
+            let mut s = Sphere::new();
+            s.set_motion(Matrix::translation(4.0, 0.0, 0.0));
+            s
+        }
+
+        fn along_z(x: Scalar, time: Scalar) -> Ray {
+            Ray::new(Point::new(x, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0)).with_time(time)
+        }
+
+        #[test]
+        fn static_shapes_ignore_time() {
+            let s = TestShape::default();
+            assert_eq!(s.end_transform(), None);
+            assert_eq!(s.transform_at(0.7), Matrix::identity());
+        }
+
+        #[test]
+        fn rays_meet_the_shape_where_it_is_at_their_time() {
+            let s = moving_sphere();
+            assert_eq!(s.intersect(&along_z(0.0, 0.0)).len(), 2);
+            assert!(s.intersect(&along_z(0.0, 1.0)).is_empty());
+            let xs = s.intersect(&along_z(2.0, 0.5));
+            assert_eq!(xs.len(), 2);
+            assert_eq!(xs[0].time, 0.5);
+        }
+
+        #[test]
+        fn times_outside_the_shutter_are_clamped() {
+            let s = moving_sphere();
+            assert_eq!(s.transform_at(-1.0), Matrix::identity());
+            assert_eq!(s.transform_at(2.0), Matrix::translation(4.0, 0.0, 0.0));
+        }
+
+        #[test]
+        fn normals_use_the_transform_at_the_hit_time() {
+            let s = moving_sphere();
+            let xs = s.intersect(&along_z(3.0, 0.75));
+            let point = along_z(3.0, 0.75).position(xs[0].t);
+            assert_eq!(s.normal_at(&point, &xs[0]), Vector::new(0.0, 0.0, -1.0));
+        }
+
+        #[test]
+        fn bounds_cover_the_whole_path() {
+            let b = moving_sphere().bounds();
+            assert_eq!(b.min, Point::new(-1.0, -1.0, -1.0));
+            assert_eq!(b.max, Point::new(5.0, 1.0, 1.0));
+        }
+
+        #[test]
+        fn groups_pass_their_motion_to_children() {
+            let mut g = Group::new();
+            g.add_child(Box::new(Sphere::new()));
+            g.set_motion(Matrix::translation(0.0, 3.0, 0.0));
+            let up = Ray::new(Point::new(0.0, 3.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert!(g.intersect(&up).is_empty());
+            assert_eq!(g.intersect(&up.with_time(1.0)).len(), 2);
+        }
+
+        #[test]
+        fn a_shape_flattened_mid_motion_is_missed_at_that_time() {
+            let mut s = Sphere::new();
+            s.set_motion(Matrix::scaling(-1.0, 1.0, 1.0));
+            assert!(s.intersect(&along_z(0.0, 0.5)).is_empty());
+            assert_eq!(s.intersect(&along_z(0.0, 0.25)).len(), 2);
+            assert_eq!(s.intersect(&along_z(0.0, 1.0)).len(), 2);
+        }
+
+        #[test]
+        fn children_added_after_the_motion_move_with_the_group() {
+            let mut g = Group::new();
+            g.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+            g.set_motion(Matrix::translation(0.0, 3.0, 0.0) * Matrix::scaling(2.0, 2.0, 2.0));
+            g.add_child(Box::new(Sphere::new()));
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::translation(1.0, 0.0, 0.0));
+            s.set_motion(Matrix::translation(2.0, 0.0, 0.0));
+            g.add_child(Box::new(s));
+
+            let children = g.children();
+            assert_eq!(
+                children[0].end_transform(),
+                Some(&(Matrix::translation(0.0, 3.0, 0.0) * Matrix::scaling(2.0, 2.0, 2.0)))
+            );
+            assert_eq!(
+                *children[1].transform(),
+                Matrix::scaling(2.0, 2.0, 2.0) * Matrix::translation(1.0, 0.0, 0.0)
+            );
+            assert_eq!(
+                children[1].end_transform(),
+                Some(
+                    &(Matrix::translation(0.0, 3.0, 0.0)
+                        * Matrix::scaling(2.0, 2.0, 2.0)
+                        * Matrix::translation(2.0, 0.0, 0.0))
+                )
+            );
+            let up = Ray::new(Point::new(0.0, 3.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert!(g.intersect(&up).is_empty());
+            assert_eq!(g.intersect(&up.with_time(1.0)).len(), 2);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use crate::triangle::intersect_triangle;
+use crate::{BoundingBox, Intersection, Intersections, Shape, ShapeData};
+use rt_core::{Point, Ray, Scalar, Vector};
+
+#[derive(Debug)]
+pub struct SmoothTriangle {
+    data: ShapeData,
+    p1: Point,
+    p2: Point,
+    p3: Point,
+    n1: Vector,
+    n2: Vector,
+    n3: Vector,
+    e1: Vector,
+    e2: Vector,
+}
+
+impl SmoothTriangle {
+    pub fn new(p1: Point, p2: Point, p3: Point, n1: Vector, n2: Vector, n3: Vector) -> Self {
+        SmoothTriangle {
+            data: ShapeData::default(),
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            e1: p2 - p1,
+            e2: p3 - p1,
+        }
+    }
+
+    pub fn p1(&self) -> Point {
+        self.p1
+    }
+
+    pub fn p2(&self) -> Point {
+        self.p2
+    }
+
+    pub fn p3(&self) -> Point {
+        self.p3
+    }
+
+    pub fn n1(&self) -> Vector {
+        self.n1
+    }
+
+    pub fn n2(&self) -> Vector {
+        self.n2
+    }
+
+    pub fn n3(&self) -> Vector {
+        self.n3
+    }
+}
+
+impl Shape for SmoothTriangle {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        match intersect_triangle(ray, &self.p1, &self.e1, &self.e2) {
+            Some((t, u, v)) => Intersections::new(vec![Intersection::with_uv(t, self, u, v)]),
+            None => Intersections::default(),
+        }
+    }
+
+    fn local_normal_at(&self, _point: &Point, hit: &Intersection) -> Vector {
+        match hit.uv {
+            Some((u, v)) => self.n2 * u + self.n3 * v + self.n1 * (1.0 - u - v),
+            None => self.e2.cross(&self.e1).normalize(),
+        }
+    }
+
+    fn local_tangent_at(&self, _point: &Point, _hit: &Intersection) -> Vector {
+        self.e1.normalize()
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+        for point in [self.p1, self.p2, self.p3] {
+            bounds.add_point(&point);
+        }
+        bounds
+    }
+
+    fn local_sample_point(&self, u: Scalar, v: Scalar) -> Option<(Point, Vector)> {
+        let s = u.sqrt();
+        let (b2, b3) = (s * (1.0 - v), s * v);
+        Some((
+            self.p1 + self.e1 * b2 + self.e2 * b3,
+            self.n2 * b2 + self.n3 * b3 + self.n1 * (1.0 - b2 - b3),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Intersections;
+    use rt_core::EPSILON;
+
+    fn smooth_triangle() -> SmoothTriangle {
+        SmoothTriangle::new(
+            Point::new(0.0, 1.0, 0.0),
+            Point::new(-1.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(-1.0, 0.0, 0.0),
+            Vector::new(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn constructing_a_smooth_triangle() {
+        let tri = smooth_triangle();
+        assert_eq!(tri.p1(), Point::new(0.0, 1.0, 0.0));
+        assert_eq!(tri.p2(), Point::new(-1.0, 0.0, 0.0));
+        assert_eq!(tri.p3(), Point::new(1.0, 0.0, 0.0));
+        assert_eq!(tri.n1(), Vector::new(0.0, 1.0, 0.0));
+        assert_eq!(tri.n2(), Vector::new(-1.0, 0.0, 0.0));
+        assert_eq!(tri.n3(), Vector::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn intersection_stores_u_and_v() {
+        let tri = smooth_triangle();
+        let r = Ray::new(Point::new(-0.2, 0.3, -2.0), Vector::new(0.0, 0.0, 1.0));
+        let (u, v) = tri.local_intersect(&r)[0].uv.unwrap();
+        assert!((u - 0.45).abs() < EPSILON);
+        assert!((v - 0.25).abs() < EPSILON);
+    }
+
+    #[test]
+    fn uses_u_and_v_to_interpolate_the_normal() {
+        let tri = smooth_triangle();
+        let hit = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+        assert_eq!(
+            tri.normal_at(&Point::origin(), &hit),
+            Vector::new(-0.5547, 0.83205, 0.0)
+        );
+    }
+
+    #[test]
+    fn preparing_the_normal_on_a_smooth_triangle() {
+        let tri = smooth_triangle();
+        let hit = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+        let r = Ray::new(Point::new(-0.2, 0.3, -2.0), Vector::new(0.0, 0.0, 1.0));
+        let xs = Intersections::new(vec![hit]);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert_eq!(comps.normalv, Vector::new(-0.5547, 0.83205, 0.0));
+    }
+
+    #[test]
+    fn samples_interpolate_the_vertex_normals() {
+        let tri = smooth_triangle();
+        let (point, normal) = tri.sample_point(1.0, 0.0).unwrap();
+        assert_eq!(point, tri.p2());
+        assert_eq!(normal, tri.n2());
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{BoundingBox, Intersection, Intersections, Shape, ShapeBuilder, ShapeData};
+use rt_core::{Point, Ray, Scalar, Vector, EPSILON};
+
+#[derive(Debug, Default)]
+pub struct Sphere {
+    data: ShapeData,
+}
+
+impl Sphere {
+    pub fn new() -> Self {
+        Sphere::default()
+    }
+
+    pub fn builder() -> ShapeBuilder<Sphere> {
+        ShapeBuilder::new(Sphere::new())
+    }
+}
+
+impl Shape for Sphere {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let sphere_to_ray = ray.origin - Point::origin();
+        let a = ray.direction.dot(&ray.direction);
+        let b = 2.0 * ray.direction.dot(&sphere_to_ray);
+        let c = sphere_to_ray.dot(&sphere_to_ray) - 1.0;
+        let discriminant = b * b - 4.0 * a * c;
+
+        if discriminant < 0.0 {
+            return Intersections::default();
+        }
+
+        let root = discriminant.sqrt();
+        Intersections::new(vec![
+            Intersection::new((-b - root) / (2.0 * a), self),
+            Intersection::new((-b + root) / (2.0 * a), self),
+        ])
+    }
+
+    fn local_normal_at(&self, point: &Point, _hit: &Intersection) -> Vector {
+        *point - Point::origin()
+    }
+
+    fn local_tangent_at(&self, point: &Point, hit: &Intersection) -> Vector {
+        let tangent = Vector::new(-point.z(), 0.0, point.x());
+        if tangent.magnitude() < EPSILON {
+            return crate::shape::perpendicular_to(&self.local_normal_at(point, hit));
+        }
+        tangent.normalize()
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+    }
+
+    fn local_sample_point(&self, u: Scalar, v: Scalar) -> Option<(Point, Vector)> {
+        let z = 1.0 - 2.0 * u;
+        let radius = (1.0 - z * z).max(0.0).sqrt();
+        let phi = 2.0 * rt_core::consts::PI * v;
+        let point = Point::new(radius * phi.cos(), radius * phi.sin(), z);
+        Some((point, point - Point::origin()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::consts::FRAC_1_SQRT_2;
+    use rt_core::Matrix;
+
+    mod intersection {
+        use super::*;
+
+        fn ts(xs: &Intersections) -> Vec<Scalar> {
+            xs.iter().map(|i| i.t).collect()
+        }
+
+        #[test]
+        fn ray_intersects_at_two_points() {
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert_eq!(ts(&Sphere::new().intersect(&r)), vec![4.0, 6.0]);
+        }
+
+        #[test]
+        fn ray_intersects_at_a_tangent() {
+            let r = Ray::new(Point::new(0.0, 1.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert_eq!(ts(&Sphere::new().intersect(&r)), vec![5.0, 5.0]);
+        }
+
+        #[test]
+        fn ray_misses() {
+            let r = Ray::new(Point::new(0.0, 2.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            assert!(Sphere::new().intersect(&r).is_empty());
+        }
+
+        #[test]
+        fn ray_originates_inside() {
+            let r = Ray::new(Point::origin(), Vector::new(0.0, 0.0, 1.0));
+            assert_eq!(ts(&Sphere::new().intersect(&r)), vec![-1.0, 1.0]);
+        }
+
+        #[test]
+        fn sphere_is_behind_ray() {
+            let r = Ray::new(Point::new(0.0, 0.0, 5.0), Vector::new(0.0, 0.0, 1.0));
+            assert_eq!(ts(&Sphere::new().intersect(&r)), vec![-6.0, -4.0]);
+        }
+
+        #[test]
+        fn intersect_sets_the_object() {
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let s = Sphere::new();
+            let xs = s.intersect(&r);
+            assert_eq!(xs[0], Intersection::new(4.0, &s));
+            assert_eq!(xs[1], Intersection::new(6.0, &s));
+        }
+
+        #[test]
+        fn scaled_sphere() {
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+            assert_eq!(ts(&s.intersect(&r)), vec![3.0, 7.0]);
+        }
+
+        #[test]
+        fn translated_sphere() {
+            let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+            assert!(s.intersect(&r).is_empty());
+        }
+    }
+
+    mod normal {
+        use super::*;
+
+        fn normal_at(s: &dyn Shape, point: Point) -> Vector {
+            s.normal_at(&point, &Intersection::new(0.0, s))
+        }
+
+        #[test]
+        fn on_each_axis() {
+            let s = Sphere::new();
+            assert_eq!(
+                normal_at(&s, Point::new(1.0, 0.0, 0.0)),
+                Vector::new(1.0, 0.0, 0.0)
+            );
+            assert_eq!(
+                normal_at(&s, Point::new(0.0, 1.0, 0.0)),
+                Vector::new(0.0, 1.0, 0.0)
+            );
+            assert_eq!(
+                normal_at(&s, Point::new(0.0, 0.0, 1.0)),
+                Vector::new(0.0, 0.0, 1.0)
+            );
+        }
+
+        #[test]
+        fn at_a_nonaxial_point() {
+            let k = Scalar::sqrt(3.0) / 3.0;
+            let n = normal_at(&Sphere::new(), Point::new(k, k, k));
+            assert_eq!(n, Vector::new(k, k, k));
+            assert_eq!(n, n.normalize());
+        }
+
+        #[test]
+        fn on_a_translated_sphere() {
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::translation(0.0, 1.0, 0.0));
+            assert_eq!(
+                normal_at(&s, Point::new(0.0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2)),
+                Vector::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2)
+            );
+        }
+    }
+
+    #[test]
+    fn sphere_has_a_bounding_box() {
+        let b = Sphere::new().local_bounds();
+        assert_eq!(b.min, Point::new(-1.0, -1.0, -1.0));
+        assert_eq!(b.max, Point::new(1.0, 1.0, 1.0));
+    }
+
+    mod tangent {
+        use super::*;
+
+        fn tangent_at(s: &dyn Shape, point: Point) -> Vector {
+            s.tangent_at(&point, &Intersection::new(0.0, s))
+        }
+
+        #[test]
+        fn tangent_follows_increasing_u() {
+            let s = Sphere::new();
+            assert_eq!(
+                tangent_at(&s, Point::new(0.0, 0.0, -1.0)),
+                Vector::new(1.0, 0.0, 0.0)
+            );
+            assert_eq!(
+                tangent_at(&s, Point::new(1.0, 0.0, 0.0)),
+                Vector::new(0.0, 0.0, 1.0)
+            );
+        }
+
+        #[test]
+        fn tangent_is_perpendicular_to_the_normal() {
+            let s = Sphere::new();
+            let k = Scalar::sqrt(3.0) / 3.0;
+            for point in [
+                Point::new(k, k, k),
+                Point::new(0.0, 1.0, 0.0),
+                Point::new(0.0, -1.0, 0.0),
+            ] {
+                let hit = Intersection::new(0.0, &s);
+                let t = s.tangent_at(&point, &hit);
+                assert!(t.dot(&s.normal_at(&point, &hit)).abs() < EPSILON);
+                assert_eq!(t, t.normalize());
+            }
+        }
+
+        #[test]
+        fn tangent_on_a_transformed_sphere() {
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::rotation_y(rt_core::consts::PI / 2.0));
+            assert_eq!(
+                tangent_at(&s, Point::new(-1.0, 0.0, 0.0)),
+                Vector::new(0.0, 0.0, -1.0)
+            );
+        }
+    }
+
+    mod sampling {
+        use super::*;
+        use rt_core::Matrix;
+
+        #[test]
+        fn samples_lie_on_the_transformed_surface() {
+            let mut s = Sphere::new();
+            s.set_transform(Matrix::scaling(2.0, 2.0, 2.0).translate(0.0, 2.0, 0.0));
+            for (u, v) in [(0.0, 0.0), (0.25, 0.5), (0.5, 0.1), (0.9, 0.7)] {
+                let (point, normal) = s.sample_point(u, v).unwrap();
+                let offset = point - Point::new(0.0, 2.0, 0.0);
+                assert!((offset.magnitude() - 2.0).abs() < EPSILON);
+                assert_eq!(normal, offset.normalize());
+            }
+        }
+    }
+}
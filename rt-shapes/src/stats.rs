@@ -0,0 +1,104 @@
+use std::cell::Cell;
+use std::ops::AddAssign;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TraversalStats {
+    pub intersection_tests: u64,
+    pub node_visits: u64,
+}
+
+impl AddAssign for TraversalStats {
+    fn add_assign(&mut self, other: TraversalStats) {
+        self.intersection_tests += other.intersection_tests;
+        self.node_visits += other.node_visits;
+    }
+}
+
+thread_local! {
+    static COUNTING: Cell<Option<TraversalStats>> = const { Cell::new(None) };
+}
+
+/// Counts the traversal work `f` does on this thread; nothing is counted otherwise.
+pub fn count_traversal<R>(f: impl FnOnce() -> R) -> (R, TraversalStats) {
+    let outer = COUNTING.with(|counting| counting.replace(Some(TraversalStats::default())));
+    let result = f();
+    let counted = COUNTING
+        .with(|counting| counting.replace(outer))
+        .unwrap_or_default();
+    if let Some(mut outer) = outer {
+        outer += counted;
+        COUNTING.with(|counting| counting.set(Some(outer)));
+    }
+    (result, counted)
+}
+
+pub(crate) fn record(update: impl FnOnce(&mut TraversalStats)) {
+    COUNTING.with(|counting| {
+        if let Some(mut stats) = counting.get() {
+            update(&mut stats);
+            counting.set(Some(stats));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Group, Shape, Sphere};
+    use rt_core::{Matrix, Point, Ray, Vector};
+
+    fn two_spheres() -> Group {
+        let mut g = Group::new();
+        g.add_child(Box::new(Sphere::new()));
+        let mut s = Sphere::new();
+        s.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+        g.add_child(Box::new(s));
+        g
+    }
+
+    fn forward() -> Ray {
+        Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0))
+    }
+
+    #[test]
+    fn nothing_is_counted_outside_a_measurement() {
+        let g = two_spheres();
+        g.intersect(&forward());
+        let (_, stats) = count_traversal(|| ());
+        assert_eq!(stats, TraversalStats::default());
+    }
+
+    #[test]
+    fn groups_count_visits_and_children_count_tests() {
+        let g = two_spheres();
+        let (xs, stats) = count_traversal(|| g.intersect(&forward()).len());
+        assert_eq!(xs, 2);
+        assert_eq!(
+            stats,
+            TraversalStats {
+                intersection_tests: 2,
+                node_visits: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn missing_the_bounds_skips_the_children() {
+        let g = two_spheres();
+        let above = Ray::new(Point::new(0.0, 5.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        let (_, stats) = count_traversal(|| g.intersect(&above));
+        assert_eq!(stats.node_visits, 1);
+        assert_eq!(stats.intersection_tests, 0);
+    }
+
+    #[test]
+    fn nested_measurements_also_count_toward_the_outer_one() {
+        let g = two_spheres();
+        let ((_, inner), outer) = count_traversal(|| {
+            g.intersect(&forward());
+            count_traversal(|| g.intersect(&forward()))
+        });
+        assert_eq!(inner.node_visits, 1);
+        assert_eq!(outer.node_visits, 2);
+    }
+}
@@ -0,0 +1,667 @@
+use crate::{Pattern, PatternData};
+use rt_core::consts::PI;
+use rt_core::{Canvas, Color, Point, Scalar, Vector};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UvMapping {
+    Spherical,
+    Planar,
+    Cylindrical,
+    Cubic,
+}
+
+impl UvMapping {
+    pub fn map(&self, point: &Point) -> (Scalar, Scalar) {
+        match self {
+            UvMapping::Spherical => spherical_map(point),
+            UvMapping::Planar => planar_map(point),
+            UvMapping::Cylindrical => cylindrical_map(point),
+            UvMapping::Cubic => {
+                let face = CubeFace::from_point(point);
+                face.uv(point)
+            }
+        }
+    }
+}
+
+fn azimuth_u(point: &Point) -> Scalar {
+    let theta = point.x().atan2(point.z());
+    let raw_u = theta / (2.0 * PI);
+    1.0 - (raw_u + 0.5)
+}
+
+pub fn spherical_map(point: &Point) -> (Scalar, Scalar) {
+    let radius = (point.x().powi(2) + point.y().powi(2) + point.z().powi(2)).sqrt();
+    let phi = (point.y() / radius).acos();
+    (azimuth_u(point), 1.0 - phi / PI)
+}
+
+pub fn planar_map(point: &Point) -> (Scalar, Scalar) {
+    (point.x().rem_euclid(1.0), point.z().rem_euclid(1.0))
+}
+
+pub fn cylindrical_map(point: &Point) -> (Scalar, Scalar) {
+    (azimuth_u(point), point.y().rem_euclid(1.0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeFace {
+    Left,
+    Right,
+    Front,
+    Back,
+    Up,
+    Down,
+}
+
+impl CubeFace {
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::Left,
+        CubeFace::Right,
+        CubeFace::Front,
+        CubeFace::Back,
+        CubeFace::Up,
+        CubeFace::Down,
+    ];
+
+    pub fn from_point(point: &Point) -> Self {
+        let (x, y, z) = (point.x(), point.y(), point.z());
+        let coord = x.abs().max(y.abs()).max(z.abs());
+        if coord == x {
+            CubeFace::Right
+        } else if coord == -x {
+            CubeFace::Left
+        } else if coord == y {
+            CubeFace::Up
+        } else if coord == -y {
+            CubeFace::Down
+        } else if coord == z {
+            CubeFace::Front
+        } else {
+            CubeFace::Back
+        }
+    }
+
+    pub fn uv(&self, point: &Point) -> (Scalar, Scalar) {
+        let wrap = |value: Scalar| value.rem_euclid(2.0) / 2.0;
+        let (x, y, z) = (point.x(), point.y(), point.z());
+        match self {
+            CubeFace::Front => (wrap(x + 1.0), wrap(y + 1.0)),
+            CubeFace::Back => (wrap(1.0 - x), wrap(y + 1.0)),
+            CubeFace::Left => (wrap(z + 1.0), wrap(y + 1.0)),
+            CubeFace::Right => (wrap(1.0 - z), wrap(y + 1.0)),
+            CubeFace::Up => (wrap(x + 1.0), wrap(1.0 - z)),
+            CubeFace::Down => (wrap(x + 1.0), wrap(z + 1.0)),
+        }
+    }
+
+    fn index(&self) -> usize {
+        CubeFace::ALL.iter().position(|face| face == self).unwrap()
+    }
+}
+
+pub trait UvPattern: std::any::Any + std::fmt::Debug + Send + Sync {
+    fn uv_pattern_at(&self, u: Scalar, v: Scalar) -> Color;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UvChecker {
+    pub width: Scalar,
+    pub height: Scalar,
+    pub a: Color,
+    pub b: Color,
+}
+
+impl UvChecker {
+    pub fn new(width: Scalar, height: Scalar, a: Color, b: Color) -> Self {
+        UvChecker {
+            width,
+            height,
+            a,
+            b,
+        }
+    }
+}
+
+impl UvPattern for UvChecker {
+    fn uv_pattern_at(&self, u: Scalar, v: Scalar) -> Color {
+        let u = (u * self.width).floor() as i64;
+        let v = (v * self.height).floor() as i64;
+        if (u + v).rem_euclid(2) == 0 {
+            self.a
+        } else {
+            self.b
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UvAlignCheck {
+    pub main: Color,
+    pub upper_left: Color,
+    pub upper_right: Color,
+    pub bottom_left: Color,
+    pub bottom_right: Color,
+}
+
+impl UvAlignCheck {
+    pub fn new(
+        main: Color,
+        upper_left: Color,
+        upper_right: Color,
+        bottom_left: Color,
+        bottom_right: Color,
+    ) -> Self {
+        UvAlignCheck {
+            main,
+            upper_left,
+            upper_right,
+            bottom_left,
+            bottom_right,
+        }
+    }
+}
+
+impl UvPattern for UvAlignCheck {
+    fn uv_pattern_at(&self, u: Scalar, v: Scalar) -> Color {
+        if v > 0.8 {
+            if u < 0.2 {
+                return self.upper_left;
+            }
+            if u > 0.8 {
+                return self.upper_right;
+            }
+        } else if v < 0.2 {
+            if u < 0.2 {
+                return self.bottom_left;
+            }
+            if u > 0.8 {
+                return self.bottom_right;
+            }
+        }
+        self.main
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UvImage {
+    canvas: Canvas,
+}
+
+impl UvImage {
+    pub fn new(canvas: Canvas) -> Self {
+        UvImage { canvas }
+    }
+
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+}
+
+impl UvPattern for UvImage {
+    fn uv_pattern_at(&self, u: Scalar, v: Scalar) -> Color {
+        let v = 1.0 - v;
+        let x = u * (self.canvas.width() - 1) as Scalar;
+        let y = v * (self.canvas.height() - 1) as Scalar;
+        self.canvas.pixel_at(x.round() as usize, y.round() as usize)
+    }
+}
+
+#[derive(Debug)]
+pub struct TextureMap {
+    data: PatternData,
+    mapping: UvMapping,
+    uv_pattern: Box<dyn UvPattern>,
+}
+
+impl TextureMap {
+    pub fn new(uv_pattern: Box<dyn UvPattern>, mapping: UvMapping) -> Self {
+        TextureMap {
+            data: PatternData::default(),
+            mapping,
+            uv_pattern,
+        }
+    }
+
+    pub fn mapping(&self) -> UvMapping {
+        self.mapping
+    }
+
+    pub fn uv_pattern(&self) -> &dyn UvPattern {
+        self.uv_pattern.as_ref()
+    }
+}
+
+impl Pattern for TextureMap {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, point: &Point) -> Color {
+        let (u, v) = self.mapping.map(point);
+        self.uv_pattern.uv_pattern_at(u, v)
+    }
+}
+
+#[derive(Debug)]
+pub struct CubeMap {
+    data: PatternData,
+    faces: [Box<dyn UvPattern>; 6],
+}
+
+impl CubeMap {
+    pub fn new(faces: [Box<dyn UvPattern>; 6]) -> Self {
+        CubeMap {
+            data: PatternData::default(),
+            faces,
+        }
+    }
+
+    pub fn face(&self, face: CubeFace) -> &dyn UvPattern {
+        self.faces[face.index()].as_ref()
+    }
+}
+
+impl Pattern for CubeMap {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, point: &Point) -> Color {
+        let face = CubeFace::from_point(point);
+        let (u, v) = face.uv(point);
+        self.face(face).uv_pattern_at(u, v)
+    }
+}
+
+#[derive(Debug)]
+pub struct VertexUvMap {
+    data: PatternData,
+    uv_pattern: Arc<dyn UvPattern>,
+    corner: Point,
+    edges: [Vector; 2],
+    uvs: [(Scalar, Scalar); 3],
+}
+
+impl VertexUvMap {
+    pub fn new(
+        uv_pattern: Arc<dyn UvPattern>,
+        [p1, p2, p3]: [Point; 3],
+        uvs: [(Scalar, Scalar); 3],
+    ) -> Self {
+        VertexUvMap {
+            data: PatternData::default(),
+            uv_pattern,
+            corner: p1,
+            edges: [p2 - p1, p3 - p1],
+            uvs,
+        }
+    }
+
+    pub fn uv_pattern(&self) -> &Arc<dyn UvPattern> {
+        &self.uv_pattern
+    }
+
+    pub fn uv_at(&self, point: &Point) -> (Scalar, Scalar) {
+        let [e1, e2] = self.edges;
+        let offset = *point - self.corner;
+        let (d11, d12, d22) = (e1.dot(&e1), e1.dot(&e2), e2.dot(&e2));
+        let (o1, o2) = (offset.dot(&e1), offset.dot(&e2));
+        let denominator = d11 * d22 - d12 * d12;
+        let v = (d22 * o1 - d12 * o2) / denominator;
+        let w = (d11 * o2 - d12 * o1) / denominator;
+        let [(u1, v1), (u2, v2), (u3, v3)] = self.uvs;
+        let weight = 1.0 - v - w;
+        (weight * u1 + v * u2 + w * u3, weight * v1 + v * v2 + w * v3)
+    }
+}
+
+impl Pattern for VertexUvMap {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, point: &Point) -> Color {
+        let (u, v) = self.uv_at(point);
+        self.uv_pattern
+            .uv_pattern_at(u.rem_euclid(1.0), v.rem_euclid(1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::consts::FRAC_1_SQRT_2;
+
+    fn white() -> Color {
+        Color::white()
+    }
+
+    fn black() -> Color {
+        Color::black()
+    }
+
+    #[test]
+    fn checker_pattern_in_2d() {
+        let checkers = UvChecker::new(2.0, 2.0, black(), white());
+        let cases = [
+            (0.0, 0.0, black()),
+            (0.5, 0.0, white()),
+            (0.0, 0.5, white()),
+            (0.5, 0.5, black()),
+            (1.0, 1.0, black()),
+        ];
+        for (u, v, expected) in cases {
+            assert_eq!(checkers.uv_pattern_at(u, v), expected);
+        }
+    }
+
+    mod mapping {
+        use super::*;
+
+        #[test]
+        fn spherical_mapping_on_a_3d_point() {
+            let cases = [
+                (Point::new(0.0, 0.0, -1.0), 0.0, 0.5),
+                (Point::new(1.0, 0.0, 0.0), 0.25, 0.5),
+                (Point::new(0.0, 0.0, 1.0), 0.5, 0.5),
+                (Point::new(-1.0, 0.0, 0.0), 0.75, 0.5),
+                (Point::new(0.0, 1.0, 0.0), 0.5, 1.0),
+                (Point::new(0.0, -1.0, 0.0), 0.5, 0.0),
+                (Point::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0), 0.25, 0.75),
+            ];
+            for (point, u, v) in cases {
+                let (pu, pv) = spherical_map(&point);
+                assert!((pu - u).abs() < rt_core::EPSILON, "{:?}", point);
+                assert!((pv - v).abs() < rt_core::EPSILON, "{:?}", point);
+            }
+        }
+
+        #[test]
+        fn planar_mapping_on_a_3d_point() {
+            let cases = [
+                (Point::new(0.25, 0.0, 0.5), 0.25, 0.5),
+                (Point::new(0.25, 0.0, -0.25), 0.25, 0.75),
+                (Point::new(0.25, 0.5, -0.25), 0.25, 0.75),
+                (Point::new(1.25, 0.0, 0.5), 0.25, 0.5),
+                (Point::new(0.25, 0.0, -1.75), 0.25, 0.25),
+                (Point::new(1.0, 0.0, -1.0), 0.0, 0.0),
+                (Point::new(0.0, 0.0, 0.0), 0.0, 0.0),
+            ];
+            for (point, u, v) in cases {
+                assert_eq!(planar_map(&point), (u, v), "{:?}", point);
+            }
+        }
+
+        #[test]
+        fn cylindrical_mapping_on_a_3d_point() {
+            let cases = [
+                (Point::new(0.0, 0.0, -1.0), 0.0, 0.0),
+                (Point::new(0.0, 0.5, -1.0), 0.0, 0.5),
+                (Point::new(0.0, 1.0, -1.0), 0.0, 0.0),
+                (Point::new(FRAC_1_SQRT_2, 0.5, -FRAC_1_SQRT_2), 0.125, 0.5),
+                (Point::new(1.0, 0.5, 0.0), 0.25, 0.5),
+                (Point::new(FRAC_1_SQRT_2, 0.5, FRAC_1_SQRT_2), 0.375, 0.5),
+                (Point::new(0.0, -0.25, 1.0), 0.5, 0.75),
+                (Point::new(-FRAC_1_SQRT_2, 0.5, FRAC_1_SQRT_2), 0.625, 0.5),
+                (Point::new(-1.0, 1.25, 0.0), 0.75, 0.25),
+                (Point::new(-FRAC_1_SQRT_2, 0.5, -FRAC_1_SQRT_2), 0.875, 0.5),
+            ];
+            for (point, u, v) in cases {
+                let (pu, pv) = cylindrical_map(&point);
+                assert!((pu - u).abs() < rt_core::EPSILON, "{:?}", point);
+                assert!((pv - v).abs() < rt_core::EPSILON, "{:?}", point);
+            }
+        }
+
+        #[test]
+        fn using_a_texture_map_with_a_spherical_map() {
+            let checkers = UvChecker::new(16.0, 8.0, black(), white());
+            let pattern = TextureMap::new(Box::new(checkers), UvMapping::Spherical);
+            let cases = [
+                (Point::new(0.4315, 0.4670, 0.7719), white()),
+                (Point::new(-0.9654, 0.2552, -0.0534), black()),
+                (Point::new(0.1039, 0.7090, 0.6975), white()),
+                (Point::new(-0.4986, -0.7856, -0.3663), black()),
+                (Point::new(-0.0317, -0.9395, 0.3411), black()),
+                (Point::new(0.4809, -0.7721, 0.4154), black()),
+                (Point::new(0.0285, -0.9612, -0.2745), black()),
+                (Point::new(-0.5734, -0.2162, -0.7903), white()),
+                (Point::new(0.7688, -0.1470, 0.6223), black()),
+                (Point::new(-0.7652, 0.2175, 0.6060), black()),
+            ];
+            for (point, expected) in cases {
+                assert_eq!(pattern.pattern_at(&point), expected, "{:?}", point);
+            }
+        }
+    }
+
+    mod align_check {
+        use super::*;
+
+        fn main() -> Color {
+            white()
+        }
+        fn ul() -> Color {
+            Color::new(1.0, 0.0, 0.0)
+        }
+        fn ur() -> Color {
+            Color::new(1.0, 1.0, 0.0)
+        }
+        fn bl() -> Color {
+            Color::new(0.0, 1.0, 0.0)
+        }
+        fn br() -> Color {
+            Color::new(0.0, 1.0, 1.0)
+        }
+
+        #[test]
+        fn layout_of_the_align_check_pattern() {
+            let pattern = UvAlignCheck::new(main(), ul(), ur(), bl(), br());
+            let cases = [
+                (0.5, 0.5, main()),
+                (0.1, 0.9, ul()),
+                (0.9, 0.9, ur()),
+                (0.1, 0.1, bl()),
+                (0.9, 0.1, br()),
+            ];
+            for (u, v, expected) in cases {
+                assert_eq!(pattern.uv_pattern_at(u, v), expected);
+            }
+        }
+    }
+
+    mod cube {
+        use super::*;
+
+        fn red() -> Color {
+            Color::new(1.0, 0.0, 0.0)
+        }
+        fn yellow() -> Color {
+            Color::new(1.0, 1.0, 0.0)
+        }
+        fn brown() -> Color {
+            Color::new(1.0, 0.5, 0.0)
+        }
+        fn green() -> Color {
+            Color::new(0.0, 1.0, 0.0)
+        }
+        fn cyan() -> Color {
+            Color::new(0.0, 1.0, 1.0)
+        }
+        fn blue() -> Color {
+            Color::new(0.0, 0.0, 1.0)
+        }
+        fn purple() -> Color {
+            Color::new(1.0, 0.0, 1.0)
+        }
+
+        fn align(main: Color, ul: Color, ur: Color, bl: Color, br: Color) -> Box<dyn UvPattern> {
+            Box::new(UvAlignCheck::new(main, ul, ur, bl, br))
+        }
+
+        fn test_cube_map() -> CubeMap {
+            CubeMap::new([
+                align(yellow(), cyan(), red(), blue(), brown()),
+                align(red(), yellow(), purple(), green(), white()),
+                align(cyan(), red(), yellow(), brown(), green()),
+                align(green(), purple(), cyan(), white(), blue()),
+                align(brown(), cyan(), purple(), red(), yellow()),
+                align(purple(), brown(), green(), blue(), white()),
+            ])
+        }
+
+        #[test]
+        fn identifying_the_face_of_a_cube_from_a_point() {
+            let cases = [
+                (Point::new(-1.0, 0.5, -0.25), CubeFace::Left),
+                (Point::new(1.1, -0.75, 0.8), CubeFace::Right),
+                (Point::new(0.1, 0.6, 0.9), CubeFace::Front),
+                (Point::new(-0.7, 0.0, -2.0), CubeFace::Back),
+                (Point::new(0.5, 1.0, 0.9), CubeFace::Up),
+                (Point::new(-0.2, -1.3, 1.1), CubeFace::Down),
+            ];
+            for (point, face) in cases {
+                assert_eq!(CubeFace::from_point(&point), face, "{:?}", point);
+            }
+        }
+
+        #[test]
+        fn uv_mapping_each_face_of_a_cube() {
+            let cases = [
+                (CubeFace::Front, Point::new(-0.5, 0.5, 1.0), 0.25, 0.75),
+                (CubeFace::Front, Point::new(0.5, -0.5, 1.0), 0.75, 0.25),
+                (CubeFace::Back, Point::new(0.5, 0.5, -1.0), 0.25, 0.75),
+                (CubeFace::Back, Point::new(-0.5, -0.5, -1.0), 0.75, 0.25),
+                (CubeFace::Left, Point::new(-1.0, 0.5, -0.5), 0.25, 0.75),
+                (CubeFace::Left, Point::new(-1.0, -0.5, 0.5), 0.75, 0.25),
+                (CubeFace::Right, Point::new(1.0, 0.5, 0.5), 0.25, 0.75),
+                (CubeFace::Right, Point::new(1.0, -0.5, -0.5), 0.75, 0.25),
+                (CubeFace::Up, Point::new(-0.5, 1.0, -0.5), 0.25, 0.75),
+                (CubeFace::Up, Point::new(0.5, 1.0, 0.5), 0.75, 0.25),
+                (CubeFace::Down, Point::new(-0.5, -1.0, 0.5), 0.25, 0.75),
+                (CubeFace::Down, Point::new(0.5, -1.0, -0.5), 0.75, 0.25),
+            ];
+            for (face, point, u, v) in cases {
+                assert_eq!(face.uv(&point), (u, v), "{:?} {:?}", face, point);
+            }
+        }
+
+        #[test]
+        fn finding_the_colors_on_a_mapped_cube() {
+            let cube = test_cube_map();
+            let cases = [
+                (Point::new(-1.0, 0.0, 0.0), yellow()),
+                (Point::new(-1.0, 0.9, -0.9), cyan()),
+                (Point::new(-1.0, 0.9, 0.9), red()),
+                (Point::new(-1.0, -0.9, -0.9), blue()),
+                (Point::new(-1.0, -0.9, 0.9), brown()),
+                (Point::new(0.0, 0.0, 1.0), cyan()),
+                (Point::new(-0.9, 0.9, 1.0), red()),
+                (Point::new(0.9, 0.9, 1.0), yellow()),
+                (Point::new(-0.9, -0.9, 1.0), brown()),
+                (Point::new(0.9, -0.9, 1.0), green()),
+                (Point::new(1.0, 0.0, 0.0), red()),
+                (Point::new(1.0, 0.9, 0.9), yellow()),
+                (Point::new(1.0, 0.9, -0.9), purple()),
+                (Point::new(1.0, -0.9, 0.9), green()),
+                (Point::new(1.0, -0.9, -0.9), white()),
+                (Point::new(0.0, 0.0, -1.0), green()),
+                (Point::new(0.9, 0.9, -1.0), purple()),
+                (Point::new(-0.9, 0.9, -1.0), cyan()),
+                (Point::new(0.9, -0.9, -1.0), white()),
+                (Point::new(-0.9, -0.9, -1.0), blue()),
+                (Point::new(0.0, 1.0, 0.0), brown()),
+                (Point::new(-0.9, 1.0, -0.9), cyan()),
+                (Point::new(0.9, 1.0, -0.9), purple()),
+                (Point::new(-0.9, 1.0, 0.9), red()),
+                (Point::new(0.9, 1.0, 0.9), yellow()),
+                (Point::new(0.0, -1.0, 0.0), purple()),
+                (Point::new(-0.9, -1.0, 0.9), brown()),
+                (Point::new(0.9, -1.0, 0.9), green()),
+                (Point::new(-0.9, -1.0, -0.9), blue()),
+                (Point::new(0.9, -1.0, -0.9), white()),
+            ];
+            for (point, expected) in cases {
+                assert_eq!(cube.pattern_at(&point), expected, "{:?}", point);
+            }
+        }
+
+        #[test]
+        fn cubic_texture_map_uses_one_pattern_on_every_face() {
+            let pattern = TextureMap::new(
+                Box::new(UvAlignCheck::new(white(), red(), red(), red(), red())),
+                UvMapping::Cubic,
+            );
+            assert_eq!(pattern.pattern_at(&Point::new(0.0, 0.0, 1.0)), white());
+            assert_eq!(pattern.pattern_at(&Point::new(1.0, 0.9, 0.9)), red());
+            assert_eq!(pattern.pattern_at(&Point::new(-0.9, -1.0, -0.9)), red());
+        }
+    }
+
+    #[test]
+    fn uv_image_looks_up_pixels_in_its_canvas() {
+        let mut canvas = Canvas::new(10, 10);
+        for y in 0..10 {
+            for x in 0..10 {
+                let value = (x + y) as Scalar / 18.0;
+                canvas.write_pixel(x, y, Color::new(value, value, value));
+            }
+        }
+        let pattern = UvImage::new(canvas);
+        let grey = |value: Scalar| Color::new(value, value, value);
+        let cases = [
+            (0.0, 0.0, grey(9.0 / 18.0)),
+            (0.3, 0.0, grey(12.0 / 18.0)),
+            (0.6, 0.3, grey(11.0 / 18.0)),
+            (1.0, 1.0, grey(9.0 / 18.0)),
+        ];
+        for (u, v, expected) in cases {
+            assert_eq!(pattern.uv_pattern_at(u, v), expected, "{} {}", u, v);
+        }
+    }
+
+    #[test]
+    fn vertex_uv_maps_interpolate_across_the_triangle() {
+        let checkers: Arc<dyn UvPattern> = Arc::new(UvChecker::new(2.0, 2.0, black(), white()));
+        let pattern = VertexUvMap::new(
+            checkers,
+            [
+                Point::new(0.0, 1.0, 0.0),
+                Point::new(-1.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+            ],
+            [(0.5, 1.0), (0.0, 0.0), (1.0, 0.0)],
+        );
+        let (u, v) = pattern.uv_at(&Point::new(0.0, 0.5, 0.0));
+        assert!((u - 0.5).abs() < 1e-6 && (v - 0.5).abs() < 1e-6);
+        assert_eq!(pattern.pattern_at(&Point::new(-0.5, 0.1, 0.0)), black());
+        assert_eq!(pattern.pattern_at(&Point::new(0.5, 0.1, 0.0)), white());
+    }
+
+    #[test]
+    fn vertex_uvs_outside_the_unit_square_repeat() {
+        let checkers: Arc<dyn UvPattern> = Arc::new(UvChecker::new(2.0, 2.0, black(), white()));
+        let corners = [
+            Point::origin(),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+        ];
+        let repeated = VertexUvMap::new(checkers, corners, [(1.0, 0.0), (2.0, 0.0), (1.0, 1.0)]);
+        assert_eq!(repeated.pattern_at(&Point::new(0.25, 0.25, 0.0)), black());
+        assert_eq!(repeated.pattern_at(&Point::new(0.75, 0.1, 0.0)), white());
+    }
+}
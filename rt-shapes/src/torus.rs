@@ -0,0 +1,154 @@
+use crate::{BoundingBox, Intersection, Intersections, Shape, ShapeData};
+use rt_core::{solve_quartic, Point, Ray, Scalar, Vector};
+
+#[derive(Debug)]
+pub struct Torus {
+    data: ShapeData,
+    pub major: Scalar,
+    pub minor: Scalar,
+}
+
+impl Torus {
+    pub fn new(major: Scalar, minor: Scalar) -> Self {
+        Torus {
+            data: ShapeData::default(),
+            major,
+            minor,
+        }
+    }
+}
+
+impl Shape for Torus {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let o = ray.origin - Point::origin();
+        let dir = ray.direction;
+        let r2 = self.major * self.major;
+        let dd = dir.dot(&dir);
+        let od = o.dot(&dir);
+        let k = o.dot(&o) + r2 - self.minor * self.minor;
+
+        let a = dd * dd;
+        let b = 4.0 * dd * od;
+        let c = 2.0 * dd * k + 4.0 * od * od - 4.0 * r2 * (dir.x().powi(2) + dir.z().powi(2));
+        let d = 4.0 * k * od - 8.0 * r2 * (o.x() * dir.x() + o.z() * dir.z());
+        let e = k * k - 4.0 * r2 * (o.x().powi(2) + o.z().powi(2));
+
+        Intersections::new(
+            solve_quartic(a, b, c, d, e)
+                .into_iter()
+                .map(|t| Intersection::new(t, self))
+                .collect(),
+        )
+    }
+
+    fn local_normal_at(&self, point: &Point, _hit: &Intersection) -> Vector {
+        let radial = Vector::new(point.x(), 0.0, point.z());
+        if radial.magnitude() == 0.0 {
+            return Vector::new(0.0, point.y().signum(), 0.0);
+        }
+        *point - (Point::origin() + radial.normalize() * self.major)
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        let outer = self.major + self.minor;
+        BoundingBox::new(
+            Point::new(-outer, -self.minor, -outer),
+            Point::new(outer, self.minor, outer),
+        )
+    }
+
+    fn local_sample_point(&self, u: Scalar, v: Scalar) -> Option<(Point, Vector)> {
+        let theta = 2.0 * rt_core::consts::PI * u;
+        let phi = 2.0 * rt_core::consts::PI * v;
+        let normal = Vector::new(phi.cos() * theta.cos(), phi.sin(), phi.cos() * theta.sin());
+        let centre = Point::new(self.major * theta.cos(), 0.0, self.major * theta.sin());
+        Some((centre + normal * self.minor, normal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rt_core::EPSILON;
+
+    fn ts(xs: &Intersections) -> Vec<Scalar> {
+        xs.iter().map(|i| i.t).collect()
+    }
+
+    fn assert_ts(xs: &Intersections, expected: &[Scalar]) {
+        let actual = ts(xs);
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < EPSILON, "{:?}", actual);
+        }
+    }
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn ray_through_the_hole_crosses_the_ring_twice() {
+            let t = Torus::new(2.0, 0.5);
+            let r = Ray::new(Point::new(-5.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0));
+            assert_ts(&t.local_intersect(&r), &[2.5, 3.5, 6.5, 7.5]);
+        }
+
+        #[test]
+        fn ray_through_the_tube() {
+            let t = Torus::new(2.0, 0.5);
+            let r = Ray::new(Point::new(2.0, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+            assert_ts(&t.local_intersect(&r), &[4.5, 5.5]);
+        }
+
+        #[test]
+        fn ray_down_the_axis_misses() {
+            let t = Torus::new(2.0, 0.5);
+            let r = Ray::new(Point::new(0.0, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+            assert!(t.local_intersect(&r).is_empty());
+        }
+
+        #[test]
+        fn unnormalized_directions_scale_t() {
+            let t = Torus::new(2.0, 0.5);
+            let r = Ray::new(Point::new(-5.0, 0.0, 0.0), Vector::new(2.0, 0.0, 0.0));
+            assert_ts(&t.local_intersect(&r), &[1.25, 1.75, 3.25, 3.75]);
+        }
+    }
+
+    #[test]
+    fn normals_point_away_from_the_tube_centre() {
+        let t = Torus::new(2.0, 0.5);
+        let hit = Intersection::new(0.0, &t);
+        let cases = [
+            (Point::new(2.5, 0.0, 0.0), Vector::new(0.5, 0.0, 0.0)),
+            (Point::new(1.5, 0.0, 0.0), Vector::new(-0.5, 0.0, 0.0)),
+            (Point::new(0.0, 0.5, -2.0), Vector::new(0.0, 0.5, 0.0)),
+        ];
+        for (point, normal) in cases {
+            assert_eq!(t.local_normal_at(&point, &hit), normal);
+        }
+    }
+
+    #[test]
+    fn bounds_enclose_the_ring() {
+        let b = Torus::new(2.0, 0.5).local_bounds();
+        assert_eq!(b.min, Point::new(-2.5, -0.5, -2.5));
+        assert_eq!(b.max, Point::new(2.5, 0.5, 2.5));
+    }
+
+    #[test]
+    fn samples_lie_on_the_surface() {
+        let t = Torus::new(2.0, 0.5);
+        let (point, normal) = t.sample_point(0.25, 0.0).unwrap();
+        assert_eq!(point, Point::new(0.0, 0.0, 2.5));
+        assert_eq!(normal, Vector::new(0.0, 0.0, 1.0));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::{BoundingBox, Intersection, Intersections, Shape, ShapeData};
+use rt_core::{Point, Ray, Scalar, Vector, EPSILON};
+
+#[derive(Debug)]
+pub struct Triangle {
+    data: ShapeData,
+    p1: Point,
+    p2: Point,
+    p3: Point,
+    e1: Vector,
+    e2: Vector,
+    normal: Vector,
+}
+
+impl Triangle {
+    pub fn new(p1: Point, p2: Point, p3: Point) -> Self {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+        Triangle {
+            data: ShapeData::default(),
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal: e2.cross(&e1).normalize(),
+        }
+    }
+
+    pub fn p1(&self) -> Point {
+        self.p1
+    }
+
+    pub fn p2(&self) -> Point {
+        self.p2
+    }
+
+    pub fn p3(&self) -> Point {
+        self.p3
+    }
+
+    pub fn e1(&self) -> Vector {
+        self.e1
+    }
+
+    pub fn e2(&self) -> Vector {
+        self.e2
+    }
+
+    pub fn normal(&self) -> Vector {
+        self.normal
+    }
+}
+
+pub(crate) fn intersect_triangle(
+    ray: &Ray,
+    p1: &Point,
+    e1: &Vector,
+    e2: &Vector,
+) -> Option<(Scalar, Scalar, Scalar)> {
+    let dir_cross_e2 = ray.direction.cross(e2);
+    let det = e1.dot(&dir_cross_e2);
+    if det.abs() < EPSILON {
+        return None;
+    }
+
+    let f = 1.0 / det;
+    let p1_to_origin = ray.origin - *p1;
+    let u = f * p1_to_origin.dot(&dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let origin_cross_e1 = p1_to_origin.cross(e1);
+    let v = f * ray.direction.dot(&origin_cross_e1);
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    Some((f * e2.dot(&origin_cross_e1), u, v))
+}
+
+impl Shape for Triangle {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        match intersect_triangle(ray, &self.p1, &self.e1, &self.e2) {
+            Some((t, u, v)) => Intersections::new(vec![Intersection::with_uv(t, self, u, v)]),
+            None => Intersections::default(),
+        }
+    }
+
+    fn local_normal_at(&self, _point: &Point, _hit: &Intersection) -> Vector {
+        self.normal
+    }
+
+    fn local_tangent_at(&self, _point: &Point, _hit: &Intersection) -> Vector {
+        self.e1.normalize()
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+        for point in [self.p1, self.p2, self.p3] {
+            bounds.add_point(&point);
+        }
+        bounds
+    }
+
+    fn local_sample_point(&self, u: Scalar, v: Scalar) -> Option<(Point, Vector)> {
+        let s = u.sqrt();
+        Some((
+            self.p1 + self.e1 * (s * (1.0 - v)) + self.e2 * (s * v),
+            self.normal,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle() -> Triangle {
+        Triangle::new(
+            Point::new(0.0, 1.0, 0.0),
+            Point::new(-1.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn constructing_a_triangle() {
+        let t = triangle();
+        assert_eq!(t.p1(), Point::new(0.0, 1.0, 0.0));
+        assert_eq!(t.p2(), Point::new(-1.0, 0.0, 0.0));
+        assert_eq!(t.p3(), Point::new(1.0, 0.0, 0.0));
+        assert_eq!(t.e1(), Vector::new(-1.0, -1.0, 0.0));
+        assert_eq!(t.e2(), Vector::new(1.0, -1.0, 0.0));
+        assert_eq!(t.normal(), Vector::new(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn normal_is_the_same_everywhere() {
+        let t = triangle();
+        let hit = Intersection::new(0.0, &t);
+        for point in [
+            Point::new(0.0, 0.5, 0.0),
+            Point::new(-0.5, 0.75, 0.0),
+            Point::new(0.5, 0.25, 0.0),
+        ] {
+            assert_eq!(t.local_normal_at(&point, &hit), t.normal());
+        }
+    }
+
+    mod intersection {
+        use super::*;
+
+        #[test]
+        fn ray_parallel_to_the_triangle() {
+            let r = Ray::new(Point::new(0.0, -1.0, -2.0), Vector::new(0.0, 1.0, 0.0));
+            assert!(triangle().local_intersect(&r).is_empty());
+        }
+
+        #[test]
+        fn ray_misses_each_edge() {
+            for origin in [
+                Point::new(1.0, 1.0, -2.0),
+                Point::new(-1.0, 1.0, -2.0),
+                Point::new(0.0, -1.0, -2.0),
+            ] {
+                let r = Ray::new(origin, Vector::new(0.0, 0.0, 1.0));
+                assert!(triangle().local_intersect(&r).is_empty());
+            }
+        }
+
+        #[test]
+        fn ray_strikes_a_triangle() {
+            let t = triangle();
+            let r = Ray::new(Point::new(0.0, 0.5, -2.0), Vector::new(0.0, 0.0, 1.0));
+            let xs = t.local_intersect(&r);
+            assert_eq!(xs.len(), 1);
+            assert_eq!(xs[0].t, 2.0);
+        }
+
+        #[test]
+        fn intersection_records_u_and_v() {
+            let t = triangle();
+            let r = Ray::new(Point::new(-0.2, 0.3, -2.0), Vector::new(0.0, 0.0, 1.0));
+            let (u, v) = t.local_intersect(&r)[0].uv.unwrap();
+            assert!((u - 0.45).abs() < EPSILON);
+            assert!((v - 0.25).abs() < EPSILON);
+        }
+    }
+
+    #[test]
+    fn triangle_has_a_bounding_box() {
+        let t = Triangle::new(
+            Point::new(-3.0, 7.0, 2.0),
+            Point::new(6.0, 2.0, -4.0),
+            Point::new(2.0, -1.0, -1.0),
+        );
+        let b = t.local_bounds();
+        assert_eq!(b.min, Point::new(-3.0, -1.0, -4.0));
+        assert_eq!(b.max, Point::new(6.0, 7.0, 2.0));
+    }
+
+    #[test]
+    fn tangent_runs_along_the_first_edge() {
+        let t = triangle();
+        let hit = Intersection::new(0.0, &t);
+        assert_eq!(
+            t.tangent_at(&Point::new(0.0, 0.5, 0.0), &hit),
+            t.e1().normalize()
+        );
+    }
+
+    #[test]
+    fn samples_lie_inside_the_triangle() {
+        let t = triangle();
+        for (u, v) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.3, 0.6)] {
+            let (point, normal) = t.sample_point(u, v).unwrap();
+            assert_eq!(point.z(), 0.0);
+            assert!(point.y() >= 0.0 && point.y() <= 1.0);
+            assert!(point.x().abs() <= 1.0 - point.y() + EPSILON);
+            assert_eq!(normal, t.normal());
+        }
+    }
+}